@@ -0,0 +1,56 @@
+-- Immutable version history for cards and relationships
+CREATE TABLE IF NOT EXISTS card_versions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  card_id UUID NOT NULL, -- no FK: history outlives deleted cards
+  version INTEGER NOT NULL,
+  operation VARCHAR(20) NOT NULL CHECK (operation IN ('create', 'update', 'delete', 'restore')),
+  snapshot JSONB NOT NULL, -- card after the change, or just before a delete
+  diff JSONB NOT NULL DEFAULT '{}', -- {field: {from, to}}
+  actor_id UUID,
+  actor_email VARCHAR(255),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  UNIQUE (card_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS relationship_versions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  relationship_id UUID NOT NULL,
+  from_card_id UUID NOT NULL,
+  to_card_id UUID NOT NULL,
+  version INTEGER NOT NULL,
+  operation VARCHAR(20) NOT NULL CHECK (operation IN ('create', 'update', 'delete', 'restore')),
+  snapshot JSONB NOT NULL,
+  diff JSONB NOT NULL DEFAULT '{}',
+  actor_id UUID,
+  actor_email VARCHAR(255),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  UNIQUE (relationship_id, version)
+);
+
+-- Indexes for history and point-in-time lookups
+CREATE INDEX IF NOT EXISTS idx_card_versions_card_created ON card_versions(card_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_card_versions_actor ON card_versions(actor_id);
+CREATE INDEX IF NOT EXISTS idx_relationship_versions_rel_created ON relationship_versions(relationship_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_relationship_versions_cards ON relationship_versions(from_card_id, to_card_id);
+
+-- Version rows are append-only
+CREATE OR REPLACE FUNCTION reject_version_mutation()
+RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'Version history rows are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS card_versions_immutable ON card_versions;
+CREATE TRIGGER card_versions_immutable
+  BEFORE UPDATE OR DELETE ON card_versions
+  FOR EACH ROW EXECUTE FUNCTION reject_version_mutation();
+
+DROP TRIGGER IF EXISTS relationship_versions_immutable ON relationship_versions;
+CREATE TRIGGER relationship_versions_immutable
+  BEFORE UPDATE OR DELETE ON relationship_versions
+  FOR EACH ROW EXECUTE FUNCTION reject_version_mutation();
+
+COMMENT ON TABLE card_versions IS 'Append-only history of card creates, updates, deletes and restores';
+COMMENT ON TABLE relationship_versions IS 'Append-only history of relationship creates, updates, deletes and restores';
+COMMENT ON COLUMN card_versions.diff IS 'Field-level changes; attribute keys are recorded as attributes.<key>';
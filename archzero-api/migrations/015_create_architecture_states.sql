@@ -0,0 +1,48 @@
+-- Architecture states: baseline / target / transition (FR-STRATEGY-01)
+CREATE TABLE IF NOT EXISTS architecture_states (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  name VARCHAR(255) NOT NULL UNIQUE,
+  description TEXT,
+  kind VARCHAR(20) NOT NULL CHECK (kind IN ('baseline', 'target', 'transition')),
+  mode VARCHAR(20) NOT NULL CHECK (mode IN ('snapshot', 'planned')),
+  base_state_id UUID REFERENCES architecture_states(id) ON DELETE RESTRICT, -- planned states only; NULL = live architecture
+  effective_date DATE,
+  created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Frozen content of snapshot states
+CREATE TABLE IF NOT EXISTS architecture_state_cards (
+  state_id UUID NOT NULL REFERENCES architecture_states(id) ON DELETE CASCADE,
+  card_id UUID NOT NULL,
+  snapshot JSONB NOT NULL,
+  PRIMARY KEY (state_id, card_id)
+);
+
+CREATE TABLE IF NOT EXISTS architecture_state_relationships (
+  state_id UUID NOT NULL REFERENCES architecture_states(id) ON DELETE CASCADE,
+  relationship_id UUID NOT NULL,
+  snapshot JSONB NOT NULL,
+  PRIMARY KEY (state_id, relationship_id)
+);
+
+-- Planned changes layered over a base state
+CREATE TABLE IF NOT EXISTS architecture_state_changes (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  state_id UUID NOT NULL REFERENCES architecture_states(id) ON DELETE CASCADE,
+  sequence INTEGER NOT NULL,
+  entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('card', 'relationship')),
+  entity_id UUID NOT NULL,
+  change_type VARCHAR(20) NOT NULL CHECK (change_type IN ('add', 'update', 'remove')),
+  payload JSONB NOT NULL DEFAULT '{}',
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  UNIQUE (state_id, sequence)
+);
+
+CREATE INDEX IF NOT EXISTS idx_architecture_states_kind ON architecture_states(kind);
+CREATE INDEX IF NOT EXISTS idx_architecture_state_changes_state ON architecture_state_changes(state_id, sequence);
+
+COMMENT ON TABLE architecture_states IS 'Named baseline, target and transition architecture states';
+COMMENT ON COLUMN architecture_states.mode IS 'snapshot = frozen copy, planned = changes layered over base_state_id or the live architecture';
+COMMENT ON COLUMN architecture_state_changes.payload IS 'Full entity for add, changed fields for update, unused for remove';
@@ -0,0 +1,40 @@
+-- User lifecycle: deactivation, invitations and password resets
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_active BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMP WITH TIME ZONE NULL;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_changed_at TIMESTAMP WITH TIME ZONE NULL;
+
+CREATE INDEX IF NOT EXISTS idx_users_active ON users(is_active);
+
+-- Invitations: the user row is created when the invitation is accepted
+CREATE TABLE IF NOT EXISTS user_invitations (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  email VARCHAR(255) NOT NULL,
+  full_name VARCHAR(255),
+  role VARCHAR(50) NOT NULL CHECK (role IN ('admin', 'architect', 'editor', 'viewer', 'arbchair', 'arbmember')),
+  token_hash CHAR(64) NOT NULL UNIQUE,
+  invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+  user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  accepted_at TIMESTAMP WITH TIME ZONE,
+  revoked_at TIMESTAMP WITH TIME ZONE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- At most one open invitation per email
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_invitations_open_email
+  ON user_invitations(email) WHERE accepted_at IS NULL AND revoked_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  token_hash CHAR(64) NOT NULL UNIQUE,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  used_at TIMESTAMP WITH TIME ZONE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_user ON password_reset_tokens(user_id);
+
+COMMENT ON COLUMN users.is_active IS 'Deactivated users cannot log in; rows are kept for audit and version history';
+COMMENT ON COLUMN user_invitations.token_hash IS 'SHA-256 of the invitation token; the token itself is only sent to the invitee';
+COMMENT ON COLUMN password_reset_tokens.token_hash IS 'SHA-256 of the reset token; the token itself is only sent to the user';
@@ -0,0 +1,20 @@
+-- Server-side sessions backing rotating refresh tokens
+CREATE TABLE IF NOT EXISTS user_sessions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  refresh_token_hash CHAR(64) NOT NULL UNIQUE,
+  previous_token_hash CHAR(64),
+  user_agent TEXT,
+  ip_address VARCHAR(64),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  last_used_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_sessions_user ON user_sessions(user_id) WHERE revoked_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_user_sessions_previous_token ON user_sessions(previous_token_hash);
+
+COMMENT ON TABLE user_sessions IS 'One row per login; access tokens carry the session id and are rejected once it is revoked';
+COMMENT ON COLUMN user_sessions.refresh_token_hash IS 'SHA-256 of the current refresh token, replaced on every refresh';
+COMMENT ON COLUMN user_sessions.previous_token_hash IS 'SHA-256 of the last rotated-out token; presenting it again revokes the session';
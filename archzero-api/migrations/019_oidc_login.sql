@@ -0,0 +1,20 @@
+-- OpenID Connect single sign-on: external identities and pending logins
+ALTER TABLE users ALTER COLUMN password_hash DROP NOT NULL;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS oidc_issuer TEXT NULL;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS oidc_subject TEXT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_oidc_identity
+  ON users(oidc_issuer, oidc_subject) WHERE oidc_subject IS NOT NULL;
+
+-- One row per authorization request, consumed by the callback
+CREATE TABLE IF NOT EXISTS oidc_login_requests (
+  state_hash CHAR(64) PRIMARY KEY,
+  code_verifier TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+COMMENT ON COLUMN users.password_hash IS 'NULL for users provisioned by single sign-on';
+COMMENT ON COLUMN users.oidc_subject IS 'Subject (sub) claim of the linked IdP identity';
+COMMENT ON COLUMN oidc_login_requests.state_hash IS 'SHA-256 of the state parameter; the PKCE verifier never leaves the server';
@@ -0,0 +1,23 @@
+-- Personal access tokens and service accounts for automation
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_service_account BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  name VARCHAR(255) NOT NULL,
+  token_prefix VARCHAR(16) NOT NULL,
+  token_hash CHAR(64) NOT NULL UNIQUE,
+  scopes TEXT[] NOT NULL DEFAULT '{}',
+  created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  last_used_at TIMESTAMP WITH TIME ZONE NULL,
+  last_used_ip VARCHAR(64) NULL,
+  revoked_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
+
+COMMENT ON COLUMN users.is_service_account IS 'Non-human account that only authenticates with API tokens';
+COMMENT ON COLUMN api_tokens.token_hash IS 'SHA-256 of the token; the token itself is shown once at creation';
+COMMENT ON COLUMN api_tokens.scopes IS 'resource:action permissions, further limited by the owner''s role';
@@ -0,0 +1,10 @@
+-- SCIM 2.0 provisioning: users and groups (teams) managed by an identity platform
+ALTER TABLE users ADD COLUMN IF NOT EXISTS scim_managed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS scim_external_id TEXT NULL;
+ALTER TABLE teams ADD COLUMN IF NOT EXISTS scim_external_id TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_users_scim_external_id ON users(scim_external_id) WHERE scim_external_id IS NOT NULL;
+
+COMMENT ON COLUMN users.scim_managed IS 'Provisioned over SCIM; its role follows its groups through scim.role_mappings';
+COMMENT ON COLUMN users.scim_external_id IS 'externalId assigned by the provisioning client';
+COMMENT ON COLUMN teams.scim_external_id IS 'externalId assigned by the provisioning client';
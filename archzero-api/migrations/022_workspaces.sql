@@ -0,0 +1,61 @@
+-- Multi-tenant workspaces: every card, relationship, ARB entity, export and notification belongs to one
+CREATE TABLE IF NOT EXISTS workspaces (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  name VARCHAR(255) NOT NULL UNIQUE,
+  description TEXT,
+  created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Existing data moves into the default workspace, which every user can open with their global role
+INSERT INTO workspaces (id, name, description)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Default', 'Default workspace')
+ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS workspace_members (
+  workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  role VARCHAR(50) NOT NULL,
+  added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (workspace_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_members_user ON workspace_members(user_id);
+
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE relationships ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE arb_templates ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE arb_audit_logs ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE arb_notifications ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE exports ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE scheduled_exports ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+
+CREATE INDEX IF NOT EXISTS idx_cards_workspace_id ON cards(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_relationships_workspace_id ON relationships(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_arb_templates_workspace_id ON arb_templates(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_arb_audit_logs_workspace_id ON arb_audit_logs(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_arb_notifications_workspace_id ON arb_notifications(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_exports_workspace_id ON exports(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_scheduled_exports_workspace_id ON scheduled_exports(workspace_id);
+
+-- Cards made visible, read-only, in other workspaces
+CREATE TABLE IF NOT EXISTS card_shares (
+  card_id UUID NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+  workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+  shared_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (card_id, workspace_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_shares_workspace ON card_shares(workspace_id);
+
+COMMENT ON TABLE workspace_members IS 'Per-workspace roles; a membership in the default workspace overrides the global role there';
+COMMENT ON TABLE card_shares IS 'Read-only visibility of a card in a workspace other than its own';
@@ -0,0 +1,22 @@
+-- Metamodel registry: validation rules for the JSONB attributes of each card type
+CREATE TABLE IF NOT EXISTS metamodel_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    card_type VARCHAR(50) NOT NULL,
+    attribute_key VARCHAR(100) NOT NULL,
+    is_required BOOLEAN NOT NULL DEFAULT FALSE,
+    regex_pattern TEXT NULL,
+    allowed_values TEXT[] NULL,
+    description TEXT NULL,
+    example_value TEXT NULL,
+    created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (card_type, attribute_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_metamodel_rules_card_type ON metamodel_rules(card_type);
+
+COMMENT ON TABLE metamodel_rules IS 'Per card type attribute rules enforced on card create, update and import';
+COMMENT ON COLUMN metamodel_rules.regex_pattern IS 'Value must match this pattern (Rust regex syntax)';
+COMMENT ON COLUMN metamodel_rules.allowed_values IS 'Value must be one of these; NULL allows any value';
+COMMENT ON COLUMN metamodel_rules.example_value IS 'Shown in validation errors as a valid example';
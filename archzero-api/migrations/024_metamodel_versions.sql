@@ -0,0 +1,34 @@
+-- Versioned metamodel changes, applied to existing cards by background migrations
+CREATE TABLE IF NOT EXISTS metamodel_migrations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    version VARCHAR(20) NOT NULL UNIQUE,
+    major INT NOT NULL,
+    minor INT NOT NULL,
+    description TEXT NULL,
+    operation JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    total_cards INT NOT NULL DEFAULT 0,
+    processed_cards INT NOT NULL DEFAULT 0,
+    changed_cards INT NOT NULL DEFAULT 0,
+    error TEXT NULL,
+    created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ NULL,
+    completed_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metamodel_migrations_version ON metamodel_migrations(major DESC, minor DESC);
+
+-- Card types no longer accepted for new cards
+CREATE TABLE IF NOT EXISTS metamodel_retired_card_types (
+    card_type VARCHAR(50) PRIMARY KEY,
+    replacement_type VARCHAR(50) NULL,
+    retired_in_version VARCHAR(20) NOT NULL,
+    retired_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS metamodel_version VARCHAR(20) NOT NULL DEFAULT '1.0';
+
+COMMENT ON TABLE metamodel_migrations IS 'Metamodel versions; each applies one operation to existing cards';
+COMMENT ON COLUMN metamodel_migrations.operation IS 'rename_attribute, backfill_attribute or retire_card_type with its parameters';
+COMMENT ON COLUMN cards.metamodel_version IS 'Metamodel version the card was created in or last migrated to';
@@ -0,0 +1,40 @@
+-- Card type registry: built-in types plus types admins register at runtime
+CREATE TABLE IF NOT EXISTS card_types (
+    name VARCHAR(50) PRIMARY KEY,
+    label VARCHAR(100) NOT NULL,
+    layer VARCHAR(20) NOT NULL CHECK (layer IN ('strategy', 'application', 'technology', 'governance', 'arb')),
+    description TEXT NULL,
+    attribute_schema JSONB NOT NULL DEFAULT '[]',
+    allowed_relationship_types TEXT[] NOT NULL DEFAULT '{}',
+    color VARCHAR(7) NOT NULL CHECK (color ~ '^#[0-9a-fA-F]{6}$'),
+    built_in BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_types_layer ON card_types(layer);
+
+INSERT INTO card_types (name, label, layer, color, built_in) VALUES
+    ('BusinessCapability', 'Business Capability', 'strategy', '#8b5cf6', TRUE),
+    ('Objective', 'Objective', 'strategy', '#a78bfa', TRUE),
+    ('Application', 'Application', 'application', '#3b82f6', TRUE),
+    ('Interface', 'Interface', 'application', '#06b6d4', TRUE),
+    ('ITComponent', 'IT Component', 'technology', '#10b981', TRUE),
+    ('Platform', 'Platform', 'technology', '#14b8a6', TRUE),
+    ('ArchitecturePrinciple', 'Architecture Principle', 'governance', '#6366f1', TRUE),
+    ('TechnologyStandard', 'Technology Standard', 'governance', '#8b5cf6', TRUE),
+    ('ArchitecturePolicy', 'Architecture Policy', 'governance', '#f43f5e', TRUE),
+    ('Exception', 'Exception', 'governance', '#f59e0b', TRUE),
+    ('Initiative', 'Initiative', 'governance', '#ec4899', TRUE),
+    ('Risk', 'Risk', 'governance', '#ef4444', TRUE),
+    ('ComplianceRequirement', 'Compliance Requirement', 'governance', '#f97316', TRUE),
+    ('ComplianceAudit', 'Compliance Audit', 'governance', '#64748b', TRUE),
+    ('ARBMeeting', 'ARB Meeting', 'arb', '#64748b', TRUE),
+    ('ARBSubmission', 'ARB Submission', 'arb', '#64748b', TRUE)
+ON CONFLICT (name) DO NOTHING;
+
+COMMENT ON TABLE card_types IS 'Card types cards may use; built-in types cannot be deleted';
+COMMENT ON COLUMN card_types.attribute_schema IS 'Attribute definitions (key, data type, required, allowed values, range) checked on card writes';
+COMMENT ON COLUMN card_types.allowed_relationship_types IS 'Relationship types cards of this type may take part in; empty allows all';
+COMMENT ON COLUMN card_types.color IS 'Display colour of the type in graphs and landscapes';
@@ -0,0 +1,40 @@
+-- Relationship matrix: which card types a relationship type may connect, in which direction
+-- and with what cardinality (docs/02-relationship-spec.md §2). Relationship types without
+-- rules connect any cards; once a type has rules, every new relationship must match one.
+CREATE TABLE IF NOT EXISTS relationship_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    relationship_type VARCHAR(50) NOT NULL,
+    source_type VARCHAR(50) NOT NULL,
+    target_type VARCHAR(50) NOT NULL,
+    cardinality VARCHAR(20) NOT NULL DEFAULT 'many_to_many'
+        CHECK (cardinality IN ('one_to_one', 'one_to_many', 'many_to_one', 'many_to_many')),
+    bidirectional BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT NULL,
+    created_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (relationship_type, source_type, target_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_relationship_rules_type ON relationship_rules(relationship_type);
+
+-- Governance layer of the relationship spec; '*' is any card type
+INSERT INTO relationship_rules (relationship_type, source_type, target_type, cardinality, description) VALUES
+    ('guides', 'ArchitecturePrinciple', 'Application', 'many_to_many', 'Principle influences application decisions'),
+    ('appliesTo', 'ArchitecturePrinciple', 'BusinessCapability', 'many_to_many', 'Principle applies to capability scope'),
+    ('appliesTo', 'ArchitecturePolicy', '*', 'many_to_many', 'Policy governs card'),
+    ('appliesTo', 'Exception', '*', 'many_to_one', 'Exception for a specific card'),
+    ('standardizes', 'TechnologyStandard', 'ITComponent', 'many_to_many', 'Component conforms to technology standard'),
+    ('enforces', 'ArchitecturePolicy', 'ArchitecturePrinciple', 'many_to_many', 'Policy operationalizes principle'),
+    ('exemptsFrom', 'Exception', 'ArchitecturePolicy', 'many_to_one', 'Approved deviation from one policy'),
+    ('impacts', 'Initiative', '*', 'many_to_many', 'Initiative affects card state'),
+    ('achieves', 'Initiative', 'Objective', 'many_to_many', 'Initiative contributes to objective'),
+    ('threatens', 'Risk', '*', 'many_to_many', 'Risk threatens card'),
+    ('mitigatedBy', 'Risk', 'Initiative', 'many_to_many', 'Initiative mitigates risk'),
+    ('requiresComplianceFrom', 'ComplianceRequirement', 'Application', 'many_to_many', 'Application must comply with requirement')
+ON CONFLICT (relationship_type, source_type, target_type) DO NOTHING;
+
+COMMENT ON TABLE relationship_rules IS 'Allowed source and target card types per relationship type, enforced on relationship create and import';
+COMMENT ON COLUMN relationship_rules.source_type IS 'Card type name, or * for any card type';
+COMMENT ON COLUMN relationship_rules.cardinality IS 'one_to_many: a target has at most one source; many_to_one: a source has at most one target';
+COMMENT ON COLUMN relationship_rules.bidirectional IS 'The rule also allows relationships from the target type to the source type';
@@ -0,0 +1,66 @@
+-- Ranked card search: a weighted tsvector over name, tags, description and selected
+-- attributes, kept up to date by trigger. Fuzzy matching uses the pg_trgm indexes of 001.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Attribute keys whose values are searchable
+CREATE TABLE IF NOT EXISTS card_search_attributes (
+    attribute_key VARCHAR(100) PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO card_search_attributes (attribute_key) VALUES
+    ('vendor'),
+    ('hosting_type'),
+    ('business_owner'),
+    ('technology_stack'),
+    ('framework'),
+    ('category')
+ON CONFLICT (attribute_key) DO NOTHING;
+
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS search_vector tsvector;
+
+-- Name weighs most, then tags, description and attributes
+CREATE OR REPLACE FUNCTION card_search_vector(
+    p_name TEXT, p_description TEXT, p_tags TEXT[], p_attributes JSONB
+) RETURNS tsvector AS $$
+    SELECT setweight(to_tsvector('english', COALESCE(p_name, '')), 'A')
+        || setweight(to_tsvector('english', COALESCE(array_to_string(p_tags, ' '), '')), 'B')
+        || setweight(to_tsvector('english', COALESCE(p_description, '')), 'C')
+        || setweight(to_tsvector('english', COALESCE((
+            SELECT string_agg(p_attributes ->> attribute_key, ' ')
+            FROM card_search_attributes
+            WHERE jsonb_typeof(p_attributes -> attribute_key) IN ('string', 'number')
+        ), '')), 'D')
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION cards_search_vector_trigger() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector := card_search_vector(NEW.name, NEW.description, NEW.tags, NEW.attributes);
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cards_search_vector ON cards;
+CREATE TRIGGER cards_search_vector
+    BEFORE INSERT OR UPDATE OF name, description, tags, attributes ON cards
+    FOR EACH ROW EXECUTE FUNCTION cards_search_vector_trigger();
+
+-- Changing the searchable attributes reindexes every card
+CREATE OR REPLACE FUNCTION card_search_attributes_trigger() RETURNS trigger AS $$
+BEGIN
+    UPDATE cards SET search_vector = card_search_vector(name, description, tags, attributes);
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS card_search_attributes_changed ON card_search_attributes;
+CREATE TRIGGER card_search_attributes_changed
+    AFTER INSERT OR UPDATE OR DELETE ON card_search_attributes
+    FOR EACH STATEMENT EXECUTE FUNCTION card_search_attributes_trigger();
+
+UPDATE cards SET search_vector = card_search_vector(name, description, tags, attributes);
+
+CREATE INDEX IF NOT EXISTS idx_cards_search_vector ON cards USING gin (search_vector);
+
+COMMENT ON TABLE card_search_attributes IS 'Card attributes included in full-text search';
+COMMENT ON COLUMN cards.search_vector IS 'Weighted full-text vector: name A, tags B, description C, searchable attributes D';
@@ -0,0 +1,29 @@
+-- Saved searches: named card filters, sort and columns, personal or shared with a team
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    team_id UUID NULL REFERENCES teams(id) ON DELETE SET NULL,
+    workspace_id UUID NOT NULL
+        DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id) ON DELETE CASCADE,
+    query JSONB NOT NULL,
+    columns TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_owner ON saved_searches(workspace_id, owner_id);
+CREATE INDEX IF NOT EXISTS idx_saved_searches_team ON saved_searches(team_id) WHERE team_id IS NOT NULL;
+
+-- Pins are per user, so each team member pins shared searches for themselves
+CREATE TABLE IF NOT EXISTS saved_search_pins (
+    saved_search_id UUID NOT NULL REFERENCES saved_searches(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    pinned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (saved_search_id, user_id)
+);
+
+COMMENT ON COLUMN saved_searches.team_id IS 'Shared with the members of this team; NULL keeps the search personal';
+COMMENT ON COLUMN saved_searches.query IS 'Card filters, boolean query AST and sort, run through the /api/v1/cards path';
+COMMENT ON COLUMN saved_searches.columns IS 'Card fields shown for the results, in order';
@@ -0,0 +1,18 @@
+-- Computed card quality: the factors behind cards.quality_score and the issues that cost points
+CREATE TABLE IF NOT EXISTS card_quality (
+    card_id UUID PRIMARY KEY REFERENCES cards(id) ON DELETE CASCADE,
+    score INT NOT NULL DEFAULT 0 CHECK (score BETWEEN 0 AND 100),
+    completeness INT NOT NULL DEFAULT 0,
+    freshness INT NOT NULL DEFAULT 0,
+    ownership INT NOT NULL DEFAULT 0,
+    relationships INT NOT NULL DEFAULT 0,
+    compliance INT NOT NULL DEFAULT 0,
+    issues JSONB NOT NULL DEFAULT '[]',
+    last_reviewed_at TIMESTAMPTZ NULL,
+    calculated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_quality_score ON card_quality(score);
+
+COMMENT ON TABLE card_quality IS 'Recalculated when a card, its relationships, its metamodel rules or the policies change, and nightly';
+COMMENT ON COLUMN card_quality.last_reviewed_at IS 'Last time someone confirmed the card is current; counts like an update for freshness';
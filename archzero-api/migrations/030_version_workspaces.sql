@@ -0,0 +1,13 @@
+-- Version history belongs to the workspace of its card or relationship, so it is only readable there
+ALTER TABLE card_versions ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE relationship_versions ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+
+UPDATE card_versions v SET workspace_id = c.workspace_id
+FROM cards c WHERE c.id = v.card_id AND v.workspace_id <> c.workspace_id;
+UPDATE relationship_versions v SET workspace_id = r.workspace_id
+FROM relationships r WHERE r.id = v.relationship_id AND v.workspace_id <> r.workspace_id;
+
+CREATE INDEX IF NOT EXISTS idx_card_versions_workspace_id ON card_versions(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_relationship_versions_workspace_id ON relationship_versions(workspace_id);
@@ -0,0 +1,17 @@
+-- Architecture states and their frozen content belong to the workspace they were captured in
+ALTER TABLE architecture_states ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE architecture_state_cards ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE architecture_state_relationships ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+ALTER TABLE architecture_state_changes ADD COLUMN IF NOT EXISTS workspace_id UUID NOT NULL
+  DEFAULT '00000000-0000-0000-0000-000000000001' REFERENCES workspaces(id);
+
+-- State names are unique within a workspace
+ALTER TABLE architecture_states DROP CONSTRAINT IF EXISTS architecture_states_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_architecture_states_workspace_name ON architecture_states(workspace_id, name);
+
+CREATE INDEX IF NOT EXISTS idx_architecture_state_cards_workspace_id ON architecture_state_cards(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_architecture_state_relationships_workspace_id ON architecture_state_relationships(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_architecture_state_changes_workspace_id ON architecture_state_changes(workspace_id);
@@ -1,5 +1,8 @@
 use serde::Deserialize;
-use config::{Config, ConfigError, Environment, File};
+use config::{Config, ConfigError, File};
+
+use crate::models::card::CardType;
+use crate::models::user::UserRole;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Database {
@@ -18,13 +21,409 @@ pub struct Cache {
 #[derive(Debug, Deserialize, Clone)]
 pub struct Jwt {
     pub secret: String,
-    pub expiration_hours: i64,
+    /// Access token lifetime; clients renew with their refresh token
+    #[serde(default = "default_access_token_minutes")]
+    pub access_token_minutes: i64,
+    /// Lifetime of a login session (refresh token)
+    #[serde(default = "default_refresh_token_days")]
+    pub refresh_token_days: i64,
+}
+
+fn default_access_token_minutes() -> i64 {
+    15
+}
+
+fn default_refresh_token_days() -> i64 {
+    30
+}
+
+/// Deployment environment; test-only routes are never mounted in production
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Development,
+    Test,
+    Production,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Server {
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub environment: Environment,
+    /// Mount the unauthenticated /api/v1/test reset endpoints used by the E2E suite;
+    /// ignored in production
+    #[serde(default)]
+    pub enable_test_routes: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct GraphSync {
+    /// Minutes between background reconciliation runs (0 disables the job)
+    #[serde(default)]
+    pub interval_minutes: u64,
+    /// Repair drift automatically instead of only logging it
+    #[serde(default)]
+    pub auto_repair: bool,
+}
+
+/// Computed card quality scores, recalculated on every change and nightly
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct DataQuality {
+    /// Hour of day (UTC) of the nightly recalculation; unset disables it
+    pub nightly_hour_utc: Option<u32>,
+    /// Cards neither updated nor reviewed for longer than this start losing freshness
+    pub stale_after_days: i64,
+    /// Relationships cards of a type should have, e.g. Applications `reliesOn`
+    pub expected_relationships: Vec<ExpectedRelationship>,
+}
+
+impl Default for DataQuality {
+    fn default() -> Self {
+        Self {
+            nightly_hour_utc: Some(2),
+            stale_after_days: 180,
+            expected_relationships: vec![ExpectedRelationship {
+                card_type: "Application".to_string(),
+                relationship_type: "reliesOn".to_string(),
+            }],
+        }
+    }
+}
+
+/// An outgoing relationship type expected on every card of `card_type`
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ExpectedRelationship {
+    pub card_type: String,
+    pub relationship_type: String,
+}
+
+/// Which graph store backs topology, impact and graph queries
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphBackendKind {
+    /// External Neo4j database; startup fails if it cannot be reached unless
+    /// `Graph::fallback_to_embedded` is set
+    #[default]
+    Neo4j,
+    /// In-process traversals over PostgreSQL relationships, no Neo4j required
+    Embedded,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Graph {
+    #[serde(default)]
+    pub backend: GraphBackendKind,
+    /// Use the embedded backend when the configured Neo4j cannot be reached at startup
+    #[serde(default)]
+    pub fallback_to_embedded: bool,
+}
+
+/// Rules applied whenever a password is set (admin create, invitation accept, reset, change)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+        }
+    }
+}
+
+/// Invitation and password reset tokens
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Accounts {
+    /// Frontend URL that links in invitation and reset messages point to
+    pub link_base_url: String,
+    pub invitation_expiry_hours: i64,
+    pub reset_expiry_minutes: i64,
+    /// Turn off to require single sign-on; also disables password reset, change and invitations
+    pub password_login_enabled: bool,
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Self {
+            link_base_url: "http://localhost:5173".to_string(),
+            invitation_expiry_hours: 72,
+            reset_expiry_minutes: 60,
+            password_login_enabled: true,
+        }
+    }
+}
+
+/// Personal access tokens and service-account tokens
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ApiTokens {
+    /// Lifetime when the request does not specify one
+    pub default_expiry_days: i64,
+    /// Longest lifetime a token may be created with
+    pub max_expiry_days: i64,
+}
+
+impl Default for ApiTokens {
+    fn default() -> Self {
+        Self {
+            default_expiry_days: 90,
+            max_expiry_days: 365,
+        }
+    }
+}
+
+/// OpenID Connect single sign-on (authorization code flow with PKCE)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Oidc {
+    pub enabled: bool,
+    /// Issuer URL; `/.well-known/openid-configuration` is read from here
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Omit for public clients
+    pub client_secret: Option<String>,
+    /// This API's callback, registered with the IdP
+    pub redirect_url: String,
+    pub scopes: Vec<String>,
+    /// ID token claim holding the user's groups
+    pub groups_claim: String,
+    /// Checked in order; the first mapping whose group the user has sets their role on every login
+    pub role_mappings: Vec<GroupRoleMapping>,
+    /// Role for users no mapping matches; with mappings configured it also replaces the role
+    /// of existing users whose groups no longer match one
+    pub default_role: UserRole,
+    /// Frontend page that receives the tokens (or an error) in the URL fragment
+    pub post_login_redirect: String,
+    pub login_timeout_minutes: i64,
+}
+
+/// Maps an identity-provider group to a role (single sign-on and SCIM)
+#[derive(Debug, Deserialize, Clone)]
+pub struct GroupRoleMapping {
+    pub group: String,
+    pub role: UserRole,
+}
+
+/// Role of the first mapping whose group is among `groups`
+pub fn role_for_groups(mappings: &[GroupRoleMapping], groups: &[String]) -> Option<UserRole> {
+    mappings
+        .iter()
+        .find(|mapping| groups.iter().any(|group| group == &mapping.group))
+        .map(|mapping| mapping.role.clone())
+}
+
+impl Default for Oidc {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer_url: String::new(),
+            client_id: String::new(),
+            client_secret: None,
+            redirect_url: "http://localhost:3000/api/v1/auth/oidc/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string(), "profile".to_string()],
+            groups_claim: "groups".to_string(),
+            role_mappings: Vec::new(),
+            default_role: UserRole::Viewer,
+            post_login_redirect: "http://localhost:5173/auth/callback".to_string(),
+            login_timeout_minutes: 10,
+        }
+    }
+}
+
+/// SCIM 2.0 provisioning of users and groups (teams) by an identity platform
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Scim {
+    pub enabled: bool,
+    /// Checked in order against the names of a provisioned user's groups; the first match
+    /// sets their role whenever their memberships change
+    pub role_mappings: Vec<GroupRoleMapping>,
+    /// Role for provisioned users no mapping matches
+    pub default_role: UserRole,
+}
+
+impl Default for Scim {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            role_mappings: Vec::new(),
+            default_role: UserRole::Viewer,
+        }
+    }
+}
+
+/// Card-level access rules enforced by `CardService`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CardAccess {
+    /// Attribute keys removed from cards read by `redacted_roles`
+    pub restricted_attributes: Vec<String>,
+    pub redacted_roles: Vec<UserRole>,
+    /// Card types only `governance_roles` may create, edit or delete
+    pub governance_card_types: Vec<CardType>,
+    pub governance_roles: Vec<UserRole>,
+}
+
+impl Default for CardAccess {
+    fn default() -> Self {
+        Self {
+            restricted_attributes: [
+                "cost",
+                "annual_cost",
+                "license_cost",
+                "budget_total",
+                "budget_spent",
+                "contract_value",
+                "contract_end_date",
+                "contract_reference",
+            ]
+            .iter()
+            .map(|key| key.to_string())
+            .collect(),
+            redacted_roles: vec![UserRole::Viewer],
+            governance_card_types: vec![
+                CardType::ArchitecturePrinciple,
+                CardType::TechnologyStandard,
+                CardType::ArchitecturePolicy,
+                CardType::ComplianceRequirement,
+            ],
+            governance_roles: vec![UserRole::Admin, UserRole::Architect, UserRole::ArbChair],
+        }
+    }
+}
+
+/// Sliding-window rate limiting with counters in Redis, so limits hold across replicas
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimit {
+    /// Unset means on in production and off elsewhere
+    pub enabled: Option<bool>,
+    /// Redis holding the counters; defaults to `cache.redis_url`
+    pub redis_url: Option<String>,
+    pub key_prefix: String,
+    /// Let requests through while Redis is unreachable instead of rejecting them;
+    /// groups may override it (the `auth` group fails closed)
+    pub fail_open: bool,
+    /// Limit for paths no group matches
+    pub default_max_requests: u32,
+    pub default_window_seconds: u32,
+    /// Checked in order; the first group with a matching path prefix applies
+    pub groups: Vec<RouteGroupLimit>,
+    /// Per-client limits that replace the group limit
+    pub clients: Vec<ClientLimit>,
+    /// Clients (`user:<id>`, `token:<prefix>`, `ip:<address>`) and path prefixes that are never limited
+    pub allow_list: Vec<String>,
+    /// Reverse proxies (addresses or CIDR ranges) whose `X-Forwarded-For` is believed. The client
+    /// is the right-most forwarded address that is not a trusted proxy; with none configured it is
+    /// always the connecting peer.
+    pub trusted_proxies: Vec<String>,
+}
+
+impl RateLimit {
+    pub fn is_enabled(&self, environment: Environment) -> bool {
+        self.enabled.unwrap_or(environment == Environment::Production)
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            enabled: None,
+            redis_url: None,
+            key_prefix: "ratelimit".to_string(),
+            fail_open: true,
+            default_max_requests: 300,
+            default_window_seconds: 60,
+            groups: vec![
+                // Login attempts stay limited while Redis is down
+                RouteGroupLimit { fail_open: Some(false), ..RouteGroupLimit::new("auth", &["/api/v1/auth/"], 20, 60) },
+                RouteGroupLimit::new("bulk", &["/api/v1/import", "/api/v1/bulk", "/api/v1/cards/bulk", "/api/v1/export"], 30, 60),
+                RouteGroupLimit::new("scim", &["/scim/v2"], 600, 60),
+            ],
+            clients: Vec::new(),
+            allow_list: vec!["/api/v1/health".to_string()],
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// Limit shared by every path starting with one of `path_prefixes`
+#[derive(Debug, Deserialize, Clone)]
+pub struct RouteGroupLimit {
+    pub name: String,
+    pub path_prefixes: Vec<String>,
+    pub max_requests: u32,
+    pub window_seconds: u32,
+    /// Replaces `fail_open` for this group
+    #[serde(default)]
+    pub fail_open: Option<bool>,
+}
+
+impl RouteGroupLimit {
+    fn new(name: &str, path_prefixes: &[&str], max_requests: u32, window_seconds: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            path_prefixes: path_prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            max_requests,
+            window_seconds,
+            fail_open: None,
+        }
+    }
+}
+
+/// Limit for one user, API token or IP address, in one route group or all of them
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClientLimit {
+    /// `user:<id>`, `token:<prefix>` (as listed for the token) or `ip:<address>`
+    pub client: String,
+    /// Route group name; unset applies to every group
+    pub group: Option<String>,
+    pub max_requests: u32,
+    pub window_seconds: u32,
+}
+
+/// Double-submit cookie CSRF protection for cookie-authenticated browser requests;
+/// requests carrying a Bearer token, or none of the session cookies, are never checked
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Csrf {
+    pub enabled: bool,
+    /// Key signing the tokens; defaults to `jwt.secret`
+    pub secret: Option<String>,
+    pub token_ttl_seconds: u64,
+    /// Cookie the token is issued in; browsers echo it in the `X-CSRF-Token` header
+    pub cookie_name: String,
+    /// Cookies that authenticate a request (for example a session cookie set by a fronting
+    /// proxy). Only requests carrying one of them are checked; the API itself authenticates
+    /// with Bearer tokens, so none by default.
+    pub session_cookies: Vec<String>,
+}
+
+impl Default for Csrf {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            secret: None,
+            token_ttl_seconds: 3600,
+            cookie_name: "csrf_token".to_string(),
+            session_cookies: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -33,6 +432,28 @@ pub struct Settings {
     pub cache: Cache,
     pub jwt: Jwt,
     pub server: Server,
+    #[serde(default)]
+    pub graph: Graph,
+    #[serde(default)]
+    pub graph_sync: GraphSync,
+    #[serde(default)]
+    pub data_quality: DataQuality,
+    #[serde(default)]
+    pub accounts: Accounts,
+    #[serde(default)]
+    pub api_tokens: ApiTokens,
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    #[serde(default)]
+    pub card_access: CardAccess,
+    #[serde(default)]
+    pub oidc: Oidc,
+    #[serde(default)]
+    pub scim: Scim,
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    #[serde(default)]
+    pub csrf: Csrf,
 }
 
 impl Settings {
@@ -40,7 +461,7 @@ impl Settings {
         let s = Config::builder()
             .add_source(File::with_name("config/default"))
             .add_source(File::with_name("config/local").required(false))
-            .add_source(Environment::with_prefix("ARCHZERO").separator("__"))
+            .add_source(config::Environment::with_prefix("ARCHZERO").separator("__"))
             .build()?;
 
         s.try_deserialize()
@@ -1,10 +1,12 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
 
+use crate::models::metamodel::AttributeViolation;
+
 pub type Result<T> = std::result::Result<T, AppError>;
 
 #[derive(Debug, thiserror::Error)]
@@ -24,9 +26,16 @@ pub enum AppError {
     #[error("JWT error: {0}")]
     Jwt(#[from] jsonwebtoken::errors::Error),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Card attributes breaking metamodel rules; rendered as a structured VALIDATION_ERROR
+    #[error("Validation error: {}", describe_violations(.0))]
+    AttributeValidation(Vec<AttributeViolation>),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
@@ -37,8 +46,20 @@ pub enum AppError {
     Internal(#[from] anyhow::Error),
 }
 
+fn describe_violations(violations: &[AttributeViolation]) -> String {
+    violations
+        .iter()
+        .map(|violation| format!("{}: {}", violation.field, violation.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::RateLimitExceeded { retry_after } => *retry_after,
+            _ => None,
+        };
         let (status, error_message) = match self {
             AppError::Database(e) => {
                 // Check for unique constraint violation
@@ -68,7 +89,22 @@ impl IntoResponse for AppError {
                 tracing::error!("JWT error: {:?}", e);
                 (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
             }
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::AttributeValidation(violations) => {
+                // The first violation in the spec's shape; all of them under `violations`
+                let first = violations.first();
+                let body = json!({
+                    "error": {
+                        "code": "VALIDATION_ERROR",
+                        "message": first.map_or("Invalid attributes", |v| v.message.as_str()),
+                        "field": first.map(|v| v.field.as_str()),
+                        "details": first.map(|v| &v.details),
+                        "violations": &violations,
+                    }
+                });
+                return (StatusCode::BAD_REQUEST, Json(body)).into_response();
+            }
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::RateLimitExceeded { .. } => {
                 (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string())
@@ -83,6 +119,10 @@ impl IntoResponse for AppError {
             "error": error_message,
         });
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let Some(seconds) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
     }
 }
@@ -0,0 +1,227 @@
+/*!
+ * API Token Handlers
+ *
+ * Personal access tokens for the current user and admin-managed service accounts
+ * with their tokens. Tokens are shown once, at creation.
+ */
+
+use axum::{extract::{Extension, Path, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::api_token::*,
+    models::user::Claims,
+    state::AppState,
+};
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Auth("Invalid token subject".to_string()))
+}
+
+/// Tokens can only be issued from an interactive login, so a leaked token cannot mint others
+fn require_interactive(claims: &Claims) -> Result<(), AppError> {
+    if claims.scopes.is_some() {
+        return Err(AppError::Forbidden("API tokens cannot be used to manage API tokens".to_string()));
+    }
+    Ok(())
+}
+
+/// List the current user's API tokens
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/tokens",
+    responses(
+        (status = 200, description = "Personal API tokens", body = Vec<ApiToken>),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn list_my_api_tokens(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<ApiToken>>, AppError> {
+    let tokens = state.api_token_service.list(caller_id(&claims)?).await?;
+    Ok(Json(tokens))
+}
+
+/// Create a personal API token
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/tokens",
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 200, description = "Token created; the token is only returned now", body = CreatedApiToken),
+        (status = 400, description = "Invalid scopes or lifetime"),
+        (status = 401, description = "Not authenticated"),
+        (status = 403, description = "Called with an API token")
+    ),
+    tag = "Auth"
+)]
+pub async fn create_my_api_token(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> Result<Json<CreatedApiToken>, AppError> {
+    require_interactive(&claims)?;
+    let user_id = caller_id(&claims)?;
+    let created = state.api_token_service.create(user_id, user_id, req).await?;
+    Ok(Json(created))
+}
+
+/// Revoke one of the current user's API tokens
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/tokens/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Token ID")
+    ),
+    responses(
+        (status = 200, description = "Token revoked"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Token not found")
+    ),
+    tag = "Auth"
+)]
+pub async fn revoke_my_api_token(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<()>, AppError> {
+    state.api_token_service.revoke(caller_id(&claims)?, id).await?;
+    Ok(Json(()))
+}
+
+/// List service accounts
+#[utoipa::path(
+    get,
+    path = "/api/v1/service-accounts",
+    responses(
+        (status = 200, description = "Service accounts", body = Vec<ServiceAccount>),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Users"
+)]
+pub async fn list_service_accounts(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ServiceAccount>>, AppError> {
+    let accounts = state.api_token_service.list_service_accounts().await?;
+    Ok(Json(accounts))
+}
+
+/// Create a service account
+#[utoipa::path(
+    post,
+    path = "/api/v1/service-accounts",
+    request_body = CreateServiceAccountRequest,
+    responses(
+        (status = 200, description = "Service account created", body = ServiceAccount),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "A service account with this name already exists")
+    ),
+    tag = "Users"
+)]
+pub async fn create_service_account(
+    State(state): State<AppState>,
+    Json(req): Json<CreateServiceAccountRequest>,
+) -> Result<Json<ServiceAccount>, AppError> {
+    let account = state.api_token_service.create_service_account(req).await?;
+    Ok(Json(account))
+}
+
+/// Deactivate a service account and revoke its tokens
+#[utoipa::path(
+    delete,
+    path = "/api/v1/service-accounts/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Service account ID")
+    ),
+    responses(
+        (status = 200, description = "Service account deactivated"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Service account not found")
+    ),
+    tag = "Users"
+)]
+pub async fn deactivate_service_account(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    state.api_token_service.deactivate_service_account(id).await?;
+    Ok(Json(()))
+}
+
+/// List a service account's tokens
+#[utoipa::path(
+    get,
+    path = "/api/v1/service-accounts/{id}/tokens",
+    params(
+        ("id" = Uuid, Path, description = "Service account ID")
+    ),
+    responses(
+        (status = 200, description = "Tokens", body = Vec<ApiToken>),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Service account not found")
+    ),
+    tag = "Users"
+)]
+pub async fn list_service_account_tokens(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApiToken>>, AppError> {
+    state.api_token_service.get_service_account(id).await?;
+    let tokens = state.api_token_service.list(id).await?;
+    Ok(Json(tokens))
+}
+
+/// Issue a token for a service account
+#[utoipa::path(
+    post,
+    path = "/api/v1/service-accounts/{id}/tokens",
+    params(
+        ("id" = Uuid, Path, description = "Service account ID")
+    ),
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 200, description = "Token created; the token is only returned now", body = CreatedApiToken),
+        (status = 400, description = "Invalid scopes or lifetime"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Service account not found")
+    ),
+    tag = "Users"
+)]
+pub async fn create_service_account_token(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> Result<Json<CreatedApiToken>, AppError> {
+    require_interactive(&claims)?;
+    state.api_token_service.get_service_account(id).await?;
+    let created = state.api_token_service.create(id, caller_id(&claims)?, req).await?;
+    Ok(Json(created))
+}
+
+/// Revoke a service account token
+#[utoipa::path(
+    delete,
+    path = "/api/v1/service-accounts/{id}/tokens/{token_id}",
+    params(
+        ("id" = Uuid, Path, description = "Service account ID"),
+        ("token_id" = Uuid, Path, description = "Token ID")
+    ),
+    responses(
+        (status = 200, description = "Token revoked"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Token not found")
+    ),
+    tag = "Users"
+)]
+pub async fn revoke_service_account_token(
+    Path((id, token_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    state.api_token_service.revoke(id, token_id).await?;
+    Ok(Json(()))
+}
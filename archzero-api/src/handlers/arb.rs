@@ -11,7 +11,7 @@ use crate::{
     models::{
         arb::*,
         arb_template::*,
-        card::{CardType, LifecyclePhase, CreateCardRequest, UpdateCardRequest},
+        card::{CardPage, CardType, LifecyclePhase, CreateCardRequest, UpdateCardRequest},
         user::Claims,
     },
     state::AppState,
@@ -135,6 +135,7 @@ pub fn card_to_arb_submission(card: crate::models::card::Card) -> Result<ARBSubm
     params(ARBMeetingSearchParams),
     responses(
         (status = 200, description = "ARB meetings listed successfully", body = ARBMeetingListResponse),
+        (status = 400, description = "Invalid sort or cursor"),
         (status = 500, description = "Internal server error")
     ),
     tag = "ARB",
@@ -148,15 +149,20 @@ pub async fn list_meetings(
     let page_size = params.page_size.unwrap_or(20).min(100);
 
     // Build query filter - we'll need to filter by attributes
-    let (cards, total) = match state.card_service.list(crate::models::card::CardSearchParams {
+    let page_result = state.card_service.list_page(crate::models::card::CardSearchParams {
         card_type: Some(CardType::ARBMeeting),
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: params.sort.clone(),
+        cursor: params.cursor.clone(),
         page: Some(page),
         page_size: Some(page_size),
-    }).await {
+    }).await;
+    let CardPage { cards, total, next_cursor } = match page_result {
         Ok(result) => result,
+        Err(AppError::Validation(_)) => return Err(StatusCode::BAD_REQUEST),
         Err(e) => {
             tracing::error!("Failed to list ARB meetings: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
@@ -202,6 +208,7 @@ pub async fn list_meetings(
             page,
             limit: page_size,
             total,
+            next_cursor,
         },
     }))
 }
@@ -283,6 +290,7 @@ pub async fn create_meeting(
         quality_score: None,
         description: Some("ARB Meeting".to_string()),
         owner_id: Some(user_id),
+        team_id: None,
         attributes: Some(attributes),
         tags: Some(vec!["arb".to_string(), "meeting".to_string()]),
     };
@@ -376,6 +384,8 @@ pub async fn update_meeting(
         description: None,
         attributes: Some(attrs),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = match state.card_service.update(id, update_req).await {
@@ -449,6 +459,9 @@ pub async fn get_meeting_agenda(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     }).await {
@@ -546,6 +559,8 @@ pub async fn add_submission_to_agenda(
         description: None,
         attributes: Some(attrs),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     match state.card_service.update(req.submission_id, update_req).await {
@@ -564,6 +579,7 @@ pub async fn add_submission_to_agenda(
     params(ARBSubmissionSearchParams),
     responses(
         (status = 200, description = "ARB submissions listed successfully", body = ARBSubmissionListResponse),
+        (status = 400, description = "Invalid sort or cursor"),
         (status = 500, description = "Internal server error")
     ),
     tag = "ARB",
@@ -576,15 +592,20 @@ pub async fn list_submissions(
     let page = params.page.unwrap_or(1).max(1);
     let page_size = params.page_size.unwrap_or(20).min(100);
 
-    let (cards, total) = match state.card_service.list(crate::models::card::CardSearchParams {
+    let page_result = state.card_service.list_page(crate::models::card::CardSearchParams {
         card_type: Some(CardType::ARBSubmission),
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: params.sort.clone(),
+        cursor: params.cursor.clone(),
         page: Some(page),
         page_size: Some(page_size),
-    }).await {
+    }).await;
+    let CardPage { cards, total, next_cursor } = match page_result {
         Ok(result) => result,
+        Err(AppError::Validation(_)) => return Err(StatusCode::BAD_REQUEST),
         Err(e) => {
             tracing::error!("Failed to list ARB submissions: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
@@ -638,6 +659,7 @@ pub async fn list_submissions(
             page,
             limit: page_size,
             total,
+            next_cursor,
         },
     }))
 }
@@ -768,6 +790,7 @@ pub async fn create_submission(
         quality_score: None,
         description: Some(req.rationale.clone()),
         owner_id: Some(submitted_by),
+        team_id: None,
         attributes: Some(attributes),
         tags: Some(vec!["arb".to_string(), "submission".to_string()]),
     };
@@ -830,6 +853,8 @@ pub async fn create_submission(
                 description: None,
                 attributes: Some(card_attrs),
                 tags: None,
+                owner_id: None,
+                team_id: None,
             }
         ).await;
     }
@@ -911,6 +936,8 @@ pub async fn update_submission(
         description: req.rationale.clone(),
         attributes: Some(attrs),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = match state.card_service.update(id, update_req).await {
@@ -1054,6 +1081,8 @@ pub async fn record_decision(
         description: None,
         attributes: Some(attrs),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     match state.card_service.update(submission_id, update_req).await {
@@ -1121,6 +1150,8 @@ pub async fn record_decision(
                             description: None,
                             attributes: Some(card_attrs),
                             tags: None,
+                            owner_id: None,
+                            team_id: None,
                         }
                     ).await;
                 }
@@ -1155,6 +1186,9 @@ pub async fn get_dashboard(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     }).await {
@@ -1171,6 +1205,9 @@ pub async fn get_dashboard(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     }).await {
@@ -1258,6 +1295,9 @@ pub async fn get_statistics(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     }).await {
@@ -1274,6 +1314,9 @@ pub async fn get_statistics(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     }).await {
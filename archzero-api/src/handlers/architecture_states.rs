@@ -0,0 +1,220 @@
+/*!
+ * Architecture State Handlers
+ *
+ * Baseline, target and transition states (FR-STRATEGY-01) and diffs between them.
+ */
+
+use axum::{extract::{Extension, Path, Query, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::Claims,
+    models::architecture_state::*,
+    state::AppState,
+};
+
+/// List architecture states
+#[utoipa::path(
+    get,
+    path = "/api/v1/architecture-states",
+    responses(
+        (status = 200, description = "Architecture states", body = Vec<ArchitectureState>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn list_architecture_states(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ArchitectureState>>, AppError> {
+    let states = state.architecture_state_service.list().await?;
+    Ok(Json(states))
+}
+
+/// Create an architecture state
+///
+/// `snapshot` states copy the current cards and relationships; `planned` states start empty
+/// and collect changes over `baseStateId` (or the live architecture).
+#[utoipa::path(
+    post,
+    path = "/api/v1/architecture-states",
+    request_body = CreateArchitectureStateRequest,
+    responses(
+        (status = 200, description = "Architecture state created", body = ArchitectureState),
+        (status = 400, description = "Invalid request"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn create_architecture_state(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateArchitectureStateRequest>,
+) -> Result<Json<ArchitectureState>, AppError> {
+    let created_by = Uuid::parse_str(&claims.sub).ok();
+    let architecture_state = state.architecture_state_service.create(req, created_by).await?;
+    Ok(Json(architecture_state))
+}
+
+/// Get an architecture state
+#[utoipa::path(
+    get,
+    path = "/api/v1/architecture-states/{id}",
+    params(
+        ("id" = Uuid, Path, description = "State ID")
+    ),
+    responses(
+        (status = 200, description = "Architecture state", body = ArchitectureState),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn get_architecture_state(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ArchitectureState>, AppError> {
+    let architecture_state = state.architecture_state_service.get(id).await?;
+    Ok(Json(architecture_state))
+}
+
+/// Update an architecture state's metadata
+#[utoipa::path(
+    put,
+    path = "/api/v1/architecture-states/{id}",
+    params(
+        ("id" = Uuid, Path, description = "State ID")
+    ),
+    request_body = UpdateArchitectureStateRequest,
+    responses(
+        (status = 200, description = "Architecture state updated", body = ArchitectureState),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn update_architecture_state(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateArchitectureStateRequest>,
+) -> Result<Json<ArchitectureState>, AppError> {
+    let architecture_state = state.architecture_state_service.update(id, req).await?;
+    Ok(Json(architecture_state))
+}
+
+/// Delete an architecture state
+#[utoipa::path(
+    delete,
+    path = "/api/v1/architecture-states/{id}",
+    params(
+        ("id" = Uuid, Path, description = "State ID")
+    ),
+    responses(
+        (status = 200, description = "Architecture state deleted"),
+        (status = 400, description = "State is the base of another state"),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn delete_architecture_state(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<()>, AppError> {
+    state.architecture_state_service.delete(id).await?;
+    Ok(Json(()))
+}
+
+/// List the planned changes of a state, in application order
+#[utoipa::path(
+    get,
+    path = "/api/v1/architecture-states/{id}/changes",
+    params(
+        ("id" = Uuid, Path, description = "State ID")
+    ),
+    responses(
+        (status = 200, description = "Planned changes", body = Vec<PlannedChange>),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn list_planned_changes(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PlannedChange>>, AppError> {
+    let changes = state.architecture_state_service.list_changes(id).await?;
+    Ok(Json(state.architecture_state_service.redacted_changes(changes)))
+}
+
+/// Add a planned change to a planned state
+#[utoipa::path(
+    post,
+    path = "/api/v1/architecture-states/{id}/changes",
+    params(
+        ("id" = Uuid, Path, description = "State ID")
+    ),
+    request_body = CreatePlannedChangeRequest,
+    responses(
+        (status = 200, description = "Planned change added", body = PlannedChange),
+        (status = 400, description = "Invalid change or state is a snapshot"),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn add_planned_change(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreatePlannedChangeRequest>,
+) -> Result<Json<PlannedChange>, AppError> {
+    let change = state.architecture_state_service.add_change(id, req).await?;
+    Ok(Json(change))
+}
+
+/// Remove a planned change
+#[utoipa::path(
+    delete,
+    path = "/api/v1/architecture-states/{id}/changes/{change_id}",
+    params(
+        ("id" = Uuid, Path, description = "State ID"),
+        ("change_id" = Uuid, Path, description = "Planned change ID")
+    ),
+    responses(
+        (status = 200, description = "Planned change removed"),
+        (status = 404, description = "Planned change not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn delete_planned_change(
+    State(state): State<AppState>,
+    Path((id, change_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<()>, AppError> {
+    state.architecture_state_service.delete_change(id, change_id).await?;
+    Ok(Json(()))
+}
+
+/// Diff two architecture states
+///
+/// Cards and relationships are classified as added, removed or changed, with attribute-level changes.
+/// Use `current` for the live architecture.
+#[utoipa::path(
+    get,
+    path = "/api/v1/architecture-states/diff",
+    params(StateDiffParams),
+    responses(
+        (status = 200, description = "State diff", body = StateDiff),
+        (status = 400, description = "Invalid state reference"),
+        (status = 404, description = "State not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Architecture States"
+)]
+pub async fn diff_architecture_states(
+    State(state): State<AppState>,
+    Query(params): Query<StateDiffParams>,
+) -> Result<Json<StateDiff>, AppError> {
+    let diff = state.architecture_state_service.diff(&params.from, &params.to).await?;
+    Ok(Json(diff))
+}
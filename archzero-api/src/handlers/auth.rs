@@ -1,34 +1,333 @@
-use axum::{Json, extract::State};
+use axum::{Json, extract::{Extension, Path, Query, State}, http::{header, HeaderMap}, response::Redirect};
 use uuid::Uuid;
-use crate::models::user::{LoginRequest, LoginResponse, UserRole};
+use crate::error::AppError;
+use crate::middleware::csrf::cookie_value;
+use crate::middleware::rate_limit::ClientIp;
+use crate::models::user::{
+    AcceptInvitationRequest, AuthProviders, ChangePasswordRequest, Claims, ConfirmPasswordResetRequest, LoginRequest,
+    LoginResponse, OidcCallbackQuery, PasswordResetRequest, RefreshRequest, Session, SessionClient, User, UserAccount,
+};
 use crate::Result;
+use crate::services::oidc_service::LOGIN_COOKIE;
 use crate::state::AppState;
 
+fn claims_user_id(claims: &Claims) -> Result<Uuid> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Auth("Invalid token subject".to_string()))
+}
+
+fn session_client(headers: &HeaderMap, client_ip: ClientIp) -> SessionClient {
+    SessionClient {
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        ip_address: client_ip.0,
+    }
+}
+
 pub async fn login(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>> {
     // Call auth service to authenticate
-    let response = state.auth_service.login(req).await?;
+    let response = state.auth_service.login(req, session_client(&headers, client_ip)).await?;
 
     Ok(Json(response))
 }
 
-pub async fn logout() -> &'static str {
-    // JWT is stateless, logout is client-side
-    "Logged out"
+/// Exchange a refresh token for a new access/refresh token pair
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New token pair", body = LoginResponse),
+        (status = 401, description = "Refresh token invalid, expired, revoked or reused")
+    ),
+    tag = "Auth"
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<Json<LoginResponse>> {
+    let response = state.auth_service.refresh(&req.refresh_token).await?;
+    Ok(Json(response))
 }
 
-pub async fn me() -> Result<Json<crate::models::user::User>> {
-    // TODO: Implement when auth middleware is set up
-    let user = crate::models::user::User {
-        id: Uuid::new_v4(),
-        email: "user@example.com".to_string(),
-        full_name: None,
-        role: UserRole::Viewer,
-        created_at: chrono::Utc::now(),
-        updated_at: chrono::Utc::now(),
-    };
+/// Log out, revoking the current session
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    responses(
+        (status = 200, description = "Session revoked"),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<()>> {
+    if let Some(session_id) = claims.sid {
+        state.auth_service.revoke_session(claims_user_id(&claims)?, session_id).await?;
+    }
+    Ok(Json(()))
+}
 
+/// The authenticated user
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    responses(
+        (status = 200, description = "Current user", body = User),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn me(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<User>> {
+    let user = state.auth_service.get_user(claims_user_id(&claims)?).await?;
     Ok(Json(user))
 }
+
+/// Permissions granted to the authenticated user's role
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/permissions",
+    responses(
+        (status = 200, description = "Granted permissions as resource:action strings", body = Vec<String>),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn my_permissions(Extension(claims): Extension<Claims>) -> Json<Vec<String>> {
+    Json(claims.permissions())
+}
+
+/// List the current user's active sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    responses(
+        (status = 200, description = "Active sessions", body = Vec<Session>),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<Session>>> {
+    let sessions = state.auth_service.list_sessions(claims_user_id(&claims)?, claims.sid).await?;
+    Ok(Json(sessions))
+}
+
+/// Revoke one of the current user's sessions (e.g. another device)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session revoked"),
+        (status = 401, description = "Not authenticated"),
+        (status = 404, description = "Session not found")
+    ),
+    tag = "Auth"
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<()>> {
+    state.auth_service.revoke_session(claims_user_id(&claims)?, id).await?;
+    Ok(Json(()))
+}
+
+/// Revoke every session of the current user except the one making the request
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions",
+    responses(
+        (status = 200, description = "Number of sessions revoked", body = u64),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<u64>> {
+    let revoked = state.auth_service.revoke_other_sessions(claims_user_id(&claims)?, claims.sid).await?;
+    Ok(Json(revoked))
+}
+
+/// Accept an invitation and set a password
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/invitations/accept",
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 200, description = "Account created", body = UserAccount),
+        (status = 400, description = "Invalid or expired invitation, or password does not meet the policy")
+    ),
+    tag = "Auth"
+)]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    Json(req): Json<AcceptInvitationRequest>,
+) -> Result<Json<UserAccount>> {
+    let user = state.user_service.accept_invitation(req).await?;
+    Ok(Json(user))
+}
+
+/// Request a password reset link
+///
+/// Always succeeds, whether or not the email belongs to an active account.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 200, description = "Reset link sent if the account exists")
+    ),
+    tag = "Auth"
+)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(req): Json<PasswordResetRequest>,
+) -> Result<Json<()>> {
+    state.user_service.request_password_reset(&req.email).await?;
+    Ok(Json(()))
+}
+
+/// Set a new password with a reset token
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset/confirm",
+    request_body = ConfirmPasswordResetRequest,
+    responses(
+        (status = 200, description = "Password reset"),
+        (status = 400, description = "Invalid or expired token, or password does not meet the policy")
+    ),
+    tag = "Auth"
+)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    Json(req): Json<ConfirmPasswordResetRequest>,
+) -> Result<Json<()>> {
+    state.user_service.confirm_password_reset(req).await?;
+    Ok(Json(()))
+}
+
+/// Change the current user's password
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/change-password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed"),
+        (status = 400, description = "Current password incorrect or new password does not meet the policy"),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Auth"
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<()>> {
+    state.user_service.change_password(claims_user_id(&claims)?, claims.sid, req).await?;
+    Ok(Json(()))
+}
+
+/// Sign-in methods enabled on this server
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/providers",
+    responses(
+        (status = 200, description = "Enabled sign-in methods", body = AuthProviders)
+    ),
+    tag = "Auth"
+)]
+pub async fn auth_providers(State(state): State<AppState>) -> Json<AuthProviders> {
+    Json(AuthProviders {
+        password: state.auth_service.password_login_enabled(),
+        oidc: state.oidc_service.enabled(),
+    })
+}
+
+/// Start single sign-on: redirects to the IdP and sets a cookie binding the login to this browser
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/login",
+    responses(
+        (status = 303, description = "Redirect to the IdP authorization endpoint"),
+        (status = 404, description = "Single sign-on is not configured")
+    ),
+    tag = "Auth"
+)]
+pub async fn oidc_login(State(state): State<AppState>) -> Result<([(header::HeaderName, String); 1], Redirect)> {
+    let (url, cookie) = state.oidc_service.begin_login().await?;
+    Ok(([(header::SET_COOKIE, cookie)], Redirect::to(&url)))
+}
+
+/// IdP callback: completes single sign-on and redirects to the frontend with the
+/// tokens (or an error) in the URL fragment, so they never reach server logs.
+/// Only the browser holding the login cookie from `/auth/oidc/login` can complete the login.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/callback",
+    params(
+        ("code" = Option<String>, Query, description = "Authorization code"),
+        ("state" = Option<String>, Query, description = "State from the login request"),
+        ("error" = Option<String>, Query, description = "Error reported by the IdP")
+    ),
+    responses(
+        (status = 303, description = "Redirect to the frontend")
+    ),
+    tag = "Auth"
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> ([(header::HeaderName, String); 1], Redirect) {
+    let redirect = state.oidc_service.post_login_redirect();
+    let login_cookie = cookie_value(&headers, LOGIN_COOKIE);
+
+    let result = match (query.code, query.state, query.error) {
+        (_, _, Some(error)) => Err(AppError::Auth(query.error_description.unwrap_or(error))),
+        (Some(code), Some(login_state), None) => {
+            state.oidc_service
+                .complete_login(&code, &login_state, login_cookie.as_deref(), session_client(&headers, client_ip))
+                .await
+        }
+        _ => Err(AppError::Validation("Missing code or state".to_string())),
+    };
+
+    let fragment = match result {
+        Ok(response) => url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("token", &response.token)
+            .append_pair("refresh_token", &response.refresh_token)
+            .append_pair("expires_in", &response.expires_in.to_string())
+            .finish(),
+        Err(e) => {
+            tracing::warn!("Single sign-on failed: {}", e);
+            url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("error", &e.to_string())
+                .finish()
+        }
+    };
+
+    (
+        [(header::SET_COOKIE, state.oidc_service.clear_login_cookie())],
+        Redirect::to(&format!("{}#{}", redirect, fragment)),
+    )
+}
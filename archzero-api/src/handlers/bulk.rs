@@ -180,6 +180,9 @@ pub async fn bulk_export_cards(
                 card_type: None,
                 lifecycle_phase: None,
                 tags: None,
+                query: None,
+                sort: None,
+                cursor: None,
                 page: None,
                 page_size: Some(10000),
             })
@@ -3,7 +3,10 @@ use uuid::Uuid;
 use serde::Serialize;
 use utoipa::ToSchema;
 
-use crate::models::card::{Card, CreateCardRequest, UpdateCardRequest, CardSearchParams};
+use crate::models::card::{Card, CardPage, CreateCardRequest, UpdateCardRequest, CardSearchParams};
+use crate::models::card_query::CardQueryRequest;
+use crate::models::search::{CardSearchQuery, CardSearchResponse, SimilarCardsQuery, SimilarCardsResponse};
+use crate::models::version::{AsOfParams, CardVersion};
 use crate::Result;
 use crate::state::AppState;
 
@@ -13,6 +16,8 @@ pub struct CardListResponse {
     pub total: i64,
     pub page: u32,
     pub page_size: u32,
+    /// Pass as `cursor` for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 /// Create a new card
@@ -65,6 +70,7 @@ pub async fn get_card(
     params(CardSearchParams),
     responses(
         (status = 200, description = "Cards retrieved successfully", body = CardListResponse),
+        (status = 400, description = "Invalid query"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Cards"
@@ -75,12 +81,90 @@ pub async fn list_cards(
 ) -> Result<Json<CardListResponse>> {
     let page = params.page.unwrap_or(1);
     let page_size = params.page_size.unwrap_or(20);
-    let (cards, total) = state.saga_orchestrator.get_card_service().list(params).await?;
+    let CardPage { cards, total, next_cursor } = state.saga_orchestrator.get_card_service().list_page(params).await?;
     Ok(Json(CardListResponse {
         data: cards,
         total,
         page,
         page_size,
+        next_cursor,
+    }))
+}
+
+/// Relevance-ranked full-text and fuzzy search with highlights and facet counts
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/search",
+    params(CardSearchQuery),
+    responses(
+        (status = 200, description = "Ranked matches and facets", body = CardSearchResponse),
+        (status = 400, description = "Missing search text"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn search_cards(
+    State(state): State<AppState>,
+    Query(params): Query<CardSearchQuery>,
+) -> Result<Json<CardSearchResponse>> {
+    let response = state.saga_orchestrator.get_card_service().search(params).await?;
+    Ok(Json(response))
+}
+
+/// Similar cards (shared tags, attribute values and neighbours) and suggested missing relationships
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/{id}/similar",
+    params(
+        ("id" = Uuid, Path, description = "Card ID"),
+        SimilarCardsQuery
+    ),
+    responses(
+        (status = 200, description = "Similar cards, most similar first, with suggested relationships", body = SimilarCardsResponse),
+        (status = 404, description = "Card not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn similar_cards(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<SimilarCardsQuery>,
+) -> Result<Json<SimilarCardsResponse>> {
+    let response = state.saga_orchestrator.get_card_service().similar(id, params).await?;
+    Ok(Json(response))
+}
+
+/// Search cards with a boolean query AST
+#[utoipa::path(
+    post,
+    path = "/api/v1/cards/query",
+    request_body = CardQueryRequest,
+    responses(
+        (status = 200, description = "Matching cards", body = CardListResponse),
+        (status = 400, description = "Invalid query"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn query_cards(
+    State(state): State<AppState>,
+    Json(req): Json<CardQueryRequest>,
+) -> Result<Json<CardListResponse>> {
+    let params = CardSearchParams {
+        sort: req.sort,
+        cursor: req.cursor,
+        page: req.page,
+        page_size: req.page_size,
+        ..Default::default()
+    };
+    let CardPage { cards, total, next_cursor } = state.saga_orchestrator.get_card_service().list_matching_page(params, Some(&req.query)).await?;
+    Ok(Json(CardListResponse {
+        data: cards,
+        total,
+        page: req.page.unwrap_or(1),
+        page_size: req.page_size.unwrap_or(20),
+        next_cursor,
     }))
 }
 
@@ -130,3 +214,74 @@ pub async fn delete_card(
     state.saga_orchestrator.delete_card(id).await?;
     Ok(Json(()))
 }
+
+/// List a card's version history, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/{id}/history",
+    params(
+        ("id" = Uuid, Path, description = "Card ID")
+    ),
+    responses(
+        (status = 200, description = "Card versions", body = Vec<CardVersion>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn get_card_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<CardVersion>>> {
+    let history = state.version_service.card_history(id).await?;
+    Ok(Json(state.card_service.redacted_versions(history)))
+}
+
+/// Get a card as it was at a point in time
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/{id}/as-of",
+    params(
+        ("id" = Uuid, Path, description = "Card ID"),
+        AsOfParams
+    ),
+    responses(
+        (status = 200, description = "Card as of the given time", body = Card),
+        (status = 404, description = "Card did not exist at that time"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn get_card_as_of(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<AsOfParams>,
+) -> Result<Json<Card>> {
+    let card = state.version_service.card_as_of(id, params.at).await?;
+    Ok(Json(state.card_service.redacted(card)))
+}
+
+/// Restore a card to a previous version
+///
+/// Restoring a delete version brings the card back as it was just before deletion.
+#[utoipa::path(
+    post,
+    path = "/api/v1/cards/{id}/versions/{version}/restore",
+    params(
+        ("id" = Uuid, Path, description = "Card ID"),
+        ("version" = i32, Path, description = "Version number")
+    ),
+    responses(
+        (status = 200, description = "Card restored successfully", body = Card),
+        (status = 404, description = "Version not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Cards"
+)]
+pub async fn restore_card_version(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(Uuid, i32)>,
+) -> Result<Json<Card>> {
+    let snapshot = state.version_service.card_version(id, version).await?.snapshot_card()?;
+    let card = state.saga_orchestrator.restore_card_version(snapshot).await?;
+    Ok(Json(card))
+}
@@ -131,6 +131,7 @@ pub async fn create_compliance_requirement(
         quality_score: None,
         description: Some(req.description.clone()),
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: Some(vec![framework_str.clone()]),
     };
@@ -201,6 +202,8 @@ pub async fn update_compliance_requirement(
         description: req.description.clone(),
         attributes: Some(attributes),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = state.card_service.update(id, card_req).await?;
@@ -391,57 +394,38 @@ pub async fn get_compliance_dashboard(
     let mut by_card_type: HashMap<String, CardTypeBreakdown> = HashMap::new();
 
     for card_type_str in applicable_types {
-        // Convert string to CardType
-        let card_type = match card_type_str.as_str() {
-            "BusinessCapability" => Ok(CardType::BusinessCapability),
-            "Objective" => Ok(CardType::Objective),
-            "Application" => Ok(CardType::Application),
-            "Interface" => Ok(CardType::Interface),
-            "ITComponent" => Ok(CardType::ITComponent),
-            "Platform" => Ok(CardType::Platform),
-            "ArchitecturePrinciple" => Ok(CardType::ArchitecturePrinciple),
-            "TechnologyStandard" => Ok(CardType::TechnologyStandard),
-            "ArchitecturePolicy" => Ok(CardType::ArchitecturePolicy),
-            "Exception" => Ok(CardType::Exception),
-            "Initiative" => Ok(CardType::Initiative),
-            "Risk" => Ok(CardType::Risk),
-            "ComplianceRequirement" => Ok(CardType::ComplianceRequirement),
-            "DataObject" => Ok(CardType::ITComponent), // Map DataObject to ITComponent
-            _ => Err(crate::error::AppError::Internal(anyhow::anyhow!("Unknown card type: {}", card_type_str)))
+        // Built-in or registered at runtime; names no card uses simply count zero cards
+        let ct = CardType::from_name(&card_type_str);
+        let params = CardSearchParams {
+            card_type: Some(ct),
+            ..Default::default()
         };
 
-        if let Ok(ct) = card_type {
-            let params = CardSearchParams {
-                card_type: Some(ct),
-                ..Default::default()
-            };
-
-            let (cards, _) = state.card_service.list(params).await?;
-            let total = cards.len() as i32;
-            let mut compliant = 0;
+        let (cards, _) = state.card_service.list(params).await?;
+        let total = cards.len() as i32;
+        let mut compliant = 0;
 
-            for card in &cards {
-                let card_controls: Vec<String> = card.attributes.get("controls")
-                    .and_then(|v| serde_json::from_value(v.clone()).ok())
-                    .unwrap_or_default();
+        for card in &cards {
+            let card_controls: Vec<String> = card.attributes.get("controls")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
 
-                let has_all_controls = required_controls.iter()
-                    .all(|c| card_controls.contains(c));
+            let has_all_controls = required_controls.iter()
+                .all(|c| card_controls.contains(c));
 
-                if has_all_controls {
-                    compliant += 1;
-                }
+            if has_all_controls {
+                compliant += 1;
             }
+        }
 
-            summary.total_applicable_cards += total;
-            summary.compliant += compliant;
-            summary.non_compliant += total - compliant;
+        summary.total_applicable_cards += total;
+        summary.compliant += compliant;
+        summary.non_compliant += total - compliant;
 
-            by_card_type.insert(card_type_str, CardTypeBreakdown {
-                total,
-                compliant,
-            });
-        }
+        by_card_type.insert(card_type_str, CardTypeBreakdown {
+            total,
+            compliant,
+        });
     }
 
     // Calculate compliance rate
@@ -506,6 +490,9 @@ impl Default for CardSearchParams {
             card_type: None,
             lifecycle_phase: None,
             tags: None,
+            query: None,
+            sort: None,
+            cursor: None,
             page: None,
             page_size: None,
         }
@@ -634,6 +621,7 @@ pub async fn create_compliance_audit(
         quality_score: None,
         description: Some(req.notes.clone()),
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: Some(vec![req.framework.clone()]),
     };
@@ -706,6 +694,8 @@ pub async fn update_compliance_audit(
         description: req.notes.clone(),
         attributes: Some(attributes),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = state.card_service.update(id, card_req).await?;
@@ -4,12 +4,11 @@
  * Provides endpoints for generating and managing CSRF tokens.
  */
 
-use axum::{extract::State, Json};
+use axum::{extract::State, http::header, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::{state::AppState, Result};
-use crate::error::AppError;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CsrfTokenResponse {
@@ -29,25 +28,30 @@ pub struct CsrfValidateResponse {
 
 /// Generate a new CSRF token
 ///
-/// Returns a new CSRF token that should be included in state-changing requests.
+/// Returns a new CSRF token and sets it as a cookie. Browser sessions echo it in the
+/// X-CSRF-Token header of state-changing requests; Bearer-token clients need none.
 #[utoipa::path(
     post,
     path = "/api/v1/csrf/token",
     responses(
-        (status = 200, description = "CSRF token generated successfully", body = CsrfTokenResponse),
+        (status = 200, description = "CSRF token generated successfully; also set as a cookie", body = CsrfTokenResponse),
         (status = 500, description = "Internal server error")
     ),
     tag = "CSRF"
 )]
 pub async fn generate_csrf_token(
     State(state): State<AppState>,
-) -> Result<Json<CsrfTokenResponse>> {
-    let token = state.csrf_service.generate_token().await;
+) -> Result<([(header::HeaderName, String); 1], Json<CsrfTokenResponse>)> {
+    let token = state.csrf_service.generate_token();
+    let cookie = state.csrf_service.cookie(&token);
 
-    Ok(Json(CsrfTokenResponse {
-        token,
-        expires_in: 3600, // 1 hour in seconds
-    }))
+    Ok((
+        [(header::SET_COOKIE, cookie)],
+        Json(CsrfTokenResponse {
+            token,
+            expires_in: state.csrf_service.ttl().as_secs(),
+        }),
+    ))
 }
 
 /// Validate a CSRF token
@@ -68,7 +72,7 @@ pub async fn validate_csrf_token(
     State(state): State<AppState>,
     Json(req): Json<CsrfValidateRequest>,
 ) -> Result<Json<CsrfValidateResponse>> {
-    let is_valid = state.csrf_service.validate_token(&req.token).await;
+    let is_valid = state.csrf_service.validate_token(&req.token);
 
     Ok(Json(CsrfValidateResponse { valid: is_valid }))
 }
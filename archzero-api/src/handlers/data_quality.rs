@@ -0,0 +1,102 @@
+/*!
+ * Data Quality Handlers
+ *
+ * Computed card quality scores: the dashboard of the worst cards by type and owner,
+ * the factors behind a card's score, marking a card as reviewed, and recalculation
+ * after bulk changes.
+ */
+
+use axum::{extract::{Path, Query, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::data_quality::*,
+    state::AppState,
+};
+
+/// Data quality of the current workspace: averages, score bands and the worst cards by type and owner
+#[utoipa::path(
+    get,
+    path = "/api/v1/data-quality",
+    params(DataQualityParams),
+    responses(
+        (status = 200, description = "Data quality dashboard", body = DataQualityDashboard),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Data Quality"
+)]
+pub async fn get_data_quality_dashboard(
+    State(state): State<AppState>,
+    Query(params): Query<DataQualityParams>,
+) -> Result<Json<DataQualityDashboard>, AppError> {
+    let dashboard = state.data_quality_service.dashboard(params).await?;
+    Ok(Json(dashboard))
+}
+
+/// Recalculate the quality score of every card, or of the cards of one type
+#[utoipa::path(
+    post,
+    path = "/api/v1/data-quality/recalculate",
+    params(
+        ("type" = Option<String>, Query, description = "Only cards of this type")
+    ),
+    responses(
+        (status = 200, description = "Cards recalculated", body = QualityRecalculation),
+        (status = 403, description = "Metamodel management permission required")
+    ),
+    tag = "Data Quality"
+)]
+pub async fn recalculate_data_quality(
+    State(state): State<AppState>,
+    Query(params): Query<DataQualityParams>,
+) -> Result<Json<QualityRecalculation>, AppError> {
+    let result = state.data_quality_service.recalculate(params.card_type.as_deref()).await?;
+    Ok(Json(result))
+}
+
+/// Quality score of a card and the factors and issues behind it
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/{id}/quality",
+    params(
+        ("id" = Uuid, Path, description = "Card ID")
+    ),
+    responses(
+        (status = 200, description = "Card quality", body = CardQuality),
+        (status = 404, description = "Card not found")
+    ),
+    tag = "Data Quality"
+)]
+pub async fn get_card_quality(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CardQuality>, AppError> {
+    // Only cards the caller can see
+    state.card_service.get(id).await?;
+    let quality = state.data_quality_service.card_quality(id).await?;
+    Ok(Json(quality))
+}
+
+/// Confirm a card is still accurate, restoring its freshness without changing it
+#[utoipa::path(
+    post,
+    path = "/api/v1/cards/{id}/review",
+    params(
+        ("id" = Uuid, Path, description = "Card ID")
+    ),
+    responses(
+        (status = 200, description = "Card reviewed", body = CardQuality),
+        (status = 403, description = "Card update permission required"),
+        (status = 404, description = "Card not found")
+    ),
+    tag = "Data Quality"
+)]
+pub async fn review_card(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CardQuality>, AppError> {
+    state.card_service.get(id).await?;
+    let quality = state.data_quality_service.review(id).await?;
+    Ok(Json(quality))
+}
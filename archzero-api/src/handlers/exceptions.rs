@@ -32,6 +32,9 @@ pub async fn list_exceptions(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: params.page,
         page_size: params.page_size,
     };
@@ -160,6 +163,7 @@ pub async fn create_exception_request(
         quality_score: None,
         description: Some(req.justification.clone()),
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: Some(vec!["exception".to_string(), "pending".to_string()]),
     };
@@ -230,6 +234,8 @@ pub async fn approve_exception(
         description: None,
         attributes: Some(attributes),
         tags: Some(vec!["exception".to_string(), "approved".to_string()]),
+        owner_id: None,
+        team_id: None,
     };
 
     // Use SAGA orchestrator for update (ensures consistency between PostgreSQL and Neo4j)
@@ -296,6 +302,8 @@ pub async fn reject_exception(
         description: None,
         attributes: Some(attributes),
         tags: Some(vec!["exception".to_string(), "rejected".to_string()]),
+        owner_id: None,
+        team_id: None,
     };
 
     // Use SAGA orchestrator for update (ensures consistency between PostgreSQL and Neo4j)
@@ -335,6 +343,9 @@ pub async fn list_expiring_exceptions(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
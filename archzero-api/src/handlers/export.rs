@@ -1,4 +1,4 @@
-use axum::extract::{Json, State, Query};
+use axum::extract::{Extension, Json, State, Query};
 use axum::response::{Response, IntoResponse};
 use axum::http::{StatusCode, header};
 use serde::{Deserialize, Serialize};
@@ -8,6 +8,7 @@ use std::collections::HashMap;
 use crate::{
     error::AppError,
     state::AppState,
+    models::user::Claims,
     models::export::{ExportRequest, ExportFormat, ExportFilters, CreateScheduledExportRequest, UpdateScheduledExportRequest, ScheduledExport, ScheduledExportsResponse},
 };
 
@@ -29,6 +30,7 @@ pub struct ExportHistoryParams {
         (status = 200, description = "Export file generated successfully", content_type = "text/csv"),
         (status = 200, description = "Export file generated successfully", content_type = "application/json"),
         (status = 400, description = "Invalid format"),
+        (status = 404, description = "Saved search not found"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Export",
@@ -36,14 +38,21 @@ pub struct ExportHistoryParams {
 )]
 pub async fn export_cards(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Json(req): Json<ExportRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Get user ID from JWT (would normally come from middleware)
     // For now, use a placeholder - in real implementation, extract from request
     let user_id = Uuid::new_v4(); // Placeholder
 
-    // Call export service
-    let export = state.export_service.export_cards(req, user_id).await?;
+    // Call export service; a saved search selects the cards itself
+    let export = match req.filters.as_ref().and_then(|filters| filters.saved_search_id) {
+        Some(saved_search_id) => {
+            let cards = state.saved_search_service.cards(saved_search_id, &claims).await?;
+            state.export_service.export_card_list(&cards, req.format, user_id).await?
+        }
+        None => state.export_service.export_cards(req, user_id).await?,
+    };
 
     // Read the file
     let file_content = tokio::fs::read(&export.file_path.ok_or_else(||
@@ -191,16 +200,27 @@ pub async fn get_export_history(
     Ok(Json(json_value))
 }
 
+/// Scheduled exports are not stored or run yet, so they cannot take their cards from a saved search
+fn reject_saved_search(filters: Option<&ExportFilters>) -> Result<(), AppError> {
+    if filters.is_some_and(|filters| filters.saved_search_id.is_some()) {
+        return Err(AppError::Validation(
+            "Scheduled exports do not support saved searches yet; export the saved search directly".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Create scheduled export endpoint
 ///
-/// Create a new scheduled export job
+/// Create a new scheduled export job. Scheduled exports are not run yet, and `filters.savedSearchId`
+/// is rejected: only on-demand exports and reports can take their cards from a saved search.
 #[utoipa::path(
     post,
     path = "/api/v1/export/scheduled",
     request_body = CreateScheduledExportRequest,
     responses(
         (status = 201, description = "Scheduled export created successfully"),
-        (status = 400, description = "Bad request"),
+        (status = 400, description = "Bad request, or a saved search was given"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Export",
@@ -210,6 +230,8 @@ pub async fn create_scheduled_export(
     State(_state): State<AppState>,
     Json(_req): Json<CreateScheduledExportRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
+    reject_saved_search(_req.filters.as_ref())?;
+
     // Placeholder - would validate cron, calculate next_run_at, and save to database
     let user_id = Uuid::new_v4(); // Placeholder - get from JWT
 
@@ -255,7 +277,7 @@ pub async fn list_scheduled_exports(
 
 /// Update scheduled export endpoint
 ///
-/// Update an existing scheduled export
+/// Update an existing scheduled export; as on create, `filters.savedSearchId` is rejected
 #[utoipa::path(
     put,
     path = "/api/v1/export/scheduled/{id}",
@@ -265,6 +287,7 @@ pub async fn list_scheduled_exports(
     request_body = UpdateScheduledExportRequest,
     responses(
         (status = 200, description = "Scheduled export updated successfully"),
+        (status = 400, description = "Bad request, or a saved search was given"),
         (status = 403, description = "Forbidden - not owned by user"),
         (status = 404, description = "Scheduled export not found"),
         (status = 500, description = "Internal server error")
@@ -277,6 +300,8 @@ pub async fn update_scheduled_export(
     axum::extract::Path(id): axum::extract::Path<Uuid>,
     Json(_req): Json<UpdateScheduledExportRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
+    reject_saved_search(_req.filters.as_ref())?;
+
     // Placeholder - would validate ownership and update database
     Ok(Json(serde_json::json!({
         "id": id,
@@ -326,4 +351,20 @@ mod tests {
         assert_eq!(params.limit, Some(100));
         assert_eq!(params.offset, Some(0));
     }
+
+    #[test]
+    fn test_scheduled_exports_reject_saved_searches() {
+        let filters = |saved_search_id| ExportFilters {
+            card_type: Some("Application".to_string()),
+            lifecycle_state: None,
+            domain: None,
+            date_from: None,
+            date_to: None,
+            saved_search_id,
+        };
+
+        assert!(reject_saved_search(None).is_ok());
+        assert!(reject_saved_search(Some(&filters(None))).is_ok());
+        assert!(matches!(reject_saved_search(Some(&filters(Some(Uuid::new_v4())))), Err(AppError::Validation(_))));
+    }
 }
@@ -8,13 +8,18 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use utoipa::ToSchema;
 
 use crate::{
-    services::CardService,
+    services::{CardService, GraphBackend},
+    services::card_type_service::DEFAULT_CARD_COLOR,
+    services::embedded_graph_service::AdjacencyGraph,
+    services::version_service::current_workspace,
     error::AppError,
-    models::card::CardSearchParams,
+    models::card::{Card, CardSearchParams},
+    models::graph_sync::GraphRelationshipEdge,
     state::AppState,
 };
 
@@ -111,14 +116,15 @@ pub async fn get_graph(
 ) -> Result<Json<GraphData>, AppError> {
     let depth = params.depth.unwrap_or(2).min(5);
     let min_confidence = params.min_confidence.unwrap_or(0.0);
+    let colors = state.card_type_service.colors().await?;
 
-    // Get cards and relationships from Neo4j
+    // Get cards from PostgreSQL and relationships from the graph backend
     let (nodes, edges) = if let Some(center_id) = params.center_card_id {
         // Get graph centered on a specific card
-        get_graph_centered(&state.card_service, center_id, depth, min_confidence).await?
+        get_graph_centered(&state.card_service, state.graph_backend.as_ref(), &colors, center_id, depth, min_confidence).await?
     } else {
         // Get full graph
-        get_full_graph(&state.card_service, depth, min_confidence).await?
+        get_full_graph(&state.card_service, state.graph_backend.as_ref(), &colors, depth, min_confidence).await?
     };
 
     Ok(Json(GraphData { nodes, edges }))
@@ -137,8 +143,8 @@ pub async fn get_graph(
 pub async fn get_graph_stats(
     State(state): State<AppState>,
 ) -> Result<Json<GraphStats>, AppError> {
-    // Calculate graph statistics from Neo4j
-    let stats = calculate_graph_stats(&state.card_service).await?;
+    // Calculate graph statistics from the graph backend
+    let stats = calculate_graph_stats(state.graph_backend.as_ref()).await?;
 
     Ok(Json(stats))
 }
@@ -168,39 +174,64 @@ pub async fn get_node_count(
 
 async fn get_graph_centered(
     card_service: &CardService,
+    graph: &dyn GraphBackend,
+    colors: &HashMap<String, String>,
     center_id: Uuid,
-    _depth: u32,
-    _min_confidence: f64,
+    depth: u32,
+    min_confidence: f64,
 ) -> Result<(Vec<GraphNode>, Vec<GraphEdge>), AppError> {
-    // For now, just return the center card
     let center_card = card_service.get(center_id).await?;
 
-    let nodes = vec![GraphNode {
-        id: center_card.id.to_string(),
-        position: NodePosition { x: 500.0, y: 300.0 },
-        data: GraphNodeData {
-            id: center_card.id.to_string(),
-            name: center_card.name.clone(),
-            node_type: format!("{:?}", center_card.card_type),
-            lifecycle_phase: format!("{:?}", center_card.lifecycle_phase),
-            quality_score: center_card.quality_score,
-            description: center_card.description,
-            tags: center_card.tags.clone(),
-            color: get_card_color(&format!("{:?}", center_card.card_type)),
-            size: 1.0,
-        },
-    }];
+    let edges: Vec<GraphRelationshipEdge> = graph.neighbourhood(center_id, depth).await?
+        .into_iter()
+        .filter(|edge| edge.confidence.unwrap_or(1.0) >= min_confidence)
+        .collect();
+
+    // Center card first, then every card touched by a neighbourhood edge, fetched in one query
+    let mut seen = HashSet::from([center_id]);
+    let neighbour_ids: Vec<Uuid> = edges.iter()
+        .flat_map(|edge| [edge.from_card_id, edge.to_card_id])
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let mut neighbours: HashMap<Uuid, Card> = card_service.get_many(&neighbour_ids).await?
+        .into_iter()
+        .map(|card| (card.id, card))
+        .collect();
 
-    // TODO: Get relationships and connected cards
-    let edges = vec![];
+    // Keep edge order for a stable layout; cards the caller cannot see are dropped
+    let mut visible = HashSet::from([center_id]);
+    let mut cards = vec![center_card];
+    for id in neighbour_ids {
+        if let Some(card) = neighbours.remove(&id) {
+            visible.insert(id);
+            cards.push(card);
+        }
+    }
 
-    Ok((nodes, edges))
+    let ring = cards.len().saturating_sub(1).max(1) as f64;
+    let nodes: Vec<GraphNode> = cards.into_iter()
+        .enumerate()
+        .map(|(i, card)| {
+            // Center card in the middle, neighbours on a circle around it
+            let (x, y) = if i == 0 {
+                (500.0, 300.0)
+            } else {
+                let angle = ((i - 1) as f64 / ring) * 2.0 * std::f64::consts::PI;
+                (500.0 + 250.0 * angle.cos(), 300.0 + 250.0 * angle.sin())
+            };
+            to_graph_node(card, colors, x, y)
+        })
+        .collect();
+
+    Ok((nodes, to_graph_edges(edges, &visible)))
 }
 
 async fn get_full_graph(
     card_service: &CardService,
+    graph: &dyn GraphBackend,
+    colors: &HashMap<String, String>,
     _depth: u32,
-    _min_confidence: f64,
+    min_confidence: f64,
 ) -> Result<(Vec<GraphNode>, Vec<GraphEdge>), AppError> {
     // Get all cards (limited for performance)
     let (cards, _total) = card_service.list(CardSearchParams {
@@ -209,6 +240,8 @@ async fn get_full_graph(
         ..Default::default()
     }).await?;
 
+    let card_ids: HashSet<Uuid> = cards.iter().map(|card| card.id).collect();
+
     let nodes: Vec<GraphNode> = cards.into_iter()
         .enumerate()
         .map(|(i, card)| {
@@ -218,59 +251,81 @@ async fn get_full_graph(
             let x = 500.0 + radius * angle.cos();
             let y = 300.0 + radius * angle.sin();
 
-            GraphNode {
-                id: card.id.to_string(),
-                position: NodePosition { x, y },
-                data: GraphNodeData {
-                    id: card.id.to_string(),
-                    name: card.name.clone(),
-                    node_type: format!("{:?}", card.card_type),
-                    lifecycle_phase: format!("{:?}", card.lifecycle_phase),
-                    quality_score: card.quality_score,
-                    description: card.description,
-                    tags: card.tags.clone(),
-                    color: get_card_color(&format!("{:?}", card.card_type)),
-                    size: 1.0,
-                },
-            }
+            to_graph_node(card, colors, x, y)
         })
         .collect();
 
-    // TODO: Get relationships
-    let edges = vec![];
+    let workspace_id = current_workspace();
+    let edges: Vec<GraphRelationshipEdge> = graph.list_relationship_edges().await?
+        .into_iter()
+        .filter(|edge| workspace_id.is_none() || edge.workspace_id == workspace_id)
+        .filter(|edge| edge.confidence.unwrap_or(1.0) >= min_confidence)
+        .collect();
 
-    Ok((nodes, edges))
+    Ok((nodes, to_graph_edges(edges, &card_ids)))
 }
 
 async fn calculate_graph_stats(
-    _card_service: &CardService,
+    graph: &dyn GraphBackend,
 ) -> Result<GraphStats, AppError> {
-    // Query Neo4j for graph statistics
-    // For now, return placeholder data
+    // Only the current workspace's own cards and relationships count
+    let workspace_id = current_workspace();
+    let node_ids: Vec<Uuid> = graph.list_card_nodes().await?
+        .into_iter()
+        .filter(|node| workspace_id.is_none() || node.workspace_id == workspace_id)
+        .map(|node| node.id)
+        .collect();
+    let edges: Vec<GraphRelationshipEdge> = graph.list_relationship_edges().await?
+        .into_iter()
+        .filter(|edge| workspace_id.is_none() || edge.workspace_id == workspace_id)
+        .collect();
+
+    let total_nodes = node_ids.len() as u32;
+    let total_edges = edges.len() as u32;
+    let adjacency = AdjacencyGraph::from_edges(edges);
+
     Ok(GraphStats {
-        total_nodes: 0,
-        total_edges: 0,
-        connected_components: 0,
-        average_degree: 0.0,
-        max_depth: 0,
+        total_nodes,
+        total_edges,
+        connected_components: adjacency.connected_components(&node_ids),
+        average_degree: if total_nodes == 0 { 0.0 } else { 2.0 * total_edges as f64 / total_nodes as f64 },
+        max_depth: adjacency.max_depth(),
     })
 }
 
-fn get_card_color(card_type: &str) -> String {
-    match card_type {
-        "BusinessCapability" => "#8b5cf6".to_string(),    // purple
-        "Objective" => "#a78bfa".to_string(),             // light purple
-        "Application" => "#3b82f6".to_string(),            // blue
-        "Interface" => "#06b6d4".to_string(),              // cyan
-        "ITComponent" => "#10b981".to_string(),            // emerald
-        "Platform" => "#14b8a6".to_string(),               // teal
-        "ArchitecturePrinciple" => "#6366f1".to_string(),  // indigo
-        "TechnologyStandard" => "#8b5cf6".to_string(),     // violet
-        "ArchitecturePolicy" => "#f43f5e".to_string(),     // rose
-        "Exception" => "#f59e0b".to_string(),              // amber
-        "Initiative" => "#ec4899".to_string(),             // pink
-        "Risk" => "#ef4444".to_string(),                   // red
-        "ComplianceRequirement" => "#f97316".to_string(),  // orange
-        _ => "#64748b".to_string(),                        // slate
+/// `colors` maps card type names to their registered display colour
+fn to_graph_node(card: Card, colors: &HashMap<String, String>, x: f64, y: f64) -> GraphNode {
+    GraphNode {
+        id: card.id.to_string(),
+        position: NodePosition { x, y },
+        data: GraphNodeData {
+            id: card.id.to_string(),
+            name: card.name.clone(),
+            node_type: card.card_type.to_string(),
+            lifecycle_phase: format!("{:?}", card.lifecycle_phase),
+            quality_score: card.quality_score,
+            description: card.description,
+            tags: card.tags.clone(),
+            color: colors.get(card.card_type.as_str()).cloned().unwrap_or_else(|| DEFAULT_CARD_COLOR.to_string()),
+            size: 1.0,
+        },
     }
 }
+
+/// Convert backend edges to ReactFlow edges, keeping only edges between visible nodes
+fn to_graph_edges(edges: Vec<GraphRelationshipEdge>, visible: &HashSet<Uuid>) -> Vec<GraphEdge> {
+    edges.into_iter()
+        .filter(|edge| visible.contains(&edge.from_card_id) && visible.contains(&edge.to_card_id))
+        .map(|edge| GraphEdge {
+            id: edge.id.unwrap_or_else(Uuid::new_v4).to_string(),
+            source: edge.from_card_id.to_string(),
+            target: edge.to_card_id.to_string(),
+            data: GraphEdgeData {
+                relationship_type: edge.edge_type,
+                confidence: edge.confidence.unwrap_or(1.0),
+                valid_from: edge.valid_from,
+                valid_to: edge.valid_to,
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+/*!
+ * Graph Reconciliation Handlers
+ *
+ * Admin endpoints for comparing PostgreSQL with Neo4j and repairing drift.
+ */
+
+use axum::{extract::{State, Extension}, Json};
+
+use crate::{
+    error::AppError,
+    models::Claims,
+    models::graph_sync::{ReconciliationReport, ReconcileRequest, ReconciliationResult},
+    state::AppState,
+};
+
+/// Report differences between PostgreSQL and Neo4j without changing anything
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/graph-sync",
+    responses(
+        (status = 200, description = "Drift report", body = ReconciliationReport),
+        (status = 403, description = "Admin role required"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_graph_sync_report(
+    State(state): State<AppState>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let report = state.graph_sync_service.diff().await?;
+    Ok(Json(report))
+}
+
+/// Reconcile Neo4j with PostgreSQL
+///
+/// `report` only diffs, `repair` fixes drifted nodes and edges, `rebuild` recreates the whole graph.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/graph-sync",
+    request_body = ReconcileRequest,
+    responses(
+        (status = 200, description = "Reconciliation result", body = ReconciliationResult),
+        (status = 403, description = "Admin role required"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn reconcile_graph(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ReconcileRequest>,
+) -> Result<Json<ReconciliationResult>, AppError> {
+    tracing::info!("Graph reconciliation ({:?}) requested by {}", req.mode, claims.email);
+    let result = state.graph_sync_service.reconcile(req.mode).await?;
+    Ok(Json(result))
+}
@@ -1,6 +1,6 @@
 /**
  * Bulk Import Handlers
- * Support CSV and Excel file imports with validation and progress tracking
+ * Support CSV and Excel file imports of cards and relationships with validation and progress tracking
  */
 
 use axum::{
@@ -13,7 +13,10 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::{
-    services::CardService,
+    models::card::{CardType, CreateCardRequest, LifecyclePhase},
+    models::relationship::{CreateRelationshipRequest, RelationshipType},
+    services::{CardService, SagaOrchestrator},
+    services::version_service::{current_actor, CURRENT_ACTOR},
     error::AppError,
     state::AppState,
 };
@@ -85,6 +88,24 @@ pub struct ColumnMapping {
     pub quality_score: Option<String>,
 }
 
+/// CSV columns of a relationship import; card IDs are those of existing cards
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RelationshipColumnMapping {
+    pub from_card_id: Option<String>,
+    pub to_card_id: Option<String>,
+    #[serde(rename = "type")]
+    pub relationship_type: Option<String>,
+    pub valid_from: Option<String>,
+    pub valid_to: Option<String>,
+    pub confidence: Option<String>,
+}
+
+/// What the rows of an import job become
+enum ImportTarget {
+    Cards(Arc<CardService>, ColumnMapping),
+    Relationships(Arc<SagaOrchestrator>, RelationshipColumnMapping),
+}
+
 // Import result
 #[derive(Debug, Serialize)]
 pub struct ImportResult {
@@ -101,9 +122,31 @@ pub struct ImportResult {
 /// Processes CSV/Excel file and imports cards
 pub async fn bulk_import_cards(
     State(state): State<AppState>,
-    mut multipart: Multipart,
+    multipart: Multipart,
 ) -> Result<Json<ImportResult>, AppError> {
-    // Parse multipart form data
+    let (file_name, file_data, column_mapping_json) = read_upload(multipart).await?;
+    let column_mapping: ColumnMapping = serde_json::from_str(&column_mapping_json)
+        .map_err(|e| AppError::Validation(format!("Invalid column mapping JSON: {}", e)))?;
+
+    start_import(&state, file_name, file_data, ImportTarget::Cards(state.card_service.clone(), column_mapping)).await
+}
+
+/// Start bulk relationship import job
+///
+/// Relationships are created one row at a time, so the relationship rules apply to each
+pub async fn bulk_import_relationships(
+    State(state): State<AppState>,
+    multipart: Multipart,
+) -> Result<Json<ImportResult>, AppError> {
+    let (file_name, file_data, column_mapping_json) = read_upload(multipart).await?;
+    let column_mapping: RelationshipColumnMapping = serde_json::from_str(&column_mapping_json)
+        .map_err(|e| AppError::Validation(format!("Invalid column mapping JSON: {}", e)))?;
+
+    start_import(&state, file_name, file_data, ImportTarget::Relationships(state.saga_orchestrator.clone(), column_mapping)).await
+}
+
+/// Read the uploaded file and its column mapping JSON from the multipart form
+async fn read_upload(mut multipart: Multipart) -> Result<(String, Vec<u8>, String), AppError> {
     let mut file_name = String::new();
     let mut file_data: Vec<u8> = Vec::new();
     let mut column_mapping_json = String::new();
@@ -143,10 +186,16 @@ pub async fn bulk_import_cards(
         return Err(AppError::Validation("No file uploaded".to_string()));
     }
 
-    // Parse column mapping
-    let column_mapping: ColumnMapping = serde_json::from_str(&column_mapping_json)
-        .map_err(|e| AppError::Validation(format!("Invalid column mapping JSON: {}", e)))?;
+    Ok((file_name, file_data, column_mapping_json))
+}
 
+/// Create an import job and process its rows in the background
+async fn start_import(
+    state: &AppState,
+    file_name: String,
+    file_data: Vec<u8>,
+    target: ImportTarget,
+) -> Result<Json<ImportResult>, AppError> {
     // Create import job
     let job_id = Uuid::new_v4();
     let job = ImportJob {
@@ -165,10 +214,15 @@ pub async fn bulk_import_cards(
     state.import_jobs.lock().await.insert(job_id, job.clone());
 
     // Process import in background
-    let card_service_clone = state.card_service.clone();
     let import_jobs_clone = state.import_jobs.clone();
+    // Run as the caller, so imported cards land in their workspace
+    let actor = current_actor();
     tokio::spawn(async move {
-        process_import(card_service_clone, import_jobs_clone, job_id, file_data, column_mapping).await;
+        let import = process_import(target, import_jobs_clone, job_id, file_name, file_data);
+        match actor {
+            Some(actor) => CURRENT_ACTOR.scope(actor, import).await,
+            None => import.await,
+        }
     });
 
     Ok(Json(ImportResult {
@@ -195,24 +249,324 @@ pub async fn get_import_status(
 }
 
 async fn process_import(
-    _card_service: Arc<CardService>,
+    target: ImportTarget,
     import_jobs: Arc<Mutex<std::collections::HashMap<Uuid, ImportJob>>>,
     job_id: Uuid,
-    _file_data: Vec<u8>,
-    _column_mapping: ColumnMapping,
+    file_name: String,
+    file_data: Vec<u8>,
+) {
+    let lower_name = file_name.to_lowercase();
+    if lower_name.ends_with(".xlsx") || lower_name.ends_with(".xls") {
+        finish_job(&import_jobs, job_id, ImportStatus::Failed, Some(ImportError {
+            row: 0,
+            field: "file".to_string(),
+            message: "Excel files are not supported yet; save the sheet as CSV".to_string(),
+            severity: ErrorSeverity::Error,
+        })).await;
+        return;
+    }
+
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file_data.as_slice());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            finish_job(&import_jobs, job_id, ImportStatus::Failed, Some(ImportError {
+                row: 1,
+                field: "file".to_string(),
+                message: format!("Failed to read CSV header: {}", e),
+                severity: ErrorSeverity::Error,
+            })).await;
+            return;
+        }
+    };
+    let records: Vec<_> = reader.records().collect();
+
+    if let Some(job) = import_jobs.lock().await.get_mut(&job_id) {
+        job.total_rows = records.len() as u32;
+    }
+
+    for (index, record) in records.into_iter().enumerate() {
+        // Row 1 is the header
+        let row = index as u32 + 2;
+        let result = match record {
+            Ok(record) => match &target {
+                ImportTarget::Cards(card_service, column_mapping) => match row_to_card_request(&headers, &record, column_mapping) {
+                    // Cards go through CardService, so metamodel rules and access checks apply
+                    Ok(req) => card_service.create(req).await.map(|_| ()).map_err(|e| row_errors(row, e)),
+                    Err(error) => Err(vec![ImportError { row, ..error }]),
+                },
+                ImportTarget::Relationships(saga_orchestrator, column_mapping) => match row_to_relationship_request(&headers, &record, column_mapping) {
+                    // Same path as the API, so relationship rules and graph sync apply
+                    Ok(req) => saga_orchestrator.create_relationship(req).await.map(|_| ()).map_err(|e| row_errors(row, e)),
+                    Err(error) => Err(vec![ImportError { row, ..error }]),
+                },
+            },
+            Err(e) => Err(vec![ImportError {
+                row,
+                field: "row".to_string(),
+                message: format!("Malformed CSV row: {}", e),
+                severity: ErrorSeverity::Error,
+            }]),
+        };
+
+        let mut jobs = import_jobs.lock().await;
+        if let Some(job) = jobs.get_mut(&job_id) {
+            job.processed_rows += 1;
+            match result {
+                Ok(()) => job.successful_rows += 1,
+                Err(errors) => {
+                    job.failed_rows += 1;
+                    job.errors.extend(errors);
+                }
+            }
+        }
+    }
+
+    finish_job(&import_jobs, job_id, ImportStatus::Completed, None).await;
+}
+
+async fn finish_job(
+    import_jobs: &Mutex<std::collections::HashMap<Uuid, ImportJob>>,
+    job_id: Uuid,
+    status: ImportStatus,
+    error: Option<ImportError>,
 ) {
-    // This is a simplified implementation
-    // In production, you would:
-    // 1. Parse CSV/Excel based on file type
-    // 2. Validate each row
-    // 3. Map columns using column_mapping
-    // 4. Insert cards in transaction batches
-    // 5. Update job progress
-
-    // For now, just mark as completed
     let mut jobs = import_jobs.lock().await;
     if let Some(job) = jobs.get_mut(&job_id) {
-        job.status = ImportStatus::Completed;
+        job.status = status;
+        job.errors.extend(error);
         job.completed_at = Some(chrono::Utc::now());
     }
 }
+
+/// Build a card from a CSV row; columns not mapped to a card field become attributes
+fn row_to_card_request(
+    headers: &csv::StringRecord,
+    record: &csv::StringRecord,
+    mapping: &ColumnMapping,
+) -> Result<CreateCardRequest, ImportError> {
+    let cell = |column: &Option<String>| -> Option<String> {
+        let column = column.as_deref()?;
+        let index = headers.iter().position(|header| header == column)?;
+        record.get(index).map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+    };
+    let invalid = |field: &str, message: String| ImportError {
+        row: 0,
+        field: field.to_string(),
+        message,
+        severity: ErrorSeverity::Error,
+    };
+
+    let name = cell(&mapping.name).ok_or_else(|| invalid("name", "Name is required".to_string()))?;
+    let card_type_value = cell(&mapping.card_type).ok_or_else(|| invalid("type", "Type is required".to_string()))?;
+    // Custom types are checked against the registry when the card is created
+    let card_type = CardType::from_name(&card_type_value);
+    let lifecycle_phase: LifecyclePhase = match cell(&mapping.lifecycle_phase) {
+        Some(phase) => serde_json::from_value(serde_json::Value::String(phase.clone()))
+            .map_err(|_| invalid("lifecycle_phase", format!("Unknown lifecycle phase: {}", phase)))?,
+        None => LifecyclePhase::Active,
+    };
+    let owner_id = cell(&mapping.owner_id)
+        .map(|owner| Uuid::parse_str(&owner).map_err(|_| invalid("owner_id", format!("Invalid owner ID: {}", owner))))
+        .transpose()?;
+    let quality_score = cell(&mapping.quality_score)
+        .map(|score| score.parse::<i32>().map_err(|_| invalid("quality_score", format!("Invalid quality score: {}", score))))
+        .transpose()?;
+    let tags = cell(&mapping.tags).map(|tags| {
+        tags.split([';', ','])
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect()
+    });
+
+    let mapped: Vec<&str> = [
+        &mapping.name,
+        &mapping.card_type,
+        &mapping.lifecycle_phase,
+        &mapping.description,
+        &mapping.tags,
+        &mapping.owner_id,
+        &mapping.quality_score,
+    ]
+    .into_iter()
+    .filter_map(|column| column.as_deref())
+    .collect();
+    let attributes: serde_json::Map<String, serde_json::Value> = headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(header, value)| !mapped.contains(header) && !value.trim().is_empty())
+        .map(|(header, value)| (header.to_string(), serde_json::Value::String(value.trim().to_string())))
+        .collect();
+
+    Ok(CreateCardRequest {
+        name,
+        card_type,
+        lifecycle_phase,
+        quality_score,
+        description: cell(&mapping.description),
+        owner_id,
+        team_id: None,
+        attributes: Some(serde_json::Value::Object(attributes)),
+        tags,
+    })
+}
+
+/// Build a relationship from a CSV row; unmapped columns become attributes
+fn row_to_relationship_request(
+    headers: &csv::StringRecord,
+    record: &csv::StringRecord,
+    mapping: &RelationshipColumnMapping,
+) -> Result<CreateRelationshipRequest, ImportError> {
+    let cell = |column: &Option<String>| -> Option<String> {
+        let column = column.as_deref()?;
+        let index = headers.iter().position(|header| header == column)?;
+        record.get(index).map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+    };
+    let invalid = |field: &str, message: String| ImportError {
+        row: 0,
+        field: field.to_string(),
+        message,
+        severity: ErrorSeverity::Error,
+    };
+    let card_id = |field: &str, column: &Option<String>| {
+        let value = cell(column).ok_or_else(|| invalid(field, "Card ID is required".to_string()))?;
+        Uuid::parse_str(&value).map_err(|_| invalid(field, format!("Invalid card ID: {}", value)))
+    };
+
+    let from_card_id = card_id("from_card_id", &mapping.from_card_id)?;
+    let to_card_id = card_id("to_card_id", &mapping.to_card_id)?;
+    let type_value = cell(&mapping.relationship_type).ok_or_else(|| invalid("type", "Type is required".to_string()))?;
+    let relationship_type: RelationshipType = serde_json::from_value(serde_json::Value::String(type_value.clone()))
+        .map_err(|_| invalid("type", format!("Unknown relationship type: {}", type_value)))?;
+    let confidence = cell(&mapping.confidence)
+        .map(|value| value.parse::<f64>().map_err(|_| invalid("confidence", format!("Invalid confidence: {}", value))))
+        .transpose()?;
+
+    let mapped: Vec<&str> = [
+        &mapping.from_card_id,
+        &mapping.to_card_id,
+        &mapping.relationship_type,
+        &mapping.valid_from,
+        &mapping.valid_to,
+        &mapping.confidence,
+    ]
+    .into_iter()
+    .filter_map(|column| column.as_deref())
+    .collect();
+    let attributes: serde_json::Map<String, serde_json::Value> = headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(header, value)| !mapped.contains(header) && !value.trim().is_empty())
+        .map(|(header, value)| (header.to_string(), serde_json::Value::String(value.trim().to_string())))
+        .collect();
+
+    Ok(CreateRelationshipRequest {
+        from_card_id,
+        to_card_id,
+        relationship_type,
+        valid_from: cell(&mapping.valid_from),
+        valid_to: cell(&mapping.valid_to),
+        attributes: Some(serde_json::Value::Object(attributes)),
+        confidence,
+    })
+}
+
+/// One import error per broken metamodel rule, naming the value and what is allowed
+fn row_errors(row: u32, error: AppError) -> Vec<ImportError> {
+    match error {
+        AppError::AttributeValidation(violations) => violations
+            .into_iter()
+            .map(|violation| {
+                let mut message = violation.message;
+                if let Some(value) = &violation.details.provided_value {
+                    message.push_str(&format!(" (provided {})", value));
+                }
+                if let Some(allowed) = &violation.details.allowed_values {
+                    message.push_str(&format!("; allowed: {}", allowed.join(", ")));
+                }
+                if let Some(pattern) = &violation.details.required_pattern {
+                    message.push_str(&format!("; pattern: {}", pattern));
+                }
+                ImportError {
+                    row,
+                    field: violation.field,
+                    message,
+                    severity: ErrorSeverity::Error,
+                }
+            })
+            .collect(),
+        other => vec![ImportError {
+            row,
+            field: "row".to_string(),
+            message: other.to_string(),
+            severity: ErrorSeverity::Error,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_to_card_request_maps_unmapped_columns_to_attributes() {
+        let headers = csv::StringRecord::from(vec!["Name", "Kind", "Tags", "hosting_type", "notes"]);
+        let record = csv::StringRecord::from(vec!["Billing", "Application", "finance; core", "SaaS", ""]);
+        let mapping = ColumnMapping {
+            name: Some("Name".to_string()),
+            card_type: Some("Kind".to_string()),
+            lifecycle_phase: None,
+            description: None,
+            tags: Some("Tags".to_string()),
+            owner_id: None,
+            quality_score: None,
+        };
+
+        let req = row_to_card_request(&headers, &record, &mapping).unwrap();
+        assert_eq!(req.name, "Billing");
+        assert_eq!(req.card_type, CardType::Application);
+        assert_eq!(req.tags, Some(vec!["finance".to_string(), "core".to_string()]));
+        // Empty cells are left out
+        assert_eq!(req.attributes, Some(serde_json::json!({"hosting_type": "SaaS"})));
+
+        // Names other than built-in types are custom types, checked against the registry on create
+        let record = csv::StringRecord::from(vec!["Billing", "DataObject", "", "", ""]);
+        let req = row_to_card_request(&headers, &record, &mapping).unwrap();
+        assert_eq!(req.card_type, CardType::Custom("DataObject".to_string()));
+
+        let record = csv::StringRecord::from(vec!["Billing", "", "", "", ""]);
+        let error = row_to_card_request(&headers, &record, &mapping).unwrap_err();
+        assert_eq!(error.field, "type");
+    }
+
+    #[test]
+    fn test_row_to_relationship_request() {
+        let from = Uuid::new_v4();
+        let to = Uuid::new_v4();
+        let headers = csv::StringRecord::from(vec!["From", "To", "Type", "Confidence", "note"]);
+        let mapping = RelationshipColumnMapping {
+            from_card_id: Some("From".to_string()),
+            to_card_id: Some("To".to_string()),
+            relationship_type: Some("Type".to_string()),
+            valid_from: None,
+            valid_to: None,
+            confidence: Some("Confidence".to_string()),
+        };
+
+        let record = csv::StringRecord::from(vec![from.to_string(), to.to_string(), "appliesTo".to_string(), "0.8".to_string(), "via CMDB".to_string()]);
+        let req = row_to_relationship_request(&headers, &record, &mapping).unwrap();
+        assert_eq!((req.from_card_id, req.to_card_id), (from, to));
+        assert_eq!(req.relationship_type, RelationshipType::AppliesTo);
+        assert_eq!(req.confidence, Some(0.8));
+        assert_eq!(req.attributes, Some(serde_json::json!({"note": "via CMDB"})));
+
+        let record = csv::StringRecord::from(vec![from.to_string(), to.to_string(), "linksTo".to_string(), String::new(), String::new()]);
+        let error = row_to_relationship_request(&headers, &record, &mapping).unwrap_err();
+        assert_eq!(error.field, "type");
+
+        let record = csv::StringRecord::from(vec![from.to_string(), "billing".to_string(), "appliesTo".to_string(), String::new(), String::new()]);
+        let error = row_to_relationship_request(&headers, &record, &mapping).unwrap_err();
+        assert_eq!(error.field, "to_card_id");
+    }
+}
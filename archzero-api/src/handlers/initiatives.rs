@@ -34,6 +34,9 @@ pub async fn list_initiatives(
         card_type: Some(CardType::Initiative),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: Some(page),
         page_size: Some(20),
     };
@@ -325,6 +328,7 @@ pub async fn create_initiative(
         quality_score: None,
         description: req.description,
         owner_id: None,
+        team_id: None,
         attributes: Some(serde_json::Value::Object(attributes)),
         tags: None,
     };
@@ -444,6 +448,8 @@ pub async fn update_initiative(
         description: req.description.clone(),
         attributes: Some(serde_json::Value::Object(attributes)),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = state.saga_orchestrator.update_card(id, card_req).await?;
@@ -584,7 +590,7 @@ pub async fn get_initiative_impact_map(
                         .unwrap_or("No description")
                         .to_string();
 
-                    let card_type_str = format!("{:?}", target_card.card_type);
+                    let card_type_str = target_card.card_type.to_string();
 
                     impacted_cards.push(ImpactedCard {
                         card_id: target_card.id,
@@ -662,7 +668,7 @@ pub async fn link_cards_to_initiative(
                     confidence: None,
                 };
 
-                match state.relationship_service.create(relationship_req).await {
+                match state.saga_orchestrator.create_relationship(relationship_req).await {
                     Ok(_) => linked_count += 1,
                     Err(e) => {
                         tracing::warn!("Failed to link card {} to initiative {}: {}", card_link.card_id, id, e);
@@ -0,0 +1,489 @@
+/*!
+ * Metamodel Handlers
+ *
+ * Card types, attribute rules per card type, the relationship rules matrix and versioned
+ * migrations of existing cards.
+ * Everyone can read them (card forms use them); only admins change them.
+ */
+
+use axum::{extract::{Extension, Path, Query, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::card_type::*,
+    models::metamodel::*,
+    models::relationship::*,
+    models::user::Claims,
+    state::AppState,
+};
+
+/// List metamodel rules, optionally for one card type
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/rules",
+    params(
+        ("card_type" = Option<String>, Query, description = "Only rules of this card type")
+    ),
+    responses(
+        (status = 200, description = "Metamodel rules", body = Vec<MetamodelRule>)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn list_metamodel_rules(
+    State(state): State<AppState>,
+    Query(query): Query<MetamodelRuleQuery>,
+) -> Result<Json<Vec<MetamodelRule>>, AppError> {
+    let rules = state.metamodel_service.list(query.card_type.as_deref()).await?;
+    Ok(Json(rules))
+}
+
+/// Get a metamodel rule
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID")
+    ),
+    responses(
+        (status = 200, description = "Metamodel rule", body = MetamodelRule),
+        (status = 404, description = "Rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn get_metamodel_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<MetamodelRule>, AppError> {
+    let rule = state.metamodel_service.get(id).await?;
+    Ok(Json(rule))
+}
+
+/// Add an attribute rule to a card type
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/rules",
+    request_body = CreateMetamodelRuleRequest,
+    responses(
+        (status = 200, description = "Rule created", body = MetamodelRule),
+        (status = 400, description = "Unknown card type, invalid pattern or empty allowed values"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "The card type already has a rule for the attribute")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn create_metamodel_rule(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateMetamodelRuleRequest>,
+) -> Result<Json<MetamodelRule>, AppError> {
+    let rule = state.metamodel_service.create(req, Uuid::parse_str(&claims.sub).ok()).await?;
+    state.data_quality_service.recalculate_in_background(Some(rule.card_type.clone()));
+    Ok(Json(rule))
+}
+
+/// Replace the settings of a rule
+#[utoipa::path(
+    put,
+    path = "/api/v1/metamodel/rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID")
+    ),
+    request_body = UpdateMetamodelRuleRequest,
+    responses(
+        (status = 200, description = "Rule updated", body = MetamodelRule),
+        (status = 400, description = "Invalid pattern or empty allowed values"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn update_metamodel_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateMetamodelRuleRequest>,
+) -> Result<Json<MetamodelRule>, AppError> {
+    let rule = state.metamodel_service.update(id, req).await?;
+    state.data_quality_service.recalculate_in_background(Some(rule.card_type.clone()));
+    Ok(Json(rule))
+}
+
+/// Delete a rule
+#[utoipa::path(
+    delete,
+    path = "/api/v1/metamodel/rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID")
+    ),
+    responses(
+        (status = 200, description = "Rule deleted"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn delete_metamodel_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    let rule = state.metamodel_service.get(id).await?;
+    state.metamodel_service.delete(id).await?;
+    state.data_quality_service.recalculate_in_background(Some(rule.card_type));
+    Ok(Json(()))
+}
+
+/// Check card attributes against the rules without saving anything
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/validate",
+    request_body = ValidateAttributesRequest,
+    responses(
+        (status = 200, description = "Violations, if any", body = ValidateAttributesResponse),
+        (status = 400, description = "Unknown card type")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn validate_card_attributes(
+    State(state): State<AppState>,
+    Json(req): Json<ValidateAttributesRequest>,
+) -> Result<Json<ValidateAttributesResponse>, AppError> {
+    let violations = state.metamodel_service.check(&req.card_type, &req.attributes).await?;
+    Ok(Json(ValidateAttributesResponse {
+        valid: violations.is_empty(),
+        violations,
+    }))
+}
+
+/// Current metamodel version and retired card types
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/version",
+    responses(
+        (status = 200, description = "Metamodel version", body = MetamodelVersionInfo)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn get_metamodel_version(
+    State(state): State<AppState>,
+) -> Result<Json<MetamodelVersionInfo>, AppError> {
+    let info = state.metamodel_migration_service.version_info().await?;
+    Ok(Json(info))
+}
+
+/// List metamodel migrations, newest version first
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/migrations",
+    responses(
+        (status = 200, description = "Metamodel migrations", body = Vec<MetamodelMigration>)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn list_metamodel_migrations(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MetamodelMigration>>, AppError> {
+    let migrations = state.metamodel_migration_service.list().await?;
+    Ok(Json(migrations))
+}
+
+/// Get a metamodel migration and its progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/migrations/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Migration ID")
+    ),
+    responses(
+        (status = 200, description = "Metamodel migration", body = MetamodelMigration),
+        (status = 404, description = "Migration not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn get_metamodel_migration(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<MetamodelMigration>, AppError> {
+    let migration = state.metamodel_migration_service.get(id).await?;
+    Ok(Json(migration))
+}
+
+/// Report what a migration would change without changing anything
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/migrations/dry-run",
+    request_body = MetamodelOperation,
+    responses(
+        (status = 200, description = "Affected cards and rule changes", body = MetamodelDryRun),
+        (status = 400, description = "Invalid operation"),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn dry_run_metamodel_migration(
+    State(state): State<AppState>,
+    Json(operation): Json<MetamodelOperation>,
+) -> Result<Json<MetamodelDryRun>, AppError> {
+    let report = state.metamodel_migration_service.dry_run(&operation).await?;
+    Ok(Json(report))
+}
+
+/// Start a migration to a new metamodel version; cards are migrated in the background
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/migrations",
+    request_body = CreateMetamodelMigrationRequest,
+    responses(
+        (status = 200, description = "Migration started", body = MetamodelMigration),
+        (status = 400, description = "Invalid operation or another migration in progress"),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn create_metamodel_migration(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateMetamodelMigrationRequest>,
+) -> Result<Json<MetamodelMigration>, AppError> {
+    let migration = state.metamodel_migration_service.clone()
+        .start(req, Uuid::parse_str(&claims.sub).ok())
+        .await?;
+    Ok(Json(migration))
+}
+
+/// List card types, built in and registered at runtime
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/card-types",
+    params(
+        ("layer" = Option<CardLayer>, Query, description = "Only card types of this layer")
+    ),
+    responses(
+        (status = 200, description = "Card types", body = Vec<CardTypeDefinition>)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn list_card_types(
+    State(state): State<AppState>,
+    Query(query): Query<CardTypeQuery>,
+) -> Result<Json<Vec<CardTypeDefinition>>, AppError> {
+    let card_types = state.card_type_service.list(query.layer).await?;
+    Ok(Json(card_types))
+}
+
+/// Get a card type
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/card-types/{name}",
+    params(
+        ("name" = String, Path, description = "Card type name")
+    ),
+    responses(
+        (status = 200, description = "Card type", body = CardTypeDefinition),
+        (status = 404, description = "Card type not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn get_card_type(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<CardTypeDefinition>, AppError> {
+    let card_type = state.card_type_service.get(&name).await?;
+    Ok(Json(card_type))
+}
+
+/// Register a card type
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/card-types",
+    request_body = CreateCardTypeRequest,
+    responses(
+        (status = 200, description = "Card type registered", body = CardTypeDefinition),
+        (status = 400, description = "Invalid name, colour, schema or relationship type"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "A card type with this name exists")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn create_card_type(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateCardTypeRequest>,
+) -> Result<Json<CardTypeDefinition>, AppError> {
+    let card_type = state.card_type_service.create(req, Uuid::parse_str(&claims.sub).ok()).await?;
+    Ok(Json(card_type))
+}
+
+/// Replace the settings of a card type
+#[utoipa::path(
+    put,
+    path = "/api/v1/metamodel/card-types/{name}",
+    params(
+        ("name" = String, Path, description = "Card type name")
+    ),
+    request_body = UpdateCardTypeRequest,
+    responses(
+        (status = 200, description = "Card type updated", body = CardTypeDefinition),
+        (status = 400, description = "Invalid colour, schema or relationship type"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Card type not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn update_card_type(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateCardTypeRequest>,
+) -> Result<Json<CardTypeDefinition>, AppError> {
+    let card_type = state.card_type_service.update(&name, req).await?;
+    // The attribute schema decides which attributes are required and recommended
+    state.data_quality_service.recalculate_in_background(Some(card_type.name.clone()));
+    Ok(Json(card_type))
+}
+
+/// Delete a custom card type no card uses
+#[utoipa::path(
+    delete,
+    path = "/api/v1/metamodel/card-types/{name}",
+    params(
+        ("name" = String, Path, description = "Card type name")
+    ),
+    responses(
+        (status = 200, description = "Card type deleted"),
+        (status = 400, description = "Built-in card type or still used by cards"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Card type not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn delete_card_type(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    state.card_type_service.delete(&name).await?;
+    Ok(Json(()))
+}
+
+/// List relationship rules, optionally for one relationship type
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/relationship-rules",
+    params(
+        ("relationshipType" = Option<RelationshipType>, Query, description = "Only rules of this relationship type")
+    ),
+    responses(
+        (status = 200, description = "Relationship rules", body = Vec<RelationshipRule>)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn list_relationship_rules(
+    State(state): State<AppState>,
+    Query(query): Query<RelationshipRuleQuery>,
+) -> Result<Json<Vec<RelationshipRule>>, AppError> {
+    let rules = state.relationship_rule_service.list(query.relationship_type.as_ref()).await?;
+    Ok(Json(rules))
+}
+
+/// Get a relationship rule
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/relationship-rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Relationship rule ID")
+    ),
+    responses(
+        (status = 200, description = "Relationship rule", body = RelationshipRule),
+        (status = 404, description = "Relationship rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn get_relationship_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<RelationshipRule>, AppError> {
+    let rule = state.relationship_rule_service.get(id).await?;
+    Ok(Json(rule))
+}
+
+/// Allow a relationship type between two card types
+#[utoipa::path(
+    post,
+    path = "/api/v1/metamodel/relationship-rules",
+    request_body = CreateRelationshipRuleRequest,
+    responses(
+        (status = 200, description = "Relationship rule created", body = RelationshipRule),
+        (status = 400, description = "Unknown card type"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "A rule for these types exists")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn create_relationship_rule(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateRelationshipRuleRequest>,
+) -> Result<Json<RelationshipRule>, AppError> {
+    let rule = state.relationship_rule_service.create(req, Uuid::parse_str(&claims.sub).ok()).await?;
+    Ok(Json(rule))
+}
+
+/// Replace the cardinality, direction and description of a relationship rule
+#[utoipa::path(
+    put,
+    path = "/api/v1/metamodel/relationship-rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Relationship rule ID")
+    ),
+    request_body = UpdateRelationshipRuleRequest,
+    responses(
+        (status = 200, description = "Relationship rule updated", body = RelationshipRule),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Relationship rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn update_relationship_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateRelationshipRuleRequest>,
+) -> Result<Json<RelationshipRule>, AppError> {
+    let rule = state.relationship_rule_service.update(id, req).await?;
+    Ok(Json(rule))
+}
+
+/// Delete a relationship rule
+#[utoipa::path(
+    delete,
+    path = "/api/v1/metamodel/relationship-rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Relationship rule ID")
+    ),
+    responses(
+        (status = 200, description = "Relationship rule deleted"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Relationship rule not found")
+    ),
+    tag = "Metamodel"
+)]
+pub async fn delete_relationship_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    state.relationship_rule_service.delete(id).await?;
+    Ok(Json(()))
+}
+
+/// Existing relationships the current rules would reject
+#[utoipa::path(
+    get,
+    path = "/api/v1/metamodel/relationship-rules/violations",
+    responses(
+        (status = 200, description = "Relationships breaking a rule", body = RelationshipViolationReport)
+    ),
+    tag = "Metamodel"
+)]
+pub async fn list_relationship_rule_violations(
+    State(state): State<AppState>,
+) -> Result<Json<RelationshipViolationReport>, AppError> {
+    let report = state.relationship_rule_service.violations().await?;
+    Ok(Json(report))
+}
@@ -1,4 +1,6 @@
+pub mod api_tokens;
 pub mod arb;
+pub mod architecture_states;
 pub mod auth;
 pub mod bia;
 pub mod bulk;
@@ -6,24 +8,33 @@ pub mod cache;
 pub mod cards;
 pub mod compliance;
 pub mod csrf;
+pub mod data_quality;
 pub mod exceptions;
 pub mod export;
 pub mod graph;
+pub mod graph_sync;
 pub mod health;
 pub mod import;
 pub mod initiatives;
+pub mod metamodel;
 pub mod migration;
 pub mod policies;
 pub mod principles;
 pub mod relationships;
 pub mod reports;
 pub mod risks;
+pub mod saved_searches;
+pub mod scim;
 pub mod standards;
 pub mod tco;
+pub mod teams;
 pub mod test_reset;
 pub mod users;
+pub mod workspaces;
 
+pub use api_tokens::*;
 pub use arb::*;
+pub use architecture_states::*;
 pub use auth::*;
 pub use bia::*;
 pub use bulk::*;
@@ -31,19 +42,25 @@ pub use cache::*;
 pub use cards::*;
 pub use compliance::*;
 pub use csrf::*;
+pub use data_quality::*;
 pub use exceptions::*;
 pub use export::*;
 pub use graph::*;
+pub use graph_sync::*;
 pub use health::*;
 pub use import::*;
 pub use initiatives::*;
+pub use metamodel::*;
 pub use migration::*;
 pub use policies::*;
 pub use principles::*;
 pub use relationships::*;
 pub use reports::*;
 pub use risks::*;
+pub use scim::*;
 pub use standards::*;
 pub use tco::*;
+pub use teams::*;
 pub use test_reset::*;
 pub use users::*;
+pub use workspaces::*;
@@ -6,7 +6,7 @@ use crate::state::AppState;
 // Re-export all policy models at the handler level for OpenAPI schema access
 pub use crate::models::policies::*;
 
-use crate::models::card::{Card, CardType, CardSearchParams, CreateCardRequest, UpdateCardRequest, LifecyclePhase};
+use crate::models::card::{Card, CardPage, CardType, CardSearchParams, CreateCardRequest, UpdateCardRequest, LifecyclePhase};
 use crate::Result;
 use crate::error::AppError;
 
@@ -19,6 +19,7 @@ use crate::error::AppError;
     params(PolicySearchParams),
     responses(
         (status = 200, description = "Policies retrieved successfully", body = PolicyListResponse),
+        (status = 400, description = "Invalid sort or cursor"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Policies"
@@ -28,6 +29,8 @@ pub async fn list_policies(
     Query(params): Query<PolicySearchParams>,
 ) -> Result<Json<PolicyListResponse>> {
     let card_service = state.saga_orchestrator.get_card_service();
+    let page = params.page.unwrap_or(1);
+    let page_size = params.page_size.unwrap_or(50);
 
     // Build card search params to filter for ArchitecturePolicy type
     let card_params = CardSearchParams {
@@ -35,14 +38,17 @@ pub async fn list_policies(
         q: None,
         lifecycle_phase: None,
         tags: None,
-        page: params.page,
-        page_size: params.page_size,
+        query: None,
+        sort: params.sort.clone(),
+        cursor: params.cursor.clone(),
+        page: Some(page),
+        page_size: Some(page_size),
     };
 
-    let (cards, total) = card_service.list(card_params).await?;
+    let CardPage { cards, total, next_cursor } = card_service.list_page(card_params).await?;
 
-    // Filter policies by severity and enforcement mode
-    let mut policies: Vec<ArchitecturePolicy> = cards
+    // Filter policies by severity and enforcement mode; the cards are already paged
+    let policies: Vec<ArchitecturePolicy> = cards
         .into_iter()
         .filter_map(|card| card_to_policy(card))
         .filter(|policy| {
@@ -60,25 +66,13 @@ pub async fn list_policies(
         })
         .collect();
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(50);
-
-    // Manual pagination after filtering
-    let start = ((page.saturating_sub(1)) * page_size) as usize;
-    let end = (start + page_size as usize).min(policies.len());
-
-    let paginated_policies = if start < policies.len() {
-        policies.drain(start..end).collect()
-    } else {
-        Vec::new()
-    };
-
     Ok(Json(PolicyListResponse {
-        data: paginated_policies,
+        data: policies,
         pagination: PolicyPagination {
             page,
             limit: page_size,
             total,
+            next_cursor,
         },
     }))
 }
@@ -142,6 +136,7 @@ pub async fn create_policy(
         quality_score: None,
         description: req.description,
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: None,
     };
@@ -205,6 +200,8 @@ pub async fn update_policy(
         description: req.description,
         attributes: Some(attributes),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let updated_card = state.saga_orchestrator.update_card(id, card_req).await?;
@@ -271,6 +268,9 @@ pub async fn check_policy_compliance(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
@@ -467,6 +467,9 @@ pub async fn list_violations(
         q: None,
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
@@ -498,6 +501,9 @@ pub async fn list_violations(
                 q: None,
                 lifecycle_phase: None,
                 tags: None,
+                query: None,
+                sort: None,
+                cursor: None,
                 page: None,
                 page_size: None,
             }).await?;
@@ -586,14 +592,14 @@ fn evaluate_policy_against_card(policy_card: &Card, card: &Card) -> CardComplian
 }
 
 /// Internal result of policy evaluation
-struct PolicyEvaluationResult {
-    status: ComplianceStatus,
+pub(crate) struct PolicyEvaluationResult {
+    pub(crate) status: ComplianceStatus,
     missing_requirements: Option<Vec<String>>,
     violation_details: Option<Vec<String>>,
 }
 
-/// Evaluate policy rules against card attributes
-fn evaluate_policy_rules(
+/// Evaluate policy rules against card attributes; also scores policy compliance in data quality
+pub(crate) fn evaluate_policy_rules(
     rule_json: &serde_json::Value,
     card_attrs: &serde_json::Value,
     card_type: &crate::models::card::CardType,
@@ -58,7 +58,7 @@ fn card_to_principle(card: crate::models::card::Card) -> Result<ArchitecturePrin
         id: card.id,
         name: card.name,
         description: card.description,
-        card_type: format!("{:?}", card.card_type),
+        card_type: card.card_type.to_string(),
         statement,
         rationale,
         implications,
@@ -96,6 +96,9 @@ pub async fn list_principles(
         card_type: Some(CardType::ArchitecturePrinciple),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: Some(page),
         page_size: Some(limit),
     };
@@ -210,6 +213,7 @@ pub async fn create_principle(
         quality_score: None,
         description: req.description,
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: None,
     };
@@ -277,6 +281,8 @@ pub async fn update_principle(
         description: req.description,
         attributes: Some(attrs),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let updated_card = state.saga_orchestrator.update_card(id, update_req).await?;
@@ -354,6 +360,9 @@ pub async fn get_principle_compliance(
         card_type: None, // All card types
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: Some(1),
         page_size: Some(10000), // Get all cards
     };
@@ -1,8 +1,9 @@
-use axum::{extract::{Path, Query, State}, Json};
+use axum::{extract::{Path, Query, State}, http::{HeaderMap, HeaderValue}, Json};
 use uuid::Uuid;
 use utoipa::ToSchema;
 
 use crate::models::relationship::{Relationship, CreateRelationshipRequest, UpdateRelationshipRequest};
+use crate::models::version::RelationshipVersion;
 use crate::state::AppState;
 use crate::Result;
 
@@ -22,7 +23,7 @@ pub async fn create_relationship(
     State(state): State<AppState>,
     Json(req): Json<CreateRelationshipRequest>,
 ) -> Result<Json<Relationship>> {
-    let relationship = state.relationship_service.create(req).await?;
+    let relationship = state.saga_orchestrator.create_relationship(req).await?;
     Ok(Json(relationship))
 }
 
@@ -48,15 +49,20 @@ pub async fn get_relationship(
     Ok(Json(relationship))
 }
 
-/// List relationships
+/// List relationships; with a `limit`, the cursor of the next page is in the `X-Next-Cursor` header
 #[utoipa::path(
     get,
     path = "/api/v1/relationships",
     params(
-        ("card_id" = Option<Uuid>, Query, description = "Filter by card ID")
+        ("card_id" = Option<Uuid>, Query, description = "Filter by card ID"),
+        ("sort" = Option<String>, Query, description = "Sort fields, e.g. -confidence,attributes.criticality; newest first by default"),
+        ("cursor" = Option<String>, Query, description = "X-Next-Cursor of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Page size, at most 1000; all relationships when not given")
     ),
     responses(
-        (status = 200, description = "Relationships retrieved successfully", body = Vec<Relationship>),
+        (status = 200, description = "Relationships retrieved successfully", body = Vec<Relationship>,
+            headers(("X-Next-Cursor" = String, description = "Cursor of the next page, absent on the last page"))),
+        (status = 400, description = "Invalid sort or cursor"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Relationships"
@@ -64,13 +70,16 @@ pub async fn get_relationship(
 pub async fn list_relationships(
     State(state): State<AppState>,
     Query(params): Query<CardRelationshipParams>,
-) -> Result<Json<Vec<Relationship>>> {
-    let relationships = if let Some(card_id) = params.card_id {
-        state.relationship_service.list_for_card(card_id).await?
-    } else {
-        state.relationship_service.list_all().await?
-    };
-    Ok(Json(relationships))
+) -> Result<(HeaderMap, Json<Vec<Relationship>>)> {
+    let (relationships, next_cursor) = state.relationship_service
+        .list_page(params.card_id, params.sort.as_deref(), params.cursor.as_deref(), params.limit)
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    if let Some(cursor) = next_cursor.and_then(|cursor| HeaderValue::from_str(&cursor).ok()) {
+        headers.insert("x-next-cursor", cursor);
+    }
+    Ok((headers, Json(relationships)))
 }
 
 /// Update a relationship
@@ -94,7 +103,7 @@ pub async fn update_relationship(
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateRelationshipRequest>,
 ) -> Result<Json<Relationship>> {
-    let relationship = state.relationship_service.update(id, req).await?;
+    let relationship = state.saga_orchestrator.update_relationship(id, req).await?;
     Ok(Json(relationship))
 }
 
@@ -116,11 +125,35 @@ pub async fn delete_relationship(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<()>> {
-    state.relationship_service.delete(id).await?;
+    state.saga_orchestrator.delete_relationship(id).await?;
     Ok(Json(()))
 }
 
+/// List a relationship's version history, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/relationships/{id}/history",
+    params(
+        ("id" = Uuid, Path, description = "Relationship ID")
+    ),
+    responses(
+        (status = 200, description = "Relationship versions", body = Vec<RelationshipVersion>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Relationships"
+)]
+pub async fn get_relationship_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<RelationshipVersion>>> {
+    let history = state.version_service.relationship_history(id).await?;
+    Ok(Json(history))
+}
+
 #[derive(serde::Deserialize, ToSchema)]
 pub struct CardRelationshipParams {
     pub card_id: Option<Uuid>,
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
 }
@@ -1,4 +1,4 @@
-use axum::extract::{Json, State, Path};
+use axum::extract::{Extension, Json, State, Path};
 use axum::response::IntoResponse;
 use axum::http::{header, StatusCode};
 use uuid::Uuid;
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     error::AppError,
     state::AppState,
+    models::user::Claims,
     services::report_service::{ReportRequest, ReportFormat},
     models::export::{ReportTemplate, CreateReportTemplateRequest, UpdateReportTemplateRequest},
 };
@@ -120,6 +121,9 @@ pub struct CustomReportRequest {
     pub format: ReportFormat,
     #[serde(default)]
     pub filters: Option<serde_json::Value>,
+    /// Include the cards of a saved search in the report data
+    #[serde(default)]
+    pub saved_search_id: Option<Uuid>,
 }
 
 /// Generate a custom report endpoint
@@ -133,6 +137,7 @@ pub struct CustomReportRequest {
         (status = 200, description = "Custom report generated successfully", content_type = "application/pdf"),
         (status = 200, description = "Custom report generated successfully", content_type = "application/vnd.openxmlformats-officedocument.presentationml.presentation"),
         (status = 400, description = "Invalid section structure"),
+        (status = 404, description = "Saved search not found"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Reports",
@@ -140,6 +145,7 @@ pub struct CustomReportRequest {
 )]
 pub async fn generate_custom_report(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Json(req): Json<CustomReportRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate title
@@ -161,11 +167,19 @@ pub async fn generate_custom_report(
     // Capture format
     let format = req.format.clone();
 
+    // Cards of the saved search the report is filtered by
+    let cards = match req.saved_search_id {
+        Some(saved_search_id) => Some(state.saved_search_service.cards(saved_search_id, &claims).await?),
+        None => None,
+    };
+
     // Convert custom report to standard report request
     let data = serde_json::json!({
         "title": req.title,
         "sections": req.sections,
-        "filters": req.filters
+        "filters": req.filters,
+        "savedSearchId": req.saved_search_id,
+        "cards": cards
     });
 
     let report_request = ReportRequest {
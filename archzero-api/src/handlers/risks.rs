@@ -5,7 +5,7 @@ use axum::{
 use uuid::Uuid;
 
 use crate::models::risks::*;
-use crate::models::card::{CardType, CreateCardRequest, UpdateCardRequest};
+use crate::models::card::{CardPage, CardType, CreateCardRequest, UpdateCardRequest};
 use crate::error::AppError;
 use crate::state::AppState;
 use crate::Result;
@@ -18,10 +18,13 @@ use crate::Result;
         ("risk_type" = Option<RiskType>, Query, description = "Filter by risk type"),
         ("status" = Option<RiskStatus>, Query, description = "Filter by status"),
         ("min_score" = Option<i32>, Query, description = "Filter by minimum risk score"),
+        ("sort" = Option<String>, Query, description = "Sort fields, e.g. -attributes.riskScore,name"),
+        ("cursor" = Option<String>, Query, description = "Cursor of the next page from a previous response"),
         ("page" = Option<u32>, Query, description = "Page number")
     ),
     responses(
         (status = 200, description = "List of risks", body = RiskListResponse),
+        (status = 400, description = "Invalid sort or cursor"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Risks"
@@ -36,12 +39,15 @@ pub async fn list_risks(
         card_type: Some(CardType::Risk),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: params.sort.clone(),
+        cursor: params.cursor.clone(),
         page: params.page,
         page_size: None,
     };
 
     // Get all risk cards
-    let (cards, total) = state.card_service.list(card_params).await?;
+    let CardPage { cards, total, next_cursor } = state.card_service.list_page(card_params).await?;
 
     // Convert cards to risks and apply filters
     let risks: Vec<Risk> = cards
@@ -81,6 +87,7 @@ pub async fn list_risks(
             limit,
             total,
             total_pages,
+            next_cursor,
         },
     }))
 }
@@ -171,6 +178,7 @@ pub async fn create_risk(
         quality_score: None,
         description: Some(req.description),
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: None,
     };
@@ -298,6 +306,8 @@ pub async fn update_risk(
         quality_score: None,
         attributes: Some(attributes),
         tags: None,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = state.card_service.update(id, card_req).await?;
@@ -347,6 +357,9 @@ pub async fn get_risk_heat_map(
         card_type: Some(CardType::Risk),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
@@ -410,6 +423,9 @@ pub async fn get_top_risks(
         card_type: Some(CardType::Risk),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
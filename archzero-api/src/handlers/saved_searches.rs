@@ -0,0 +1,204 @@
+/*!
+ * Saved Search Handlers
+ *
+ * Named card searches, personal or shared with a team, pinned per user. Anyone who can
+ * read cards can save searches; only the owner or an admin can change or delete one.
+ */
+
+use axum::{extract::{Extension, Path, Query, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    handlers::cards::CardListResponse,
+    models::card::CardPage,
+    models::saved_search::*,
+    models::user::Claims,
+    state::AppState,
+};
+
+/// List the saved searches the current user owns or that are shared with their teams
+#[utoipa::path(
+    get,
+    path = "/api/v1/saved-searches",
+    responses(
+        (status = 200, description = "Saved searches, pinned first", body = Vec<SavedSearch>),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn list_saved_searches(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<SavedSearch>>, AppError> {
+    let searches = state.saved_search_service.list(&claims).await?;
+    Ok(Json(searches))
+}
+
+/// Save a search
+#[utoipa::path(
+    post,
+    path = "/api/v1/saved-searches",
+    request_body = CreateSavedSearchRequest,
+    responses(
+        (status = 200, description = "Saved search created", body = SavedSearch),
+        (status = 400, description = "Invalid query, sort or columns"),
+        (status = 403, description = "Sharing with a team the user is not a member of"),
+        (status = 404, description = "Team not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn create_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateSavedSearchRequest>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let search = state.saved_search_service.create(req, &claims).await?;
+    Ok(Json(search))
+}
+
+/// Get a saved search
+#[utoipa::path(
+    get,
+    path = "/api/v1/saved-searches/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Saved search", body = SavedSearch),
+        (status = 404, description = "Saved search not found or not shared with the user")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn get_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let search = state.saved_search_service.get(id, &claims).await?;
+    Ok(Json(search))
+}
+
+/// Replace a saved search
+#[utoipa::path(
+    put,
+    path = "/api/v1/saved-searches/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    request_body = UpdateSavedSearchRequest,
+    responses(
+        (status = 200, description = "Saved search updated", body = SavedSearch),
+        (status = 400, description = "Invalid query, sort or columns"),
+        (status = 403, description = "Not the owner"),
+        (status = 404, description = "Saved search not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn update_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateSavedSearchRequest>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let search = state.saved_search_service.update(id, req, &claims).await?;
+    Ok(Json(search))
+}
+
+/// Delete a saved search
+#[utoipa::path(
+    delete,
+    path = "/api/v1/saved-searches/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Saved search deleted"),
+        (status = 403, description = "Not the owner"),
+        (status = 404, description = "Saved search not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn delete_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    state.saved_search_service.delete(id, &claims).await?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// Run a saved search; results match `GET /api/v1/cards` with the saved filters
+#[utoipa::path(
+    get,
+    path = "/api/v1/saved-searches/{id}/run",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID"),
+        SavedSearchRunParams
+    ),
+    responses(
+        (status = 200, description = "Matching cards", body = CardListResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 404, description = "Saved search not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn run_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<SavedSearchRunParams>,
+) -> Result<Json<CardListResponse>, AppError> {
+    let CardPage { cards, total, next_cursor } = state.saved_search_service.run(id, &params, &claims).await?;
+    Ok(Json(CardListResponse {
+        data: cards,
+        total,
+        page: params.page.unwrap_or(1),
+        page_size: params.page_size.unwrap_or(20),
+        next_cursor,
+    }))
+}
+
+/// Pin a saved search for the current user
+#[utoipa::path(
+    put,
+    path = "/api/v1/saved-searches/{id}/pin",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Saved search pinned", body = SavedSearch),
+        (status = 404, description = "Saved search not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn pin_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let search = state.saved_search_service.pin(id, &claims).await?;
+    Ok(Json(search))
+}
+
+/// Unpin a saved search for the current user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/saved-searches/{id}/pin",
+    params(
+        ("id" = Uuid, Path, description = "Saved search ID")
+    ),
+    responses(
+        (status = 200, description = "Saved search unpinned", body = SavedSearch),
+        (status = 404, description = "Saved search not found")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn unpin_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let search = state.saved_search_service.unpin(id, &claims).await?;
+    Ok(Json(search))
+}
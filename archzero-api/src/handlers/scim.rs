@@ -0,0 +1,331 @@
+/*!
+ * SCIM 2.0 Handlers
+ *
+ * `/scim/v2/Users` and `/scim/v2/Groups` for identity platforms. Responses use the
+ * `application/scim+json` media type and errors the SCIM error schema. Clients
+ * authenticate with a service-account API token holding the `users:*` scopes.
+ */
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::scim::*,
+    state::AppState,
+};
+
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+/// A SCIM response body with the SCIM media type
+pub struct ScimJson<T>(pub StatusCode, pub T);
+
+impl<T: Serialize> IntoResponse for ScimJson<T> {
+    fn into_response(self) -> Response {
+        (self.0, [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)], Json(self.1)).into_response()
+    }
+}
+
+/// `AppError` rendered with the SCIM error schema
+pub struct ScimError(AppError);
+
+impl From<AppError> for ScimError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let (status, scim_type, detail) = match self.0 {
+            AppError::Database(e) if e.as_database_error().and_then(|db| db.code()).as_deref() == Some("23505") => {
+                (StatusCode::CONFLICT, Some("uniqueness"), "Resource already exists".to_string())
+            }
+            AppError::Validation(msg) if msg.starts_with("Invalid filter") => {
+                (StatusCode::BAD_REQUEST, Some("invalidFilter"), msg)
+            }
+            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, Some("invalidValue"), msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, None, msg),
+            AppError::Auth(msg) => (StatusCode::UNAUTHORIZED, None, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, None, msg),
+            other => {
+                tracing::error!("SCIM request failed: {:?}", other);
+                (StatusCode::INTERNAL_SERVER_ERROR, None, "Internal server error".to_string())
+            }
+        };
+
+        let mut body = json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": status.as_u16().to_string(),
+            "detail": detail,
+        });
+        if let Some(scim_type) = scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+        ScimJson(status, body).into_response()
+    }
+}
+
+type ScimResult<T> = Result<ScimJson<T>, ScimError>;
+
+/// Capabilities advertised to provisioning clients
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ServiceProviderConfig",
+    responses(
+        (status = 200, description = "SCIM service provider configuration")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_service_provider_config(State(state): State<AppState>) -> ScimResult<serde_json::Value> {
+    state.scim_service.ensure_enabled()?;
+    Ok(ScimJson(StatusCode::OK, json!({
+        "schemas": [SERVICE_PROVIDER_CONFIG_SCHEMA],
+        "patch": {"supported": true},
+        "bulk": {"supported": false, "maxOperations": 0, "maxPayloadSize": 0},
+        "filter": {"supported": true, "maxResults": 200},
+        "changePassword": {"supported": false},
+        "sort": {"supported": false},
+        "etag": {"supported": false},
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "API token",
+            "description": "Service-account API token with the users:read, users:write and users:delete scopes"
+        }]
+    })))
+}
+
+/// List users, optionally filtered (e.g. `userName eq "jane@example.com"`)
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users",
+    params(
+        ("filter" = Option<String>, Query, description = "SCIM filter: eq, ne, co, sw, ew, pr joined with and"),
+        ("startIndex" = Option<i64>, Query, description = "1-based index of the first result"),
+        ("count" = Option<i64>, Query, description = "Page size (max 200)")
+    ),
+    responses(
+        (status = 200, description = "Matching users", body = ScimUserList),
+        (status = 400, description = "Invalid filter")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult<ScimListResponse<ScimUser>> {
+    state.scim_service.ensure_enabled()?;
+    let users = state.scim_service.list_users(&query).await?;
+    Ok(ScimJson(StatusCode::OK, users))
+}
+
+/// Get a user
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User", body = ScimUser),
+        (status = 404, description = "User not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_get_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> ScimResult<ScimUser> {
+    state.scim_service.ensure_enabled()?;
+    let user = state.scim_service.get_user(id).await?;
+    Ok(ScimJson(StatusCode::OK, user))
+}
+
+/// Provision a user
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Users",
+    request_body = ScimCreateUserRequest,
+    responses(
+        (status = 201, description = "User created", body = ScimUser),
+        (status = 400, description = "Invalid user"),
+        (status = 409, description = "userName already exists")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_create_user(
+    State(state): State<AppState>,
+    Json(req): Json<ScimCreateUserRequest>,
+) -> ScimResult<ScimUser> {
+    state.scim_service.ensure_enabled()?;
+    let user = state.scim_service.create_user(req).await?;
+    Ok(ScimJson(StatusCode::CREATED, user))
+}
+
+/// Update a user; `active: false` deactivates it and revokes its sessions
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = ScimPatchRequest,
+    responses(
+        (status = 200, description = "Updated user", body = ScimUser),
+        (status = 400, description = "Invalid patch operation"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_patch_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<ScimPatchRequest>,
+) -> ScimResult<ScimUser> {
+    state.scim_service.ensure_enabled()?;
+    let user = state.scim_service.patch_user(id, &req.operations).await?;
+    Ok(ScimJson(StatusCode::OK, user))
+}
+
+/// Deprovision a user (deactivated, not deleted)
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "User deactivated"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_delete_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, ScimError> {
+    state.scim_service.ensure_enabled()?;
+    state.scim_service.delete_user(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List groups (teams), optionally filtered (e.g. `displayName eq "Architects"`)
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups",
+    params(
+        ("filter" = Option<String>, Query, description = "SCIM filter: eq, ne, co, sw, ew, pr joined with and"),
+        ("startIndex" = Option<i64>, Query, description = "1-based index of the first result"),
+        ("count" = Option<i64>, Query, description = "Page size (max 200)")
+    ),
+    responses(
+        (status = 200, description = "Matching groups", body = ScimGroupList),
+        (status = 400, description = "Invalid filter")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_list_groups(
+    State(state): State<AppState>,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult<ScimListResponse<ScimGroup>> {
+    state.scim_service.ensure_enabled()?;
+    let groups = state.scim_service.list_groups(&query).await?;
+    Ok(ScimJson(StatusCode::OK, groups))
+}
+
+/// Get a group
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Group (team) ID")
+    ),
+    responses(
+        (status = 200, description = "Group", body = ScimGroup),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_get_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> ScimResult<ScimGroup> {
+    state.scim_service.ensure_enabled()?;
+    let group = state.scim_service.get_group(id).await?;
+    Ok(ScimJson(StatusCode::OK, group))
+}
+
+/// Create a group (team)
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Groups",
+    request_body = ScimCreateGroupRequest,
+    responses(
+        (status = 201, description = "Group created", body = ScimGroup),
+        (status = 400, description = "Invalid group"),
+        (status = 409, description = "displayName already exists")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_create_group(
+    State(state): State<AppState>,
+    Json(req): Json<ScimCreateGroupRequest>,
+) -> ScimResult<ScimGroup> {
+    state.scim_service.ensure_enabled()?;
+    let group = state.scim_service.create_group(req).await?;
+    Ok(ScimJson(StatusCode::CREATED, group))
+}
+
+/// Rename a group or change its members; member roles follow the role mappings
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Group (team) ID")
+    ),
+    request_body = ScimPatchRequest,
+    responses(
+        (status = 200, description = "Updated group", body = ScimGroup),
+        (status = 400, description = "Invalid patch operation"),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_patch_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<ScimPatchRequest>,
+) -> ScimResult<ScimGroup> {
+    state.scim_service.ensure_enabled()?;
+    let group = state.scim_service.patch_group(id, &req.operations).await?;
+    Ok(ScimJson(StatusCode::OK, group))
+}
+
+/// Delete a group (team)
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Group (team) ID")
+    ),
+    responses(
+        (status = 204, description = "Group deleted"),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "SCIM"
+)]
+pub async fn scim_delete_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, ScimError> {
+    state.scim_service.ensure_enabled()?;
+    state.scim_service.delete_group(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -34,6 +34,9 @@ pub async fn list_standards(
         card_type: Some(CardType::TechnologyStandard),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: Some(page),
         page_size: Some(limit),
     };
@@ -219,6 +222,7 @@ pub async fn create_standard(
         quality_score: None,
         description: req.description,
         owner_id: None,
+        team_id: None,
         attributes: Some(attributes),
         tags: req.tags,
     };
@@ -292,6 +296,8 @@ pub async fn update_standard(
         description: req.description,
         attributes: Some(attributes),
         tags: req.tags,
+        owner_id: None,
+        team_id: None,
     };
 
     let card = state.saga_orchestrator.update_card(id, update_req).await?;
@@ -375,6 +381,9 @@ pub async fn get_radar(
         card_type: Some(CardType::TechnologyStandard),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
@@ -442,6 +451,9 @@ pub async fn get_debt_report(
         card_type: Some(CardType::TechnologyStandard),
         lifecycle_phase: None,
         tags: None,
+        query: None,
+        sort: None,
+        cursor: None,
         page: None,
         page_size: None,
     };
@@ -0,0 +1,121 @@
+/*!
+ * Team Handlers
+ *
+ * Teams that own cards together; members may edit the team's cards.
+ */
+
+use axum::{extract::{Path, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::team::*,
+    state::AppState,
+};
+
+/// List teams with their members
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams",
+    responses(
+        (status = 200, description = "Teams", body = Vec<Team>),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Users"
+)]
+pub async fn list_teams(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Team>>, AppError> {
+    let teams = state.team_service.list().await?;
+    Ok(Json(teams))
+}
+
+/// Create a team
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams",
+    request_body = CreateTeamRequest,
+    responses(
+        (status = 200, description = "Team created", body = Team),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "Team name already exists")
+    ),
+    tag = "Users"
+)]
+pub async fn create_team(
+    State(state): State<AppState>,
+    Json(req): Json<CreateTeamRequest>,
+) -> Result<Json<Team>, AppError> {
+    let team = state.team_service.create(req).await?;
+    Ok(Json(team))
+}
+
+/// Delete a team; its cards lose the team but keep their owner
+#[utoipa::path(
+    delete,
+    path = "/api/v1/teams/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Team ID")
+    ),
+    responses(
+        (status = 200, description = "Team deleted"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Team not found")
+    ),
+    tag = "Users"
+)]
+pub async fn delete_team(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<()>, AppError> {
+    state.team_service.delete(id).await?;
+    Ok(Json(()))
+}
+
+/// Add a user to a team
+#[utoipa::path(
+    post,
+    path = "/api/v1/teams/{id}/members",
+    params(
+        ("id" = Uuid, Path, description = "Team ID")
+    ),
+    request_body = AddTeamMemberRequest,
+    responses(
+        (status = 200, description = "Updated team", body = Team),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Team or user not found")
+    ),
+    tag = "Users"
+)]
+pub async fn add_team_member(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<AddTeamMemberRequest>,
+) -> Result<Json<Team>, AppError> {
+    let team = state.team_service.add_member(id, req.user_id).await?;
+    Ok(Json(team))
+}
+
+/// Remove a user from a team
+#[utoipa::path(
+    delete,
+    path = "/api/v1/teams/{id}/members/{user_id}",
+    params(
+        ("id" = Uuid, Path, description = "Team ID"),
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Updated team", body = Team),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Team not found")
+    ),
+    tag = "Users"
+)]
+pub async fn remove_team_member(
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+) -> Result<Json<Team>, AppError> {
+    let team = state.team_service.remove_member(id, user_id).await?;
+    Ok(Json(team))
+}
@@ -1,83 +1,240 @@
+/*!
+ * User Management Handlers
+ *
+ * User CRUD, deactivation, unlock and invitations. The routers are guarded by the
+ * `users` resource, which only admins hold.
+ */
+
 use axum::{
-    extract::{Path, State, Extension},
-    http::StatusCode,
+    extract::{Path, Query, State, Extension},
     Json,
 };
 use uuid::Uuid;
-use sqlx::FromRow;
 
 use crate::{
     error::AppError,
-    models::{Claims, UserRole},
+    models::Claims,
+    models::user::*,
     state::AppState,
 };
 
-// Response model for users
-#[derive(Debug, serde::Serialize, FromRow)]
-pub struct UserResponse {
-    pub id: Uuid,
-    pub email: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub full_name: Option<String>,
-    pub role: String,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
+/// List users
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    params(UserListParams),
+    responses(
+        (status = 200, description = "Users", body = Vec<UserAccount>),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Users"
+)]
+pub async fn get_users(
+    State(state): State<AppState>,
+    Query(params): Query<UserListParams>,
+) -> Result<Json<Vec<UserAccount>>, AppError> {
+    let users = state.user_service.list(params.include_inactive.unwrap_or(false)).await?;
+    Ok(Json(users))
 }
 
-// GET /api/v1/users - List all users
-pub async fn get_users(
+/// Get a user
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User", body = UserAccount),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "Users"
+)]
+pub async fn get_user(
+    Path(id): Path<Uuid>,
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
-) -> Result<Json<Vec<UserResponse>>, AppError> {
-    // Only admins can list users
-    if claims.role != UserRole::Admin {
-        return Err(AppError::Auth("Insufficient permissions".to_string()));
-    }
+) -> Result<Json<UserAccount>, AppError> {
+    let user = state.user_service.get(id).await?;
+    Ok(Json(user))
+}
 
-    let users = sqlx::query_as::<_, UserResponse>(
-        "SELECT id, email, full_name, role, created_at, updated_at FROM users ORDER BY created_at DESC"
-    )
-    .fetch_all(state.auth_service.get_pool())
-    .await
-    .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
+/// Create a user with an initial password
+#[utoipa::path(
+    post,
+    path = "/api/v1/users",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "User created", body = UserAccount),
+        (status = 400, description = "Password does not meet the policy"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "Email already in use")
+    ),
+    tag = "Users"
+)]
+pub async fn create_user(
+    State(state): State<AppState>,
+    Json(req): Json<RegisterRequest>,
+) -> Result<Json<UserAccount>, AppError> {
+    let user = state.user_service.create(req).await?;
+    Ok(Json(user))
+}
 
-    Ok(Json(users))
+/// Update a user's email, name or role
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "User updated", body = UserAccount),
+        (status = 400, description = "Would remove the last active admin"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "Users"
+)]
+pub async fn update_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<UserAccount>, AppError> {
+    let user = state.user_service.update(id, req).await?;
+    Ok(Json(user))
 }
 
-// GET /api/v1/users/:id - Get a specific user
-pub async fn get_user(
+/// Deactivate a user
+///
+/// Users are never hard-deleted; the account is kept for audit and history but can no longer log in.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User deactivated", body = UserAccount),
+        (status = 400, description = "Own account or last active admin"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "Users"
+)]
+pub async fn delete_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-) -> Result<Json<UserResponse>, AppError> {
-    // Only admins can view user details
-    if claims.role != UserRole::Admin {
-        return Err(AppError::Auth("Insufficient permissions".to_string()));
-    }
+) -> Result<Json<UserAccount>, AppError> {
+    let acting_user = Uuid::parse_str(&claims.sub).ok();
+    let user = state.user_service.deactivate(id, acting_user).await?;
+    Ok(Json(user))
+}
 
-    let user = sqlx::query_as::<_, UserResponse>(
-        "SELECT id, email, full_name, role, created_at, updated_at FROM users WHERE id = $1"
-    )
-    .bind(id)
-    .fetch_optional(state.auth_service.get_pool())
-    .await
-    .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
-    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+/// Reactivate a deactivated user
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/reactivate",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User reactivated", body = UserAccount),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "Users"
+)]
+pub async fn reactivate_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<UserAccount>, AppError> {
+    let user = state.user_service.reactivate(id).await?;
+    Ok(Json(user))
+}
 
+/// Unlock an account locked by failed login attempts
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/unlock",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User unlocked", body = UserAccount),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "Users"
+)]
+pub async fn unlock_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<UserAccount>, AppError> {
+    let user = state.user_service.unlock(id).await?;
     Ok(Json(user))
 }
 
-// POST /api/v1/users - Create a new user (not implemented for now)
-pub async fn create_user() -> &'static str {
-    "User creation not yet implemented"
+/// List invitations
+#[utoipa::path(
+    get,
+    path = "/api/v1/invitations",
+    responses(
+        (status = 200, description = "Invitations", body = Vec<Invitation>),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Users"
+)]
+pub async fn list_invitations(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Invitation>>, AppError> {
+    let invitations = state.user_service.list_invitations().await?;
+    Ok(Json(invitations))
 }
 
-// PUT /api/v1/users/:id - Update a user (not implemented for now)
-pub async fn update_user() -> &'static str {
-    "User update not yet implemented"
+/// Invite a user by email
+///
+/// The invitation link is delivered through the configured message sender; the token is not returned.
+#[utoipa::path(
+    post,
+    path = "/api/v1/invitations",
+    request_body = CreateInvitationRequest,
+    responses(
+        (status = 200, description = "Invitation sent", body = Invitation),
+        (status = 400, description = "Email already belongs to a user"),
+        (status = 403, description = "Admin role required")
+    ),
+    tag = "Users"
+)]
+pub async fn create_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateInvitationRequest>,
+) -> Result<Json<Invitation>, AppError> {
+    let invited_by = Uuid::parse_str(&claims.sub).ok();
+    let invitation = state.user_service.invite(req, invited_by).await?;
+    Ok(Json(invitation))
 }
 
-// DELETE /api/v1/users/:id - Delete a user (not implemented for now)
-pub async fn delete_user() -> &'static str {
-    "User deletion not yet implemented"
+/// Revoke an open invitation
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invitations/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 200, description = "Invitation revoked", body = Invitation),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Open invitation not found")
+    ),
+    tag = "Users"
+)]
+pub async fn revoke_invitation(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<Invitation>, AppError> {
+    let invitation = state.user_service.revoke_invitation(id).await?;
+    Ok(Json(invitation))
 }
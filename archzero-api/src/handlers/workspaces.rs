@@ -0,0 +1,284 @@
+/*!
+ * Workspace Handlers
+ *
+ * Workspaces, their members and read-only card shares between workspaces. Requests
+ * pick a workspace with the `X-Workspace-Id` header; without it they run in the
+ * default workspace.
+ */
+
+use axum::{extract::{Extension, Path, State}, Json};
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    models::user::Claims,
+    models::workspace::*,
+    services::workspace_service::require_global_admin,
+    state::AppState,
+};
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Auth("Invalid token subject".to_string()))
+}
+
+/// List the workspaces the current user can open, with their role in each
+#[utoipa::path(
+    get,
+    path = "/api/v1/workspaces",
+    responses(
+        (status = 200, description = "Accessible workspaces", body = Vec<WorkspaceAccess>),
+        (status = 401, description = "Not authenticated")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<WorkspaceAccess>>, AppError> {
+    let workspaces = state.workspace_service.list_for_user(caller_id(&claims)?, &claims.role).await?;
+    Ok(Json(workspaces))
+}
+
+/// Create a workspace; the creator becomes its admin
+#[utoipa::path(
+    post,
+    path = "/api/v1/workspaces",
+    request_body = CreateWorkspaceRequest,
+    responses(
+        (status = 200, description = "Workspace created", body = Workspace),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Admin role required"),
+        (status = 409, description = "Workspace name already exists")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateWorkspaceRequest>,
+) -> Result<Json<Workspace>, AppError> {
+    require_global_admin(&claims)?;
+    let workspace = state.workspace_service.create(req, caller_id(&claims)?).await?;
+    Ok(Json(workspace))
+}
+
+/// Get a workspace the current user can open
+#[utoipa::path(
+    get,
+    path = "/api/v1/workspaces/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID")
+    ),
+    responses(
+        (status = 200, description = "Workspace", body = Workspace),
+        (status = 403, description = "No access to the workspace"),
+        (status = 404, description = "Workspace not found")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn get_workspace(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Workspace>, AppError> {
+    state.workspace_service.resolve(caller_id(&claims)?, &claims.role, Some(id)).await?;
+    let workspace = state.workspace_service.get(id).await?;
+    Ok(Json(workspace))
+}
+
+/// Rename or describe a workspace
+#[utoipa::path(
+    put,
+    path = "/api/v1/workspaces/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID")
+    ),
+    request_body = UpdateWorkspaceRequest,
+    responses(
+        (status = 200, description = "Workspace updated", body = Workspace),
+        (status = 403, description = "Workspace admin role required"),
+        (status = 404, description = "Workspace not found"),
+        (status = 409, description = "Workspace name already exists")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn update_workspace(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<UpdateWorkspaceRequest>,
+) -> Result<Json<Workspace>, AppError> {
+    state.workspace_service.require_admin(&claims, id).await?;
+    let workspace = state.workspace_service.update(id, req).await?;
+    Ok(Json(workspace))
+}
+
+/// Delete an empty workspace
+#[utoipa::path(
+    delete,
+    path = "/api/v1/workspaces/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID")
+    ),
+    responses(
+        (status = 200, description = "Workspace deleted"),
+        (status = 400, description = "Default workspace, or the workspace still holds cards"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Workspace not found")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn delete_workspace(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<()>, AppError> {
+    require_global_admin(&claims)?;
+    state.workspace_service.delete(id).await?;
+    Ok(Json(()))
+}
+
+/// List a workspace's members and their roles
+#[utoipa::path(
+    get,
+    path = "/api/v1/workspaces/{id}/members",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID")
+    ),
+    responses(
+        (status = 200, description = "Members", body = Vec<WorkspaceMember>),
+        (status = 403, description = "Workspace admin role required"),
+        (status = 404, description = "Workspace not found")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn list_workspace_members(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<WorkspaceMember>>, AppError> {
+    state.workspace_service.require_admin(&claims, id).await?;
+    let members = state.workspace_service.list_members(id).await?;
+    Ok(Json(members))
+}
+
+/// Add a member to a workspace or change their role there
+#[utoipa::path(
+    put,
+    path = "/api/v1/workspaces/{id}/members",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID")
+    ),
+    request_body = SetWorkspaceMemberRequest,
+    responses(
+        (status = 200, description = "Members after the change", body = Vec<WorkspaceMember>),
+        (status = 403, description = "Workspace admin role required"),
+        (status = 404, description = "Workspace or user not found")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn set_workspace_member(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<SetWorkspaceMemberRequest>,
+) -> Result<Json<Vec<WorkspaceMember>>, AppError> {
+    state.workspace_service.require_admin(&claims, id).await?;
+    let members = state.workspace_service.set_member(id, req).await?;
+    Ok(Json(members))
+}
+
+/// Remove a member from a workspace
+#[utoipa::path(
+    delete,
+    path = "/api/v1/workspaces/{id}/members/{user_id}",
+    params(
+        ("id" = Uuid, Path, description = "Workspace ID"),
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Member removed"),
+        (status = 403, description = "Workspace admin role required"),
+        (status = 404, description = "Not a member")
+    ),
+    tag = "Workspaces"
+)]
+pub async fn remove_workspace_member(
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<()>, AppError> {
+    state.workspace_service.require_admin(&claims, id).await?;
+    state.workspace_service.remove_member(id, user_id).await?;
+    Ok(Json(()))
+}
+
+/// List the workspaces a card of the current workspace is shared with
+#[utoipa::path(
+    get,
+    path = "/api/v1/cards/{id}/shares",
+    params(
+        ("id" = Uuid, Path, description = "Card ID")
+    ),
+    responses(
+        (status = 200, description = "Card shares", body = Vec<CardShare>),
+        (status = 404, description = "Card not found in the current workspace")
+    ),
+    tag = "Cards"
+)]
+pub async fn list_card_shares(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<CardShare>>, AppError> {
+    let shares = state.workspace_service.list_card_shares(id, claims.workspace_id).await?;
+    Ok(Json(shares))
+}
+
+/// Share a card read-only with another workspace
+#[utoipa::path(
+    post,
+    path = "/api/v1/cards/{id}/shares",
+    params(
+        ("id" = Uuid, Path, description = "Card ID")
+    ),
+    request_body = ShareCardRequest,
+    responses(
+        (status = 200, description = "Card shares after the change", body = Vec<CardShare>),
+        (status = 400, description = "Target is the card's own workspace"),
+        (status = 404, description = "Card or workspace not found")
+    ),
+    tag = "Cards"
+)]
+pub async fn share_card(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ShareCardRequest>,
+) -> Result<Json<Vec<CardShare>>, AppError> {
+    let shares = state.workspace_service.share_card(id, claims.workspace_id, req, caller_id(&claims)?).await?;
+    Ok(Json(shares))
+}
+
+/// Stop sharing a card with a workspace
+#[utoipa::path(
+    delete,
+    path = "/api/v1/cards/{id}/shares/{workspace_id}",
+    params(
+        ("id" = Uuid, Path, description = "Card ID"),
+        ("workspace_id" = Uuid, Path, description = "Workspace the card is shared with")
+    ),
+    responses(
+        (status = 200, description = "Share removed"),
+        (status = 404, description = "Card not shared with the workspace")
+    ),
+    tag = "Cards"
+)]
+pub async fn unshare_card(
+    Path((id, workspace_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<()>, AppError> {
+    state.workspace_service.unshare_card(id, claims.workspace_id, workspace_id).await?;
+    Ok(Json(()))
+}
@@ -25,11 +25,15 @@ use utoipa::OpenApi;
         handlers::cards::list_cards,
         handlers::cards::update_card,
         handlers::cards::delete_card,
+        handlers::cards::get_card_history,
+        handlers::cards::get_card_as_of,
+        handlers::cards::restore_card_version,
         handlers::relationships::create_relationship,
         handlers::relationships::get_relationship,
         handlers::relationships::list_relationships,
         handlers::relationships::update_relationship,
         handlers::relationships::delete_relationship,
+        handlers::relationships::get_relationship_history,
         handlers::health::health_check,
         handlers::bia::list_profiles,
         handlers::bia::get_profile,
@@ -125,51 +129,63 @@ pub async fn create_app(settings: Settings) -> axum::Router {
     use sqlx::postgres::PgPool;
     use tokio::sync::Mutex;
     use handlers::{auth, cards, health, relationships, bia, migration, tco, risks, compliance,
-                    principles, standards, policies, exceptions, initiatives, arb, graph, import as import_handler, bulk, cache};
+                    principles, standards, policies, exceptions, initiatives, arb, graph, graph_sync, architecture_states, import as import_handler, bulk, cache, users, teams, api_tokens, scim, workspaces, metamodel, saved_searches, data_quality};
     use services::{
-        CardService, AuthService, RelationshipService, Neo4jService,
-        SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ExportService
+        CardService, AuthService, RelationshipService,
+        SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ExportService, GraphSyncService, VersionService, ArchitectureStateService, UserService, TeamService, OidcService, ApiTokenService, ScimService, WorkspaceService, MetamodelService, MetamodelMigrationService, CardTypeService, RelationshipRuleService, SavedSearchService, DataQualityService,
+        account_sender::LogMessageSender, graph_backend::connect_graph_backend,
     };
     use state::AppState;
+    use models::permission::{Action, Permission, Resource};
 
     // Connect to databases (in tests, use test configuration)
     let pool = PgPool::connect(&settings.database.postgres_url)
         .await
         .expect("Failed to connect to PostgreSQL");
 
-    let neo4j_service = Arc::new(
-        Neo4jService::new(
-            &settings.database.neo4j_uri,
-            &settings.database.neo4j_user,
-            &settings.database.neo4j_password,
-        )
-        .await
-        .expect("Failed to connect to Neo4j")
-    );
-
     // Initialize services
-    let card_service = Arc::new(CardService::new(pool.clone()));
+    let card_service = Arc::new(
+        CardService::new(pool.clone(), settings.card_access.clone()).with_data_quality(settings.data_quality.clone()),
+    );
     let auth_service = Arc::new(AuthService::new(
         pool.clone(),
         settings.jwt.secret.clone(),
-        settings.jwt.expiration_hours * 3600,
-    ));
-    let relationship_service = Arc::new(RelationshipService::new(pool.clone()));
+        settings.jwt.access_token_minutes * 60,
+        settings.jwt.refresh_token_days,
+    ).with_password_login(settings.accounts.password_login_enabled));
+    let relationship_service = Arc::new(RelationshipService::new(pool.clone()).with_data_quality(settings.data_quality.clone()));
+
+    // Connect the graph backend (Neo4j, or embedded traversals over PostgreSQL)
+    let graph_backend = connect_graph_backend(&settings, card_service.clone(), relationship_service.clone())
+        .await
+        .expect("Failed to connect graph backend");
+    tracing::info!("Graph backend: {}", graph_backend.name());
 
     // Initialize SAGA orchestrator
     let saga_orchestrator = Arc::new(SagaOrchestrator::new(
         card_service.clone(),
         relationship_service.clone(),
-        neo4j_service.clone(),
+        graph_backend.clone(),
     ));
 
     // Initialize Phase 2 intelligence services
     let bia_service = Arc::new(BIAService::new());
-    let topology_service = Arc::new(TopologyService::new(neo4j_service.clone()));
+    let topology_service = Arc::new(TopologyService::new(graph_backend.clone()));
     let migration_service = Arc::new(MigrationService::new());
     let tco_service = Arc::new(TCOService::new());
-    let csrf_service = Arc::new(CsrfService::new());
-    let rate_limit_service = Arc::new(RateLimitService::new());
+    let csrf_service = Arc::new(
+        CsrfService::new(settings.csrf.clone(), &settings.jwt.secret)
+            .with_secure_cookie(settings.server.environment == crate::config::Environment::Production),
+    );
+    // Rate limiting: counters in Redis so limits hold across replicas
+    let rate_limit_redis = settings.rate_limit.redis_url.clone()
+        .or_else(|| settings.cache.redis_url.clone())
+        .unwrap_or_else(|| "redis://127.0.0.1:6379".to_string());
+    let rate_limit_service = Arc::new(RateLimitService::with_redis(
+        &rate_limit_redis,
+        settings.rate_limit.clone(),
+        settings.rate_limit.is_enabled(settings.server.environment),
+    ).expect("Invalid rate limit Redis URL"));
 
     // Initialize ARB Template Service
     let arb_template_service = Arc::new(ArbTemplateService::new(pool.clone()));
@@ -186,6 +202,46 @@ pub async fn create_app(settings: Settings) -> axum::Router {
     // Initialize Report Service
     let report_service = Arc::new(services::ReportService::new(pool.clone()));
 
+    // Initialize version history service
+    let version_service = Arc::new(VersionService::new(pool.clone()));
+
+    // Initialize architecture states (baseline/target/transition)
+    let architecture_state_service = Arc::new(ArchitectureStateService::new(
+        pool.clone(),
+        card_service.clone(),
+        relationship_service.clone(),
+    ));
+
+    // Initialize user management
+    let user_service = Arc::new(UserService::new(
+        pool.clone(),
+        auth_service.clone(),
+        Arc::new(LogMessageSender),
+        settings.accounts.clone(),
+        settings.password_policy.clone(),
+    ));
+    let team_service = Arc::new(TeamService::new(pool.clone()));
+    let oidc_service = Arc::new(
+        OidcService::new(pool.clone(), auth_service.clone(), settings.oidc.clone())
+            .with_secure_cookie(settings.server.environment == crate::config::Environment::Production),
+    );
+    let api_token_service = Arc::new(ApiTokenService::new(pool.clone(), settings.api_tokens.clone()));
+    let scim_service = Arc::new(ScimService::new(pool.clone(), user_service.clone(), settings.scim.clone()));
+    let workspace_service = Arc::new(WorkspaceService::new(pool.clone()));
+    let metamodel_service = Arc::new(MetamodelService::new(pool.clone()));
+    let card_type_service = Arc::new(CardTypeService::new(pool.clone()));
+    let relationship_rule_service = Arc::new(RelationshipRuleService::new(pool.clone()));
+    let saved_search_service = Arc::new(SavedSearchService::new(pool.clone(), card_service.clone()));
+    let data_quality_service = Arc::new(DataQualityService::new(pool.clone(), settings.data_quality.clone()));
+    let metamodel_migration_service = Arc::new(MetamodelMigrationService::new(pool.clone(), card_service.clone()));
+
+    // Initialize graph reconciliation service
+    let graph_sync_service = Arc::new(GraphSyncService::new(
+        card_service.clone(),
+        relationship_service.clone(),
+        graph_backend.clone(),
+    ));
+
     // Initialize Phase 5: Redis Cache Service (optional - fails gracefully if Redis unavailable)
     let cache_service = if let Ok(cache) = CacheService::new(&settings.cache.redis_url.clone().unwrap_or_else(|| "redis://127.0.0.1:6379".to_string())) {
         Arc::new(cache)
@@ -204,7 +260,7 @@ pub async fn create_app(settings: Settings) -> axum::Router {
         card_service: card_service.clone(),
         auth_service: auth_service.clone(),
         relationship_service: relationship_service.clone(),
-        neo4j_service: neo4j_service.clone(),
+        graph_backend: graph_backend.clone(),
         saga_orchestrator: saga_orchestrator.clone(),
         bia_service: bia_service.clone(),
         topology_service: topology_service.clone(),
@@ -218,10 +274,34 @@ pub async fn create_app(settings: Settings) -> axum::Router {
         arb_notification_service: arb_notification_service.clone(),
         export_service: export_service.clone(),
         report_service: report_service.clone(),
+        graph_sync_service: graph_sync_service.clone(),
+        version_service: version_service.clone(),
+        architecture_state_service: architecture_state_service.clone(),
+        user_service: user_service.clone(),
+        team_service: team_service.clone(),
+        oidc_service: oidc_service.clone(),
+        api_token_service: api_token_service.clone(),
+        scim_service: scim_service.clone(),
+        workspace_service: workspace_service.clone(),
+        metamodel_service: metamodel_service.clone(),
+        metamodel_migration_service: metamodel_migration_service.clone(),
+        card_type_service: card_type_service.clone(),
+        relationship_rule_service: relationship_rule_service.clone(),
+        saved_search_service: saved_search_service.clone(),
+        data_quality_service: data_quality_service.clone(),
         import_jobs: Arc::new(Mutex::new(std::collections::HashMap::new())),
     };
 
-    // Build router
+    // Exact permissions for routes whose action differs from their HTTP method
+    const POLICIES_READ: Permission = Permission::new(Resource::Policies, Action::Read);
+    const EXPORTS_READ: Permission = Permission::new(Resource::Exports, Action::Read);
+    const METAMODEL_READ: Permission = Permission::new(Resource::Metamodel, Action::Read);
+    const CARDS_READ: Permission = Permission::new(Resource::Cards, Action::Read);
+    const CARDS_WRITE: Permission = Permission::new(Resource::Cards, Action::Write);
+    const CARDS_DELETE: Permission = Permission::new(Resource::Cards, Action::Delete);
+    const EXCEPTIONS_APPROVE: Permission = Permission::new(Resource::Exceptions, Action::Approve);
+
+    // Build router; protected routers are authenticated, then authorized per resource
     Router::new()
         .nest(
             "/api/v1/health",
@@ -230,21 +310,195 @@ pub async fn create_app(settings: Settings) -> axum::Router {
         .nest(
             "/api/v1/auth",
             Router::new()
-                .route("/login", post(auth::login))
                 .route("/logout", post(auth::logout))
-                .route("/me", get(auth::me)),
+                .route("/me", get(auth::me))
+                .route("/permissions", get(auth::my_permissions))
+                .route("/change-password", post(auth::change_password))
+                .route("/sessions", get(auth::list_sessions).delete(auth::revoke_other_sessions))
+                .route("/sessions/:id", delete(auth::revoke_session))
+                .route("/tokens", get(api_tokens::list_my_api_tokens).post(api_tokens::create_my_api_token))
+                .route("/tokens/:id", delete(api_tokens::revoke_my_api_token))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                ))
+                // Public routes (added after the layer, so not authenticated)
+                .route("/login", post(auth::login))
+                .route("/refresh", post(auth::refresh))
+                .route("/invitations/accept", post(auth::accept_invitation))
+                .route("/password-reset", post(auth::request_password_reset))
+                .route("/password-reset/confirm", post(auth::confirm_password_reset))
+                .route("/providers", get(auth::auth_providers))
+                .route("/oidc/login", get(auth::oidc_login))
+                .route("/oidc/callback", get(auth::oidc_callback)),
+        )
+        .nest(
+            "/api/v1/users",
+            Router::new()
+                .route("/", get(users::get_users).post(users::create_user))
+                .route("/:id", get(users::get_user).put(users::update_user).delete(users::delete_user))
+                .route("/:id/reactivate", post(users::reactivate_user))
+                .route("/:id/unlock", post(users::unlock_user))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/teams",
+            Router::new()
+                .route("/", get(teams::list_teams).post(teams::create_team))
+                .route("/:id", delete(teams::delete_team))
+                .route("/:id/members", post(teams::add_team_member))
+                .route("/:id/members/:user_id", delete(teams::remove_team_member))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/workspaces",
+            Router::new()
+                .route("/", get(workspaces::list_workspaces).post(workspaces::create_workspace))
+                .route("/:id", get(workspaces::get_workspace).put(workspaces::update_workspace).delete(workspaces::delete_workspace))
+                .route("/:id/members", get(workspaces::list_workspace_members).put(workspaces::set_workspace_member))
+                .route("/:id/members/:user_id", delete(workspaces::remove_workspace_member))
+                // Access is checked per workspace in the handlers
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/metamodel",
+            Router::new()
+                .route("/rules", get(metamodel::list_metamodel_rules).post(metamodel::create_metamodel_rule))
+                .route("/rules/:id", get(metamodel::get_metamodel_rule).put(metamodel::update_metamodel_rule).delete(metamodel::delete_metamodel_rule))
+                .route("/version", get(metamodel::get_metamodel_version))
+                .route("/migrations", get(metamodel::list_metamodel_migrations).post(metamodel::create_metamodel_migration))
+                .route("/migrations/dry-run", post(metamodel::dry_run_metamodel_migration))
+                .route("/migrations/:id", get(metamodel::get_metamodel_migration))
+                .route("/card-types", get(metamodel::list_card_types).post(metamodel::create_card_type))
+                .route("/card-types/:name", get(metamodel::get_card_type).put(metamodel::update_card_type).delete(metamodel::delete_card_type))
+                .route("/relationship-rules", get(metamodel::list_relationship_rules).post(metamodel::create_relationship_rule))
+                .route("/relationship-rules/violations", get(metamodel::list_relationship_rule_violations))
+                .route("/relationship-rules/:id", get(metamodel::get_relationship_rule).put(metamodel::update_relationship_rule).delete(metamodel::delete_relationship_rule))
+                .layer(axum::middleware::from_fn_with_state(Resource::Metamodel, middleware::authorize))
+                // Validating attributes only needs read access
+                .route("/validate", post(metamodel::validate_card_attributes).layer(axum::middleware::from_fn_with_state(METAMODEL_READ, middleware::require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/scim/v2",
+            Router::new()
+                .route("/ServiceProviderConfig", get(scim::scim_service_provider_config))
+                .route("/Users", get(scim::scim_list_users).post(scim::scim_create_user))
+                .route(
+                    "/Users/:id",
+                    get(scim::scim_get_user).patch(scim::scim_patch_user).delete(scim::scim_delete_user),
+                )
+                .route("/Groups", get(scim::scim_list_groups).post(scim::scim_create_group))
+                .route(
+                    "/Groups/:id",
+                    get(scim::scim_get_group).patch(scim::scim_patch_group).delete(scim::scim_delete_group),
+                )
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/service-accounts",
+            Router::new()
+                .route("/", get(api_tokens::list_service_accounts).post(api_tokens::create_service_account))
+                .route("/:id", delete(api_tokens::deactivate_service_account))
+                .route(
+                    "/:id/tokens",
+                    get(api_tokens::list_service_account_tokens).post(api_tokens::create_service_account_token),
+                )
+                .route("/:id/tokens/:token_id", delete(api_tokens::revoke_service_account_token))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/invitations",
+            Router::new()
+                .route("/", get(users::list_invitations).post(users::create_invitation))
+                .route("/:id", delete(users::revoke_invitation))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         .nest(
             "/api/v1/cards",
             Router::new()
                 .route("/", get(cards::list_cards).post(cards::create_card))
-                .route("/:id", get(cards::get_card).put(cards::update_card).delete(cards::delete_card)),
+                .route("/search", get(cards::search_cards))
+                .route("/:id", get(cards::get_card).put(cards::update_card).delete(cards::delete_card))
+                .route("/:id/history", get(cards::get_card_history))
+                .route("/:id/similar", get(cards::similar_cards))
+                .route("/:id/quality", get(data_quality::get_card_quality))
+                .route("/:id/as-of", get(cards::get_card_as_of))
+                .route("/:id/versions/:version/restore", post(cards::restore_card_version))
+                .route("/:id/shares", get(workspaces::list_card_shares).post(workspaces::share_card))
+                .route("/:id/shares/:workspace_id", delete(workspaces::unshare_card))
+                .layer(axum::middleware::from_fn_with_state(Resource::Cards, middleware::authorize))
+                // Searching only needs read access
+                .route("/query", post(cards::query_cards).layer(axum::middleware::from_fn_with_state(CARDS_READ, middleware::require_permission)))
+                // Reviewing changes only the card's freshness
+                .route("/:id/review", post(data_quality::review_card).layer(axum::middleware::from_fn_with_state(CARDS_WRITE, middleware::require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/saved-searches",
+            Router::new()
+                .route("/", get(saved_searches::list_saved_searches).post(saved_searches::create_saved_search))
+                .route("/:id", get(saved_searches::get_saved_search).put(saved_searches::update_saved_search).delete(saved_searches::delete_saved_search))
+                .route("/:id/run", get(saved_searches::run_saved_search))
+                .route("/:id/pin", put(saved_searches::pin_saved_search).delete(saved_searches::unpin_saved_search))
+                .layer(axum::middleware::from_fn_with_state(CARDS_READ, middleware::require_permission))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        // Recalculating is a metamodel administration task; the dashboard only needs read access to cards
+        .nest(
+            "/api/v1/data-quality",
+            Router::new()
+                .route("/recalculate", post(data_quality::recalculate_data_quality))
+                .layer(axum::middleware::from_fn_with_state(Resource::Metamodel, middleware::authorize))
+                .route("/", get(data_quality::get_data_quality_dashboard).layer(axum::middleware::from_fn_with_state(CARDS_READ, middleware::require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         .nest(
             "/api/v1/relationships",
             Router::new()
                 .route("/", get(relationships::list_relationships).post(relationships::create_relationship))
-                .route("/:id", get(relationships::get_relationship).put(relationships::update_relationship).delete(relationships::delete_relationship)),
+                .route("/:id", get(relationships::get_relationship).put(relationships::update_relationship).delete(relationships::delete_relationship))
+                .route("/:id/history", get(relationships::get_relationship_history))
+                .layer(axum::middleware::from_fn_with_state(Resource::Relationships, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 2: BIA endpoints
         .nest(
@@ -253,7 +507,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/profiles", get(bia::list_profiles))
                 .route("/profiles/:name", get(bia::get_profile))
                 .route("/assessments", post(bia::create_assessment))
-                .route("/assessments/:id", get(bia::get_assessment)),
+                .route("/assessments/:id", get(bia::get_assessment))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 2: Topology endpoints
         .nest(
@@ -263,7 +522,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/cards/:card_id/metrics", get(bia::get_topology_metrics))
                 .route("/cards/:card_id/dependents", get(bia::get_dependents))
                 .route("/cards/:card_id/dependencies", get(bia::get_dependencies))
-                .route("/critical-paths", get(bia::get_critical_paths)),
+                .route("/critical-paths", get(bia::get_critical_paths))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 2: Migration endpoints
         .nest(
@@ -271,7 +535,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
             Router::new()
                 .route("/assess", post(migration::assess_migration))
                 .route("/recommendations/:id", get(migration::get_recommendation))
-                .route("/cards/:card_id/recommendations", get(migration::get_card_recommendations)),
+                .route("/cards/:card_id/recommendations", get(migration::get_card_recommendations))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 2: TCO endpoints
         .nest(
@@ -281,7 +550,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/portfolio", get(tco::get_portfolio_tco))
                 .route("/cards/:card_id", get(tco::get_tco_breakdown))
                 .route("/cards/:card_id/comparison", get(tco::get_tco_comparison))
-                .route("/cards/:card_id/trend", get(tco::get_cost_trend)),
+                .route("/cards/:card_id/trend", get(tco::get_cost_trend))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Risk register endpoints
         .nest(
@@ -290,7 +564,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/", get(risks::list_risks).post(risks::create_risk))
                 .route("/:id", get(risks::get_risk).put(risks::update_risk).delete(risks::delete_risk))
                 .route("/heat-map", get(risks::get_risk_heat_map))
-                .route("/top-10", get(risks::get_top_risks)),
+                .route("/top-10", get(risks::get_top_risks))
+                .layer(axum::middleware::from_fn_with_state(Resource::Risks, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Compliance requirements endpoints
         .nest(
@@ -299,7 +578,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/", get(compliance::list_compliance_requirements).post(compliance::create_compliance_requirement))
                 .route("/:id", get(compliance::get_compliance_requirement).put(compliance::update_compliance_requirement).delete(compliance::delete_compliance_requirement))
                 .route("/:id/assess", post(compliance::assess_card_compliance))
-                .route("/:id/dashboard", get(compliance::get_compliance_dashboard)),
+                .route("/:id/dashboard", get(compliance::get_compliance_dashboard))
+                .layer(axum::middleware::from_fn_with_state(Resource::Compliance, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - Principles
         .nest(
@@ -307,7 +591,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
             Router::new()
                 .route("/", get(principles::list_principles).post(principles::create_principle))
                 .route("/:id", get(principles::get_principle).put(principles::update_principle).delete(principles::delete_principle))
-                .route("/:id/compliance", get(principles::get_principle_compliance)),
+                .route("/:id/compliance", get(principles::get_principle_compliance))
+                .layer(axum::middleware::from_fn_with_state(Resource::Principles, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - Standards
         .nest(
@@ -316,7 +605,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/", get(standards::list_standards).post(standards::create_standard))
                 .route("/:id", get(standards::get_standard).put(standards::update_standard).delete(standards::delete_standard))
                 .route("/:id/radar", get(standards::get_radar))
-                .route("/debt-report", get(standards::get_debt_report)),
+                .route("/debt-report", get(standards::get_debt_report))
+                .layer(axum::middleware::from_fn_with_state(Resource::Standards, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - Policies
         .nest(
@@ -324,9 +618,15 @@ pub async fn create_app(settings: Settings) -> axum::Router {
             Router::new()
                 .route("/", get(policies::list_policies).post(policies::create_policy))
                 .route("/:id", get(policies::get_policy).put(policies::update_policy).delete(policies::delete_policy))
-                .route("/:id/compliance", post(policies::check_policy_compliance))
-                .route("/:id/validate", post(policies::validate_policy))
-                .route("/violations", get(policies::list_violations)),
+                .route("/violations", get(policies::list_violations))
+                .layer(axum::middleware::from_fn_with_state(Resource::Policies, middleware::authorize))
+                // Compliance checks evaluate cards against policies without changing anything
+                .route("/:id/compliance", post(policies::check_policy_compliance).layer(axum::middleware::from_fn_with_state(POLICIES_READ, middleware::require_permission)))
+                .route("/:id/validate", post(policies::validate_policy).layer(axum::middleware::from_fn_with_state(POLICIES_READ, middleware::require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - Exceptions
         .nest(
@@ -334,9 +634,14 @@ pub async fn create_app(settings: Settings) -> axum::Router {
             Router::new()
                 .route("/", get(exceptions::list_exceptions).post(exceptions::create_exception_request))
                 .route("/:id", get(exceptions::get_exception).delete(exceptions::delete_exception))
-                .route("/:id/approve", post(exceptions::approve_exception))
-                .route("/:id/reject", post(exceptions::reject_exception))
-                .route("/expiring", get(exceptions::list_expiring_exceptions)),
+                .route("/:id/approve", post(exceptions::approve_exception).layer(axum::middleware::from_fn_with_state(EXCEPTIONS_APPROVE, middleware::require_permission)))
+                .route("/:id/reject", post(exceptions::reject_exception).layer(axum::middleware::from_fn_with_state(EXCEPTIONS_APPROVE, middleware::require_permission)))
+                .route("/expiring", get(exceptions::list_expiring_exceptions))
+                .layer(axum::middleware::from_fn_with_state(Resource::Exceptions, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - Initiatives
         .nest(
@@ -345,7 +650,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/", get(initiatives::list_initiatives).post(initiatives::create_initiative))
                 .route("/:id", get(initiatives::get_initiative).put(initiatives::update_initiative).delete(initiatives::delete_initiative))
                 .route("/:id/impact-map", get(initiatives::get_initiative_impact_map))
-                .route("/:id/link-cards", post(initiatives::link_cards_to_initiative)),
+                .route("/:id/link-cards", post(initiatives::link_cards_to_initiative))
+                .layer(axum::middleware::from_fn_with_state(Resource::Initiatives, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 3: Governance - ARB (Architecture Review Board)
         .nest(
@@ -356,7 +666,12 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/meetings/:id/agenda", get(arb::get_meeting_agenda))
                 .route("/meetings/:id/agenda/:submission_id", post(arb::add_submission_to_agenda))
                 .route("/submissions", get(arb::list_submissions).post(arb::create_submission))
-                .route("/submissions/:id", get(arb::get_submission)),
+                .route("/submissions/:id", get(arb::get_submission))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 4: Graph endpoints
         .nest(
@@ -364,22 +679,39 @@ pub async fn create_app(settings: Settings) -> axum::Router {
             Router::new()
                 .route("/", get(graph::get_graph))
                 .route("/stats", get(graph::get_graph_stats))
-                .route("/nodes/count", get(graph::get_node_count)),
+                .route("/nodes/count", get(graph::get_node_count))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 4: Import endpoints
         .nest(
             "/api/v1/import",
             Router::new()
                 .route("/bulk", post(import_handler::bulk_import_cards))
-                .route("/jobs/:job_id", get(import_handler::get_import_status)),
+                .route("/relationships", post(import_handler::bulk_import_relationships))
+                .route("/jobs/:job_id", get(import_handler::get_import_status))
+                .layer(axum::middleware::from_fn_with_state(Resource::Imports, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 4: Bulk operations endpoints
         .nest(
             "/api/v1/bulk",
             Router::new()
-                .route("/cards/delete", post(bulk::bulk_delete_cards))
+                .route("/cards/delete", post(bulk::bulk_delete_cards).layer(axum::middleware::from_fn_with_state(CARDS_DELETE, middleware::require_permission)))
                 .route("/cards/update", post(bulk::bulk_update_cards))
-                .route("/cards/export", post(bulk::bulk_export_cards)),
+                .layer(axum::middleware::from_fn_with_state(Resource::Cards, middleware::authorize))
+                // Exporting only needs read access
+                .route("/cards/export", post(bulk::bulk_export_cards).layer(axum::middleware::from_fn_with_state(EXPORTS_READ, middleware::require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // Phase 5: Cache endpoints
         .nest(
@@ -389,10 +721,43 @@ pub async fn create_app(settings: Settings) -> axum::Router {
                 .route("/stats", get(cache::get_cache_stats))
                 .route("/stats/reset", post(cache::reset_cache_stats))
                 .route("/flush", delete(cache::flush_cache))
-                .route("/warm", post(cache::warm_cache)),
+                .route("/warm", post(cache::warm_cache))
+                .layer(axum::middleware::from_fn_with_state(Resource::Cache, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        // Architecture states (baseline/target/transition) endpoints
+        .nest(
+            "/api/v1/architecture-states",
+            Router::new()
+                .route("/", get(architecture_states::list_architecture_states).post(architecture_states::create_architecture_state))
+                .route("/diff", get(architecture_states::diff_architecture_states))
+                .route("/:id", get(architecture_states::get_architecture_state).put(architecture_states::update_architecture_state).delete(architecture_states::delete_architecture_state))
+                .route("/:id/changes", get(architecture_states::list_planned_changes).post(architecture_states::add_planned_change))
+                .route("/:id/changes/:change_id", delete(architecture_states::delete_planned_change))
+                .layer(axum::middleware::from_fn_with_state(Resource::ArchitectureStates, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
+        )
+        // Admin: Graph reconciliation endpoints
+        .nest(
+            "/api/v1/admin/graph-sync",
+            Router::new()
+                .route("/", get(graph_sync::get_graph_sync_report).post(graph_sync::reconcile_graph))
+                .layer(axum::middleware::from_fn_with_state(Resource::Admin, middleware::authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    middleware::auth_middleware,
+                )),
         )
         // API Documentation
         .route("/api-docs/openapi.json", get(openapi_json))
+        .layer(axum::middleware::from_fn_with_state(app_state.clone(), middleware::csrf_protect))
+        .layer(axum::middleware::from_fn_with_state(app_state.clone(), middleware::rate_limit_middleware))
         .layer(tower_http::cors::CorsLayer::permissive())
         .with_state(app_state)
 }
@@ -11,13 +11,13 @@ use uuid::Uuid;
 use utoipa::OpenApi;
 
 use archzero_api::{
-    config::Settings,
+    config::{Environment, Settings},
     state::AppState,
-    handlers::{auth, cards, health, relationships, bia, migration, tco, policies, principles, standards, exceptions, initiatives, risks, compliance, arb, graph, import, bulk, csrf, cache, test_reset, users, export, reports},
-    services::{CardService, AuthService, RelationshipService, Neo4jService, SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ARBNotificationService, ExportService, ExportScheduler, ReportService},
-    middleware::{security_headers, security_logging, rate_limit_middleware, auth_middleware},
+    handlers::{architecture_states, auth, cards, health, relationships, bia, migration, tco, policies, principles, standards, exceptions, initiatives, risks, compliance, arb, graph, graph_sync, import, bulk, csrf, cache, test_reset, users, teams, api_tokens, scim, workspaces, metamodel, saved_searches, data_quality, export, reports},
+    services::{CardService, AuthService, RelationshipService, SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ARBNotificationService, ExportService, ExportScheduler, ReportService, GraphSyncService, VersionService, ArchitectureStateService, UserService, TeamService, OidcService, ApiTokenService, ScimService, WorkspaceService, MetamodelService, MetamodelMigrationService, CardTypeService, RelationshipRuleService, SavedSearchService, DataQualityService, account_sender::LogMessageSender, graph_backend::connect_graph_backend},
+    middleware::{security_headers, security_logging, csrf_protect, rate_limit_middleware, auth_middleware, authorize, require_permission},
     models::card::{Card, CardType, LifecyclePhase, CreateCardRequest, UpdateCardRequest, CardSearchParams},
-    models::relationship::{Relationship, RelationshipType, CreateRelationshipRequest, UpdateRelationshipRequest},
+    models::relationship::*,
     models::principles::*,
     models::standards::*,
     models::policies::*,
@@ -27,6 +27,21 @@ use archzero_api::{
     models::compliance::*,
     models::arb::*,
     models::arb_template::*,
+    models::graph_sync::*,
+    models::version::*,
+    models::architecture_state::*,
+    models::user::*,
+    models::team::*,
+    models::api_token::*,
+    models::scim::*,
+    models::workspace::*,
+    models::card_query::*,
+    models::search::*,
+    models::saved_search::*,
+    models::data_quality::*,
+    models::card_type::*,
+    models::metamodel::*,
+    models::permission::{Action, Permission, Resource},
 };
 
 /// Arc Zero API Documentation
@@ -36,13 +51,20 @@ use archzero_api::{
         cards::create_card,
         cards::get_card,
         cards::list_cards,
+        cards::query_cards,
+        cards::search_cards,
+        cards::similar_cards,
         cards::update_card,
         cards::delete_card,
+        cards::get_card_history,
+        cards::get_card_as_of,
+        cards::restore_card_version,
         relationships::create_relationship,
         relationships::get_relationship,
         relationships::list_relationships,
         relationships::update_relationship,
         relationships::delete_relationship,
+        relationships::get_relationship_history,
         health::health_check,
         bia::list_profiles,
         bia::get_profile,
@@ -131,6 +153,112 @@ use archzero_api::{
         graph::get_graph,
         graph::get_graph_stats,
         graph::get_node_count,
+        // Admin: Graph reconciliation
+        graph_sync::get_graph_sync_report,
+        graph_sync::reconcile_graph,
+        architecture_states::list_architecture_states,
+        architecture_states::create_architecture_state,
+        architecture_states::get_architecture_state,
+        architecture_states::update_architecture_state,
+        architecture_states::delete_architecture_state,
+        architecture_states::list_planned_changes,
+        architecture_states::add_planned_change,
+        architecture_states::delete_planned_change,
+        architecture_states::diff_architecture_states,
+        users::get_users,
+        users::get_user,
+        users::create_user,
+        users::update_user,
+        users::delete_user,
+        users::reactivate_user,
+        users::unlock_user,
+        users::list_invitations,
+        users::create_invitation,
+        users::revoke_invitation,
+        teams::list_teams,
+        teams::create_team,
+        teams::delete_team,
+        teams::add_team_member,
+        teams::remove_team_member,
+        api_tokens::list_my_api_tokens,
+        api_tokens::create_my_api_token,
+        api_tokens::revoke_my_api_token,
+        api_tokens::list_service_accounts,
+        api_tokens::create_service_account,
+        api_tokens::deactivate_service_account,
+        api_tokens::list_service_account_tokens,
+        api_tokens::create_service_account_token,
+        api_tokens::revoke_service_account_token,
+        scim::scim_service_provider_config,
+        scim::scim_list_users,
+        scim::scim_get_user,
+        scim::scim_create_user,
+        scim::scim_patch_user,
+        scim::scim_delete_user,
+        scim::scim_list_groups,
+        scim::scim_get_group,
+        scim::scim_create_group,
+        scim::scim_patch_group,
+        scim::scim_delete_group,
+        workspaces::list_workspaces,
+        workspaces::create_workspace,
+        workspaces::get_workspace,
+        workspaces::update_workspace,
+        workspaces::delete_workspace,
+        workspaces::list_workspace_members,
+        workspaces::set_workspace_member,
+        workspaces::remove_workspace_member,
+        workspaces::list_card_shares,
+        workspaces::share_card,
+        workspaces::unshare_card,
+        metamodel::list_metamodel_rules,
+        metamodel::get_metamodel_rule,
+        metamodel::create_metamodel_rule,
+        metamodel::update_metamodel_rule,
+        metamodel::delete_metamodel_rule,
+        metamodel::validate_card_attributes,
+        metamodel::get_metamodel_version,
+        metamodel::list_metamodel_migrations,
+        metamodel::get_metamodel_migration,
+        metamodel::dry_run_metamodel_migration,
+        metamodel::create_metamodel_migration,
+        metamodel::list_card_types,
+        metamodel::get_card_type,
+        metamodel::create_card_type,
+        metamodel::update_card_type,
+        metamodel::delete_card_type,
+        metamodel::list_relationship_rules,
+        metamodel::get_relationship_rule,
+        metamodel::create_relationship_rule,
+        metamodel::update_relationship_rule,
+        metamodel::delete_relationship_rule,
+        metamodel::list_relationship_rule_violations,
+        saved_searches::list_saved_searches,
+        saved_searches::create_saved_search,
+        saved_searches::get_saved_search,
+        saved_searches::update_saved_search,
+        saved_searches::delete_saved_search,
+        saved_searches::run_saved_search,
+        saved_searches::pin_saved_search,
+        saved_searches::unpin_saved_search,
+        data_quality::get_data_quality_dashboard,
+        data_quality::recalculate_data_quality,
+        data_quality::get_card_quality,
+        data_quality::review_card,
+        auth::refresh,
+        auth::logout,
+        auth::me,
+        auth::my_permissions,
+        auth::list_sessions,
+        auth::revoke_session,
+        auth::revoke_other_sessions,
+        auth::accept_invitation,
+        auth::request_password_reset,
+        auth::confirm_password_reset,
+        auth::change_password,
+        auth::auth_providers,
+        auth::oidc_login,
+        auth::oidc_callback,
     ),
     components(
         schemas(
@@ -145,7 +273,120 @@ use archzero_api::{
             CreateRelationshipRequest,
             UpdateRelationshipRequest,
             cards::CardListResponse,
+            CardQuery,
+            QueryCondition,
+            QueryOperator,
+            RelationshipPredicate,
+            RelationshipDirection,
+            CardQueryRequest,
+            CardSearchHit,
+            FacetCount,
+            SearchFacets,
+            CardSearchResponse,
+            SimilarCard,
+            SuggestedRelationship,
+            SimilarCardsResponse,
             relationships::CardRelationshipParams,
+            CardVersion,
+            RelationshipVersion,
+            VersionOperation,
+            Actor,
+            ArchitectureState,
+            StateKind,
+            StateMode,
+            CreateArchitectureStateRequest,
+            UpdateArchitectureStateRequest,
+            StateEntityType,
+            PlannedChangeType,
+            PlannedChange,
+            CreatePlannedChangeRequest,
+            StateDiffParams,
+            ChangedEntity,
+            EntityDiff,
+            StateDiff,
+            User,
+            UserRole,
+            UserAccount,
+            RegisterRequest,
+            UpdateUserRequest,
+            Invitation,
+            InvitationStatus,
+            Team,
+            ApiToken,
+            CreateApiTokenRequest,
+            CreatedApiToken,
+            ServiceAccount,
+            CreateServiceAccountRequest,
+            ScimUser,
+            ScimName,
+            ScimEmail,
+            ScimMember,
+            ScimMeta,
+            ScimGroup,
+            ScimCreateUserRequest,
+            ScimCreateGroupRequest,
+            ScimPatchRequest,
+            ScimPatchOperation,
+            ScimUserList,
+            ScimGroupList,
+            Workspace,
+            WorkspaceAccess,
+            WorkspaceMember,
+            CreateWorkspaceRequest,
+            UpdateWorkspaceRequest,
+            SetWorkspaceMemberRequest,
+            CardShare,
+            ShareCardRequest,
+            MetamodelRule,
+            CreateMetamodelRuleRequest,
+            UpdateMetamodelRuleRequest,
+            ValidateAttributesRequest,
+            ValidateAttributesResponse,
+            AttributeViolation,
+            ViolationDetails,
+            MetamodelOperation,
+            RetireMode,
+            MetamodelMigrationStatus,
+            MetamodelMigration,
+            CreateMetamodelMigrationRequest,
+            MetamodelDryRun,
+            MetamodelDryRunSample,
+            MetamodelVersionInfo,
+            CardLayer,
+            AttributeDataType,
+            AttributeDefinition,
+            CardTypeDefinition,
+            CreateCardTypeRequest,
+            UpdateCardTypeRequest,
+            RelationshipCardinality,
+            RelationshipRule,
+            CreateRelationshipRuleRequest,
+            UpdateRelationshipRuleRequest,
+            RelationshipEndpoint,
+            RelationshipRuleViolation,
+            RelationshipViolationReport,
+            SavedSearchQuery,
+            SavedSearch,
+            CreateSavedSearchRequest,
+            UpdateSavedSearchRequest,
+            CardQuality,
+            CardQualitySummary,
+            QualityBands,
+            TypeQuality,
+            OwnerQuality,
+            DataQualityDashboard,
+            QualityRecalculation,
+            CreateTeamRequest,
+            AddTeamMemberRequest,
+            CreateInvitationRequest,
+            AcceptInvitationRequest,
+            PasswordResetRequest,
+            ConfirmPasswordResetRequest,
+            ChangePasswordRequest,
+            LoginResponse,
+            RefreshRequest,
+            AuthProviders,
+            Session,
             health::HealthResponse,
             // Phase 3: Governance & Compliance schemas
             // Principles
@@ -273,6 +514,12 @@ use archzero_api::{
             graph::GraphEdgeData,
             graph::GraphStats,
             graph::GraphSearchParams,
+            // Admin: Graph reconciliation
+            ReconcileMode,
+            ReconcileRequest,
+            ReconciliationReport,
+            ReconciliationResult,
+            DriftedEntity,
         )
     ),
     tags(
@@ -292,6 +539,15 @@ use archzero_api::{
         (name = "Compliance", description = "Compliance requirements tracking endpoints"),
         (name = "ARB", description = "Architecture Review Board workflow endpoints"),
         (name = "Graph", description = "Graph visualization endpoints"),
+        (name = "Admin", description = "Administrative maintenance endpoints"),
+        (name = "Architecture States", description = "Baseline, target and transition architecture states"),
+        (name = "Users", description = "User administration and invitations"),
+        (name = "SCIM", description = "SCIM 2.0 user and group provisioning"),
+        (name = "Workspaces", description = "Workspaces, per-workspace roles and members"),
+        (name = "Metamodel", description = "Attribute rules per card type, enforced on card writes"),
+        (name = "Saved Searches", description = "Named card searches, personal or shared with a team"),
+        (name = "Data Quality", description = "Computed card quality scores and the data quality dashboard"),
+        (name = "Auth", description = "Sessions, token refresh, invitation acceptance and password lifecycle"),
     ),
     info(
         title = "Arc Zero API",
@@ -328,41 +584,47 @@ async fn main() -> anyhow::Result<()> {
     let pool = PgPool::connect(&settings.database.postgres_url).await?;
     tracing::info!("Connected to PostgreSQL");
 
-    // Connect to Neo4j
-    let neo4j_service = Arc::new(
-        Neo4jService::new(
-            &settings.database.neo4j_uri,
-            &settings.database.neo4j_user,
-            &settings.database.neo4j_password,
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to Neo4j: {}", e))?
-    );
-    tracing::info!("Connected to Neo4j");
-
     // Initialize services
-    let card_service = Arc::new(CardService::new(pool.clone()));
+    let card_service = Arc::new(
+        CardService::new(pool.clone(), settings.card_access.clone()).with_data_quality(settings.data_quality.clone()),
+    );
     let auth_service = Arc::new(AuthService::new(
         pool.clone(),
         settings.jwt.secret.clone(),
-        settings.jwt.expiration_hours * 3600,
-    ));
-    let relationship_service = Arc::new(RelationshipService::new(pool.clone()));
+        settings.jwt.access_token_minutes * 60,
+        settings.jwt.refresh_token_days,
+    ).with_password_login(settings.accounts.password_login_enabled));
+    let relationship_service = Arc::new(RelationshipService::new(pool.clone()).with_data_quality(settings.data_quality.clone()));
+
+    // Connect the graph backend (Neo4j, or embedded traversals over PostgreSQL)
+    let graph_backend = connect_graph_backend(&settings, card_service.clone(), relationship_service.clone()).await?;
+    tracing::info!("Graph backend: {}", graph_backend.name());
 
     // Initialize SAGA orchestrator
     let saga_orchestrator = Arc::new(SagaOrchestrator::new(
         card_service.clone(),
         relationship_service.clone(),
-        neo4j_service.clone(),
+        graph_backend.clone(),
     ));
 
     // Initialize Phase 2 intelligence services
     let bia_service = Arc::new(BIAService::new());
-    let topology_service = Arc::new(TopologyService::new(neo4j_service.clone()));
+    let topology_service = Arc::new(TopologyService::new(graph_backend.clone()));
     let migration_service = Arc::new(MigrationService::new());
     let tco_service = Arc::new(TCOService::new());
-    let csrf_service = Arc::new(CsrfService::new());
-    let rate_limit_service = Arc::new(RateLimitService::new());
+    let csrf_service = Arc::new(
+        CsrfService::new(settings.csrf.clone(), &settings.jwt.secret)
+            .with_secure_cookie(settings.server.environment == Environment::Production),
+    );
+    // Rate limiting: counters in Redis so limits hold across replicas
+    let rate_limit_redis = settings.rate_limit.redis_url.clone()
+        .or_else(|| settings.cache.redis_url.clone())
+        .unwrap_or_else(|| "redis://127.0.0.1:6379".to_string());
+    let rate_limit_service = Arc::new(RateLimitService::with_redis(
+        &rate_limit_redis,
+        settings.rate_limit.clone(),
+        settings.rate_limit.is_enabled(settings.server.environment),
+    )?);
 
     // Initialize Phase 5: Redis Cache Service
     let redis_url = settings.cache.redis_url.clone().unwrap_or_else(|| "redis://127.0.0.1:6379".to_string());
@@ -394,6 +656,61 @@ async fn main() -> anyhow::Result<()> {
     // Initialize Report Service
     let report_service = Arc::new(ReportService::new(pool.clone()));
 
+    // Initialize version history service
+    let version_service = Arc::new(VersionService::new(pool.clone()));
+
+    // Initialize architecture states (baseline/target/transition)
+    let architecture_state_service = Arc::new(ArchitectureStateService::new(
+        pool.clone(),
+        card_service.clone(),
+        relationship_service.clone(),
+    ));
+
+    // Initialize user management (invitation and reset links go to the log until a sender is configured)
+    let user_service = Arc::new(UserService::new(
+        pool.clone(),
+        auth_service.clone(),
+        Arc::new(LogMessageSender),
+        settings.accounts.clone(),
+        settings.password_policy.clone(),
+    ));
+    let team_service = Arc::new(TeamService::new(pool.clone()));
+    let oidc_service = Arc::new(
+        OidcService::new(pool.clone(), auth_service.clone(), settings.oidc.clone())
+            .with_secure_cookie(settings.server.environment == Environment::Production),
+    );
+    let api_token_service = Arc::new(ApiTokenService::new(pool.clone(), settings.api_tokens.clone()));
+    let scim_service = Arc::new(ScimService::new(pool.clone(), user_service.clone(), settings.scim.clone()));
+    let workspace_service = Arc::new(WorkspaceService::new(pool.clone()));
+    let metamodel_service = Arc::new(MetamodelService::new(pool.clone()));
+    let card_type_service = Arc::new(CardTypeService::new(pool.clone()));
+    let relationship_rule_service = Arc::new(RelationshipRuleService::new(pool.clone()));
+    let saved_search_service = Arc::new(SavedSearchService::new(pool.clone(), card_service.clone()));
+    let data_quality_service = Arc::new(DataQualityService::new(pool.clone(), settings.data_quality.clone()));
+    let metamodel_migration_service = Arc::new(MetamodelMigrationService::new(pool.clone(), card_service.clone()));
+    if let Err(e) = metamodel_migration_service.clone().resume_interrupted().await {
+        tracing::warn!("Failed to resume metamodel migrations: {:?}", e);
+    }
+
+    // Initialize graph reconciliation (PostgreSQL -> graph backend)
+    let graph_sync_service = Arc::new(GraphSyncService::new(
+        card_service.clone(),
+        relationship_service.clone(),
+        graph_backend.clone(),
+    ));
+    if settings.graph_sync.interval_minutes > 0 {
+        let mode = if settings.graph_sync.auto_repair { ReconcileMode::Repair } else { ReconcileMode::Report };
+        graph_sync_service.clone().start_periodic(
+            std::time::Duration::from_secs(settings.graph_sync.interval_minutes * 60),
+            mode,
+        );
+    }
+
+    // Rescore every card nightly; freshness decays without any change to the card
+    if let Some(hour) = settings.data_quality.nightly_hour_utc {
+        data_quality_service.clone().start_nightly(hour);
+    }
+
     // Initialize Export Scheduler
     let export_scheduler = Arc::new(ExportScheduler::new().await?);
 
@@ -411,7 +728,7 @@ async fn main() -> anyhow::Result<()> {
         card_service: card_service.clone(),
         auth_service: auth_service.clone(),
         relationship_service: relationship_service.clone(),
-        neo4j_service: neo4j_service.clone(),
+        graph_backend: graph_backend.clone(),
         saga_orchestrator: saga_orchestrator.clone(),
         bia_service: bia_service.clone(),
         topology_service: topology_service.clone(),
@@ -425,10 +742,51 @@ async fn main() -> anyhow::Result<()> {
         arb_notification_service: arb_notification_service.clone(),
         export_service: export_service.clone(),
         report_service: report_service.clone(),
+        graph_sync_service: graph_sync_service.clone(),
+        version_service: version_service.clone(),
+        architecture_state_service: architecture_state_service.clone(),
+        user_service: user_service.clone(),
+        team_service: team_service.clone(),
+        oidc_service: oidc_service.clone(),
+        api_token_service: api_token_service.clone(),
+        scim_service: scim_service.clone(),
+        workspace_service: workspace_service.clone(),
+        metamodel_service: metamodel_service.clone(),
+        metamodel_migration_service: metamodel_migration_service.clone(),
+        card_type_service: card_type_service.clone(),
+        relationship_rule_service: relationship_rule_service.clone(),
+        saved_search_service: saved_search_service.clone(),
+        data_quality_service: data_quality_service.clone(),
         import_jobs: import_jobs.clone(),
     };
 
-    // Build our application with routes
+    // Exact permissions for routes whose action differs from their HTTP method
+    const EXPORTS_READ: Permission = Permission::new(Resource::Exports, Action::Read);
+    const METAMODEL_READ: Permission = Permission::new(Resource::Metamodel, Action::Read);
+    const CARDS_READ: Permission = Permission::new(Resource::Cards, Action::Read);
+    const CARDS_WRITE: Permission = Permission::new(Resource::Cards, Action::Write);
+    const REPORTS_READ: Permission = Permission::new(Resource::Reports, Action::Read);
+    const POLICIES_READ: Permission = Permission::new(Resource::Policies, Action::Read);
+    const EXCEPTIONS_APPROVE: Permission = Permission::new(Resource::Exceptions, Action::Approve);
+    const ARB_APPROVE: Permission = Permission::new(Resource::Arb, Action::Approve);
+
+    // Test-only endpoints: unauthenticated, so opt-in and never mounted in production
+    let test_routes = if settings.server.enable_test_routes
+        && settings.server.environment != Environment::Production
+    {
+        tracing::warn!("Unauthenticated test reset routes are mounted under /api/v1/test");
+        Router::new()
+            .route("/api/v1/test/reset-auth-state", post(test_reset::reset_auth_state))
+            .route("/api/v1/test/cleanup-all-cards", post(test_reset::cleanup_all_cards))
+            .route("/api/v1/test/seed-arb-users", post(test_reset::seed_arb_users))
+    } else {
+        Router::new()
+    };
+
+    // Build our application with routes.
+    // Every protected router is authenticated by `auth_middleware` and authorized by
+    // `authorize` for its resource (action from the HTTP method); routes whose action
+    // differs declare an exact permission with `require_permission`.
     let app = Router::new()
         .nest(
             "/api/v1/health",
@@ -449,24 +807,142 @@ async fn main() -> anyhow::Result<()> {
                 .route("/stats", get(cache::get_cache_stats))
                 .route("/stats/reset", post(cache::reset_cache_stats))
                 .route("/flush", post(cache::flush_cache))
-                .route("/warm", post(cache::warm_cache)),
+                .route("/warm", post(cache::warm_cache))
+                .layer(axum::middleware::from_fn_with_state(Resource::Cache, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
-        // Test-only endpoints (only available in development)
-        .route("/api/v1/test/reset-auth-state", post(test_reset::reset_auth_state))
-        .route("/api/v1/test/cleanup-all-cards", post(test_reset::cleanup_all_cards))
-        .route("/api/v1/test/seed-arb-users", post(test_reset::seed_arb_users))
+        .merge(test_routes)
         .nest(
             "/api/v1/auth",
             Router::new()
-                .route("/login", post(auth::login))
                 .route("/logout", post(auth::logout))
-                .route("/me", get(auth::me)),
+                .route("/me", get(auth::me))
+                .route("/permissions", get(auth::my_permissions))
+                .route("/change-password", post(auth::change_password))
+                .route("/sessions", get(auth::list_sessions).delete(auth::revoke_other_sessions))
+                .route("/sessions/:id", delete(auth::revoke_session))
+                .route("/tokens", get(api_tokens::list_my_api_tokens).post(api_tokens::create_my_api_token))
+                .route("/tokens/:id", delete(api_tokens::revoke_my_api_token))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                ))
+                // Public routes (added after the layer, so not authenticated)
+                .route("/login", post(auth::login))
+                .route("/refresh", post(auth::refresh))
+                .route("/invitations/accept", post(auth::accept_invitation))
+                .route("/password-reset", post(auth::request_password_reset))
+                .route("/password-reset/confirm", post(auth::confirm_password_reset))
+                .route("/providers", get(auth::auth_providers))
+                .route("/oidc/login", get(auth::oidc_login))
+                .route("/oidc/callback", get(auth::oidc_callback)),
         )
         .nest(
             "/api/v1/users",
             Router::new()
                 .route("/", get(users::get_users).post(users::create_user))
                 .route("/:id", get(users::get_user).put(users::update_user).delete(users::delete_user))
+                .route("/:id/reactivate", post(users::reactivate_user))
+                .route("/:id/unlock", post(users::unlock_user))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/teams",
+            Router::new()
+                .route("/", get(teams::list_teams).post(teams::create_team))
+                .route("/:id", delete(teams::delete_team))
+                .route("/:id/members", post(teams::add_team_member))
+                .route("/:id/members/:user_id", delete(teams::remove_team_member))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/workspaces",
+            Router::new()
+                .route("/", get(workspaces::list_workspaces).post(workspaces::create_workspace))
+                .route("/:id", get(workspaces::get_workspace).put(workspaces::update_workspace).delete(workspaces::delete_workspace))
+                .route("/:id/members", get(workspaces::list_workspace_members).put(workspaces::set_workspace_member))
+                .route("/:id/members/:user_id", delete(workspaces::remove_workspace_member))
+                // Access is checked per workspace in the handlers
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/metamodel",
+            Router::new()
+                .route("/rules", get(metamodel::list_metamodel_rules).post(metamodel::create_metamodel_rule))
+                .route("/rules/:id", get(metamodel::get_metamodel_rule).put(metamodel::update_metamodel_rule).delete(metamodel::delete_metamodel_rule))
+                .route("/version", get(metamodel::get_metamodel_version))
+                .route("/migrations", get(metamodel::list_metamodel_migrations).post(metamodel::create_metamodel_migration))
+                .route("/migrations/dry-run", post(metamodel::dry_run_metamodel_migration))
+                .route("/migrations/:id", get(metamodel::get_metamodel_migration))
+                .route("/card-types", get(metamodel::list_card_types).post(metamodel::create_card_type))
+                .route("/card-types/:name", get(metamodel::get_card_type).put(metamodel::update_card_type).delete(metamodel::delete_card_type))
+                .route("/relationship-rules", get(metamodel::list_relationship_rules).post(metamodel::create_relationship_rule))
+                .route("/relationship-rules/violations", get(metamodel::list_relationship_rule_violations))
+                .route("/relationship-rules/:id", get(metamodel::get_relationship_rule).put(metamodel::update_relationship_rule).delete(metamodel::delete_relationship_rule))
+                .layer(axum::middleware::from_fn_with_state(Resource::Metamodel, authorize))
+                // Validating attributes only needs read access
+                .route("/validate", post(metamodel::validate_card_attributes).layer(axum::middleware::from_fn_with_state(METAMODEL_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/scim/v2",
+            Router::new()
+                .route("/ServiceProviderConfig", get(scim::scim_service_provider_config))
+                .route("/Users", get(scim::scim_list_users).post(scim::scim_create_user))
+                .route(
+                    "/Users/:id",
+                    get(scim::scim_get_user).patch(scim::scim_patch_user).delete(scim::scim_delete_user),
+                )
+                .route("/Groups", get(scim::scim_list_groups).post(scim::scim_create_group))
+                .route(
+                    "/Groups/:id",
+                    get(scim::scim_get_group).patch(scim::scim_patch_group).delete(scim::scim_delete_group),
+                )
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/service-accounts",
+            Router::new()
+                .route("/", get(api_tokens::list_service_accounts).post(api_tokens::create_service_account))
+                .route("/:id", delete(api_tokens::deactivate_service_account))
+                .route(
+                    "/:id/tokens",
+                    get(api_tokens::list_service_account_tokens).post(api_tokens::create_service_account_token),
+                )
+                .route("/:id/tokens/:token_id", delete(api_tokens::revoke_service_account_token))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/v1/invitations",
+            Router::new()
+                .route("/", get(users::list_invitations).post(users::create_invitation))
+                .route("/:id", delete(users::revoke_invitation))
+                .layer(axum::middleware::from_fn_with_state(Resource::Users, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -476,32 +952,93 @@ async fn main() -> anyhow::Result<()> {
             "/api/v1/cards",
             Router::new()
                 .route("/", get(cards::list_cards).post(cards::create_card))
-                .route("/:id", get(cards::get_card).put(cards::update_card).delete(cards::delete_card)),
+                .route("/search", get(cards::search_cards))
+                .route("/:id", get(cards::get_card).put(cards::update_card).delete(cards::delete_card))
+                .route("/:id/history", get(cards::get_card_history))
+                .route("/:id/similar", get(cards::similar_cards))
+                .route("/:id/quality", get(data_quality::get_card_quality))
+                .route("/:id/as-of", get(cards::get_card_as_of))
+                .route("/:id/versions/:version/restore", post(cards::restore_card_version))
+                .route("/:id/shares", get(workspaces::list_card_shares).post(workspaces::share_card))
+                .route("/:id/shares/:workspace_id", delete(workspaces::unshare_card))
+                .layer(axum::middleware::from_fn_with_state(Resource::Cards, authorize))
+                // Searching only needs read access
+                .route("/query", post(cards::query_cards).layer(axum::middleware::from_fn_with_state(CARDS_READ, require_permission)))
+                // Reviewing changes only the card's freshness
+                .route("/:id/review", post(data_quality::review_card).layer(axum::middleware::from_fn_with_state(CARDS_WRITE, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        // Saving and running searches only needs read access to cards
+        .nest(
+            "/api/v1/saved-searches",
+            Router::new()
+                .route("/", get(saved_searches::list_saved_searches).post(saved_searches::create_saved_search))
+                .route("/:id", get(saved_searches::get_saved_search).put(saved_searches::update_saved_search).delete(saved_searches::delete_saved_search))
+                .route("/:id/run", get(saved_searches::run_saved_search))
+                .route("/:id/pin", put(saved_searches::pin_saved_search).delete(saved_searches::unpin_saved_search))
+                .layer(axum::middleware::from_fn_with_state(CARDS_READ, require_permission))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        // Recalculating is a metamodel administration task; the dashboard only needs read access to cards
+        .nest(
+            "/api/v1/data-quality",
+            Router::new()
+                .route("/recalculate", post(data_quality::recalculate_data_quality))
+                .layer(axum::middleware::from_fn_with_state(Resource::Metamodel, authorize))
+                .route("/", get(data_quality::get_data_quality_dashboard).layer(axum::middleware::from_fn_with_state(CARDS_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         .nest(
             "/api/v1/relationships",
             Router::new()
                 .route("/", get(relationships::list_relationships).post(relationships::create_relationship))
-                .route("/:id", get(relationships::get_relationship).put(relationships::update_relationship).delete(relationships::delete_relationship)),
+                .route("/:id", get(relationships::get_relationship).put(relationships::update_relationship).delete(relationships::delete_relationship))
+                .route("/:id/history", get(relationships::get_relationship_history))
+                .layer(axum::middleware::from_fn_with_state(Resource::Relationships, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 4: Export endpoints
         .nest(
             "/api/v1/export",
             Router::new()
-                .route("/cards", post(export::export_cards))
-                .route("/history", get(export::get_export_history))
-                .route("/:domain", post(export::export_domain))
                 .route("/scheduled", post(export::create_scheduled_export).get(export::list_scheduled_exports))
-                .route("/scheduled/:id", put(export::update_scheduled_export).delete(export::delete_scheduled_export)),
+                .route("/scheduled/:id", put(export::update_scheduled_export).delete(export::delete_scheduled_export))
+                .route("/history", get(export::get_export_history))
+                .layer(axum::middleware::from_fn_with_state(Resource::Exports, authorize))
+                // Generating an export only needs read access
+                .route("/cards", post(export::export_cards).layer(axum::middleware::from_fn_with_state(EXPORTS_READ, require_permission)))
+                .route("/:domain", post(export::export_domain).layer(axum::middleware::from_fn_with_state(EXPORTS_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 4: Report generation endpoints
         .nest(
             "/api/v1/reports",
             Router::new()
-                .route("/generate", post(reports::generate_report))
-                .route("/custom", post(reports::generate_custom_report))
                 .route("/templates", get(reports::list_templates).post(reports::create_template))
-                .route("/templates/:id", put(reports::update_template).delete(reports::delete_template)),
+                .route("/templates/:id", put(reports::update_template).delete(reports::delete_template))
+                .layer(axum::middleware::from_fn_with_state(Resource::Reports, authorize))
+                // Generating a report only needs read access
+                .route("/generate", post(reports::generate_report).layer(axum::middleware::from_fn_with_state(REPORTS_READ, require_permission)))
+                .route("/custom", post(reports::generate_custom_report).layer(axum::middleware::from_fn_with_state(REPORTS_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 2: BIA endpoints
         .nest(
@@ -510,7 +1047,12 @@ async fn main() -> anyhow::Result<()> {
                 .route("/profiles", get(bia::list_profiles))
                 .route("/profiles/:name", get(bia::get_profile))
                 .route("/assessments", post(bia::create_assessment))
-                .route("/assessments/:id", get(bia::get_assessment)),
+                .route("/assessments/:id", get(bia::get_assessment))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 2: Topology endpoints
         .nest(
@@ -520,7 +1062,12 @@ async fn main() -> anyhow::Result<()> {
                 .route("/cards/:card_id/metrics", get(bia::get_topology_metrics))
                 .route("/cards/:card_id/dependents", get(bia::get_dependents))
                 .route("/cards/:card_id/dependencies", get(bia::get_dependencies))
-                .route("/critical-paths", get(bia::get_critical_paths)),
+                .route("/critical-paths", get(bia::get_critical_paths))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 2: Migration endpoints
         .nest(
@@ -528,7 +1075,12 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/assess", post(migration::assess_migration))
                 .route("/recommendations/:id", get(migration::get_recommendation))
-                .route("/cards/:card_id/recommendations", get(migration::get_card_recommendations)),
+                .route("/cards/:card_id/recommendations", get(migration::get_card_recommendations))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 2: TCO endpoints
         .nest(
@@ -538,7 +1090,12 @@ async fn main() -> anyhow::Result<()> {
                 .route("/portfolio", get(tco::get_portfolio_tco))
                 .route("/cards/:card_id", get(tco::get_tco_breakdown))
                 .route("/cards/:card_id/comparison", get(tco::get_tco_comparison))
-                .route("/cards/:card_id/trend", get(tco::get_cost_trend)),
+                .route("/cards/:card_id/trend", get(tco::get_cost_trend))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Architecture Policy endpoints
         .nest(
@@ -546,10 +1103,15 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/", get(policies::list_policies).post(policies::create_policy))
                 .route("/:id", get(policies::get_policy).put(policies::update_policy).delete(policies::delete_policy))
-                .route("/check", post(policies::check_policy_compliance))
-                .route("/:id/validate", post(policies::validate_policy))
                 .route("/violations", get(policies::list_violations))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Policies, authorize))
+                // Compliance checks evaluate cards against policies without changing anything
+                .route("/check", post(policies::check_policy_compliance).layer(axum::middleware::from_fn_with_state(POLICIES_READ, require_permission)))
+                .route("/:id/validate", post(policies::validate_policy).layer(axum::middleware::from_fn_with_state(POLICIES_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Architecture Principles endpoints
         .nest(
@@ -558,7 +1120,11 @@ async fn main() -> anyhow::Result<()> {
                 .route("/", get(principles::list_principles).post(principles::create_principle))
                 .route("/:id", get(principles::get_principle).put(principles::update_principle).delete(principles::delete_principle))
                 .route("/:id/compliance", get(principles::get_principle_compliance))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Principles, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Technology Standards endpoints
         .nest(
@@ -568,7 +1134,11 @@ async fn main() -> anyhow::Result<()> {
                 .route("/:id", get(standards::get_standard).put(standards::update_standard).delete(standards::delete_standard))
                 .route("/radar", get(standards::get_radar))
                 .route("/debt-report", get(standards::get_debt_report))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Standards, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Exceptions endpoints
         .nest(
@@ -576,10 +1146,14 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/", get(exceptions::list_exceptions).post(exceptions::create_exception_request))
                 .route("/:id", get(exceptions::get_exception).delete(exceptions::delete_exception))
-                .route("/:id/approve", post(exceptions::approve_exception))
-                .route("/:id/reject", post(exceptions::reject_exception))
+                .route("/:id/approve", post(exceptions::approve_exception).layer(axum::middleware::from_fn_with_state(EXCEPTIONS_APPROVE, require_permission)))
+                .route("/:id/reject", post(exceptions::reject_exception).layer(axum::middleware::from_fn_with_state(EXCEPTIONS_APPROVE, require_permission)))
                 .route("/expiring", get(exceptions::list_expiring_exceptions))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Exceptions, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Initiatives endpoints
         .nest(
@@ -589,7 +1163,11 @@ async fn main() -> anyhow::Result<()> {
                 .route("/:id", get(initiatives::get_initiative).put(initiatives::update_initiative).delete(initiatives::delete_initiative))
                 .route("/:id/impact-map", get(initiatives::get_initiative_impact_map))
                 .route("/:id/link-cards", post(initiatives::link_cards_to_initiative))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Initiatives, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Risks endpoints
         .nest(
@@ -599,7 +1177,11 @@ async fn main() -> anyhow::Result<()> {
                 .route("/:id", get(risks::get_risk).put(risks::update_risk).delete(risks::delete_risk))
                 .route("/heat-map", get(risks::get_risk_heat_map))
                 .route("/top-10", get(risks::get_top_risks))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Risks, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: Compliance Requirements endpoints
         .nest(
@@ -609,7 +1191,11 @@ async fn main() -> anyhow::Result<()> {
                 .route("/:id", get(compliance::get_compliance_requirement).put(compliance::update_compliance_requirement).delete(compliance::delete_compliance_requirement))
                 .route("/:id/assess", post(compliance::assess_card_compliance))
                 .route("/:id/dashboard", get(compliance::get_compliance_dashboard))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Compliance, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 4.2: Compliance Audit endpoints
         .nest(
@@ -617,7 +1203,11 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/", get(compliance::list_compliance_audits).post(compliance::create_compliance_audit))
                 .route("/:id", get(compliance::get_compliance_audit).put(compliance::update_compliance_audit).delete(compliance::delete_compliance_audit))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Compliance, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 3: ARB Workflow endpoints (with authentication)
         .nest(
@@ -626,6 +1216,7 @@ async fn main() -> anyhow::Result<()> {
                 .route("/", get(arb::list_meetings).post(arb::create_meeting))
                 .route("/:id", get(arb::get_meeting).put(arb::update_meeting).delete(arb::delete_meeting))
                 .route("/:id/agenda", get(arb::get_meeting_agenda).post(arb::add_submission_to_agenda))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -636,7 +1227,8 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/", get(arb::list_submissions).post(arb::create_submission))
                 .route("/:id", get(arb::get_submission).put(arb::update_submission).delete(arb::delete_submission))
-                .route("/:id/decision", post(arb::record_decision))
+                .route("/:id/decision", post(arb::record_decision).layer(axum::middleware::from_fn_with_state(ARB_APPROVE, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -648,6 +1240,7 @@ async fn main() -> anyhow::Result<()> {
                 .route("/", get(arb::list_templates).post(arb::create_template))
                 .route("/:id", get(arb::get_template).put(arb::update_template).delete(arb::delete_template))
                 .route("/from-template", post(arb::create_from_template))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -659,6 +1252,7 @@ async fn main() -> anyhow::Result<()> {
                 .route("/", get(arb::get_audit_logs))
                 .route("/:entity_type/:entity_id", get(arb::get_entity_audit_logs))
                 .route("/export", get(arb::export_audit_logs))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -672,6 +1266,7 @@ async fn main() -> anyhow::Result<()> {
                 .route("/:id/read", post(arb::mark_notification_read))
                 .route("/read-all", post(arb::mark_all_read))
                 .route("/:id", delete(arb::delete_notification))
+                .layer(axum::middleware::from_fn_with_state(Resource::Notifications, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -682,6 +1277,7 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/dashboard", get(arb::get_dashboard))
                 .route("/statistics", get(arb::get_statistics))
+                .layer(axum::middleware::from_fn_with_state(Resource::Arb, authorize))
                 .layer(axum::middleware::from_fn_with_state(
                     app_state.clone(),
                     auth_middleware,
@@ -693,15 +1289,51 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/", get(graph::get_graph))
                 .route("/stats", get(graph::get_graph_stats))
-                .route("/count", get(graph::get_node_count)),
+                .route("/count", get(graph::get_node_count))
+                .layer(axum::middleware::from_fn_with_state(Resource::Analysis, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        // Architecture states (baseline/target/transition) endpoints
+        .nest(
+            "/api/v1/architecture-states",
+            Router::new()
+                .route("/", get(architecture_states::list_architecture_states).post(architecture_states::create_architecture_state))
+                .route("/diff", get(architecture_states::diff_architecture_states))
+                .route("/:id", get(architecture_states::get_architecture_state).put(architecture_states::update_architecture_state).delete(architecture_states::delete_architecture_state))
+                .route("/:id/changes", get(architecture_states::list_planned_changes).post(architecture_states::add_planned_change))
+                .route("/:id/changes/:change_id", delete(architecture_states::delete_planned_change))
+                .layer(axum::middleware::from_fn_with_state(Resource::ArchitectureStates, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
+        )
+        // Admin: Graph reconciliation endpoints
+        .nest(
+            "/api/v1/admin/graph-sync",
+            Router::new()
+                .route("/", get(graph_sync::get_graph_sync_report).post(graph_sync::reconcile_graph))
+                .layer(axum::middleware::from_fn_with_state(Resource::Admin, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 4: Bulk Import endpoints
         .nest(
             "/api/v1/import",
             Router::new()
                 .route("/cards", post(import::bulk_import_cards))
+                .route("/relationships", post(import::bulk_import_relationships))
                 .route("/status/:job_id", get(import::get_import_status))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Imports, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // Phase 4: Bulk Operations endpoints
         .nest(
@@ -709,28 +1341,31 @@ async fn main() -> anyhow::Result<()> {
             Router::new()
                 .route("/bulk", axum::routing::delete(bulk::bulk_delete_cards))
                 .route("/bulk/update", axum::routing::put(bulk::bulk_update_cards))
-                // Removed Extension layer to fix type inference),
+                .layer(axum::middleware::from_fn_with_state(Resource::Cards, authorize))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         .nest(
             "/api/v1/export",
             Router::new()
-                .route("/bulk", post(bulk::bulk_export_cards))
-                // Removed Extension layer to fix type inference),
+                .route("/bulk", post(bulk::bulk_export_cards).layer(axum::middleware::from_fn_with_state(EXPORTS_READ, require_permission)))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth_middleware,
+                )),
         )
         // API Documentation routes
         .route("/api-docs/openapi.json", get(openapi_json))
         // Security middleware (applied to all routes)
         .layer(axum::middleware::from_fn(security_headers))
         .layer(axum::middleware::from_fn(security_logging))
-        // Phase 5: CSRF Protection (Available via /api/v1/csrf/token endpoint)
-        // Note: CSRF middleware is disabled globally to prevent breaking existing API calls.
-        // Clients should obtain tokens from /api/v1/csrf/token and include them in
-        // the X-CSRF-Token header for state-changing operations.
-        // .layer(axum::middleware::from_fn(csrf_protect))
-        // Phase 5: Rate Limiting (Available via rate_limit_service)
-        // Note: Rate limiting middleware is disabled globally to prevent blocking development.
-        // To enable, uncomment the line below and adjust rate limits in middleware/rate_limit.rs
-        // .layer(axum::middleware::from_fn(rate_limit_middleware))
+        // Phase 5: CSRF Protection for cookie-authenticated requests; Bearer clients are exempt.
+        // Browsers obtain a token from /api/v1/csrf/token and echo it in X-CSRF-Token.
+        .layer(axum::middleware::from_fn_with_state(app_state.clone(), csrf_protect))
+        // Phase 5: Rate Limiting; on by default in production, see [rate_limit] in config
+        .layer(axum::middleware::from_fn_with_state(app_state.clone(), rate_limit_middleware))
         // CORS (should be after security headers)
         .layer(CorsLayer::permissive())
         // Application state
@@ -741,7 +1376,8 @@ async fn main() -> anyhow::Result<()> {
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("Server listening on {}", addr);
 
-    axum::serve(listener, app)
+    // Peer addresses identify clients for rate limiting and session records
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await?;
 
     Ok(())
@@ -4,7 +4,14 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use crate::error::AppError;
+use crate::middleware::rate_limit::ClientIp;
+use crate::models::version::Actor;
+use crate::models::workspace::WORKSPACE_HEADER;
+use crate::services::api_token_service::TOKEN_PREFIX;
+use crate::services::version_service::CURRENT_ACTOR;
 use crate::state::AppState;
+use uuid::Uuid;
 
 pub async fn auth_middleware(
     State(state): State<AppState>,
@@ -20,16 +27,65 @@ pub async fn auth_middleware(
         .and_then(|h| h.strip_prefix("Bearer "))
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    let claims = state
-        .auth_service
-        .verify_token(token)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    // API tokens (personal or service account) are opaque; everything else is a JWT
+    let mut claims = if token.starts_with(TOKEN_PREFIX) {
+        state
+            .api_token_service
+            .authenticate(token, request.extensions().get::<ClientIp>().and_then(|ip| ip.0.clone()))
+            .await
+            .map_err(|e| match e {
+                AppError::Auth(_) => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            })?
+    } else {
+        let claims = state
+            .auth_service
+            .verify_token(token)
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        // Tokens of a revoked session (logout, deactivation, password reset) stop working immediately
+        if let Some(session_id) = claims.sid {
+            let active = state
+                .auth_service
+                .session_active(session_id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            if !active {
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        }
+        claims
+    };
+
+    // Every request runs in one workspace, chosen by header, with the caller's role there
+    let requested = match request.headers().get(WORKSPACE_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| Uuid::parse_str(v.trim()).ok())
+                .ok_or(StatusCode::BAD_REQUEST)?,
+        ),
+        None => None,
+    };
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let (workspace_id, workspace_role) = state
+        .workspace_service
+        .resolve(user_id, &claims.role, requested)
+        .await
+        .map_err(|e| match e {
+            AppError::Forbidden(_) | AppError::NotFound(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+    claims.workspace_id = Some(workspace_id);
+    claims.workspace_role = Some(workspace_role);
 
     // Add claims to request extensions
+    let actor = Actor::from(&claims);
     request.extensions_mut().insert(claims);
 
-    Ok(next.run(request).await)
+    // Expose the actor to services for version history and workspace scoping
+    Ok(CURRENT_ACTOR.scope(actor, next.run(request)).await)
 }
 
-// Note: require_role would be implemented as a separate middleware layer
-// For now, we'll handle role checks directly in handlers
+// Role checks live in `middleware::authorization`, layered inside this middleware
@@ -0,0 +1,110 @@
+/*!
+ * Role-based authorization
+ *
+ * Routers declare the resource they expose; the action is derived from the HTTP
+ * method (GET = read, POST/PUT/PATCH = write, DELETE = delete). Routes whose action
+ * differs (approvals, read-only POSTs such as report generation) declare an exact
+ * permission with `require_permission`. Both run after `auth_middleware`.
+ */
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::models::permission::{Action, Permission, Resource};
+use crate::models::user::Claims;
+
+/// Action implied by the request method
+pub fn action_for_method(method: &Method) -> Action {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => Action::Read,
+        Method::DELETE => Action::Delete,
+        _ => Action::Write,
+    }
+}
+
+/// Guard a router: `from_fn_with_state(Resource::Cards, authorize)`
+pub async fn authorize(
+    State(resource): State<Resource>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let permission = Permission::new(resource, action_for_method(request.method()));
+    check(&request, permission)?;
+    Ok(next.run(request).await)
+}
+
+/// Guard a single route with an exact permission:
+/// `from_fn_with_state(Permission::new(Resource::Arb, Action::Approve), require_permission)`
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    check(&request, permission)?;
+    Ok(next.run(request).await)
+}
+
+fn check(request: &Request, permission: Permission) -> Result<(), AppError> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .ok_or_else(|| AppError::Auth("Authentication required".to_string()))?;
+
+    if claims.has_permission(permission) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!("Missing permission: {}", permission)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    use crate::models::user::UserRole;
+
+    fn app(role: UserRole) -> Router {
+        let claims = Claims {
+            sub: uuid::Uuid::new_v4().to_string(),
+            email: "user@archzero.local".to_string(),
+            role,
+            exp: usize::MAX,
+            sid: None,
+            scopes: None,
+            workspace_id: None,
+            workspace_role: None,
+        };
+
+        Router::new()
+            .route("/cards", get(|| async { "ok" }).delete(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(Resource::Cards, authorize))
+            .layer(Extension(claims))
+    }
+
+    async fn call(role: UserRole, method: Method) -> (StatusCode, String) {
+        let response = app(role)
+            .oneshot(Request::builder().method(method).uri("/cards").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_response_names_missing_permission() {
+        assert_eq!(call(UserRole::Viewer, Method::GET).await.0, StatusCode::OK);
+        assert_eq!(call(UserRole::Editor, Method::DELETE).await.0, StatusCode::OK);
+
+        let (status, body) = call(UserRole::Viewer, Method::DELETE).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.contains("Missing permission: cards:delete"), "{}", body);
+    }
+}
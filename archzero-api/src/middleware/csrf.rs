@@ -1,15 +1,28 @@
 /**
  * CSRF Protection Middleware
  *
- * Validates CSRF tokens for state-changing operations (POST, PUT, DELETE, PATCH).
- * Safe methods (GET, HEAD, OPTIONS, TRACE) are exempt from CSRF validation.
+ * Validates double-submit CSRF tokens for state-changing operations (POST, PUT,
+ * DELETE, PATCH) from cookie-authenticated browser sessions, i.e. requests carrying
+ * one of the configured `csrf.session_cookies`: the `X-CSRF-Token` header must match
+ * the token cookie and carry a valid signature. Unrelated cookies do not count, so
+ * pre-authentication requests (login, refresh, password reset) pass unchecked.
+ * Safe methods (GET, HEAD, OPTIONS, TRACE) are exempt from CSRF validation, and so
+ * are requests with an `Authorization: Bearer` header. Browsers never attach that
+ * header on their own, and a forged one authenticates as the bearer token, not the
+ * victim's cookie.
  *
- * NOTE: The global CSRF middleware is disabled by default to prevent breaking
- * existing API clients. CSRF tokens can be obtained from /api/v1/csrf/token and
- * should be included in the X-CSRF-Token header for state-changing operations.
+ * Tokens are obtained from /api/v1/csrf/token, which also sets the cookie.
  */
 
-use axum::http::{HeaderMap, Method};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::state::AppState;
 
 /// CSRF header name for token validation
 pub const CSRF_HEADER_NAME: &str = "X-CSRF-Token";
@@ -37,6 +50,57 @@ pub fn extract_csrf_token(headers: &HeaderMap) -> Option<String> {
     None
 }
 
+/// Read a cookie from the request's `Cookie` headers
+pub fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Only unsafe requests authenticated by a session cookie rather than a Bearer token need a CSRF token
+pub fn requires_csrf_check(method: &Method, headers: &HeaderMap, session_cookies: &[String]) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h.starts_with("Bearer "));
+
+    !is_safe_method(method)
+        && !bearer
+        && session_cookies.iter().any(|name| cookie_value(headers, name).is_some())
+}
+
+/// CSRF protection middleware
+///
+/// Returns 403 Forbidden if a cookie-authenticated state-changing request lacks a
+/// matching, valid token.
+pub async fn csrf_protect(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let service = &state.csrf_service;
+    if !service.enabled() || !requires_csrf_check(req.method(), req.headers(), service.session_cookies()) {
+        return Ok(next.run(req).await);
+    }
+
+    let cookie = cookie_value(req.headers(), service.cookie_name());
+    let submitted = extract_csrf_token(req.headers());
+    match (cookie, submitted) {
+        (Some(cookie), Some(submitted)) if cookie == submitted && service.validate_token(&submitted) => {
+            Ok(next.run(req).await)
+        }
+        _ => {
+            tracing::warn!("CSRF check failed for {} {}", req.method(), req.uri().path());
+            Err(AppError::Forbidden("Invalid or missing CSRF token".to_string()))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +116,30 @@ mod tests {
         assert!(!is_safe_method(&Method::DELETE));
         assert!(!is_safe_method(&Method::PATCH));
     }
+
+    #[test]
+    fn test_requires_csrf_check_only_for_cookie_sessions() {
+        use axum::http::HeaderValue;
+
+        let sessions = vec!["session".to_string()];
+
+        let mut cookie_only = HeaderMap::new();
+        cookie_only.insert(header::COOKIE, HeaderValue::from_static("theme=dark; session=s1; csrf_token=abc"));
+        assert!(requires_csrf_check(&Method::POST, &cookie_only, &sessions));
+        assert!(!requires_csrf_check(&Method::GET, &cookie_only, &sessions));
+        assert_eq!(cookie_value(&cookie_only, "csrf_token"), Some("abc".to_string()));
+
+        let mut bearer = cookie_only.clone();
+        bearer.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+        assert!(!requires_csrf_check(&Method::POST, &bearer, &sessions));
+
+        // No cookies means no ambient credentials to forge with
+        assert!(!requires_csrf_check(&Method::POST, &HeaderMap::new(), &sessions));
+
+        // Unrelated cookies (e.g. on /auth/login before signing in) are not a session
+        let mut unrelated = HeaderMap::new();
+        unrelated.insert(header::COOKIE, HeaderValue::from_static("theme=dark; csrf_token=abc"));
+        assert!(!requires_csrf_check(&Method::POST, &unrelated, &sessions));
+        assert!(!requires_csrf_check(&Method::POST, &cookie_only, &[]));
+    }
 }
@@ -1,9 +1,11 @@
 pub mod auth;
+pub mod authorization;
 pub mod csrf;
 pub mod rate_limit;
 pub mod security;
 
 pub use auth::*;
+pub use authorization::*;
 pub use csrf::*;
 pub use rate_limit::*;
 pub use security::*;
@@ -1,97 +1,132 @@
 /**
  * Rate Limiting Middleware
  *
- * Applies rate limiting to incoming requests based on IP address and endpoint.
- * Uses the RateLimitService to check and enforce rate limits.
+ * Limits each client per route group using the RateLimitService. A client is the
+ * authenticated user (JWT), the API token (by its listed prefix), or otherwise the
+ * client IP address. Credentials only count once verified, so forged tokens cannot
+ * escape the per-IP limit. Limits and the allow-list come from the `rate_limit` settings;
+ * the middleware is a no-op unless rate limiting is enabled (by default, in production).
  *
- * NOTE: This middleware is disabled by default to prevent blocking development.
- * To enable, uncomment the middleware layer in main.rs.
+ * The client IP is the connecting peer. Forwarded headers are only read when the peer is
+ * one of `rate_limit.trusted_proxies`, so clients cannot pick their own bucket. The
+ * middleware records the address as a `ClientIp` request extension for later handlers.
  */
 
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
 use axum::{
-    extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap, HeaderValue},
     middleware::Next,
     response::Response,
 };
 
 use crate::state::AppState;
-use crate::services::rate_limit::{RateLimitConfig, RateLimitResult};
+use crate::services::api_token_service::TOKEN_PREFIX;
+use crate::services::rate_limit::TrustedProxy;
+use crate::services::{ApiTokenService, AuthService};
 use crate::error::AppError;
 
-/// Default rate limit configuration (adjust based on your needs)
-const DEFAULT_RATE_LIMIT: RateLimitConfig = RateLimitConfig {
-    max_requests: 100,
-    window_seconds: 60,
-};
+/// Client address resolved by `rate_limit_middleware`; `None` when the peer is unknown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientIp(pub Option<String>);
 
-/// Strict rate limit for sensitive endpoints (e.g., auth, critical operations)
-const STRICT_RATE_LIMIT: RateLimitConfig = RateLimitConfig {
-    max_requests: 10,
-    window_seconds: 60,
-};
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
 
-/// Extract IP address from request headers
-fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
-    // Try common proxy headers first
-    if let Some(forwarded_for) = headers.get("x-forwarded-for") {
-        if let Ok(forwarded_str) = forwarded_for.to_str() {
-            // X-Forwarded-For can contain multiple IPs, take the first one
-            if let Some(first_ip) = forwarded_str.split(',').next() {
-                return Some(first_ip.trim().to_string());
-            }
-        }
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<ClientIp>().cloned().unwrap_or_default())
+    }
+}
+
+/// Client address of a request: the peer, or, when the peer is a trusted proxy, the right-most
+/// `X-Forwarded-For` entry that is not a trusted proxy (entries left of it are client-supplied)
+pub(crate) fn extract_client_ip(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &[TrustedProxy]) -> Option<String> {
+    let peer = peer?;
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|proxy| proxy.contains(ip));
+    if !is_trusted(peer) {
+        return Some(peer.to_string());
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    if let Some(client) = forwarded.iter().rev().find(|entry| !entry.parse().is_ok_and(is_trusted)) {
+        return Some(client.to_string());
+    }
+    if let Some(first) = forwarded.first() {
+        return Some(first.to_string());
     }
 
-    if let Some(real_ip) = headers.get("x-real-ip") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            return Some(ip_str.to_string());
+    for header in ["x-real-ip", "cf-connecting-ip"] {
+        if let Some(ip) = headers.get(header).and_then(|value| value.to_str().ok()) {
+            return Some(ip.trim().to_string());
         }
     }
 
-    if let Some(cf_connecting_ip) = headers.get("cf-connecting-ip") {
-        if let Ok(ip_str) = cf_connecting_ip.to_str() {
-            return Some(ip_str.to_string());
+    Some(peer.to_string())
+}
+
+/// Identify the client a request counts against: `token:<prefix>`, `user:<id>` or `ip:<address>`.
+/// Unverifiable credentials count against the IP.
+async fn client_key(api_tokens: &ApiTokenService, auth: &AuthService, headers: &HeaderMap, client_ip: &ClientIp) -> String {
+    let bearer = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    if let Some(token) = bearer {
+        if token.starts_with(TOKEN_PREFIX) {
+            match api_tokens.verified_prefix(token).await {
+                Ok(Some(prefix)) => return format!("token:{}", prefix),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Rate limiting by IP, API token check failed: {}", e),
+            }
+        } else if let Ok(claims) = auth.verify_token(token) {
+            return format!("user:{}", claims.sub);
         }
     }
 
-    None
+    format!("ip:{}", client_ip.0.as_deref().unwrap_or("unknown"))
 }
 
 /// Rate limiting middleware
 ///
-/// Checks rate limits based on client IP and request endpoint.
-/// Returns 429 Too Many Requests if limit is exceeded.
+/// Checks the client's sliding-window limit for the request's route group.
+/// Returns 429 Too Many Requests with `Retry-After` if the limit is exceeded.
 pub async fn rate_limit_middleware(
     State(state): State<AppState>,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Result<Response, AppError> {
-    // Extract client IP
-    let headers = req.headers();
-    let client_ip = extract_client_ip(headers).unwrap_or_else(|| "unknown".to_string());
+    let service = &state.rate_limit_service;
+    let peer = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+    let client_ip = ClientIp(extract_client_ip(peer, req.headers(), service.trusted_proxies()));
+    req.extensions_mut().insert(client_ip.clone());
 
-    // Get the request path
-    let path = req.uri().path();
-
-    // Determine appropriate rate limit based on endpoint
-    let ip_config = if path.contains("/auth/") || path.contains("/csrf/") {
-        // Stricter limits for auth and CSRF endpoints
-        STRICT_RATE_LIMIT
-    } else {
-        DEFAULT_RATE_LIMIT
-    };
+    if !service.enabled() {
+        return Ok(next.run(req).await);
+    }
 
-    let endpoint_config = DEFAULT_RATE_LIMIT;
+    let client = client_key(&state.api_token_service, &state.auth_service, req.headers(), &client_ip).await;
+    let path = req.uri().path();
+    if service.is_allow_listed(&client, path) {
+        return Ok(next.run(req).await);
+    }
 
-    // Check combined rate limit (IP + endpoint)
-    let result = state
-        .rate_limit_service
-        .check_combined_rate_limit(&client_ip, path, &ip_config, &endpoint_config)
-        .await;
+    let rule = service.rule_for(&client, path);
+    let result = service.check_client(&client, &rule).await;
 
     if !result.allowed {
-        // Rate limit exceeded - return 429
+        tracing::warn!("Rate limit exceeded for {} on {} ({})", client, path, rule.group);
         return Err(AppError::RateLimitExceeded {
             retry_after: result.retry_after,
         });
@@ -101,13 +136,9 @@ pub async fn rate_limit_middleware(
     let mut response = next.run(req).await;
 
     let headers = response.headers_mut();
-    headers.insert("X-RateLimit-Limit", ip_config.max_requests.to_string().parse().unwrap());
-    headers.insert("X-RateLimit-Remaining", result.remaining.to_string().parse().unwrap());
-    headers.insert("X-RateLimit-Reset", result.reset_at.to_string().parse().unwrap());
-
-    if let Some(retry_after) = result.retry_after {
-        headers.insert("Retry-After", retry_after.to_string().parse().unwrap());
-    }
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(rule.config.max_requests));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(result.remaining));
+    headers.insert("X-RateLimit-Reset", HeaderValue::from(result.reset_at));
 
     Ok(response)
 }
@@ -117,12 +148,22 @@ mod tests {
     use super::*;
     use axum::http::HeaderValue;
 
+    const PROXY: &str = "10.0.0.2";
+
+    fn proxy() -> Option<IpAddr> {
+        PROXY.parse().ok()
+    }
+
+    fn trusted() -> Vec<TrustedProxy> {
+        ["10.0.0.0/24", "70.41.3.0/24"].iter().filter_map(|proxy| TrustedProxy::parse(proxy)).collect()
+    }
+
     #[test]
     fn test_extract_client_ip_from_x_forwarded_for() {
         let mut headers = HeaderMap::new();
         headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.195, 70.41.3.18"));
 
-        let ip = extract_client_ip(&headers);
+        let ip = extract_client_ip(proxy(), &headers, &trusted());
         assert_eq!(ip, Some("203.0.113.195".to_string()));
     }
 
@@ -131,7 +172,7 @@ mod tests {
         let mut headers = HeaderMap::new();
         headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.42"));
 
-        let ip = extract_client_ip(&headers);
+        let ip = extract_client_ip(proxy(), &headers, &trusted());
         assert_eq!(ip, Some("198.51.100.42".to_string()));
     }
 
@@ -140,15 +181,50 @@ mod tests {
         let mut headers = HeaderMap::new();
         headers.insert("cf-connecting-ip", HeaderValue::from_static("192.0.2.1"));
 
-        let ip = extract_client_ip(&headers);
+        let ip = extract_client_ip(proxy(), &headers, &trusted());
         assert_eq!(ip, Some("192.0.2.1".to_string()));
     }
 
+    #[test]
+    fn test_forwarded_headers_need_a_trusted_peer() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("198.51.100.1"));
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.2"));
+        let direct: Option<IpAddr> = "203.0.113.9".parse().ok();
+
+        // A direct client cannot choose its bucket, and without trusted proxies nobody can
+        assert_eq!(extract_client_ip(direct, &headers, &trusted()), Some("203.0.113.9".to_string()));
+        assert_eq!(extract_client_ip(proxy(), &headers, &[]), Some(PROXY.to_string()));
+
+        // Behind the proxy, spoofed entries left of the first untrusted hop are ignored
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.2.3.4, 203.0.113.50, 10.0.0.7"));
+        assert_eq!(extract_client_ip(proxy(), &headers, &trusted()), Some("203.0.113.50".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_forged_tokens_share_the_ip_bucket() {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(500))
+            .connect_lazy("postgres://localhost:1/unused")
+            .unwrap();
+        let api_tokens = ApiTokenService::new(pool.clone(), crate::config::ApiTokens::default());
+        let auth = AuthService::new(pool, "secret".to_string(), 900, 30);
+
+        let client_ip = ClientIp(Some("203.0.113.7".to_string()));
+        let mut keys = Vec::new();
+        for bearer in ["Bearer az_aaaaaaaa0123456789", "Bearer az_bbbbbbbb0123456789", "Bearer forged.jwt.token"] {
+            let mut headers = HeaderMap::new();
+            headers.insert("Authorization", HeaderValue::from_static(bearer));
+            keys.push(client_key(&api_tokens, &auth, &headers, &client_ip).await);
+        }
+        assert_eq!(keys, vec!["ip:203.0.113.7".to_string(); 3]);
+    }
+
     #[test]
     fn test_extract_client_ip_unknown() {
         let headers = HeaderMap::new();
 
-        let ip = extract_client_ip(&headers);
+        let ip = extract_client_ip(None, &headers, &trusted());
         assert_eq!(ip, Some("unknown".to_string()));
     }
 }
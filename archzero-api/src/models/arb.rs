@@ -80,6 +80,10 @@ pub struct ARBMeetingSearchParams {
     pub status: Option<ARBMeetingStatus>,
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
+    /// Comma-separated sort fields of `GET /api/v1/cards`, e.g. `attributes.scheduledDate` or `-updated_at`
+    pub sort: Option<String>,
+    /// `nextCursor` of the previous page; continues after it instead of using `page`
+    pub cursor: Option<String>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
@@ -147,6 +151,10 @@ pub struct ARBSubmissionSearchParams {
     pub meeting_id: Option<Uuid>,
     pub submission_type: Option<ARBSubmissionType>,
     pub status: Option<ARBSubmissionStatus>,
+    /// Comma-separated sort fields of `GET /api/v1/cards`, e.g. `attributes.priority` or `-updated_at`
+    pub sort: Option<String>,
+    /// `nextCursor` of the previous page; continues after it instead of using `page`
+    pub cursor: Option<String>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
@@ -223,6 +231,9 @@ pub struct ARBPagination {
     pub page: u32,
     pub limit: u32,
     pub total: i64,
+    /// Cursor of the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// ARB Meeting Agenda Item
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use utoipa::{ToSchema, IntoParams};
+
+/// Role of a state in the roadmap (FR-STRATEGY-01)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateKind {
+    Baseline,
+    Target,
+    Transition,
+}
+
+/// How a state stores its content
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMode {
+    /// Frozen copy of all cards and relationships taken when the state is created
+    Snapshot,
+    /// Planned changes layered over a base state, or over the live architecture
+    Planned,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchitectureState {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub kind: StateKind,
+    pub mode: StateMode,
+    /// Planned states only: the state the changes apply to (`None` = live architecture)
+    pub base_state_id: Option<Uuid>,
+    pub effective_date: Option<NaiveDate>,
+    pub card_count: i64,
+    pub relationship_count: i64,
+    pub change_count: i64,
+    pub created_by: Option<Uuid>,
+    /// Workspace the state was created in; it is not visible in other workspaces
+    pub workspace_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateArchitectureStateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub kind: StateKind,
+    pub mode: StateMode,
+    pub base_state_id: Option<Uuid>,
+    pub effective_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateArchitectureStateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub kind: Option<StateKind>,
+    pub effective_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StateEntityType {
+    Card,
+    Relationship,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedChangeType {
+    Add,
+    Update,
+    Remove,
+}
+
+/// One planned change in a `planned` state, applied in `sequence` order
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    pub id: Uuid,
+    pub state_id: Uuid,
+    pub sequence: i32,
+    pub entity_type: StateEntityType,
+    pub entity_id: Uuid,
+    pub change_type: PlannedChangeType,
+    /// Full entity for `add`, changed fields for `update`, ignored for `remove`
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePlannedChangeRequest {
+    pub entity_type: StateEntityType,
+    /// Required for `update` and `remove`; generated for `add` when omitted
+    pub entity_id: Option<Uuid>,
+    pub change_type: PlannedChangeType,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+/// `from`/`to` accept a state ID or `current` for the live architecture
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffParams {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedEntity {
+    pub id: Uuid,
+    pub name: Option<String>,
+    /// Field-level changes as `{field: {from, to}}`; attributes are keyed as `attributes.<key>`
+    pub changes: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityDiff {
+    pub added: Vec<serde_json::Value>,
+    pub removed: Vec<serde_json::Value>,
+    pub changed: Vec<ChangedEntity>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiff {
+    pub from: String,
+    pub to: String,
+    pub cards: EntityDiff,
+    pub relationships: EntityDiff,
+}
@@ -3,8 +3,9 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use utoipa::{ToSchema, IntoParams};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
-#[serde(rename_all = "PascalCase")]
+/// Card type, serialized as its name. Built-in types are variants; types admins
+/// register at runtime (`card_types` table) are `Custom`.
+#[derive(Debug, Clone, PartialEq)]
 pub enum CardType {
     // Layer A: Strategic
     BusinessCapability,
@@ -27,6 +28,98 @@ pub enum CardType {
     // Layer E: ARB (Architecture Review Board)
     ARBMeeting,
     ARBSubmission,
+    /// Registered at runtime; only valid once the name is in the card type registry
+    Custom(String),
+}
+
+impl CardType {
+    pub const BUILT_IN: [CardType; 16] = [
+        CardType::BusinessCapability,
+        CardType::Objective,
+        CardType::Application,
+        CardType::Interface,
+        CardType::ITComponent,
+        CardType::Platform,
+        CardType::ArchitecturePrinciple,
+        CardType::TechnologyStandard,
+        CardType::ArchitecturePolicy,
+        CardType::Exception,
+        CardType::Initiative,
+        CardType::Risk,
+        CardType::ComplianceRequirement,
+        CardType::ComplianceAudit,
+        CardType::ARBMeeting,
+        CardType::ARBSubmission,
+    ];
+
+    /// Built-in variant with this name, else a custom type
+    pub fn from_name(name: &str) -> Self {
+        Self::BUILT_IN
+            .into_iter()
+            .find(|card_type| card_type.as_str() == name)
+            .unwrap_or_else(|| CardType::Custom(name.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            CardType::BusinessCapability => "BusinessCapability",
+            CardType::Objective => "Objective",
+            CardType::Application => "Application",
+            CardType::Interface => "Interface",
+            CardType::ITComponent => "ITComponent",
+            CardType::Platform => "Platform",
+            CardType::ArchitecturePrinciple => "ArchitecturePrinciple",
+            CardType::TechnologyStandard => "TechnologyStandard",
+            CardType::ArchitecturePolicy => "ArchitecturePolicy",
+            CardType::Exception => "Exception",
+            CardType::Initiative => "Initiative",
+            CardType::Risk => "Risk",
+            CardType::ComplianceRequirement => "ComplianceRequirement",
+            CardType::ComplianceAudit => "ComplianceAudit",
+            CardType::ARBMeeting => "ARBMeeting",
+            CardType::ARBSubmission => "ARBSubmission",
+            CardType::Custom(name) => name,
+        }
+    }
+
+    pub fn is_built_in(&self) -> bool {
+        !matches!(self, CardType::Custom(_))
+    }
+}
+
+impl std::fmt::Display for CardType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CardType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CardType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name.is_empty() {
+            return Err(serde::de::Error::custom("card type must not be empty"));
+        }
+        Ok(CardType::from_name(&name))
+    }
+}
+
+impl<'s> ToSchema<'s> for CardType {
+    fn schema() -> (&'s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {
+        (
+            "CardType",
+            utoipa::openapi::ObjectBuilder::new()
+                .schema_type(utoipa::openapi::SchemaType::String)
+                .description(Some("Built-in card type name or a card type registered at runtime"))
+                .example(Some(serde_json::json!("Application")))
+                .into(),
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
@@ -50,9 +143,19 @@ pub struct Card {
     #[serde(rename = "type")]
     pub card_type: CardType,
     pub lifecycle_phase: LifecyclePhase,
+    /// Computed data quality, 0-100; see `GET /api/v1/cards/{id}/quality`
     pub quality_score: Option<i32>,
     pub description: Option<String>,
     pub owner_id: Option<Uuid>,
+    /// Owning team; with `owner_id`, limits who may edit the card
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    /// Workspace the card belongs to; other workspaces only see it if it is shared with them
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    /// Metamodel version the card was created in or last migrated to
+    #[serde(default)]
+    pub metamodel_version: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub attributes: serde_json::Value,
@@ -67,9 +170,11 @@ pub struct CreateCardRequest {
     #[serde(rename = "type")]
     pub card_type: CardType,
     pub lifecycle_phase: LifecyclePhase,
+    /// Ignored: the platform computes the score (`GET /api/v1/cards/{id}/quality`)
     pub quality_score: Option<i32>,
     pub description: Option<String>,
     pub owner_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
     pub attributes: Option<serde_json::Value>,
     pub tags: Option<Vec<String>>,
 }
@@ -79,8 +184,12 @@ pub struct CreateCardRequest {
 pub struct UpdateCardRequest {
     pub name: Option<String>,
     pub lifecycle_phase: Option<LifecyclePhase>,
+    /// Ignored: the platform computes the score (`GET /api/v1/cards/{id}/quality`)
     pub quality_score: Option<i32>,
     pub description: Option<String>,
+    /// Reassign ownership; only the current owner, team or an admin can do this
+    pub owner_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
     pub attributes: Option<serde_json::Value>,
     pub tags: Option<Vec<String>>,
 }
@@ -93,6 +202,21 @@ pub struct CardSearchParams {
     pub card_type: Option<CardType>,
     pub lifecycle_phase: Option<LifecyclePhase>,
     pub tags: Option<Vec<String>>,
+    /// Boolean query in the text syntax, e.g. `type = Application AND outgoing(reliesOn, tags contains legacy)`
+    pub query: Option<String>,
+    /// Comma-separated sort fields, `-` for descending, e.g. `-updated_at,name`; defaults to relevance
+    /// when searching with `q`, otherwise newest first. Also `attributes.<key>`.
+    pub sort: Option<String>,
+    /// Cursor of the previous page; continues after it instead of using `page`
+    pub cursor: Option<String>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
+
+/// A page of cards; `next_cursor` is `None` on the last page
+#[derive(Debug, Clone)]
+pub struct CardPage {
+    pub cards: Vec<Card>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
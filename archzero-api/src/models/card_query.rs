@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::relationship::RelationshipType;
+
+/// Boolean card query, as a JSON AST or parsed from the text syntax, e.g.
+/// `type = Application AND outgoing(reliesOn, type = Platform AND tags contains legacy)`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CardQuery {
+    And(Vec<CardQuery>),
+    Or(Vec<CardQuery>),
+    Not(Box<CardQuery>),
+    Condition(QueryCondition),
+    Related(RelationshipPredicate),
+}
+
+/// A test on one field of a card
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryCondition {
+    /// A card column (`name`, `description`, `type`, `lifecycle_phase`, `quality_score`, `tags`,
+    /// `owner_id`, `team_id`, `created`, `updated`), `attributes.<path>`, or inside a relationship
+    /// predicate `relationship.valid_from` / `relationship.valid_to`
+    pub field: String,
+    pub op: QueryOperator,
+    /// A list for `in`, two values for `between`, nothing for `exists`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryOperator {
+    Equals,
+    /// Case-insensitive substring; for tags and attribute arrays, membership
+    Contains,
+    StartsWith,
+    In,
+    /// Inclusive range
+    Between,
+    Exists,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl QueryOperator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryOperator::Equals => "=",
+            QueryOperator::Contains => "contains",
+            QueryOperator::StartsWith => "starts_with",
+            QueryOperator::In => "in",
+            QueryOperator::Between => "between",
+            QueryOperator::Exists => "exists",
+            QueryOperator::Gt => ">",
+            QueryOperator::Gte => ">=",
+            QueryOperator::Lt => "<",
+            QueryOperator::Lte => "<=",
+        }
+    }
+}
+
+/// Cards with a relationship to a card matching `card`. Only relationships in effect today
+/// count, unless `card` has its own condition on `relationship.valid_from` or `relationship.valid_to`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipPredicate {
+    /// Any relationship type when absent
+    pub relationship_type: Option<RelationshipType>,
+    #[serde(default)]
+    pub direction: RelationshipDirection,
+    /// Any related card when absent
+    pub card: Option<Box<CardQuery>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationshipDirection {
+    /// From the card to the related card
+    #[default]
+    Outgoing,
+    Incoming,
+    Any,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardQueryRequest {
+    pub query: CardQuery,
+    /// Sort fields as in `GET /api/v1/cards`
+    pub sort: Option<String>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
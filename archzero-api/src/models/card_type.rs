@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Architecture layer of a card type (docs/01-metamodel-spec.md §2)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CardLayer {
+    Strategy,
+    Application,
+    Technology,
+    Governance,
+    Arb,
+}
+
+impl CardLayer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardLayer::Strategy => "strategy",
+            CardLayer::Application => "application",
+            CardLayer::Technology => "technology",
+            CardLayer::Governance => "governance",
+            CardLayer::Arb => "arb",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "strategy" => Some(CardLayer::Strategy),
+            "application" => Some(CardLayer::Application),
+            "technology" => Some(CardLayer::Technology),
+            "governance" => Some(CardLayer::Governance),
+            "arb" => Some(CardLayer::Arb),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeDataType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// ISO 8601 date string
+    Date,
+    Object,
+    Array,
+}
+
+/// One attribute in a card type's schema, as in the attribute tables of the metamodel spec
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeDefinition {
+    pub key: String,
+    pub data_type: AttributeDataType,
+    #[serde(default)]
+    pub required: bool,
+    /// Enum values; the attribute must be one of them
+    pub allowed_values: Option<Vec<String>>,
+    /// Inclusive range for integers and numbers
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub description: Option<String>,
+}
+
+/// A card type in the registry, built in or registered at runtime
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardTypeDefinition {
+    pub name: String,
+    pub label: String,
+    pub layer: CardLayer,
+    pub description: Option<String>,
+    pub attribute_schema: Vec<AttributeDefinition>,
+    /// Relationship types cards of this type may take part in; empty allows all
+    pub allowed_relationship_types: Vec<String>,
+    /// Display colour, `#rrggbb`
+    pub color: String,
+    pub built_in: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCardTypeRequest {
+    /// PascalCase, e.g. `DataObject`
+    pub name: String,
+    pub label: Option<String>,
+    pub layer: CardLayer,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub attribute_schema: Vec<AttributeDefinition>,
+    #[serde(default)]
+    pub allowed_relationship_types: Vec<String>,
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CardTypeQuery {
+    pub layer: Option<CardLayer>,
+}
+
+/// Replaces every setting of a card type; its name stays fixed
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCardTypeRequest {
+    pub label: String,
+    pub layer: CardLayer,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub attribute_schema: Vec<AttributeDefinition>,
+    #[serde(default)]
+    pub allowed_relationship_types: Vec<String>,
+    pub color: String,
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{ToSchema, IntoParams};
+
+/// Computed quality of a card: its `quality_score` and the factors behind it, each 0-100
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardQuality {
+    pub card_id: Uuid,
+    pub score: i32,
+    /// Required and recommended attributes of the metamodel, and the description
+    pub completeness: i32,
+    /// Time since the last update or review
+    pub freshness: i32,
+    pub ownership: i32,
+    /// Relationships cards of the type are expected to have
+    pub relationships: i32,
+    /// Architecture policies the card complies with
+    pub compliance: i32,
+    /// What cost points
+    pub issues: Vec<String>,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+    pub calculated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct DataQualityParams {
+    #[serde(rename = "type")]
+    pub card_type: Option<String>,
+    pub owner_id: Option<Uuid>,
+    /// Worst cards listed overall and per type and owner; default 10, at most 100
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardQualitySummary {
+    pub card_id: Uuid,
+    pub name: String,
+    pub card_type: String,
+    pub owner_id: Option<Uuid>,
+    pub owner_name: Option<String>,
+    pub score: i32,
+    pub issues: Vec<String>,
+}
+
+/// Card counts in the score bands of the dashboard gauge (docs/06-uiux-sitemap.md)
+#[derive(Debug, Serialize, Clone, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityBands {
+    /// 90-100
+    pub excellent: i64,
+    /// 75-89
+    pub good: i64,
+    /// 60-74
+    pub fair: i64,
+    /// 0-59
+    pub poor: i64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeQuality {
+    pub card_type: String,
+    pub card_count: i64,
+    pub average_score: f64,
+    pub worst_cards: Vec<CardQualitySummary>,
+}
+
+/// Cards of one owner; `owner_id` is `None` for the cards nobody owns
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerQuality {
+    pub owner_id: Option<Uuid>,
+    pub owner_name: Option<String>,
+    pub card_count: i64,
+    pub average_score: f64,
+    pub worst_cards: Vec<CardQualitySummary>,
+}
+
+/// Groups are ordered worst average first
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DataQualityDashboard {
+    pub card_count: i64,
+    pub average_score: f64,
+    pub bands: QualityBands,
+    pub worst_cards: Vec<CardQualitySummary>,
+    pub by_type: Vec<TypeQuality>,
+    pub by_owner: Vec<OwnerQuality>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityRecalculation {
+    pub cards: u64,
+    pub completed_at: DateTime<Utc>,
+}
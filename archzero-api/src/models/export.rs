@@ -40,6 +40,10 @@ pub struct ExportFilters {
     pub domain: Option<String>,
     pub date_from: Option<String>,  // ISO 8601 date
     pub date_to: Option<String>,    // ISO 8601 date
+    /// Take the cards from a saved search instead of the filters above (on-demand exports only;
+    /// scheduled exports reject it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_search_id: Option<Uuid>,
 }
 
 /// Export history item
@@ -273,6 +277,7 @@ mod tests {
             domain: None,
             date_from: None,
             date_to: None,
+            saved_search_id: None,
         };
         let json = serde_json::to_string(&filters).unwrap();
         assert!(json.contains(r#""cardType""#));
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Card node as currently stored in Neo4j
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphCardNode {
+    pub id: Uuid,
+    pub name: String,
+    pub card_type: String,
+    pub lifecycle_phase: String,
+    /// `None` for nodes written before workspaces existed
+    pub workspace_id: Option<Uuid>,
+}
+
+/// Relationship edge as currently stored in Neo4j.
+/// `id` is `None` for edges written before edges were keyed by relationship ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphRelationshipEdge {
+    pub id: Option<Uuid>,
+    pub from_card_id: Uuid,
+    pub to_card_id: Uuid,
+    pub edge_type: String,
+    pub valid_from: String,
+    pub valid_to: Option<String>,
+    pub confidence: Option<f64>,
+    pub attributes: serde_json::Value,
+    /// `None` for edges written before workspaces existed
+    pub workspace_id: Option<Uuid>,
+}
+
+/// How a reconciliation run should treat the drift it finds
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcileMode {
+    /// Only report differences
+    Report,
+    /// Write missing or stale nodes and edges, remove orphans
+    Repair,
+    /// Wipe the graph and rebuild it from PostgreSQL
+    Rebuild,
+}
+
+/// A card or relationship whose graph copy differs from PostgreSQL
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftedEntity {
+    pub id: Uuid,
+    pub fields: Vec<String>,
+}
+
+/// Differences between PostgreSQL (source of truth) and Neo4j
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub checked_at: DateTime<Utc>,
+    pub postgres_cards: usize,
+    pub graph_cards: usize,
+    pub postgres_relationships: usize,
+    pub graph_relationships: usize,
+    /// Cards present in PostgreSQL but missing from the graph
+    pub missing_cards: Vec<Uuid>,
+    /// Card nodes in the graph with no matching PostgreSQL card
+    pub orphaned_cards: Vec<Uuid>,
+    pub stale_cards: Vec<DriftedEntity>,
+    /// Relationships present in PostgreSQL but missing from the graph
+    pub missing_relationships: Vec<Uuid>,
+    /// Edges in the graph with no matching PostgreSQL relationship
+    pub orphaned_relationships: Vec<Uuid>,
+    pub stale_relationships: Vec<DriftedEntity>,
+    /// Edges without a relationship ID, which can only be removed by a repair or rebuild
+    pub untracked_edges: usize,
+}
+
+impl ReconciliationReport {
+    pub fn in_sync(&self) -> bool {
+        self.missing_cards.is_empty()
+            && self.orphaned_cards.is_empty()
+            && self.stale_cards.is_empty()
+            && self.missing_relationships.is_empty()
+            && self.orphaned_relationships.is_empty()
+            && self.stale_relationships.is_empty()
+            && self.untracked_edges == 0
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileRequest {
+    pub mode: ReconcileMode,
+}
+
+/// Outcome of a reconciliation run; `report` describes the drift found before any writes
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationResult {
+    pub mode: ReconcileMode,
+    pub report: ReconciliationReport,
+    pub cards_written: usize,
+    pub cards_removed: usize,
+    pub relationships_written: usize,
+    pub relationships_removed: usize,
+    pub errors: Vec<String>,
+    pub completed_at: DateTime<Utc>,
+}
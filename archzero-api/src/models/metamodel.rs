@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Validation rule for one JSONB attribute of a card type (docs/01-metamodel-spec.md §1.5)
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelRule {
+    pub id: Uuid,
+    pub card_type: String,
+    pub attribute_key: String,
+    pub is_required: bool,
+    pub regex_pattern: Option<String>,
+    pub allowed_values: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub example_value: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMetamodelRuleRequest {
+    pub card_type: String,
+    pub attribute_key: String,
+    #[serde(default)]
+    pub is_required: bool,
+    pub regex_pattern: Option<String>,
+    pub allowed_values: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub example_value: Option<String>,
+}
+
+/// Replaces every setting of a rule; its card type and attribute key stay fixed
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMetamodelRuleRequest {
+    #[serde(default)]
+    pub is_required: bool,
+    pub regex_pattern: Option<String>,
+    pub allowed_values: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub example_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelRuleQuery {
+    pub card_type: Option<String>,
+}
+
+/// Check attributes against the rules without writing a card
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateAttributesRequest {
+    pub card_type: String,
+    #[serde(default)]
+    pub attributes: serde_json::Value,
+}
+
+/// One broken rule, in the VALIDATION_ERROR shape of the metamodel spec
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+pub struct AttributeViolation {
+    /// `attributes.<key>`
+    pub field: String,
+    pub message: String,
+    pub details: ViolationDetails,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Default, ToSchema)]
+pub struct ViolationDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_by_rule: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provided_value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example_valid_value: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidateAttributesResponse {
+    pub valid: bool,
+    pub violations: Vec<AttributeViolation>,
+}
+
+/// Metamodel version used before any migration ran
+pub const INITIAL_METAMODEL_VERSION: &str = "1.0";
+
+/// A change to existing cards (docs/01-metamodel-spec.md §1.6)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MetamodelOperation {
+    /// Move a JSONB key; where both keys exist the new one wins. Rules follow the key.
+    RenameAttribute {
+        card_type: String,
+        from: String,
+        to: String,
+    },
+    /// Set a default on cards missing the attribute, then optionally make it required
+    BackfillAttribute {
+        card_type: String,
+        attribute_key: String,
+        default_value: serde_json::Value,
+        #[serde(default)]
+        make_required: bool,
+    },
+    /// Stop new cards of the type and tag, convert or archive the existing ones
+    RetireCardType {
+        card_type: String,
+        replacement: Option<String>,
+        #[serde(default)]
+        mode: RetireMode,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RetireMode {
+    /// Tag existing cards `DEPRECATED`
+    #[default]
+    Tag,
+    /// Change existing cards to the replacement type
+    Convert,
+    /// Archive existing cards
+    Archive,
+}
+
+impl MetamodelOperation {
+    pub fn card_type(&self) -> &str {
+        match self {
+            MetamodelOperation::RenameAttribute { card_type, .. }
+            | MetamodelOperation::BackfillAttribute { card_type, .. }
+            | MetamodelOperation::RetireCardType { card_type, .. } => card_type,
+        }
+    }
+
+    /// Breaking changes start a new major version, additive ones a minor version
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            MetamodelOperation::RenameAttribute { .. } => true,
+            MetamodelOperation::BackfillAttribute { .. } => false,
+            MetamodelOperation::RetireCardType { mode, .. } => *mode != RetireMode::Tag,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MetamodelMigrationStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl MetamodelMigrationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetamodelMigrationStatus::Pending => "pending",
+            MetamodelMigrationStatus::Running => "running",
+            MetamodelMigrationStatus::Completed => "completed",
+            MetamodelMigrationStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(MetamodelMigrationStatus::Pending),
+            "running" => Some(MetamodelMigrationStatus::Running),
+            "completed" => Some(MetamodelMigrationStatus::Completed),
+            "failed" => Some(MetamodelMigrationStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One metamodel version and the progress of its migration over existing cards
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelMigration {
+    pub id: Uuid,
+    pub version: String,
+    pub description: Option<String>,
+    pub operation: MetamodelOperation,
+    pub status: MetamodelMigrationStatus,
+    pub total_cards: i32,
+    pub processed_cards: i32,
+    pub changed_cards: i32,
+    pub error: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMetamodelMigrationRequest {
+    pub operation: MetamodelOperation,
+    pub description: Option<String>,
+}
+
+/// What a migration would change, without changing it
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelDryRun {
+    pub version: String,
+    pub affected_cards: usize,
+    /// Up to 50 affected cards with the fields that would change
+    pub samples: Vec<MetamodelDryRunSample>,
+    pub rule_changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelDryRunSample {
+    pub card_id: Uuid,
+    pub name: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetamodelVersionInfo {
+    pub version: String,
+    pub retired_card_types: Vec<String>,
+}
@@ -1,37 +1,64 @@
+pub mod api_token;
 pub mod arb;
 pub mod arb_audit_log;
 pub mod arb_notification;
 pub mod arb_template;
+pub mod architecture_state;
 pub mod bia;
 pub mod card;
+pub mod card_query;
+pub mod card_type;
 pub mod compliance;
+pub mod data_quality;
 pub mod exceptions;
 pub mod export;
+pub mod graph_sync;
 pub mod initiatives;
+pub mod metamodel;
 pub mod migration;
+pub mod permission;
 pub mod policies;
 pub mod principles;
 pub mod relationship;
 pub mod risks;
+pub mod saved_search;
+pub mod scim;
+pub mod search;
 pub mod standards;
 pub mod tco;
+pub mod team;
 pub mod user;
+pub mod version;
+pub mod workspace;
 
+pub use api_token::*;
 pub use arb::*;
 pub use arb_audit_log::*;
 pub use arb_notification::*;
 pub use arb_template::*;
+pub use architecture_state::*;
 pub use bia::*;
 pub use card::*;
+pub use card_query::*;
+pub use card_type::*;
 pub use compliance::*;
+pub use data_quality::*;
 pub use exceptions::*;
 pub use export::*;
+pub use graph_sync::*;
 pub use initiatives::*;
+pub use metamodel::*;
 pub use migration::*;
+pub use permission::*;
 pub use policies::*;
 pub use principles::*;
 pub use relationship::*;
 pub use risks::*;
+pub use saved_search::*;
+pub use search::*;
 pub use standards::*;
 pub use tco::*;
+pub use team::*;
 pub use user::*;
+pub use version::*;
+pub use workspace::*;
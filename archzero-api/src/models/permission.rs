@@ -0,0 +1,297 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::user::{Claims, UserRole};
+
+/// Protected resource groups; each router is guarded by exactly one
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    Cards,
+    Relationships,
+    Principles,
+    Standards,
+    Policies,
+    Exceptions,
+    Initiatives,
+    Risks,
+    Compliance,
+    Arb,
+    /// The caller's own ARB notifications
+    Notifications,
+    /// BIA, topology, migration, TCO and graph views
+    Analysis,
+    ArchitectureStates,
+    Exports,
+    Imports,
+    Reports,
+    Users,
+    Cache,
+    /// Maintenance operations such as graph reconciliation
+    Admin,
+    /// Attribute rules per card type; readable by everyone, managed by admins
+    Metamodel,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 20] = [
+        Resource::Cards,
+        Resource::Relationships,
+        Resource::Principles,
+        Resource::Standards,
+        Resource::Policies,
+        Resource::Exceptions,
+        Resource::Initiatives,
+        Resource::Risks,
+        Resource::Compliance,
+        Resource::Arb,
+        Resource::Notifications,
+        Resource::Analysis,
+        Resource::ArchitectureStates,
+        Resource::Exports,
+        Resource::Imports,
+        Resource::Reports,
+        Resource::Users,
+        Resource::Cache,
+        Resource::Admin,
+        Resource::Metamodel,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resource::Cards => "cards",
+            Resource::Relationships => "relationships",
+            Resource::Principles => "principles",
+            Resource::Standards => "standards",
+            Resource::Policies => "policies",
+            Resource::Exceptions => "exceptions",
+            Resource::Initiatives => "initiatives",
+            Resource::Risks => "risks",
+            Resource::Compliance => "compliance",
+            Resource::Arb => "arb",
+            Resource::Notifications => "notifications",
+            Resource::Analysis => "analysis",
+            Resource::ArchitectureStates => "architecture_states",
+            Resource::Exports => "exports",
+            Resource::Imports => "imports",
+            Resource::Reports => "reports",
+            Resource::Users => "users",
+            Resource::Cache => "cache",
+            Resource::Admin => "admin",
+            Resource::Metamodel => "metamodel",
+        }
+    }
+
+    /// Users, caches, maintenance and the metamodel span every workspace, so they follow the global role
+    pub fn is_instance_wide(&self) -> bool {
+        matches!(self, Resource::Users | Resource::Cache | Resource::Admin | Resource::Metamodel)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Read,
+    /// Create and update
+    Write,
+    Delete,
+    /// Approve or reject (ARB decisions, exceptions)
+    Approve,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::Read, Action::Write, Action::Delete, Action::Approve];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Read => "read",
+            Action::Write => "write",
+            Action::Delete => "delete",
+            Action::Approve => "approve",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permission {
+    pub resource: Resource,
+    pub action: Action,
+}
+
+impl Permission {
+    pub const fn new(resource: Resource, action: Action) -> Self {
+        Self { resource, action }
+    }
+
+    /// Parse the `resource:action` form
+    pub fn parse(value: &str) -> Option<Self> {
+        let (resource, action) = value.split_once(':')?;
+        let resource = Resource::ALL.iter().find(|r| r.as_str() == resource)?;
+        let action = Action::ALL.iter().find(|a| a.as_str() == action)?;
+        Some(Self::new(*resource, *action))
+    }
+}
+
+/// Rendered as `resource:action`, e.g. `cards:write`
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.resource.as_str(), self.action.as_str())
+    }
+}
+
+impl UserRole {
+    /// The role-to-permission matrix
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        use Action::*;
+        use Resource::*;
+
+        let Permission { resource, action } = permission;
+
+        // Everyone manages their own notifications; only admins touch users, cache, maintenance and the metamodel
+        if resource == Notifications {
+            return true;
+        }
+        // Card forms need the attribute rules, so everyone reads the metamodel
+        if resource == Metamodel && action == Read {
+            return true;
+        }
+        if resource.is_instance_wide() {
+            return *self == UserRole::Admin;
+        }
+
+        match self {
+            UserRole::Admin => true,
+            UserRole::Architect => match resource {
+                Arb => matches!(action, Read | Write),
+                _ => action != Approve,
+            },
+            UserRole::Editor => match resource {
+                Cards | Relationships | Initiatives | Risks | Exports | Imports | Reports | Analysis => {
+                    action != Approve
+                }
+                Exceptions | Arb => matches!(action, Read | Write),
+                _ => action == Read,
+            },
+            UserRole::ArbChair => match resource {
+                Arb | Exceptions => true,
+                Principles | Standards | Policies => matches!(action, Read | Write),
+                _ => action == Read,
+            },
+            UserRole::ArbMember => match resource {
+                Arb | Exceptions => matches!(action, Read | Write),
+                _ => action == Read,
+            },
+            UserRole::Viewer => action == Read,
+        }
+    }
+
+    /// Every permission granted to the role, as `resource:action` strings
+    pub fn permissions(&self) -> Vec<String> {
+        Resource::ALL
+            .iter()
+            .flat_map(|resource| Action::ALL.iter().map(move |action| Permission::new(*resource, *action)))
+            .filter(|permission| self.has_permission(*permission))
+            .map(|permission| permission.to_string())
+            .collect()
+    }
+}
+
+impl Claims {
+    /// Role that applies to `resource`: the workspace role if one was resolved, else the global role
+    pub fn role_for(&self, resource: Resource) -> &UserRole {
+        match &self.workspace_role {
+            Some(role) if !resource.is_instance_wide() => role,
+            _ => &self.role,
+        }
+    }
+
+    /// Role permission, limited to the token's scopes for API tokens
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        let in_scope = match &self.scopes {
+            Some(scopes) => scopes.iter().any(|scope| Permission::parse(scope) == Some(permission)),
+            None => true,
+        };
+        in_scope && self.role_for(permission.resource).has_permission(permission)
+    }
+
+    /// Every permission granted to the caller, as `resource:action` strings
+    pub fn permissions(&self) -> Vec<String> {
+        Resource::ALL
+            .iter()
+            .flat_map(|resource| Action::ALL.iter().map(move |action| Permission::new(*resource, *action)))
+            .filter(|permission| self.has_permission(*permission))
+            .map(|permission| permission.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewer_is_read_only() {
+        for resource in Resource::ALL {
+            let read = Permission::new(resource, Action::Read);
+            let expected = !matches!(resource, Resource::Users | Resource::Cache | Resource::Admin);
+            assert_eq!(UserRole::Viewer.has_permission(read), expected, "{}", read);
+        }
+        assert!(!UserRole::Viewer.has_permission(Permission::new(Resource::Cards, Action::Write)));
+        assert!(UserRole::Viewer.has_permission(Permission::new(Resource::Notifications, Action::Delete)));
+    }
+
+    #[test]
+    fn test_approval_and_admin_permissions() {
+        let approve_exception = Permission::new(Resource::Exceptions, Action::Approve);
+        let decide_arb = Permission::new(Resource::Arb, Action::Approve);
+
+        assert!(UserRole::ArbChair.has_permission(approve_exception));
+        assert!(UserRole::ArbChair.has_permission(decide_arb));
+        assert!(!UserRole::ArbMember.has_permission(decide_arb));
+        assert!(!UserRole::Architect.has_permission(approve_exception));
+        assert!(!UserRole::Architect.has_permission(Permission::new(Resource::Users, Action::Read)));
+        assert!(UserRole::Admin.has_permission(Permission::new(Resource::Admin, Action::Write)));
+        assert_eq!(Permission::new(Resource::ArchitectureStates, Action::Write).to_string(), "architecture_states:write");
+    }
+
+    #[test]
+    fn test_token_scopes_limit_role() {
+        let claims = Claims {
+            sub: uuid::Uuid::new_v4().to_string(),
+            email: "ci@service-accounts.archzero.local".to_string(),
+            role: UserRole::Editor,
+            exp: usize::MAX,
+            sid: None,
+            scopes: Some(vec!["cards:read".to_string(), "cards:write".to_string(), "users:read".to_string()]),
+            workspace_id: None,
+            workspace_role: None,
+        };
+
+        assert_eq!(Permission::parse("cards:write"), Some(Permission::new(Resource::Cards, Action::Write)));
+        assert_eq!(Permission::parse("cards:fly"), None);
+        assert!(claims.has_permission(Permission::new(Resource::Cards, Action::Write)));
+        assert!(!claims.has_permission(Permission::new(Resource::Cards, Action::Delete)));
+        // In scope but not granted to the role
+        assert!(!claims.has_permission(Permission::new(Resource::Users, Action::Read)));
+        assert_eq!(claims.permissions(), vec!["cards:read", "cards:write"]);
+    }
+
+    #[test]
+    fn test_workspace_role_excludes_instance_wide_resources() {
+        let claims = Claims {
+            sub: uuid::Uuid::new_v4().to_string(),
+            email: "lead@example.com".to_string(),
+            role: UserRole::Viewer,
+            exp: usize::MAX,
+            sid: None,
+            scopes: None,
+            workspace_id: Some(uuid::Uuid::new_v4()),
+            workspace_role: Some(UserRole::Admin),
+        };
+
+        assert!(claims.has_permission(Permission::new(Resource::Cards, Action::Delete)));
+        assert!(claims.has_permission(Permission::new(Resource::Arb, Action::Approve)));
+        assert!(!claims.has_permission(Permission::new(Resource::Users, Action::Read)));
+        assert!(!claims.has_permission(Permission::new(Resource::Admin, Action::Write)));
+    }
+}
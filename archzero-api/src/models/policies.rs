@@ -67,6 +67,10 @@ pub struct UpdatePolicyRequest {
 pub struct PolicySearchParams {
     pub severity: Option<PolicySeverity>,
     pub enforcement: Option<PolicyEnforcement>,
+    /// Comma-separated sort fields of `GET /api/v1/cards`, e.g. `-attributes.severity` or `-updated_at`
+    pub sort: Option<String>,
+    /// `nextCursor` of the previous page; continues after it instead of using `page`
+    pub cursor: Option<String>,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
@@ -172,6 +176,9 @@ pub struct PolicyPagination {
     pub page: u32,
     pub limit: u32,
     pub total: i64,
+    /// Cursor of the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Query parameters for listing violations
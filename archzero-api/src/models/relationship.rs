@@ -22,6 +22,23 @@ pub enum RelationshipType {
     ExemptsFrom,
 }
 
+impl RelationshipType {
+    pub const ALL: [RelationshipType; 12] = [
+        RelationshipType::ReliesOn,
+        RelationshipType::DependsOn,
+        RelationshipType::Guides,
+        RelationshipType::Standardizes,
+        RelationshipType::AppliesTo,
+        RelationshipType::Enforces,
+        RelationshipType::Impacts,
+        RelationshipType::Achieves,
+        RelationshipType::Threatens,
+        RelationshipType::MitigatedBy,
+        RelationshipType::RequiresComplianceFrom,
+        RelationshipType::ExemptsFrom,
+    ];
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Relationship {
@@ -34,6 +51,9 @@ pub struct Relationship {
     pub attributes: serde_json::Value,
     pub confidence: Option<f64>,
     pub created_at: DateTime<Utc>,
+    /// Workspace of the relationship's source card
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, Clone, ToSchema)]
@@ -56,3 +76,121 @@ pub struct UpdateRelationshipRequest {
     pub attributes: Option<serde_json::Value>,
     pub confidence: Option<f64>,
 }
+
+/// How many relationships of a rule a card may have (docs/02-relationship-spec.md §2)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipCardinality {
+    /// A source has at most one target and a target at most one source
+    OneToOne,
+    /// A target has at most one source, e.g. an Interface is provided by one Application
+    OneToMany,
+    /// A source has at most one target, e.g. an IT Component is part of one Platform
+    ManyToOne,
+    ManyToMany,
+}
+
+impl RelationshipCardinality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipCardinality::OneToOne => "one_to_one",
+            RelationshipCardinality::OneToMany => "one_to_many",
+            RelationshipCardinality::ManyToOne => "many_to_one",
+            RelationshipCardinality::ManyToMany => "many_to_many",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "one_to_one" => Some(RelationshipCardinality::OneToOne),
+            "one_to_many" => Some(RelationshipCardinality::OneToMany),
+            "many_to_one" => Some(RelationshipCardinality::ManyToOne),
+            "many_to_many" => Some(RelationshipCardinality::ManyToMany),
+            _ => None,
+        }
+    }
+
+    /// Whether a source card may have only one relationship of the rule
+    pub fn single_target(&self) -> bool {
+        matches!(self, RelationshipCardinality::OneToOne | RelationshipCardinality::ManyToOne)
+    }
+
+    /// Whether a target card may have only one relationship of the rule
+    pub fn single_source(&self) -> bool {
+        matches!(self, RelationshipCardinality::OneToOne | RelationshipCardinality::OneToMany)
+    }
+}
+
+/// Card types a relationship type may connect; `*` matches any card type
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipRule {
+    pub id: Uuid,
+    pub relationship_type: RelationshipType,
+    pub source_type: String,
+    pub target_type: String,
+    pub cardinality: RelationshipCardinality,
+    /// Also allows relationships from the target type to the source type
+    pub bidirectional: bool,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRelationshipRuleRequest {
+    pub relationship_type: RelationshipType,
+    pub source_type: String,
+    pub target_type: String,
+    pub cardinality: Option<RelationshipCardinality>,
+    #[serde(default)]
+    pub bidirectional: bool,
+    pub description: Option<String>,
+}
+
+/// Replaces the cardinality, direction and description of a rule; its types stay fixed
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRelationshipRuleRequest {
+    pub cardinality: RelationshipCardinality,
+    #[serde(default)]
+    pub bidirectional: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipRuleQuery {
+    pub relationship_type: Option<RelationshipType>,
+}
+
+/// One end of a relationship in a violation report
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipEndpoint {
+    pub card_id: Uuid,
+    pub name: String,
+    pub card_type: String,
+}
+
+/// An existing relationship the current rules would reject
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipRuleViolation {
+    pub relationship_id: Uuid,
+    pub relationship_type: String,
+    pub from: RelationshipEndpoint,
+    pub to: RelationshipEndpoint,
+    /// The rule whose cardinality is exceeded; None if no rule allows the connection
+    pub rule_id: Option<Uuid>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipViolationReport {
+    pub checked_relationships: usize,
+    pub violations: Vec<RelationshipRuleViolation>,
+}
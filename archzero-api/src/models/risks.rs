@@ -105,6 +105,10 @@ pub struct RiskSearchParams {
     pub risk_type: Option<RiskType>,
     pub status: Option<RiskStatus>,
     pub min_score: Option<i32>,
+    /// Comma-separated sort fields of `GET /api/v1/cards`, e.g. `-attributes.riskScore` or `-updated_at`
+    pub sort: Option<String>,
+    /// `nextCursor` of the previous page; continues after it instead of using `page`
+    pub cursor: Option<String>,
     pub page: Option<u32>,
 }
 
@@ -176,6 +180,9 @@ pub struct PaginationMetadata {
     pub limit: u32,
     pub total: i64,
     pub total_pages: u32,
+    /// Cursor of the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Response for top 10 risks
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{ToSchema, IntoParams};
+
+use crate::models::card::{CardType, LifecyclePhase};
+use crate::models::card_query::CardQuery;
+
+/// What a saved search runs: the filters of `GET /api/v1/cards`, with the boolean query as an AST
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub card_type: Option<CardType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifecycle_phase: Option<LifecyclePhase>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<CardQuery>,
+    /// Sort fields as in `GET /api/v1/cards`, e.g. `-updated_at,name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: Uuid,
+    /// Team the search is shared with; `None` keeps it personal
+    pub team_id: Option<Uuid>,
+    pub query: SavedSearchQuery,
+    /// Card fields shown for the results, in order; `attributes.<key>` for attributes
+    pub columns: Vec<String>,
+    /// Pinned by the current user
+    pub pinned: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSavedSearchRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Share with a team the caller belongs to
+    pub team_id: Option<Uuid>,
+    #[serde(default)]
+    pub query: SavedSearchQuery,
+    /// Boolean query in the text syntax of `GET /api/v1/cards?query=`; used when `query.query` is not given
+    pub query_text: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+/// Replaces the saved search
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSavedSearchRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub team_id: Option<Uuid>,
+    #[serde(default)]
+    pub query: SavedSearchQuery,
+    pub query_text: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchRunParams {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::models::card::{Card, CardType, LifecyclePhase};
+use crate::models::card_query::RelationshipDirection;
+use crate::models::relationship::RelationshipType;
+
+/// Ranked card search; the filters narrow both results and facets
+#[derive(Debug, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct CardSearchQuery {
+    /// Search text; web search syntax (`"exact phrase"`, `-excluded`, `or`), typos tolerated
+    pub q: String,
+    #[serde(rename = "type")]
+    pub card_type: Option<CardType>,
+    pub lifecycle_phase: Option<LifecyclePhase>,
+    pub tag: Option<String>,
+    pub owner_id: Option<Uuid>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardSearchHit {
+    pub card: Card,
+    /// Full-text rank plus trigram similarity of the name; higher is better
+    pub score: f64,
+    /// Name with matched words in `<mark>`; HTML in the card text is escaped
+    pub name_highlight: String,
+    /// Description fragments around the matched words, highlighted like the name
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub value: String,
+    /// Display name where the value is an ID
+    pub label: Option<String>,
+    pub count: i64,
+}
+
+/// Counts of all matching cards, not just the current page
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFacets {
+    pub types: Vec<FacetCount>,
+    pub lifecycle_phases: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
+    pub owners: Vec<FacetCount>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardSearchResponse {
+    pub data: Vec<CardSearchHit>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub facets: SearchFacets,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarCardsQuery {
+    /// Number of similar cards, default 10, at most 50
+    pub limit: Option<u32>,
+    /// Only compare with cards of the same type, e.g. to find duplicate applications
+    pub same_type: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarCard {
+    pub card: Card,
+    /// 0-100: 70% shared tags, 20% shared attribute values, 10% graph proximity
+    pub score: f64,
+    /// Overlap of tags, 0-1
+    pub tag_score: f64,
+    /// Overlap of attribute values, 0-1
+    pub attribute_score: f64,
+    /// Overlap of related cards, 0-1; 1 when the cards are related to each other
+    pub graph_score: f64,
+    pub shared_tags: Vec<String>,
+    pub shared_attributes: Vec<String>,
+    pub shared_neighbours: usize,
+}
+
+/// A relationship the card's similar cards have and the card does not
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedRelationship {
+    pub relationship_type: RelationshipType,
+    /// Outgoing: from the card to the suggested card
+    pub direction: RelationshipDirection,
+    pub card_id: Uuid,
+    pub card_name: String,
+    pub card_type: String,
+    /// 0-100: share of the similar cards' scores that have this relationship
+    pub confidence: f64,
+    pub supporting_cards: Vec<Uuid>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarCardsResponse {
+    pub card_id: Uuid,
+    pub similar: Vec<SimilarCard>,
+    pub suggested_relationships: Vec<SuggestedRelationship>,
+}
@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::{ToSchema, IntoParams};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
     Admin,
@@ -14,7 +15,33 @@ pub enum UserRole {
     Viewer,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl UserRole {
+    /// Value stored in `users.role`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Admin => "admin",
+            UserRole::ArbChair => "arbchair",
+            UserRole::ArbMember => "arbmember",
+            UserRole::Architect => "architect",
+            UserRole::Editor => "editor",
+            UserRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "admin" => Some(UserRole::Admin),
+            "arbchair" => Some(UserRole::ArbChair),
+            "arbmember" => Some(UserRole::ArbMember),
+            "architect" => Some(UserRole::Architect),
+            "editor" => Some(UserRole::Editor),
+            "viewer" => Some(UserRole::Viewer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
@@ -32,7 +59,7 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RegisterRequest {
     #[validate(email)]
     pub email: String,
@@ -42,16 +69,164 @@ pub struct RegisterRequest {
     pub role: UserRole,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
+    /// Short-lived access token (JWT)
     pub token: String,
+    /// Single-use token for `POST /auth/refresh`; a new one is returned on every refresh
+    pub refresh_token: String,
+    /// Access token lifetime in seconds
+    pub expires_in: i64,
     pub user: User,
 }
 
+/// Sign-in methods offered on the login page
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthProviders {
+    pub password: bool,
+    pub oidc: bool,
+}
+
+/// Query parameters the IdP sends back to the OIDC callback
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,  // user id
     pub email: String,
     pub role: UserRole,
     pub exp: usize,
+    /// Server-side session the token belongs to; revoking it invalidates the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Set for API tokens: the `resource:action` permissions the token is limited to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    /// Workspace the request operates in; resolved per request, never part of the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+    /// Role held in `workspace_id`; governs every resource except instance-wide ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_role: Option<UserRole>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Device details recorded when a session starts
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// True for the session the request was made with
+    pub current: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Admin view of a user account, including lifecycle state
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct UserAccount {
+    pub id: Uuid,
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    pub role: UserRole,
+    pub is_active: bool,
+    pub failed_login_attempts: i32,
+    /// Set while the account is locked by repeated failed logins
+    pub locked_until: Option<DateTime<Utc>>,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub password_changed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UserListParams {
+    /// Include deactivated users (default false)
+    pub include_inactive: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateUserRequest {
+    #[validate(email)]
+    pub email: Option<String>,
+    pub full_name: Option<String>,
+    pub role: Option<UserRole>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    pub role: UserRole,
+    pub status: InvitationStatus,
+    pub invited_by: Option<Uuid>,
+    /// The account created when the invitation was accepted
+    pub user_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateInvitationRequest {
+    #[validate(email)]
+    pub email: String,
+    pub full_name: Option<String>,
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+    pub password: String,
+    /// Overrides the name given in the invitation
+    pub full_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmPasswordResetRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
 }
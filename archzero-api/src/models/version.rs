@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::{ToSchema, IntoParams};
+
+/// User responsible for a change, taken from the authenticated request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Actor {
+    pub id: Option<Uuid>,
+    pub email: String,
+    /// Used for card access checks; not part of version history
+    #[serde(skip)]
+    pub role: Option<crate::models::user::UserRole>,
+    /// Workspace the request operates in; scopes every card and relationship query
+    #[serde(skip)]
+    pub workspace_id: Option<Uuid>,
+}
+
+impl From<&crate::models::Claims> for Actor {
+    fn from(claims: &crate::models::Claims) -> Self {
+        Self {
+            id: Uuid::parse_str(&claims.sub).ok(),
+            email: claims.email.clone(),
+            role: Some(claims.role_for(crate::models::permission::Resource::Cards).clone()),
+            workspace_id: claims.workspace_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionOperation {
+    Create,
+    Update,
+    Delete,
+    Restore,
+}
+
+impl VersionOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionOperation::Create => "create",
+            VersionOperation::Update => "update",
+            VersionOperation::Delete => "delete",
+            VersionOperation::Restore => "restore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(VersionOperation::Create),
+            "update" => Some(VersionOperation::Update),
+            "delete" => Some(VersionOperation::Delete),
+            "restore" => Some(VersionOperation::Restore),
+            _ => None,
+        }
+    }
+}
+
+/// Immutable record of one change to a card.
+/// `snapshot` is the card after the change, or the card as it was just before a delete.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardVersion {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub version: i32,
+    pub operation: VersionOperation,
+    pub snapshot: serde_json::Value,
+    /// Changed fields as `{field: {from, to}}`; attributes are keyed as `attributes.<key>`
+    pub diff: serde_json::Value,
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Immutable record of one change to a relationship
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipVersion {
+    pub id: Uuid,
+    pub relationship_id: Uuid,
+    pub from_card_id: Uuid,
+    pub to_card_id: Uuid,
+    pub version: i32,
+    pub operation: VersionOperation,
+    pub snapshot: serde_json::Value,
+    pub diff: serde_json::Value,
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AsOfParams {
+    /// Point in time (RFC 3339)
+    pub at: DateTime<Utc>,
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+use crate::models::user::UserRole;
+
+/// Workspace that holds data created before workspaces existed; open to every user
+pub const DEFAULT_WORKSPACE_ID: Uuid = Uuid::from_u128(1);
+
+/// Request header selecting the workspace a request operates in
+pub const WORKSPACE_HEADER: &str = "x-workspace-id";
+
+/// An isolated set of cards, relationships, ARB records, exports and notifications
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A workspace as seen by the current user, with the role they hold in it
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceAccess {
+    #[serde(flatten)]
+    pub workspace: Workspace,
+    pub role: UserRole,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    pub user_id: Uuid,
+    pub email: String,
+    pub full_name: Option<String>,
+    pub role: UserRole,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspaceRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Add a member, or change the role of an existing one
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspaceMemberRequest {
+    pub user_id: Uuid,
+    pub role: UserRole,
+}
+
+/// A card shown read-only in another workspace
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CardShare {
+    pub card_id: Uuid,
+    pub workspace_id: Uuid,
+    pub workspace_name: String,
+    pub shared_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareCardRequest {
+    pub workspace_id: Uuid,
+}
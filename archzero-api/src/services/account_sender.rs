@@ -0,0 +1,57 @@
+/*!
+ * Account Message Delivery
+ *
+ * Invitation and password reset messages are handed to an `AccountMessageSender`.
+ * The default `LogMessageSender` writes them to the log, which is enough for local
+ * development; deployments plug in a mail or chat integration by implementing the trait.
+ */
+
+use async_trait::async_trait;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountMessageKind {
+    Invitation,
+    PasswordReset,
+}
+
+/// An email-style message carrying a single-use link
+#[derive(Debug, Clone)]
+pub struct AccountMessage {
+    pub kind: AccountMessageKind,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// Link containing the plain token; never stored server-side
+    pub link: String,
+}
+
+#[async_trait]
+pub trait AccountMessageSender: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, message: &AccountMessage) -> Result<(), AppError>;
+}
+
+/// Writes messages to the application log instead of delivering them
+pub struct LogMessageSender;
+
+#[async_trait]
+impl AccountMessageSender for LogMessageSender {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    async fn send(&self, message: &AccountMessage) -> Result<(), AppError> {
+        tracing::info!(
+            "{:?} message for {}: {} ({})",
+            message.kind,
+            message.to,
+            message.subject,
+            message.link
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+/*!
+ * API Token Service
+ *
+ * Personal access tokens and service-account tokens for automation. Tokens are
+ * random secrets prefixed with `az_` (so `auth_middleware` can tell them from JWTs),
+ * stored as SHA-256 hashes, always expire and are limited to a set of
+ * `resource:action` scopes on top of the owner's role.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::config::ApiTokens;
+use crate::error::AppError;
+use crate::models::api_token::{ApiToken, CreateApiTokenRequest, CreateServiceAccountRequest, CreatedApiToken, ServiceAccount};
+use crate::models::permission::Permission;
+use crate::models::user::{Claims, UserRole};
+use crate::services::user_service::{generate_token, hash_token};
+
+/// Prefix that marks a Bearer credential as an API token
+pub const TOKEN_PREFIX: &str = "az_";
+
+/// Service accounts get a generated, non-deliverable address under this domain
+const SERVICE_ACCOUNT_DOMAIN: &str = "service-accounts.archzero.local";
+
+const TOKEN_COLUMNS: &str = "id, user_id, name, token_prefix, scopes, created_by, created_at, \
+     expires_at, last_used_at, last_used_ip, revoked_at";
+
+pub struct ApiTokenService {
+    pool: PgPool,
+    config: ApiTokens,
+}
+
+impl ApiTokenService {
+    pub fn new(pool: PgPool, config: ApiTokens) -> Self {
+        Self { pool, config }
+    }
+
+    /// Tokens of a user, newest first (including revoked and expired ones)
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+            TOKEN_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list API tokens: {}", e)))?;
+
+        rows.iter().map(row_to_token).collect()
+    }
+
+    /// Issue a token for `user_id`; scopes must be granted to the user's role
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        created_by: Uuid,
+        req: CreateApiTokenRequest,
+    ) -> Result<CreatedApiToken, AppError> {
+        if req.name.trim().is_empty() {
+            return Err(AppError::Validation("Token name is required".to_string()));
+        }
+
+        let role = self.active_user_role(user_id).await?;
+        let scopes = validate_scopes(&req.scopes, &role)?;
+        let expires_at = self.expiry(req.expires_in_days)?;
+
+        let token = format!("{}{}", TOKEN_PREFIX, generate_token());
+        let row = sqlx::query(&format!(
+            "INSERT INTO api_tokens (user_id, name, token_prefix, token_hash, scopes, created_by, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            TOKEN_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(req.name.trim())
+        .bind(&token[..TOKEN_PREFIX.len() + 8])
+        .bind(hash_token(&token))
+        .bind(&scopes)
+        .bind(created_by)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(CreatedApiToken {
+            token,
+            api_token: row_to_token(&row)?,
+        })
+    }
+
+    /// Revoke one of a user's tokens
+    pub async fn revoke(&self, user_id: Uuid, token_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE api_tokens SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1 AND user_id = $2",
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke API token: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("API token {} not found", token_id)));
+        }
+        Ok(())
+    }
+
+    /// Resolve a presented token to claims, recording its use.
+    /// Revoked, expired and deactivated-owner tokens are rejected.
+    pub async fn authenticate(&self, token: &str, ip_address: Option<String>) -> Result<Claims, AppError> {
+        let row = sqlx::query(
+            "SELECT t.id, t.scopes, t.expires_at, u.id AS user_id, u.email, u.role
+             FROM api_tokens t
+             JOIN users u ON u.id = t.user_id
+             WHERE t.token_hash = $1
+               AND t.revoked_at IS NULL
+               AND t.expires_at > NOW()
+               AND COALESCE(u.is_active, TRUE)",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to verify API token: {}", e)))?
+        .ok_or_else(|| AppError::Auth("Invalid or expired API token".to_string()))?;
+
+        let token_id: Uuid = row.try_get("id").map_err(missing("id"))?;
+        let user_id: Uuid = row.try_get("user_id").map_err(missing("user_id"))?;
+        let expires_at: DateTime<Utc> = row.try_get("expires_at").map_err(missing("expires_at"))?;
+        let role: String = row.try_get("role").map_err(missing("role"))?;
+
+        // Throttled so busy pipelines do not write on every request
+        sqlx::query(
+            "UPDATE api_tokens SET last_used_at = NOW(), last_used_ip = $2
+             WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')",
+        )
+        .bind(token_id)
+        .bind(ip_address)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record API token use: {}", e)))?;
+
+        Ok(Claims {
+            sub: user_id.to_string(),
+            email: row.try_get("email").map_err(missing("email"))?,
+            role: UserRole::parse(&role)
+                .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid user role: {}", role)))?,
+            exp: expires_at.timestamp() as usize,
+            sid: None,
+            scopes: Some(row.try_get("scopes").map_err(missing("scopes"))?),
+            workspace_id: None,
+            workspace_role: None,
+        })
+    }
+
+    /// Listed prefix of a presented token if it is live, without recording a use.
+    /// Lets the rate limiter count a token only once it is known to be genuine.
+    pub async fn verified_prefix(&self, token: &str) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar(
+            "SELECT token_prefix FROM api_tokens WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to verify API token: {}", e)))
+    }
+
+    pub async fn list_service_accounts(&self) -> Result<Vec<ServiceAccount>, AppError> {
+        let rows = sqlx::query(
+            "SELECT id, full_name, email, role, COALESCE(is_active, TRUE) AS is_active, created_at
+             FROM users WHERE is_service_account ORDER BY full_name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list service accounts: {}", e)))?;
+
+        rows.iter().map(row_to_service_account).collect()
+    }
+
+    pub async fn get_service_account(&self, id: Uuid) -> Result<ServiceAccount, AppError> {
+        let row = sqlx::query(
+            "SELECT id, full_name, email, role, COALESCE(is_active, TRUE) AS is_active, created_at
+             FROM users WHERE id = $1 AND is_service_account",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch service account: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Service account {} not found", id)))?;
+
+        row_to_service_account(&row)
+    }
+
+    /// Create a service account; it has no password and signs in only with tokens
+    pub async fn create_service_account(&self, req: CreateServiceAccountRequest) -> Result<ServiceAccount, AppError> {
+        let slug = slugify(&req.name);
+        if slug.is_empty() {
+            return Err(AppError::Validation("Service account name is required".to_string()));
+        }
+
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (email, full_name, role, password_hash, is_service_account, failed_login_attempts)
+             VALUES ($1, $2, $3, NULL, TRUE, 0)
+             RETURNING id",
+        )
+        .bind(format!("{}@{}", slug, SERVICE_ACCOUNT_DOMAIN))
+        .bind(req.name.trim())
+        .bind(req.role.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.get_service_account(id).await
+    }
+
+    /// Deactivate a service account and revoke all of its tokens
+    pub async fn deactivate_service_account(&self, id: Uuid) -> Result<(), AppError> {
+        self.get_service_account(id).await?;
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("UPDATE users SET is_active = FALSE, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to deactivate service account: {}", e)))?;
+
+        sqlx::query("UPDATE api_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke API tokens: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn active_user_role(&self, user_id: Uuid) -> Result<UserRole, AppError> {
+        let row = sqlx::query("SELECT role, COALESCE(is_active, TRUE) AS is_active FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch user: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let is_active: bool = row.try_get("is_active").map_err(missing("is_active"))?;
+        if !is_active {
+            return Err(AppError::Validation("Tokens cannot be issued to a deactivated account".to_string()));
+        }
+        let role: String = row.try_get("role").map_err(missing("role"))?;
+        UserRole::parse(&role).ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid user role: {}", role)))
+    }
+
+    fn expiry(&self, expires_in_days: Option<i64>) -> Result<DateTime<Utc>, AppError> {
+        let days = expires_in_days.unwrap_or(self.config.default_expiry_days);
+        if days < 1 || days > self.config.max_expiry_days {
+            return Err(AppError::Validation(format!(
+                "Token lifetime must be between 1 and {} days",
+                self.config.max_expiry_days
+            )));
+        }
+        Ok(Utc::now() + Duration::days(days))
+    }
+}
+
+/// Check requested scopes: known `resource:action` values the role is granted, deduplicated
+pub fn validate_scopes(scopes: &[String], role: &UserRole) -> Result<Vec<String>, AppError> {
+    if scopes.is_empty() {
+        return Err(AppError::Validation("At least one scope is required".to_string()));
+    }
+
+    let mut validated: Vec<String> = Vec::new();
+    for scope in scopes {
+        let permission = Permission::parse(scope)
+            .ok_or_else(|| AppError::Validation(format!("Unknown scope: {}", scope)))?;
+        if !role.has_permission(permission) {
+            return Err(AppError::Validation(format!("Scope {} is not granted to the account's role", scope)));
+        }
+        if !validated.contains(scope) {
+            validated.push(scope.clone());
+        }
+    }
+    Ok(validated)
+}
+
+fn slugify(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_token(row: &sqlx::postgres::PgRow) -> Result<ApiToken, AppError> {
+    Ok(ApiToken {
+        id: row.try_get("id").map_err(missing("id"))?,
+        user_id: row.try_get("user_id").map_err(missing("user_id"))?,
+        name: row.try_get("name").map_err(missing("name"))?,
+        token_prefix: row.try_get("token_prefix").map_err(missing("token_prefix"))?,
+        scopes: row.try_get("scopes").map_err(missing("scopes"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        expires_at: row.try_get("expires_at").map_err(missing("expires_at"))?,
+        last_used_at: row.try_get("last_used_at").map_err(missing("last_used_at"))?,
+        last_used_ip: row.try_get("last_used_ip").map_err(missing("last_used_ip"))?,
+        revoked_at: row.try_get("revoked_at").map_err(missing("revoked_at"))?,
+    })
+}
+
+fn row_to_service_account(row: &sqlx::postgres::PgRow) -> Result<ServiceAccount, AppError> {
+    let role: String = row.try_get("role").map_err(missing("role"))?;
+    Ok(ServiceAccount {
+        id: row.try_get("id").map_err(missing("id"))?,
+        name: row.try_get::<Option<String>, _>("full_name").map_err(missing("full_name"))?.unwrap_or_default(),
+        email: row.try_get("email").map_err(missing("email"))?,
+        role: UserRole::parse(&role).ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid user role: {}", role)))?,
+        is_active: row.try_get("is_active").map_err(missing("is_active"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_must_be_known_and_granted() {
+        let scopes = vec!["cards:read".to_string(), "cards:write".to_string(), "cards:read".to_string()];
+        assert_eq!(validate_scopes(&scopes, &UserRole::Editor).unwrap(), vec!["cards:read", "cards:write"]);
+
+        assert!(matches!(validate_scopes(&[], &UserRole::Editor), Err(AppError::Validation(_))));
+        assert!(matches!(
+            validate_scopes(&["cards:teleport".to_string()], &UserRole::Editor),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_scopes(&["cards:write".to_string()], &UserRole::Viewer),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_service_account_slug() {
+        assert_eq!(slugify("  CMDB Sync (nightly) "), "cmdb-sync-nightly");
+        assert_eq!(slugify("!!!"), "");
+    }
+}
@@ -3,6 +3,8 @@ use uuid::Uuid;
 use serde_json::json;
 use crate::models::arb_audit_log::{ARBAuditLog, CreateAuditLogRequest};
 use crate::error::AppError;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::version_service::current_workspace;
 
 pub struct ARBAuditService {
     pool: PgPool,
@@ -27,8 +29,8 @@ impl ARBAuditService {
             r#"
             INSERT INTO arb_audit_logs
             (id, entity_type, entity_id, action, actor_id, actor_name, actor_role,
-             changes, metadata, ip_address, user_agent, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             changes, metadata, ip_address, user_agent, created_at, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING *
             "#
         )
@@ -44,6 +46,7 @@ impl ARBAuditService {
         .bind(&ip_address)
         .bind(&user_agent)
         .bind(now)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create audit log: {}", e)))?;
@@ -62,7 +65,7 @@ impl ARBAuditService {
         let logs = sqlx::query_as::<_, ARBAuditLog>(
             r#"
             SELECT * FROM arb_audit_logs
-            WHERE entity_type = $1 AND entity_id = $2
+            WHERE entity_type = $1 AND entity_id = $2 AND ($5::uuid IS NULL OR workspace_id = $5)
             ORDER BY created_at DESC
             LIMIT $3 OFFSET $4
             "#
@@ -71,6 +74,7 @@ impl ARBAuditService {
         .bind(entity_id)
         .bind(limit)
         .bind(offset)
+        .bind(current_workspace())
         .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch audit logs: {}", e)))?;
@@ -89,6 +93,11 @@ impl ARBAuditService {
             "SELECT * FROM arb_audit_logs WHERE 1=1"
         );
 
+        // A UUID renders as hex and dashes only, so it is safe to inline
+        if let Some(workspace_id) = current_workspace() {
+            query.push_str(&format!(" AND workspace_id = '{}'", workspace_id));
+        }
+
         let mut param_count = 0;
         let mut params: Vec<String> = Vec::new();
 
@@ -2,6 +2,8 @@ use sqlx::PgPool;
 use uuid::Uuid;
 use crate::models::arb_notification::{ARBNotification, CreateNotificationRequest, NotificationResponse};
 use crate::error::AppError;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::version_service::current_workspace;
 
 pub struct ARBNotificationService {
     pool: PgPool,
@@ -24,8 +26,8 @@ impl ARBNotificationService {
             r#"
             INSERT INTO arb_notifications
             (id, recipient_id, submission_id, meeting_id, notification_type,
-             title, message, is_read, read_at, action_url, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             title, message, is_read, read_at, action_url, created_at, updated_at, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING *
             "#
         )
@@ -41,6 +43,7 @@ impl ARBNotificationService {
         .bind(&request.action_url)
         .bind(now)
         .bind(now)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create notification: {}", e)))?;
@@ -60,7 +63,7 @@ impl ARBNotificationService {
             sqlx::query_as::<_, ARBNotification>(
                 r#"
                 SELECT * FROM arb_notifications
-                WHERE recipient_id = $1
+                WHERE recipient_id = $1 AND ($4::uuid IS NULL OR workspace_id = $4)
                 ORDER BY created_at DESC
                 LIMIT $2 OFFSET $3
                 "#
@@ -68,13 +71,14 @@ impl ARBNotificationService {
             .bind(recipient_id)
             .bind(limit)
             .bind(offset)
+            .bind(current_workspace())
             .fetch_all(&self.pool)
             .await
         } else {
             sqlx::query_as::<_, ARBNotification>(
                 r#"
                 SELECT * FROM arb_notifications
-                WHERE recipient_id = $1 AND is_read = false
+                WHERE recipient_id = $1 AND is_read = false AND ($4::uuid IS NULL OR workspace_id = $4)
                 ORDER BY created_at DESC
                 LIMIT $2 OFFSET $3
                 "#
@@ -82,6 +86,7 @@ impl ARBNotificationService {
             .bind(recipient_id)
             .bind(limit)
             .bind(offset)
+            .bind(current_workspace())
             .fetch_all(&self.pool)
             .await
         }
@@ -101,13 +106,14 @@ impl ARBNotificationService {
             r#"
             UPDATE arb_notifications
             SET is_read = true, read_at = $1, updated_at = $2
-            WHERE id = $3
+            WHERE id = $3 AND ($4::uuid IS NULL OR workspace_id = $4)
             RETURNING *
             "#
         )
         .bind(now)
         .bind(now)
         .bind(notification_id)
+        .bind(current_workspace())
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to mark notification as read: {}", e)))?;
@@ -126,12 +132,13 @@ impl ARBNotificationService {
             r#"
             UPDATE arb_notifications
             SET is_read = true, read_at = $1, updated_at = $2
-            WHERE recipient_id = $3 AND is_read = false
+            WHERE recipient_id = $3 AND is_read = false AND ($4::uuid IS NULL OR workspace_id = $4)
             "#
         )
         .bind(now)
         .bind(now)
         .bind(recipient_id)
+        .bind(current_workspace())
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to mark all notifications as read: {}", e)))?;
@@ -147,10 +154,11 @@ impl ARBNotificationService {
         let count = sqlx::query_scalar::<_, i64>(
             r#"
             SELECT COUNT(*) FROM arb_notifications
-            WHERE recipient_id = $1 AND is_read = false
+            WHERE recipient_id = $1 AND is_read = false AND ($2::uuid IS NULL OR workspace_id = $2)
             "#
         )
         .bind(recipient_id)
+        .bind(current_workspace())
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to get unread count: {}", e)))?;
@@ -164,9 +172,10 @@ impl ARBNotificationService {
         notification_id: Uuid,
     ) -> Result<(), AppError> {
         sqlx::query(
-            "DELETE FROM arb_notifications WHERE id = $1"
+            "DELETE FROM arb_notifications WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)"
         )
         .bind(notification_id)
+        .bind(current_workspace())
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete notification: {}", e)))?;
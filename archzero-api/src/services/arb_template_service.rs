@@ -5,6 +5,8 @@ use chrono::{Utc, DateTime};
 
 use crate::models::arb_template::*;
 use crate::error::AppError;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::version_service::current_workspace;
 
 pub struct ArbTemplateService {
     pool: PgPool,
@@ -15,13 +17,15 @@ impl ArbTemplateService {
         Self { pool }
     }
 
-    /// List all templates
+    /// List the templates of the current workspace
     pub async fn list_templates(&self) -> Result<Vec<ARBTemplate>, AppError> {
         let templates = sqlx::query_as::<_, ARBTemplate>(
             "SELECT id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at
              FROM arb_templates
+             WHERE $1::uuid IS NULL OR workspace_id = $1
              ORDER BY created_at DESC"
         )
+        .bind(current_workspace())
         .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
@@ -34,9 +38,10 @@ impl ArbTemplateService {
         let template = sqlx::query_as::<_, ARBTemplate>(
             "SELECT id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at
              FROM arb_templates
-             WHERE id = $1"
+             WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)"
         )
         .bind(id)
+        .bind(current_workspace())
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
@@ -55,9 +60,10 @@ impl ArbTemplateService {
         let submission: Option<(serde_json::Value, Option<String>)> = sqlx::query_as(
             "SELECT c.attributes, c.attributes->>'submissionType' as submission_type
              FROM cards c
-             WHERE c.id = $1"
+             WHERE c.id = $1 AND ($2::uuid IS NULL OR c.workspace_id = $2)"
         )
         .bind(req.submission_id)
+        .bind(current_workspace())
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
@@ -71,8 +77,8 @@ impl ArbTemplateService {
         let now = Utc::now();
 
         let template = sqlx::query_as::<_, ARBTemplate>(
-            "INSERT INTO arb_templates (id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "INSERT INTO arb_templates (id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at, workspace_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
              RETURNING id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at"
         )
         .bind(template_id)
@@ -84,6 +90,7 @@ impl ArbTemplateService {
         .bind(user_id)
         .bind(now)
         .bind(now)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
@@ -111,6 +118,7 @@ impl ArbTemplateService {
 
         let now = Utc::now();
         let submission_id = Uuid::new_v4();
+        let workspace_id = current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID);
 
         // Build final attributes
         let mut final_attrs = attributes;
@@ -124,8 +132,8 @@ impl ArbTemplateService {
 
         // Create submission card
         sqlx::query(
-            "INSERT INTO cards (id, name, card_type, lifecycle_phase, attributes, owner_id, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            "INSERT INTO cards (id, name, card_type, lifecycle_phase, attributes, owner_id, created_at, updated_at, workspace_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
         )
         .bind(submission_id)
         .bind(&req.title)
@@ -135,6 +143,7 @@ impl ArbTemplateService {
         .bind(user_id)
         .bind(now)
         .bind(now)
+        .bind(workspace_id)
         .execute(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
@@ -161,6 +170,9 @@ impl ArbTemplateService {
             ).unwrap_or(crate::models::card::LifecyclePhase::Development),
             attributes: card.4,
             owner_id: card.5,
+            team_id: None,
+            workspace_id: Some(workspace_id),
+            metamodel_version: None,
             created_at: card.6,
             updated_at: card.7,
             quality_score: None,
@@ -184,7 +196,7 @@ impl ArbTemplateService {
                  description = COALESCE($2, description),
                  template_data = COALESCE($3, template_data),
                  updated_at = $4
-             WHERE id = $5
+             WHERE id = $5 AND ($6::uuid IS NULL OR workspace_id = $6)
              RETURNING id, title, description, request_type, card_id, template_data, created_by, created_at, updated_at"
         )
         .bind(req.title)
@@ -192,6 +204,7 @@ impl ArbTemplateService {
         .bind(req.template_data)
         .bind(now)
         .bind(id)
+        .bind(current_workspace())
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
@@ -202,8 +215,9 @@ impl ArbTemplateService {
 
     /// Delete a template
     pub async fn delete_template(&self, id: Uuid) -> Result<(), AppError> {
-        let rows_affected = sqlx::query("DELETE FROM arb_templates WHERE id = $1")
+        let rows_affected = sqlx::query("DELETE FROM arb_templates WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)")
             .bind(id)
+            .bind(current_workspace())
             .execute(&self.pool)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
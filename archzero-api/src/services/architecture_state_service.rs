@@ -0,0 +1,645 @@
+/*!
+ * Architecture State Service
+ *
+ * Named baseline/target/transition states. Snapshot states freeze every card and
+ * relationship at creation time; planned states hold ordered changes layered over a
+ * base state or the live architecture. Any two states can be diffed.
+ *
+ * States belong to the workspace they were created in and are only visible there.
+ */
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::architecture_state::*;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::card_access::CardAccessPolicy;
+use crate::services::version_service::{current_workspace, diff_values};
+use crate::services::{CardService, RelationshipService};
+
+/// Entities keyed by ID, stored in their serialized (camelCase) form
+pub type EntityMap = BTreeMap<Uuid, Value>;
+
+/// Cards and relationships a state resolves to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedState {
+    pub cards: EntityMap,
+    pub relationships: EntityMap,
+}
+
+/// Reference accepted by the diff endpoint for the live architecture
+pub const CURRENT_STATE: &str = "current";
+
+/// Planned states may be stacked, but not without limit
+const MAX_STATE_DEPTH: usize = 16;
+
+pub struct ArchitectureStateService {
+    pool: PgPool,
+    card_service: Arc<CardService>,
+    relationship_service: Arc<RelationshipService>,
+}
+
+impl ArchitectureStateService {
+    pub fn new(
+        pool: PgPool,
+        card_service: Arc<CardService>,
+        relationship_service: Arc<RelationshipService>,
+    ) -> Self {
+        Self {
+            pool,
+            card_service,
+            relationship_service,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ArchitectureState>, AppError> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE ($1::uuid IS NULL OR s.workspace_id = $1) ORDER BY s.effective_date NULLS LAST, s.created_at",
+            STATE_SELECT
+        ))
+            .bind(current_workspace())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list architecture states: {}", e)))?;
+
+        rows.into_iter().map(row_to_state).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<ArchitectureState, AppError> {
+        let row = sqlx::query(&format!("{} WHERE s.id = $1 AND ($2::uuid IS NULL OR s.workspace_id = $2)", STATE_SELECT))
+            .bind(id)
+            .bind(current_workspace())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch architecture state: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Architecture state {} not found", id)))?;
+
+        row_to_state(row)
+    }
+
+    /// Create a state; snapshot states capture the live architecture immediately
+    pub async fn create(
+        &self,
+        req: CreateArchitectureStateRequest,
+        created_by: Option<Uuid>,
+    ) -> Result<ArchitectureState, AppError> {
+        if req.name.trim().is_empty() {
+            return Err(AppError::Validation("State name is required".to_string()));
+        }
+        match (req.mode, req.base_state_id) {
+            (StateMode::Snapshot, Some(_)) => {
+                return Err(AppError::Validation("Snapshot states cannot have a base state".to_string()));
+            }
+            (StateMode::Planned, Some(base_id)) => {
+                self.get(base_id).await?;
+            }
+            _ => {}
+        }
+
+        let live = if req.mode == StateMode::Snapshot { Some(self.load_live().await?) } else { None };
+        let id = Uuid::new_v4();
+        let workspace_id = current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO architecture_states (id, name, description, kind, mode, base_state_id, effective_date, created_by, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(id)
+        .bind(req.name.trim())
+        .bind(&req.description)
+        .bind(enum_str(&req.kind)?)
+        .bind(enum_str(&req.mode)?)
+        .bind(req.base_state_id)
+        .bind(req.effective_date)
+        .bind(created_by)
+        .bind(workspace_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                AppError::Validation(format!("An architecture state named '{}' already exists", req.name.trim()))
+            }
+            e => AppError::Internal(anyhow::anyhow!("Failed to create architecture state: {}", e)),
+        })?;
+
+        if let Some(live) = live {
+            for (table, key, entities) in [
+                ("architecture_state_cards", "card_id", &live.cards),
+                ("architecture_state_relationships", "relationship_id", &live.relationships),
+            ] {
+                let ids: Vec<Uuid> = entities.keys().copied().collect();
+                let snapshots: Vec<Value> = entities.values().cloned().collect();
+
+                sqlx::query(&format!(
+                    "INSERT INTO {} (state_id, workspace_id, {}, snapshot) SELECT $1, $2, * FROM UNNEST($3::uuid[], $4::jsonb[])",
+                    table, key
+                ))
+                .bind(id)
+                .bind(workspace_id)
+                .bind(&ids)
+                .bind(&snapshots)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to snapshot architecture state: {}", e)))?;
+            }
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit architecture state: {}", e)))?;
+
+        self.get(id).await
+    }
+
+    pub async fn update(&self, id: Uuid, req: UpdateArchitectureStateRequest) -> Result<ArchitectureState, AppError> {
+        let kind = req.kind.as_ref().map(enum_str).transpose()?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE architecture_states SET
+                name = COALESCE($2, name),
+                description = COALESCE($3, description),
+                kind = COALESCE($4, kind),
+                effective_date = COALESCE($5, effective_date),
+                updated_at = NOW()
+            WHERE id = $1 AND ($6::uuid IS NULL OR workspace_id = $6)
+            "#,
+        )
+        .bind(id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(&req.description)
+        .bind(kind)
+        .bind(req.effective_date)
+        .bind(current_workspace())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update architecture state: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Architecture state {} not found", id)));
+        }
+
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let dependents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM architecture_states WHERE base_state_id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check dependent states: {}", e)))?;
+
+        if dependents > 0 {
+            return Err(AppError::Validation(format!(
+                "Architecture state {} is the base of {} other state(s)", id, dependents
+            )));
+        }
+
+        let result = sqlx::query("DELETE FROM architecture_states WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)")
+            .bind(id)
+            .bind(current_workspace())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete architecture state: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Architecture state {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Append a planned change to a `planned` state
+    pub async fn add_change(&self, state_id: Uuid, req: CreatePlannedChangeRequest) -> Result<PlannedChange, AppError> {
+        let state = self.get(state_id).await?;
+        if state.mode != StateMode::Planned {
+            return Err(AppError::Validation("Changes can only be added to planned states".to_string()));
+        }
+
+        let entity_id = match (req.change_type, req.entity_id) {
+            (PlannedChangeType::Add, id) => id.unwrap_or_else(Uuid::new_v4),
+            (_, Some(id)) => id,
+            (_, None) => return Err(AppError::Validation("entityId is required for update and remove".to_string())),
+        };
+        if req.change_type != PlannedChangeType::Remove && !req.payload.is_object() {
+            return Err(AppError::Validation("payload must be an object for add and update".to_string()));
+        }
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO architecture_state_changes (state_id, workspace_id, sequence, entity_type, entity_id, change_type, payload)
+            SELECT $1, $6, COALESCE(MAX(sequence), 0) + 1, $2, $3, $4, $5
+            FROM architecture_state_changes WHERE state_id = $1
+            RETURNING id, state_id, sequence, entity_type, entity_id, change_type, payload, created_at
+            "#,
+        )
+        .bind(state_id)
+        .bind(enum_str(&req.entity_type)?)
+        .bind(entity_id)
+        .bind(enum_str(&req.change_type)?)
+        .bind(&req.payload)
+        .bind(state.workspace_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to add planned change: {}", e)))?;
+
+        sqlx::query("UPDATE architecture_states SET updated_at = NOW() WHERE id = $1")
+            .bind(state_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to touch architecture state: {}", e)))?;
+
+        row_to_change(row)
+    }
+
+    /// Planned card changes without the attributes the current caller may not see
+    pub fn redacted_changes(&self, mut changes: Vec<PlannedChange>) -> Vec<PlannedChange> {
+        if let Some(policy) = self.card_service.redaction_for_caller() {
+            for change in changes.iter_mut().filter(|change| change.entity_type == StateEntityType::Card) {
+                if let Some(attributes) = change.payload.get_mut("attributes") {
+                    policy.redact_attributes(attributes);
+                }
+            }
+        }
+        changes
+    }
+
+    pub async fn list_changes(&self, state_id: Uuid) -> Result<Vec<PlannedChange>, AppError> {
+        self.get(state_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, state_id, sequence, entity_type, entity_id, change_type, payload, created_at
+            FROM architecture_state_changes
+            WHERE state_id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)
+            ORDER BY sequence
+            "#,
+        )
+        .bind(state_id)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list planned changes: {}", e)))?;
+
+        rows.into_iter().map(row_to_change).collect()
+    }
+
+    pub async fn delete_change(&self, state_id: Uuid, change_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "DELETE FROM architecture_state_changes WHERE state_id = $1 AND id = $2 AND ($3::uuid IS NULL OR workspace_id = $3)",
+        )
+            .bind(state_id)
+            .bind(change_id)
+            .bind(current_workspace())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete planned change: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Planned change {} not found", change_id)));
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a state ID, or `current`, to its cards and relationships
+    pub async fn resolve(&self, reference: &str) -> Result<ResolvedState, AppError> {
+        if reference.eq_ignore_ascii_case(CURRENT_STATE) {
+            return self.load_live().await;
+        }
+        let id = Uuid::parse_str(reference)
+            .map_err(|_| AppError::Validation(format!("'{}' is not a state ID or '{}'", reference, CURRENT_STATE)))?;
+
+        // Walk down to a snapshot (or the live architecture), then replay planned changes upwards
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = Some(id);
+        while let Some(state_id) = next {
+            if !visited.insert(state_id) || chain.len() >= MAX_STATE_DEPTH {
+                return Err(AppError::Validation(format!("Architecture state {} has a circular or too deep base chain", id)));
+            }
+            let state = self.get(state_id).await?;
+            next = if state.mode == StateMode::Planned { state.base_state_id } else { None };
+            chain.push(state);
+        }
+
+        let bottom = chain.last().expect("chain holds at least the requested state");
+        let mut resolved = if bottom.mode == StateMode::Snapshot {
+            self.load_snapshot(bottom.id).await?
+        } else {
+            self.load_live().await?
+        };
+
+        for state in chain.iter().rev().filter(|s| s.mode == StateMode::Planned) {
+            let changes = self.list_changes(state.id).await?;
+            apply_changes(&mut resolved, &changes);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Diff two states as the current caller may see them: snapshots and planned changes hold
+    /// every attribute, so restricted ones are removed before diffing
+    pub async fn diff(&self, from: &str, to: &str) -> Result<StateDiff, AppError> {
+        let mut from_state = self.resolve(from).await?;
+        let mut to_state = self.resolve(to).await?;
+        if let Some(policy) = self.card_service.redaction_for_caller() {
+            redact_state(&mut from_state, policy);
+            redact_state(&mut to_state, policy);
+        }
+
+        Ok(StateDiff {
+            from: from.to_string(),
+            to: to.to_string(),
+            cards: diff_entities(&from_state.cards, &to_state.cards),
+            relationships: diff_entities(&from_state.relationships, &to_state.relationships),
+        })
+    }
+
+    async fn load_live(&self) -> Result<ResolvedState, AppError> {
+        let mut state = ResolvedState::default();
+        for card in self.card_service.list_all_visible().await? {
+            state.cards.insert(card.id, to_value(&card)?);
+        }
+        for relationship in self.relationship_service.list_all_visible().await? {
+            state.relationships.insert(relationship.id, to_value(&relationship)?);
+        }
+        Ok(state)
+    }
+
+    async fn load_snapshot(&self, state_id: Uuid) -> Result<ResolvedState, AppError> {
+        let cards: Vec<(Uuid, Value)> = sqlx::query_as(
+            "SELECT card_id, snapshot FROM architecture_state_cards WHERE state_id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)",
+        )
+        .bind(state_id)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load state cards: {}", e)))?;
+
+        let relationships: Vec<(Uuid, Value)> = sqlx::query_as(
+            "SELECT relationship_id, snapshot FROM architecture_state_relationships WHERE state_id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)",
+        )
+        .bind(state_id)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load state relationships: {}", e)))?;
+
+        Ok(ResolvedState {
+            cards: cards.into_iter().collect(),
+            relationships: relationships.into_iter().collect(),
+        })
+    }
+}
+
+const STATE_SELECT: &str = r#"
+    SELECT s.id, s.name, s.description, s.kind, s.mode, s.base_state_id, s.effective_date,
+           s.created_by, s.workspace_id, s.created_at, s.updated_at,
+           (SELECT COUNT(*) FROM architecture_state_cards c WHERE c.state_id = s.id) AS card_count,
+           (SELECT COUNT(*) FROM architecture_state_relationships r WHERE r.state_id = s.id) AS relationship_count,
+           (SELECT COUNT(*) FROM architecture_state_changes ch WHERE ch.state_id = s.id) AS change_count
+    FROM architecture_states s
+"#;
+
+/// Remove restricted attributes from the cards of a resolved state
+pub fn redact_state(state: &mut ResolvedState, policy: &CardAccessPolicy) {
+    for card in state.cards.values_mut() {
+        if let Some(attributes) = card.get_mut("attributes") {
+            policy.redact_attributes(attributes);
+        }
+    }
+}
+
+/// Replay planned changes, in order, over a resolved state
+pub fn apply_changes(state: &mut ResolvedState, changes: &[PlannedChange]) {
+    for change in changes {
+        let entities = match change.entity_type {
+            StateEntityType::Card => &mut state.cards,
+            StateEntityType::Relationship => &mut state.relationships,
+        };
+
+        match change.change_type {
+            PlannedChangeType::Add => {
+                let mut entity = change.payload.clone();
+                if let Value::Object(fields) = &mut entity {
+                    fields.insert("id".to_string(), Value::String(change.entity_id.to_string()));
+                }
+                entities.insert(change.entity_id, entity);
+            }
+            PlannedChangeType::Update => {
+                if let Some(entity) = entities.get_mut(&change.entity_id) {
+                    merge_update(entity, &change.payload);
+                } else {
+                    tracing::warn!("Planned update for missing {:?} {}", change.entity_type, change.entity_id);
+                }
+            }
+            PlannedChangeType::Remove => {
+                entities.remove(&change.entity_id);
+            }
+        }
+    }
+}
+
+/// Shallow merge of an update payload; `attributes` are merged key by key
+fn merge_update(entity: &mut Value, patch: &Value) {
+    let (Value::Object(target), Value::Object(patch)) = (entity, patch) else {
+        return;
+    };
+
+    for (key, value) in patch {
+        match (key.as_str(), target.get_mut(key), value) {
+            ("id", _, _) => {}
+            ("attributes", Some(Value::Object(existing)), Value::Object(updates)) => {
+                for (attr, attr_value) in updates {
+                    existing.insert(attr.clone(), attr_value.clone());
+                }
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Classify entities as added, removed or changed between two states
+pub fn diff_entities(from: &EntityMap, to: &EntityMap) -> EntityDiff {
+    let mut diff = EntityDiff::default();
+
+    for (id, entity) in to {
+        match from.get(id) {
+            None => diff.added.push(entity.clone()),
+            Some(previous) => {
+                let changes = diff_values(Some(previous), Some(entity));
+                if changes.as_object().is_some_and(|c| !c.is_empty()) {
+                    diff.changed.push(ChangedEntity {
+                        id: *id,
+                        name: entity.get("name").and_then(Value::as_str).map(str::to_string),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    for (id, entity) in from {
+        if !to.contains_key(id) {
+            diff.removed.push(entity.clone());
+        }
+    }
+
+    diff
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize state entity: {}", e)))
+}
+
+fn enum_str<T: serde::Serialize>(value: &T) -> Result<String, AppError> {
+    match to_value(value)? {
+        Value::String(s) => Ok(s),
+        other => Err(AppError::Internal(anyhow::anyhow!("Unexpected enum value: {}", other))),
+    }
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(row: &sqlx::postgres::PgRow, column: &str) -> Result<T, AppError> {
+    let value: String = row.try_get(column)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e)))?;
+    serde_json::from_value(Value::String(value.clone()))
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalid {}: {}", column, value)))
+}
+
+fn row_to_state(row: sqlx::postgres::PgRow) -> Result<ArchitectureState, AppError> {
+    let get_err = |column: &str, e: sqlx::Error| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e));
+
+    Ok(ArchitectureState {
+        id: row.try_get("id").map_err(|e| get_err("id", e))?,
+        name: row.try_get("name").map_err(|e| get_err("name", e))?,
+        description: row.try_get("description").ok(),
+        kind: parse_enum(&row, "kind")?,
+        mode: parse_enum(&row, "mode")?,
+        base_state_id: row.try_get("base_state_id").ok(),
+        effective_date: row.try_get("effective_date").ok(),
+        card_count: row.try_get("card_count").unwrap_or(0),
+        relationship_count: row.try_get("relationship_count").unwrap_or(0),
+        change_count: row.try_get("change_count").unwrap_or(0),
+        created_by: row.try_get("created_by").ok(),
+        workspace_id: row.try_get("workspace_id").map_err(|e| get_err("workspace_id", e))?,
+        created_at: row.try_get("created_at").map_err(|e| get_err("created_at", e))?,
+        updated_at: row.try_get("updated_at").map_err(|e| get_err("updated_at", e))?,
+    })
+}
+
+fn row_to_change(row: sqlx::postgres::PgRow) -> Result<PlannedChange, AppError> {
+    let get_err = |column: &str, e: sqlx::Error| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e));
+
+    Ok(PlannedChange {
+        id: row.try_get("id").map_err(|e| get_err("id", e))?,
+        state_id: row.try_get("state_id").map_err(|e| get_err("state_id", e))?,
+        sequence: row.try_get("sequence").map_err(|e| get_err("sequence", e))?,
+        entity_type: parse_enum(&row, "entity_type")?,
+        entity_id: row.try_get("entity_id").map_err(|e| get_err("entity_id", e))?,
+        change_type: parse_enum(&row, "change_type")?,
+        payload: row.try_get("payload").map_err(|e| get_err("payload", e))?,
+        created_at: row.try_get("created_at").map_err(|e| get_err("created_at", e))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::config::CardAccess;
+    use crate::models::user::UserRole;
+    use crate::models::version::Actor;
+
+    fn change(sequence: i32, entity_id: Uuid, change_type: PlannedChangeType, payload: Value) -> PlannedChange {
+        PlannedChange {
+            id: Uuid::new_v4(),
+            state_id: Uuid::nil(),
+            sequence,
+            entity_type: StateEntityType::Card,
+            entity_id,
+            change_type,
+            payload,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_apply_changes_layers_over_base() {
+        let (crm, erp, portal) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut state = ResolvedState::default();
+        state.cards.insert(crm, json!({"id": crm, "name": "CRM", "attributes": {"hosting": "onprem", "owner": "sales"}}));
+        state.cards.insert(erp, json!({"id": erp, "name": "ERP", "attributes": {}}));
+
+        apply_changes(&mut state, &[
+            change(1, crm, PlannedChangeType::Update, json!({"attributes": {"hosting": "saas"}})),
+            change(2, erp, PlannedChangeType::Remove, json!({})),
+            change(3, portal, PlannedChangeType::Add, json!({"name": "Portal", "attributes": {}})),
+        ]);
+
+        assert_eq!(state.cards[&crm]["attributes"], json!({"hosting": "saas", "owner": "sales"}));
+        assert!(!state.cards.contains_key(&erp));
+        assert_eq!(state.cards[&portal]["id"], json!(portal.to_string()));
+    }
+
+    #[test]
+    fn test_diff_redacts_restricted_attributes_for_viewer() {
+        let policy = CardAccessPolicy::new(CardAccess::default());
+        let viewer = Actor { id: Some(Uuid::new_v4()), email: "viewer@archzero.local".to_string(), role: Some(UserRole::Viewer), workspace_id: None };
+        assert!(policy.redacts_for(&viewer));
+
+        let (crm, portal) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut from = ResolvedState::default();
+        from.cards.insert(crm, json!({"name": "CRM", "attributes": {"hosting": "onprem", "cost": 100, "contract_end_date": "2026-12-31"}}));
+        let mut to = ResolvedState::default();
+        to.cards.insert(crm, json!({"name": "CRM", "attributes": {"hosting": "saas", "cost": 250, "contract_end_date": "2027-12-31"}}));
+        to.cards.insert(portal, json!({"name": "Portal", "attributes": {"annual_cost": 9000}}));
+
+        redact_state(&mut from, &policy);
+        redact_state(&mut to, &policy);
+        let diff = diff_entities(&from.cards, &to.cards);
+
+        assert_eq!(diff.changed[0].changes, json!({"attributes.hosting": {"from": "onprem", "to": "saas"}}));
+        assert_eq!(diff.added, vec![json!({"name": "Portal", "attributes": {}})]);
+        let output = serde_json::to_string(&diff.changed).unwrap() + &serde_json::to_string(&diff.added).unwrap();
+        for key in ["cost", "annual_cost", "contract_end_date"] {
+            assert!(!output.contains(&format!("\"{}\"", key)) && !output.contains(&format!("attributes.{}", key)));
+        }
+    }
+
+    #[test]
+    fn test_diff_entities_classifies_changes() {
+        let (kept, changed, removed, added) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let from: EntityMap = [
+            (kept, json!({"name": "Kept", "attributes": {}})),
+            (changed, json!({"name": "CRM", "attributes": {"hosting": "onprem"}})),
+            (removed, json!({"name": "Legacy", "attributes": {}})),
+        ].into_iter().collect();
+        let to: EntityMap = [
+            (kept, json!({"name": "Kept", "attributes": {}})),
+            (changed, json!({"name": "CRM", "attributes": {"hosting": "saas"}})),
+            (added, json!({"name": "Portal", "attributes": {}})),
+        ].into_iter().collect();
+
+        let diff = diff_entities(&from, &to);
+
+        assert_eq!(diff.added, vec![json!({"name": "Portal", "attributes": {}})]);
+        assert_eq!(diff.removed, vec![json!({"name": "Legacy", "attributes": {}})]);
+        assert_eq!(diff.changed, vec![ChangedEntity {
+            id: changed,
+            name: Some("CRM".to_string()),
+            changes: json!({"attributes.hosting": {"from": "onprem", "to": "saas"}}),
+        }]);
+    }
+}
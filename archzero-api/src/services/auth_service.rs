@@ -1,18 +1,22 @@
 use anyhow::Result;
 use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
 use bcrypt::{hash, verify, DEFAULT_COST};
-use sqlx::{PgPool, FromRow};
+use sqlx::{PgConnection, PgPool, FromRow, Row};
 use uuid::Uuid;
 use chrono::{Utc, Duration};
 use serde::Deserialize;
 
-use crate::models::user::{User, Claims, LoginRequest, RegisterRequest, LoginResponse, UserRole};
+use crate::models::user::{User, Claims, LoginRequest, RegisterRequest, LoginResponse, UserRole, Session, SessionClient};
 use crate::error::AppError;
+use crate::services::user_service::{generate_token as generate_refresh_token, hash_token};
 
 pub struct AuthService {
     pool: PgPool,
     jwt_secret: String,
+    /// Access token lifetime in seconds
     jwt_expiration: i64,
+    refresh_expiration_days: i64,
+    password_login_enabled: bool,
 }
 
 #[derive(Debug, Deserialize, FromRow)]
@@ -21,30 +25,62 @@ struct UserRow {
     email: String,
     full_name: Option<String>,
     role: String,
-    password_hash: String,
+    /// `None` for single sign-on users
+    password_hash: Option<String>,
     failed_login_attempts: i32,
     locked_until: Option<chrono::DateTime<Utc>>,
+    #[sqlx(default)]
+    is_active: Option<bool>,
     created_at: chrono::DateTime<Utc>,
     updated_at: chrono::DateTime<Utc>,
 }
 
+impl UserRow {
+    fn into_user(self) -> User {
+        User {
+            id: self.id,
+            email: self.email,
+            full_name: self.full_name,
+            role: UserRole::parse(&self.role).unwrap_or(UserRole::Viewer),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
 impl AuthService {
-    pub fn new(pool: PgPool, jwt_secret: String, jwt_expiration: i64) -> Self {
+    pub fn new(pool: PgPool, jwt_secret: String, jwt_expiration: i64, refresh_expiration_days: i64) -> Self {
         Self {
             pool,
             jwt_secret,
             jwt_expiration,
+            refresh_expiration_days,
+            password_login_enabled: true,
         }
     }
 
+    /// Allow or refuse email/password login (single sign-on only when disabled)
+    pub fn with_password_login(mut self, enabled: bool) -> Self {
+        self.password_login_enabled = enabled;
+        self
+    }
+
+    pub fn password_login_enabled(&self) -> bool {
+        self.password_login_enabled
+    }
+
     pub fn get_pool(&self) -> &PgPool {
         &self.pool
     }
 
-    pub async fn login(&self, req: LoginRequest) -> Result<LoginResponse, AppError> {
+    pub async fn login(&self, req: LoginRequest, client: SessionClient) -> Result<LoginResponse, AppError> {
+        if !self.password_login_enabled {
+            return Err(AppError::Forbidden("Password login is disabled; sign in with single sign-on".to_string()));
+        }
+
         // Fetch user from database
         let user_row = sqlx::query_as::<_, UserRow>(
-            "SELECT id, email, full_name, role, password_hash, failed_login_attempts, locked_until, created_at, updated_at
+            "SELECT id, email, full_name, role, password_hash, failed_login_attempts, locked_until, is_active, created_at, updated_at
              FROM users WHERE email = $1"
         )
         .bind(&req.email)
@@ -53,6 +89,9 @@ impl AuthService {
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
 
         let user_row = user_row.ok_or(AppError::Auth("Invalid credentials".to_string()))?;
+        // Single sign-on users have no password to check
+        let password_hash = user_row.password_hash.clone()
+            .ok_or(AppError::Auth("Invalid credentials".to_string()))?;
 
         // Check if account is locked
         if let Some(locked_until) = user_row.locked_until {
@@ -68,7 +107,7 @@ impl AuthService {
         }
 
         // Verify password
-        let is_valid = verify(&req.password, &user_row.password_hash)
+        let is_valid = verify(&req.password, &password_hash)
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Password verification error: {}", e)))?;
 
         if !is_valid {
@@ -99,6 +138,11 @@ impl AuthService {
             return Err(AppError::Auth("Invalid credentials".to_string()));
         }
 
+        // Deactivated accounts keep their row but cannot log in
+        if user_row.is_active == Some(false) {
+            return Err(AppError::Auth("Account deactivated".to_string()));
+        }
+
         // Reset failed login attempts on successful login
         sqlx::query("UPDATE users SET failed_login_attempts = 0, locked_until = NULL WHERE id = $1")
             .bind(&user_row.id)
@@ -106,71 +150,240 @@ impl AuthService {
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?;
 
-        // Parse role from string (database uses lowercase)
-        let role = match user_row.role.as_str() {
-            "admin" => UserRole::Admin,
-            "arbchair" => UserRole::ArbChair,
-            "arbmember" => UserRole::ArbMember,
-            "architect" => UserRole::Architect,
-            "editor" => UserRole::Editor,
-            "viewer" => UserRole::Viewer,
-            _ => UserRole::Viewer,
-        };
+        self.start_session(user_row.into_user(), &client).await
+    }
+
+    /// Start a server-side session for an authenticated user and issue the first token pair
+    pub async fn start_session(&self, user: User, client: &SessionClient) -> Result<LoginResponse, AppError> {
+        let refresh_token = generate_refresh_token();
+        let session_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO user_sessions (user_id, refresh_token_hash, user_agent, ip_address, expires_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id"
+        )
+        .bind(user.id)
+        .bind(hash_token(&refresh_token))
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
+        .bind(Utc::now() + Duration::days(self.refresh_expiration_days))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create session: {}", e)))?;
+
+        self.token_response(user, session_id, refresh_token)
+    }
 
-        // Create user object
-        let user = User {
-            id: user_row.id,
-            email: user_row.email,
-            full_name: user_row.full_name,
-            role,
-            created_at: user_row.created_at,
-            updated_at: user_row.updated_at,
+    /// Exchange a refresh token for a new token pair. Refresh tokens are single-use: each call
+    /// rotates the session's token, and replaying a rotated-out token revokes the whole session.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<LoginResponse, AppError> {
+        let token_hash = hash_token(refresh_token);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let session = sqlx::query(
+            "SELECT s.id, s.user_id FROM user_sessions s
+             JOIN users u ON u.id = s.user_id
+             WHERE s.refresh_token_hash = $1 AND s.revoked_at IS NULL AND s.expires_at > NOW() AND u.is_active
+             FOR UPDATE OF s"
+        )
+        .bind(&token_hash)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch session: {}", e)))?;
+
+        let Some(session) = session else {
+            drop(tx);
+            // A replayed, already-rotated token means it leaked: end the session for everyone holding it
+            let reused: Option<Uuid> = sqlx::query_scalar(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE previous_token_hash = $1 AND revoked_at IS NULL
+                 RETURNING id"
+            )
+            .bind(&token_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke session: {}", e)))?;
+
+            if let Some(session_id) = reused {
+                tracing::warn!("Refresh token reuse detected, revoked session {}", session_id);
+            }
+            return Err(AppError::Auth("Invalid refresh token".to_string()));
         };
 
-        // Generate JWT token
-        let token = self.generate_token(&user)?;
+        let session_id: Uuid = session.try_get("id")
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing id: {}", e)))?;
+        let user_id: Uuid = session.try_get("user_id")
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing user_id: {}", e)))?;
+
+        let new_token = generate_refresh_token();
+        sqlx::query(
+            "UPDATE user_sessions
+             SET previous_token_hash = refresh_token_hash, refresh_token_hash = $2, last_used_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(session_id)
+        .bind(hash_token(&new_token))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to rotate refresh token: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        let user = self.get_user(user_id).await?;
+        self.token_response(user, session_id, new_token)
+    }
+
+    fn token_response(&self, user: User, session_id: Uuid, refresh_token: String) -> Result<LoginResponse, AppError> {
+        let token = self.generate_token(&user, Some(session_id))?;
+        Ok(LoginResponse {
+            token,
+            refresh_token,
+            expires_in: self.jwt_expiration,
+            user,
+        })
+    }
+
+    /// Whether access tokens of this session are still accepted
+    pub async fn session_active(&self, session_id: Uuid) -> Result<bool, AppError> {
+        let active: Option<bool> = sqlx::query_scalar(
+            "SELECT TRUE FROM user_sessions s
+             JOIN users u ON u.id = s.user_id
+             WHERE s.id = $1 AND s.revoked_at IS NULL AND s.expires_at > NOW() AND u.is_active"
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check session: {}", e)))?;
+
+        Ok(active.is_some())
+    }
+
+    /// Active sessions of a user, most recently used first
+    pub async fn list_sessions(&self, user_id: Uuid, current: Option<Uuid>) -> Result<Vec<Session>, AppError> {
+        let rows = sqlx::query(
+            "SELECT id, user_agent, ip_address, created_at, last_used_at, expires_at
+             FROM user_sessions
+             WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+             ORDER BY last_used_at DESC"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list sessions: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id: Uuid = row.try_get("id")
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing id: {}", e)))?;
+                Ok(Session {
+                    id,
+                    user_agent: row.try_get("user_agent").ok().flatten(),
+                    ip_address: row.try_get("ip_address").ok().flatten(),
+                    current: current == Some(id),
+                    created_at: row.try_get("created_at")
+                        .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing created_at: {}", e)))?,
+                    last_used_at: row.try_get("last_used_at")
+                        .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing last_used_at: {}", e)))?,
+                    expires_at: row.try_get("expires_at")
+                        .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing expires_at: {}", e)))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Revoke one of the user's own sessions
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL"
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke session: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Session not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn revoke_other_sessions(&self, user_id: Uuid, keep: Option<Uuid>) -> Result<u64, AppError> {
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to acquire connection: {}", e)))?;
+        Self::revoke_user_sessions(&mut conn, user_id, keep).await
+    }
+
+    /// Revoke every active session of a user except `keep`; returns how many were revoked
+    pub async fn revoke_user_sessions(conn: &mut PgConnection, user_id: Uuid, keep: Option<Uuid>) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = NOW()
+             WHERE user_id = $1 AND revoked_at IS NULL AND ($2::uuid IS NULL OR id <> $2)"
+        )
+        .bind(user_id)
+        .bind(keep)
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke sessions: {}", e)))?;
 
-        Ok(LoginResponse { token, user })
+        Ok(result.rows_affected())
     }
 
+    /// Create a user account. Password policy is enforced by the caller (`UserService`).
     pub async fn register(&self, req: RegisterRequest) -> Result<User, AppError> {
-        // Hash password
-        let _password_hash = hash(&req.password, DEFAULT_COST)
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Password hash failed: {}", e)))?;
+        let password_hash = Self::hash_password(&req.password)?;
 
-        // Insert user into database
-        let user_id = Uuid::new_v4();
-        let now = Utc::now();
+        let row: (Uuid, chrono::DateTime<Utc>, chrono::DateTime<Utc>) = sqlx::query_as(
+            "INSERT INTO users (email, password_hash, full_name, role, failed_login_attempts, password_changed_at)
+             VALUES ($1, $2, $3, $4, 0, NOW())
+             RETURNING id, created_at, updated_at"
+        )
+        .bind(&req.email)
+        .bind(&password_hash)
+        .bind(&req.full_name)
+        .bind(req.role.as_str())
+        .fetch_one(&self.pool)
+        .await?;
 
-        // TODO: This will fail until users table is created in migration
-        let user = User {
-            id: user_id,
-            email: req.email.clone(),
+        Ok(User {
+            id: row.0,
+            email: req.email,
             full_name: req.full_name,
             role: req.role,
-            created_at: now,
-            updated_at: now,
-        };
+            created_at: row.1,
+            updated_at: row.2,
+        })
+    }
 
-        Ok(user)
+    pub fn hash_password(password: &str) -> Result<String, AppError> {
+        hash(password, DEFAULT_COST)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Password hash failed: {}", e)))
+    }
+
+    pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, AppError> {
+        verify(password, password_hash)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Password verification error: {}", e)))
     }
 
     pub async fn get_user(&self, user_id: Uuid) -> Result<User, AppError> {
-        // TODO: Implement when users table exists
-        // For now, return a dummy user
-        Ok(User {
-            id: user_id,
-            email: "user@example.com".to_string(),
-            full_name: None,
-            role: UserRole::Viewer,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        })
+        let user_row = sqlx::query_as::<_, UserRow>(
+            "SELECT id, email, full_name, role, password_hash, failed_login_attempts, locked_until, is_active, created_at, updated_at
+             FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(user_row.into_user())
     }
 
-    pub fn generate_token(&self, user: &User) -> Result<String, AppError> {
+    pub fn generate_token(&self, user: &User, session_id: Option<Uuid>) -> Result<String, AppError> {
         let expiration = Utc::now()
-            .checked_add_signed(Duration::hours(self.jwt_expiration))
+            .checked_add_signed(Duration::seconds(self.jwt_expiration))
             .expect("valid timestamp")
             .timestamp();
 
@@ -179,6 +392,10 @@ impl AuthService {
             email: user.email.clone(),
             role: user.role.clone(),
             exp: expiration as usize,
+            sid: session_id,
+            scopes: None,
+            workspace_id: None,
+            workspace_role: None,
         };
 
         encode(
@@ -114,6 +114,15 @@ impl CachedCardService {
         if let Some(ref q) = params.q {
             map.insert("q", q.clone());
         }
+        if let Some(ref query) = params.query {
+            map.insert("query", query.clone());
+        }
+        if let Some(ref sort) = params.sort {
+            map.insert("sort", sort.clone());
+        }
+        if let Some(ref cursor) = params.cursor {
+            map.insert("cursor", cursor.clone());
+        }
         if let Some(ref card_type) = params.card_type {
             if let Ok(type_str) = serde_json::to_string(card_type) {
                 map.insert("card_type", type_str);
@@ -149,6 +158,10 @@ mod tests {
             q: Some("test".to_string()),
             card_type: None,
             lifecycle_phase: None,
+            tags: None,
+            query: None,
+            sort: None,
+            cursor: None,
         };
 
         let params2 = CardSearchParams {
@@ -157,6 +170,10 @@ mod tests {
             q: Some("test".to_string()),
             card_type: None,
             lifecycle_phase: None,
+            tags: None,
+            query: None,
+            sort: None,
+            cursor: None,
         };
 
         // Same params should generate same key
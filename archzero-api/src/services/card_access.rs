@@ -0,0 +1,198 @@
+/*!
+ * Card Access Rules
+ *
+ * Fine-grained rules on top of role permissions, applied by `CardService` so every
+ * handler built on cards (policies, risks, initiatives, ARB, ...) inherits them:
+ * - cards with an owner or team can only be changed by that owner, team members or admins
+ * - governance card types can only be changed by governance roles
+ * - restricted attribute keys are removed from cards read by redacted roles
+ *
+ * The caller is the request actor set by `auth_middleware`; background jobs run
+ * without one and are not restricted.
+ */
+
+use serde_json::Value;
+
+use crate::config::CardAccess;
+use crate::error::AppError;
+use crate::models::card::{Card, CardType};
+use crate::models::user::UserRole;
+use crate::models::version::{Actor, CardVersion};
+
+pub struct CardAccessPolicy {
+    rules: CardAccess,
+}
+
+impl CardAccessPolicy {
+    pub fn new(rules: CardAccess) -> Self {
+        Self { rules }
+    }
+
+    /// Whether cards read by `actor` have restricted attributes removed
+    pub fn redacts_for(&self, actor: &Actor) -> bool {
+        match &actor.role {
+            Some(role) => self.rules.redacted_roles.contains(role),
+            None => true,
+        }
+    }
+
+    /// Attribute keys removed from cards read by `actor`; none if it is not redacted
+    pub fn restricted_for(&self, actor: &Actor) -> &[String] {
+        if self.redacts_for(actor) {
+            &self.rules.restricted_attributes
+        } else {
+            &[]
+        }
+    }
+
+    /// Remove restricted attribute keys from a card
+    pub fn redact(&self, card: &mut Card) {
+        self.redact_attributes(&mut card.attributes);
+    }
+
+    /// Remove restricted keys from an attributes object
+    pub fn redact_attributes(&self, attributes: &mut Value) {
+        if let Some(attributes) = attributes.as_object_mut() {
+            for key in &self.rules.restricted_attributes {
+                attributes.remove(key);
+            }
+        }
+    }
+
+    /// Remove restricted attribute keys from a version snapshot and its diff
+    pub fn redact_version(&self, version: &mut CardVersion) {
+        if let Some(attributes) = version.snapshot.get_mut("attributes").and_then(Value::as_object_mut) {
+            for key in &self.rules.restricted_attributes {
+                attributes.remove(key);
+            }
+        }
+        if let Some(diff) = version.diff.as_object_mut() {
+            for key in &self.rules.restricted_attributes {
+                diff.remove(&format!("attributes.{}", key));
+            }
+        }
+    }
+
+    /// Keep the stored values of restricted keys when a redacted caller replaces attributes,
+    /// so writing back a redacted card does not erase what the caller could not see
+    pub fn preserve_restricted(&self, before: &Value, after: &mut Value) {
+        let (Some(before), Some(after)) = (before.as_object(), after.as_object_mut()) else {
+            return;
+        };
+        for key in &self.rules.restricted_attributes {
+            match before.get(key) {
+                Some(value) => {
+                    after.insert(key.clone(), value.clone());
+                }
+                None => {
+                    after.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Governance card types can only be created, changed or deleted by governance roles
+    pub fn check_card_type(&self, actor: &Actor, card_type: &CardType) -> Result<(), AppError> {
+        if !self.rules.governance_card_types.contains(card_type) {
+            return Ok(());
+        }
+        match &actor.role {
+            Some(role) if self.rules.governance_roles.contains(role) => Ok(()),
+            _ => Err(AppError::Forbidden(format!(
+                "{:?} cards can only be changed by governance roles",
+                card_type
+            ))),
+        }
+    }
+
+    /// Owned cards can only be changed by their owner, members of their team or admins.
+    /// Cards without an owner or team are open to anyone with write permission.
+    pub fn check_ownership(&self, actor: &Actor, card: &Card, is_team_member: bool) -> Result<(), AppError> {
+        if actor.role == Some(UserRole::Admin) || (card.owner_id.is_none() && card.team_id.is_none()) {
+            return Ok(());
+        }
+        if (actor.id.is_some() && actor.id == card.owner_id) || is_team_member {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(format!(
+            "Card {} can only be changed by its owner or team",
+            card.id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::models::card::LifecyclePhase;
+
+    fn actor(role: UserRole) -> Actor {
+        Actor {
+            id: Some(Uuid::new_v4()),
+            email: "user@archzero.local".to_string(),
+            role: Some(role),
+            workspace_id: None,
+        }
+    }
+
+    fn card(card_type: CardType, owner_id: Option<Uuid>) -> Card {
+        Card {
+            id: Uuid::new_v4(),
+            name: "Billing".to_string(),
+            card_type,
+            lifecycle_phase: LifecyclePhase::Active,
+            quality_score: None,
+            description: None,
+            owner_id,
+            team_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            attributes: json!({"cost": 1200, "annual_cost": 14400, "hosting": "aws"}),
+            tags: vec![],
+            status: "active".to_string(),
+            workspace_id: None,
+            metamodel_version: None,
+        }
+    }
+
+    #[test]
+    fn test_redaction_removes_and_preserves_restricted_keys() {
+        let policy = CardAccessPolicy::new(CardAccess::default());
+        assert!(policy.redacts_for(&actor(UserRole::Viewer)));
+        assert!(!policy.redacts_for(&actor(UserRole::Editor)));
+
+        let mut redacted = card(CardType::Application, None);
+        policy.redact(&mut redacted);
+        assert_eq!(redacted.attributes, json!({"hosting": "aws"}));
+
+        let stored = card(CardType::Application, None).attributes;
+        let mut written = json!({"hosting": "azure", "cost": 0});
+        policy.preserve_restricted(&stored, &mut written);
+        assert_eq!(written, json!({"hosting": "azure", "cost": 1200, "annual_cost": 14400}));
+    }
+
+    #[test]
+    fn test_ownership_and_governance_rules() {
+        let policy = CardAccessPolicy::new(CardAccess::default());
+        let owner = actor(UserRole::Editor);
+        let other = actor(UserRole::Editor);
+        let owned = card(CardType::Application, owner.id);
+
+        assert!(policy.check_ownership(&owner, &owned, false).is_ok());
+        assert!(policy.check_ownership(&other, &owned, true).is_ok());
+        assert!(matches!(policy.check_ownership(&other, &owned, false), Err(AppError::Forbidden(_))));
+        assert!(policy.check_ownership(&actor(UserRole::Admin), &owned, false).is_ok());
+        assert!(policy.check_ownership(&other, &card(CardType::Application, None), false).is_ok());
+
+        assert!(policy.check_card_type(&owner, &CardType::Application).is_ok());
+        assert!(matches!(
+            policy.check_card_type(&owner, &CardType::ArchitecturePolicy),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(policy.check_card_type(&actor(UserRole::ArbChair), &CardType::ArchitecturePolicy).is_ok());
+    }
+}
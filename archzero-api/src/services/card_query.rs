@@ -0,0 +1,860 @@
+/*!
+ * Card Query Language
+ *
+ * Parses the text syntax of boolean card queries into the `CardQuery` AST and compiles
+ * the AST into a parameterised SQL condition on `cards`. Values are always bound as
+ * parameters; field names and attribute paths are checked against a whitelist.
+ *
+ * Text syntax:
+ *
+ * ```text
+ * type = Application AND NOT lifecycle_phase in (retired, phaseOut)
+ * attributes.hosting_type starts_with "Cloud" OR quality_score between 50 and 80
+ * created >= 2024-01-01 AND outgoing(reliesOn, type = Platform AND tags contains legacy)
+ * ```
+ *
+ * `outgoing(type, query)`, `incoming(type, query)` and `related(type, query)` match cards
+ * with a relationship to a card matching `query`; `*` stands for any relationship type
+ * and the query may be left out.
+ */
+
+use chrono::NaiveDate;
+use sqlx::postgres::{PgArguments, Postgres};
+use sqlx::query::Query;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card_query::*;
+use crate::models::relationship::RelationshipType;
+use crate::services::pagination::{parse_sort, SortField, SortKey, SortKind};
+
+/// Deepest nesting of AND/OR/NOT and relationship predicates
+const MAX_DEPTH: usize = 16;
+/// Most conditions and relationship predicates in one query
+const MAX_PREDICATES: usize = 64;
+
+fn invalid(message: impl std::fmt::Display) -> AppError {
+    AppError::Validation(format!("Invalid query: {}", message))
+}
+
+// ---------------------------------------------------------------------------
+// Text syntax
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Comma,
+    /// `=`, `!=`, `>`, `>=`, `<`, `<=`
+    Symbol(&'static str),
+    Word(String),
+    Quoted(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, AppError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '=' => Token::Symbol("="),
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                i += 1;
+                Token::Symbol("!=")
+            }
+            '>' | '<' => {
+                let equals = chars.get(i + 1) == Some(&'=');
+                let symbol = match (chars[i], equals) {
+                    ('>', true) => ">=",
+                    ('>', false) => ">",
+                    ('<', true) => "<=",
+                    _ => "<",
+                };
+                if equals {
+                    i += 1;
+                }
+                Token::Symbol(symbol)
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(invalid(format!("unterminated string at position {}", start + 1))),
+                        Some('"') => break,
+                        Some('\\') if i + 1 < chars.len() => {
+                            value.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Quoted(value)
+            }
+            _ => {
+                while i < chars.len() && !chars[i].is_whitespace() && !"(),=!<>\"".contains(chars[i]) {
+                    i += 1;
+                }
+                if i == start {
+                    return Err(invalid(format!("unexpected '{}' at position {}", chars[i], start + 1)));
+                }
+                tokens.push((start, Token::Word(chars[start..i].iter().collect())));
+                continue;
+            }
+        };
+        tokens.push((start, token));
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(offset, _)| *offset).unwrap_or(self.end) + 1
+    }
+
+    fn error(&self, expected: &str) -> AppError {
+        match self.peek() {
+            Some(_) => invalid(format!("expected {} at position {}", expected, self.position())),
+            None => invalid(format!("expected {} at end of query", expected)),
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, token)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), AppError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Consume a case-insensitive keyword
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<CardQuery, AppError> {
+        let mut terms = vec![self.and()?];
+        while self.keyword("or") {
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { CardQuery::Or(terms) })
+    }
+
+    fn and(&mut self) -> Result<CardQuery, AppError> {
+        let mut terms = vec![self.unary()?];
+        while self.keyword("and") {
+            terms.push(self.unary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { CardQuery::And(terms) })
+    }
+
+    fn unary(&mut self) -> Result<CardQuery, AppError> {
+        if self.keyword("not") {
+            return Ok(CardQuery::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let query = self.or()?;
+            self.expect(Token::RParen, "')'")?;
+            return Ok(query);
+        }
+
+        let field = match self.next() {
+            Some(Token::Word(word)) => word,
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("a field, NOT or '('"));
+            }
+        };
+        let direction = match field.to_ascii_lowercase().as_str() {
+            "outgoing" => Some(RelationshipDirection::Outgoing),
+            "incoming" => Some(RelationshipDirection::Incoming),
+            "related" => Some(RelationshipDirection::Any),
+            _ => None,
+        };
+        match direction {
+            Some(direction) if self.peek() == Some(&Token::LParen) => self.relationship(direction),
+            _ => self.condition(field),
+        }
+    }
+
+    fn relationship(&mut self, direction: RelationshipDirection) -> Result<CardQuery, AppError> {
+        self.expect(Token::LParen, "'('")?;
+        let relationship_type = match self.next() {
+            Some(Token::Word(word)) if word == "*" => None,
+            Some(Token::Word(word)) => Some(
+                serde_json::from_value::<RelationshipType>(serde_json::Value::String(word.clone()))
+                    .map_err(|_| invalid(format!("unknown relationship type '{}'", word)))?,
+            ),
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("a relationship type or '*'"));
+            }
+        };
+        let card = if self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            Some(Box::new(self.or()?))
+        } else {
+            None
+        };
+        self.expect(Token::RParen, "')'")?;
+
+        Ok(CardQuery::Related(RelationshipPredicate { relationship_type, direction, card }))
+    }
+
+    fn condition(&mut self, field: String) -> Result<CardQuery, AppError> {
+        let (op, negate) = match self.next() {
+            Some(Token::Symbol("=")) => (QueryOperator::Equals, false),
+            Some(Token::Symbol("!=")) => (QueryOperator::Equals, true),
+            Some(Token::Symbol(">")) => (QueryOperator::Gt, false),
+            Some(Token::Symbol(">=")) => (QueryOperator::Gte, false),
+            Some(Token::Symbol("<")) => (QueryOperator::Lt, false),
+            Some(Token::Symbol("<=")) => (QueryOperator::Lte, false),
+            Some(Token::Word(word)) => match word.to_ascii_lowercase().as_str() {
+                "contains" => (QueryOperator::Contains, false),
+                "starts_with" => (QueryOperator::StartsWith, false),
+                "in" => (QueryOperator::In, false),
+                "between" => (QueryOperator::Between, false),
+                "exists" => (QueryOperator::Exists, false),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("an operator"));
+                }
+            },
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("an operator"));
+            }
+        };
+
+        let value = match op {
+            QueryOperator::Exists => None,
+            QueryOperator::In => {
+                self.expect(Token::LParen, "'('")?;
+                let mut values = vec![self.value()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                    values.push(self.value()?);
+                }
+                self.expect(Token::RParen, "')'")?;
+                Some(serde_json::Value::Array(values))
+            }
+            QueryOperator::Between => {
+                let low = self.value()?;
+                if !self.keyword("and") {
+                    return Err(self.error("AND"));
+                }
+                Some(serde_json::Value::Array(vec![low, self.value()?]))
+            }
+            _ => Some(self.value()?),
+        };
+
+        let condition = CardQuery::Condition(QueryCondition { field, op, value });
+        Ok(if negate { CardQuery::Not(Box::new(condition)) } else { condition })
+    }
+
+    /// A quoted string stays a string; bare numbers and booleans become JSON numbers and booleans
+    fn value(&mut self) -> Result<serde_json::Value, AppError> {
+        match self.next() {
+            Some(Token::Quoted(value)) => Ok(serde_json::Value::String(value)),
+            Some(Token::Word(word)) => Ok(match word.as_str() {
+                "true" => serde_json::Value::Bool(true),
+                "false" => serde_json::Value::Bool(false),
+                _ => word.parse::<i64>().map(serde_json::Value::from)
+                    .or_else(|_| word.parse::<f64>().map(serde_json::Value::from))
+                    .unwrap_or(serde_json::Value::String(word)),
+            }),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("a value"))
+            }
+        }
+    }
+}
+
+/// Parse the text syntax into a query AST
+pub fn parse_card_query(input: &str) -> Result<CardQuery, AppError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(invalid("query is empty"));
+    }
+    let mut parser = Parser { tokens, pos: 0, end: input.chars().count() };
+    let query = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("AND, OR or end of query"));
+    }
+    Ok(query)
+}
+
+// ---------------------------------------------------------------------------
+// SQL compilation
+// ---------------------------------------------------------------------------
+
+/// A bind parameter of a compiled query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParam {
+    Text(String),
+    TextArray(Vec<String>),
+    Number(f64),
+    NumberArray(Vec<f64>),
+    Uuid(Uuid),
+    UuidArray(Vec<Uuid>),
+    Date(NaiveDate),
+}
+
+impl QueryParam {
+    pub fn bind<'q>(&self, query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        match self.clone() {
+            QueryParam::Text(value) => query.bind(value),
+            QueryParam::TextArray(values) => query.bind(values),
+            QueryParam::Number(value) => query.bind(value),
+            QueryParam::NumberArray(values) => query.bind(values),
+            QueryParam::Uuid(value) => query.bind(value),
+            QueryParam::UuidArray(values) => query.bind(values),
+            QueryParam::Date(value) => query.bind(value),
+        }
+    }
+}
+
+/// A SQL condition on the `cards` table and the parameters it binds, numbered from `first_param`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledQuery {
+    pub sql: String,
+    pub params: Vec<QueryParam>,
+}
+
+/// Compile a query into a condition on `cards`. Related cards must be active and, when
+/// `workspace` is set, belong to or be shared into that workspace. Conditions on the
+/// `restricted` attribute keys are refused, so callers who get them redacted cannot
+/// probe their values through filters.
+pub fn compile_card_query(
+    query: &CardQuery,
+    first_param: usize,
+    workspace: Option<Uuid>,
+    restricted: &[String],
+) -> Result<CompiledQuery, AppError> {
+    let mut compiler = Compiler { params: Vec::new(), first_param, workspace, restricted, aliases: 0, predicates: 0 };
+    let sql = compiler.node(query, "cards", None, 0)?;
+    Ok(CompiledQuery { sql, params: compiler.params })
+}
+
+/// How a field is stored, which decides the operators it supports
+enum Column {
+    Text(String),
+    Number(String),
+    Uuid(String),
+    /// Timestamp column compared by UTC date
+    Date(String),
+    /// ISO date stored as text (relationship validity)
+    DateText(String),
+    Tags(String),
+    Attribute { column: String, path: Vec<String> },
+}
+
+struct Compiler<'a> {
+    params: Vec<QueryParam>,
+    first_param: usize,
+    workspace: Option<Uuid>,
+    restricted: &'a [String],
+    aliases: usize,
+    predicates: usize,
+}
+
+impl Compiler<'_> {
+    fn param(&mut self, param: QueryParam) -> String {
+        self.params.push(param);
+        format!("${}", self.first_param + self.params.len() - 1)
+    }
+
+    fn node(&mut self, query: &CardQuery, card: &str, relationship: Option<&str>, depth: usize) -> Result<String, AppError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid(format!("nested deeper than {} levels", MAX_DEPTH)));
+        }
+        match query {
+            CardQuery::And(terms) | CardQuery::Or(terms) if terms.is_empty() => {
+                Ok(if matches!(query, CardQuery::And(_)) { "TRUE" } else { "FALSE" }.to_string())
+            }
+            CardQuery::And(terms) | CardQuery::Or(terms) => {
+                let joiner = if matches!(query, CardQuery::And(_)) { " AND " } else { " OR " };
+                let parts = terms.iter()
+                    .map(|term| self.node(term, card, relationship, depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("({})", parts.join(joiner)))
+            }
+            CardQuery::Not(term) => Ok(format!("NOT ({})", self.node(term, card, relationship, depth + 1)?)),
+            CardQuery::Condition(condition) => {
+                self.count_predicate()?;
+                self.condition(condition, card, relationship)
+            }
+            CardQuery::Related(predicate) => {
+                self.count_predicate()?;
+                self.related(predicate, card, depth)
+            }
+        }
+    }
+
+    fn count_predicate(&mut self) -> Result<(), AppError> {
+        self.predicates += 1;
+        if self.predicates > MAX_PREDICATES {
+            return Err(invalid(format!("more than {} conditions", MAX_PREDICATES)));
+        }
+        Ok(())
+    }
+
+    fn related(&mut self, predicate: &RelationshipPredicate, card: &str, depth: usize) -> Result<String, AppError> {
+        self.aliases += 1;
+        let r = format!("r{}", self.aliases);
+        let c = format!("c{}", self.aliases);
+
+        let (join, anchor) = match predicate.direction {
+            RelationshipDirection::Outgoing => (format!("{c}.id = {r}.to_card_id"), format!("{r}.from_card_id = {card}.id")),
+            RelationshipDirection::Incoming => (format!("{c}.id = {r}.from_card_id"), format!("{r}.to_card_id = {card}.id")),
+            RelationshipDirection::Any => (
+                format!("{c}.id = CASE WHEN {r}.from_card_id = {card}.id THEN {r}.to_card_id ELSE {r}.from_card_id END"),
+                format!("({r}.from_card_id = {card}.id OR {r}.to_card_id = {card}.id)"),
+            ),
+        };
+
+        let mut conditions = vec![anchor, format!("{c}.status = 'active'")];
+        if let Some(relationship_type) = &predicate.relationship_type {
+            let name = serde_json::to_value(relationship_type)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default();
+            conditions.push(format!("{r}.relationship_type = {}", self.param(QueryParam::Text(name))));
+        }
+        if let Some(workspace) = self.workspace {
+            let p = self.param(QueryParam::Uuid(workspace));
+            conditions.push(format!(
+                "({c}.workspace_id = {p} OR {c}.id IN (SELECT card_id FROM card_shares WHERE workspace_id = {p}))"
+            ));
+        }
+        if !predicate.card.as_deref().is_some_and(mentions_validity) {
+            conditions.push(format!(
+                "({r}.valid_from <= to_char(CURRENT_DATE, 'YYYY-MM-DD') AND ({r}.valid_to IS NULL OR {r}.valid_to >= to_char(CURRENT_DATE, 'YYYY-MM-DD')))"
+            ));
+        }
+        if let Some(related) = &predicate.card {
+            conditions.push(self.node(related, &c, Some(&r), depth + 1)?);
+        }
+
+        Ok(format!(
+            "EXISTS (SELECT 1 FROM relationships {r} JOIN cards {c} ON {join} WHERE {})",
+            conditions.join(" AND ")
+        ))
+    }
+
+    fn column(&self, field: &str, card: &str, relationship: Option<&str>) -> Result<Column, AppError> {
+        let column = match field {
+            "name" | "description" | "type" | "lifecycle_phase" | "metamodel_version" => Column::Text(format!("{card}.{field}")),
+            "quality_score" => Column::Number(format!("{card}.quality_score")),
+            "id" | "owner_id" | "team_id" => Column::Uuid(format!("{card}.{field}")),
+            "created" | "created_at" => Column::Date(format!("({card}.created_at AT TIME ZONE 'UTC')::date")),
+            "updated" | "updated_at" => Column::Date(format!("({card}.updated_at AT TIME ZONE 'UTC')::date")),
+            "tags" => Column::Tags(format!("{card}.tags")),
+            "relationship.valid_from" | "relationship.valid_to" => {
+                let relationship = relationship
+                    .ok_or_else(|| invalid(format!("{} is only allowed inside a relationship predicate", field)))?;
+                Column::DateText(format!("{relationship}.{}", &field["relationship.".len()..]))
+            }
+            _ => match field.strip_prefix("attributes.") {
+                Some(path) => {
+                    let path: Vec<String> = path.split('.').map(str::to_string).collect();
+                    let valid = |segment: &String| {
+                        !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    };
+                    if !path.iter().all(valid) {
+                        return Err(invalid(format!("invalid attribute path '{}'", field)));
+                    }
+                    if self.restricted.contains(&path[0]) {
+                        return Err(AppError::Forbidden(format!("Attribute '{}' is restricted", path[0])));
+                    }
+                    Column::Attribute { column: format!("{card}.attributes"), path }
+                }
+                None => return Err(invalid(format!("unknown field '{}'", field))),
+            },
+        };
+        Ok(column)
+    }
+
+    fn condition(&mut self, condition: &QueryCondition, card: &str, relationship: Option<&str>) -> Result<String, AppError> {
+        let field = condition.field.as_str();
+        let op = condition.op;
+        let unsupported = || invalid(format!("'{}' is not supported for {}", op.as_str(), field));
+        let value = || condition.value.as_ref().ok_or_else(|| invalid(format!("{} {} needs a value", field, op.as_str())));
+
+        let sql = match self.column(field, card, relationship)? {
+            Column::Text(column) => match op {
+                QueryOperator::Exists => format!("({column} IS NOT NULL AND {column} <> '')"),
+                QueryOperator::Contains => format!("{column} ILIKE {}", self.param(QueryParam::Text(format!("%{}%", escape_like(&text(field, value()?)?))))),
+                QueryOperator::StartsWith => format!("{column} ILIKE {}", self.param(QueryParam::Text(format!("{}%", escape_like(&text(field, value()?)?))))),
+                QueryOperator::In => format!("{column} = ANY({})", self.param(QueryParam::TextArray(texts(field, value()?)?))),
+                QueryOperator::Between => {
+                    let (low, high) = pair(field, value()?)?;
+                    let low = self.param(QueryParam::Text(text(field, &low)?));
+                    let high = self.param(QueryParam::Text(text(field, &high)?));
+                    format!("{column} BETWEEN {low} AND {high}")
+                }
+                _ => format!("{column} {} {}", comparison(op), self.param(QueryParam::Text(text(field, value()?)?))),
+            },
+            Column::Number(column) => match op {
+                QueryOperator::Exists => format!("{column} IS NOT NULL"),
+                QueryOperator::Contains | QueryOperator::StartsWith => return Err(unsupported()),
+                QueryOperator::In => {
+                    let values = list(field, value()?)?.iter().map(|value| number(field, value)).collect::<Result<_, _>>()?;
+                    format!("{column} = ANY({})", self.param(QueryParam::NumberArray(values)))
+                }
+                QueryOperator::Between => {
+                    let (low, high) = pair(field, value()?)?;
+                    let low = self.param(QueryParam::Number(number(field, &low)?));
+                    let high = self.param(QueryParam::Number(number(field, &high)?));
+                    format!("{column} BETWEEN {low} AND {high}")
+                }
+                _ => format!("{column} {} {}", comparison(op), self.param(QueryParam::Number(number(field, value()?)?))),
+            },
+            Column::Uuid(column) => match op {
+                QueryOperator::Exists => format!("{column} IS NOT NULL"),
+                QueryOperator::Equals => format!("{column} = {}", self.param(QueryParam::Uuid(uuid(field, value()?)?))),
+                QueryOperator::In => {
+                    let values = list(field, value()?)?.iter().map(|value| uuid(field, value)).collect::<Result<_, _>>()?;
+                    format!("{column} = ANY({})", self.param(QueryParam::UuidArray(values)))
+                }
+                _ => return Err(unsupported()),
+            },
+            Column::Date(column) => match op {
+                QueryOperator::Exists => format!("{column} IS NOT NULL"),
+                QueryOperator::Contains | QueryOperator::StartsWith | QueryOperator::In => return Err(unsupported()),
+                QueryOperator::Between => {
+                    let (low, high) = pair(field, value()?)?;
+                    let low = self.param(QueryParam::Date(date(field, &low)?));
+                    let high = self.param(QueryParam::Date(date(field, &high)?));
+                    format!("{column} BETWEEN {low} AND {high}")
+                }
+                _ => format!("{column} {} {}", comparison(op), self.param(QueryParam::Date(date(field, value()?)?))),
+            },
+            Column::DateText(column) => match op {
+                QueryOperator::Exists => format!("{column} IS NOT NULL"),
+                QueryOperator::Contains | QueryOperator::StartsWith | QueryOperator::In => return Err(unsupported()),
+                QueryOperator::Between => {
+                    let (low, high) = pair(field, value()?)?;
+                    let low = self.param(QueryParam::Text(date(field, &low)?.to_string()));
+                    let high = self.param(QueryParam::Text(date(field, &high)?.to_string()));
+                    format!("{column} BETWEEN {low} AND {high}")
+                }
+                _ => format!("{column} {} {}", comparison(op), self.param(QueryParam::Text(date(field, value()?)?.to_string()))),
+            },
+            Column::Tags(column) => match op {
+                QueryOperator::Exists => format!("COALESCE(cardinality({column}), 0) > 0"),
+                QueryOperator::Equals | QueryOperator::Contains => {
+                    format!("{} = ANY({column})", self.param(QueryParam::Text(text(field, value()?)?)))
+                }
+                QueryOperator::StartsWith => format!(
+                    "EXISTS (SELECT 1 FROM unnest({column}) AS tag WHERE tag ILIKE {})",
+                    self.param(QueryParam::Text(format!("{}%", escape_like(&text(field, value()?)?))))
+                ),
+                QueryOperator::In => format!("{column} && {}", self.param(QueryParam::TextArray(texts(field, value()?)?))),
+                _ => return Err(unsupported()),
+            },
+            Column::Attribute { column, path } => {
+                let path = self.param(QueryParam::TextArray(path));
+                let json = format!("{column} #> {path}");
+                let as_text = format!("{column} #>> {path}");
+                // Compare numerically only where the attribute holds a number
+                let as_number = format!("(CASE WHEN jsonb_typeof({json}) = 'number' THEN ({as_text})::numeric END)");
+                match op {
+                    QueryOperator::Exists => format!("({json} IS NOT NULL AND jsonb_typeof({json}) <> 'null')"),
+                    QueryOperator::Equals => format!("{as_text} = {}", self.param(QueryParam::Text(text(field, value()?)?))),
+                    QueryOperator::Contains => {
+                        let value = text(field, value()?)?;
+                        let pattern = self.param(QueryParam::Text(format!("%{}%", escape_like(&value))));
+                        // Array attributes contain the value as an element
+                        format!("(({json}) ? {} OR {as_text} ILIKE {pattern})", self.param(QueryParam::Text(value)))
+                    }
+                    QueryOperator::StartsWith => format!("{as_text} ILIKE {}", self.param(QueryParam::Text(format!("{}%", escape_like(&text(field, value()?)?))))),
+                    QueryOperator::In => format!("{as_text} = ANY({})", self.param(QueryParam::TextArray(texts(field, value()?)?))),
+                    QueryOperator::Between => {
+                        let (low, high) = pair(field, value()?)?;
+                        if low.is_number() && high.is_number() {
+                            let low = self.param(QueryParam::Number(number(field, &low)?));
+                            let high = self.param(QueryParam::Number(number(field, &high)?));
+                            format!("{as_number} BETWEEN {low} AND {high}")
+                        } else {
+                            let low = self.param(QueryParam::Text(text(field, &low)?));
+                            let high = self.param(QueryParam::Text(text(field, &high)?));
+                            format!("{as_text} BETWEEN {low} AND {high}")
+                        }
+                    }
+                    _ => {
+                        let value = value()?;
+                        if value.is_number() {
+                            format!("{as_number} {} {}", comparison(op), self.param(QueryParam::Number(number(field, value)?)))
+                        } else {
+                            format!("{as_text} {} {}", comparison(op), self.param(QueryParam::Text(text(field, value)?)))
+                        }
+                    }
+                }
+            }
+        };
+        Ok(sql)
+    }
+}
+
+/// Whether a query has conditions on the relationship it is nested in
+fn mentions_validity(query: &CardQuery) -> bool {
+    match query {
+        CardQuery::And(terms) | CardQuery::Or(terms) => terms.iter().any(mentions_validity),
+        CardQuery::Not(term) => mentions_validity(term),
+        CardQuery::Condition(condition) => condition.field.starts_with("relationship."),
+        // Nested predicates have their own relationship
+        CardQuery::Related(_) => false,
+    }
+}
+
+fn comparison(op: QueryOperator) -> &'static str {
+    match op {
+        QueryOperator::Gt => ">",
+        QueryOperator::Gte => ">=",
+        QueryOperator::Lt => "<",
+        QueryOperator::Lte => "<=",
+        _ => "=",
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn text(field: &str, value: &serde_json::Value) -> Result<String, AppError> {
+    match value {
+        serde_json::Value::String(value) => Ok(value.clone()),
+        serde_json::Value::Number(value) => Ok(value.to_string()),
+        serde_json::Value::Bool(value) => Ok(value.to_string()),
+        _ => Err(invalid(format!("{} expects a single value", field))),
+    }
+}
+
+fn texts(field: &str, value: &serde_json::Value) -> Result<Vec<String>, AppError> {
+    list(field, value)?.iter().map(|value| text(field, value)).collect()
+}
+
+fn number(field: &str, value: &serde_json::Value) -> Result<f64, AppError> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(value) => value.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| invalid(format!("{} expects a number, got {}", field, value)))
+}
+
+fn uuid(field: &str, value: &serde_json::Value) -> Result<Uuid, AppError> {
+    value.as_str()
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .ok_or_else(|| invalid(format!("{} expects a UUID, got {}", field, value)))
+}
+
+fn date(field: &str, value: &serde_json::Value) -> Result<NaiveDate, AppError> {
+    value.as_str()
+        .and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        .ok_or_else(|| invalid(format!("{} expects a YYYY-MM-DD date, got {}", field, value)))
+}
+
+fn list<'v>(field: &str, value: &'v serde_json::Value) -> Result<&'v Vec<serde_json::Value>, AppError> {
+    match value {
+        serde_json::Value::Array(values) if !values.is_empty() => Ok(values),
+        _ => Err(invalid(format!("{} in expects a non-empty list", field))),
+    }
+}
+
+fn pair(field: &str, value: &serde_json::Value) -> Result<(serde_json::Value, serde_json::Value), AppError> {
+    match value {
+        serde_json::Value::Array(values) if values.len() == 2 => Ok((values[0].clone(), values[1].clone())),
+        _ => Err(invalid(format!("{} between expects two values", field))),
+    }
+}
+
+/// Card fields a listing can be sorted by, besides `attributes.<key>`
+pub const CARD_SORT_FIELDS: &[SortField] = &[
+    SortField { name: "name", sql: "name", kind: SortKind::Text },
+    SortField { name: "type", sql: "type", kind: SortKind::Text },
+    SortField { name: "lifecycle_phase", sql: "lifecycle_phase", kind: SortKind::Text },
+    SortField { name: "quality_score", sql: "COALESCE(quality_score, -1)", kind: SortKind::Integer },
+    SortField { name: "created_at", sql: "created_at", kind: SortKind::Timestamp },
+    SortField { name: "updated_at", sql: "updated_at", kind: SortKind::Timestamp },
+    SortField { name: "created", sql: "created_at", kind: SortKind::Timestamp },
+    SortField { name: "updated", sql: "updated_at", kind: SortKind::Timestamp },
+];
+
+/// Sort keys for a sort parameter such as `-updated_at,name,attributes.vendor`; `restricted`
+/// attribute keys are refused as in `compile_card_query`
+pub fn card_sort(sort: &str, restricted: &[String]) -> Result<Vec<SortKey>, AppError> {
+    parse_sort(sort, CARD_SORT_FIELDS, Some("attributes"), restricted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::config::CardAccess;
+
+    fn condition(field: &str, op: QueryOperator, value: Option<serde_json::Value>) -> CardQuery {
+        CardQuery::Condition(QueryCondition { field: field.to_string(), op, value })
+    }
+
+    #[test]
+    fn test_parse_card_query() {
+        let query = parse_card_query(
+            r#"type = Application AND (quality_score >= 80 OR NOT attributes.hosting_type in ("SaaS", PaaS))"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query,
+            CardQuery::And(vec![
+                condition("type", QueryOperator::Equals, Some(json!("Application"))),
+                CardQuery::Or(vec![
+                    condition("quality_score", QueryOperator::Gte, Some(json!(80))),
+                    CardQuery::Not(Box::new(condition("attributes.hosting_type", QueryOperator::In, Some(json!(["SaaS", "PaaS"]))))),
+                ]),
+            ])
+        );
+
+        let query = parse_card_query("created between 2024-01-01 and 2024-06-30 and outgoing(reliesOn, type = Platform and tags contains legacy)").unwrap();
+        assert_eq!(
+            query,
+            CardQuery::And(vec![
+                condition("created", QueryOperator::Between, Some(json!(["2024-01-01", "2024-06-30"]))),
+                CardQuery::Related(RelationshipPredicate {
+                    relationship_type: Some(RelationshipType::ReliesOn),
+                    direction: RelationshipDirection::Outgoing,
+                    card: Some(Box::new(CardQuery::And(vec![
+                        condition("type", QueryOperator::Equals, Some(json!("Platform"))),
+                        condition("tags", QueryOperator::Contains, Some(json!("legacy"))),
+                    ]))),
+                }),
+            ])
+        );
+
+        assert_eq!(
+            parse_card_query("related(*) AND owner_id exists").unwrap(),
+            CardQuery::And(vec![
+                CardQuery::Related(RelationshipPredicate { relationship_type: None, direction: RelationshipDirection::Any, card: None }),
+                condition("owner_id", QueryOperator::Exists, None),
+            ])
+        );
+
+        let error = parse_card_query("name = ").unwrap_err().to_string();
+        assert!(error.contains("expected a value at end of query"), "{}", error);
+        let error = parse_card_query("name = Billing description = x").unwrap_err().to_string();
+        assert!(error.contains("position 16"), "{}", error);
+        assert!(parse_card_query("outgoing(linksTo)").is_err());
+    }
+
+    #[test]
+    fn test_compile_card_query() {
+        let query = parse_card_query("type = Application AND outgoing(reliesOn, type = Platform AND tags contains legacy)").unwrap();
+        let compiled = compile_card_query(&query, 3, None, &[]).unwrap();
+        assert_eq!(
+            compiled.sql,
+            "(cards.type = $3 AND EXISTS (SELECT 1 FROM relationships r1 JOIN cards c1 ON c1.id = r1.to_card_id \
+             WHERE r1.from_card_id = cards.id AND c1.status = 'active' AND r1.relationship_type = $4 \
+             AND (r1.valid_from <= to_char(CURRENT_DATE, 'YYYY-MM-DD') AND (r1.valid_to IS NULL OR r1.valid_to >= to_char(CURRENT_DATE, 'YYYY-MM-DD'))) \
+             AND (c1.type = $5 AND $6 = ANY(c1.tags))))"
+        );
+        assert_eq!(
+            compiled.params,
+            vec![
+                QueryParam::Text("Application".to_string()),
+                QueryParam::Text("reliesOn".to_string()),
+                QueryParam::Text("Platform".to_string()),
+                QueryParam::Text("legacy".to_string()),
+            ]
+        );
+
+        // Values never reach the SQL
+        let query = parse_card_query(r#"name contains "50%'; DROP TABLE cards; --""#).unwrap();
+        let compiled = compile_card_query(&query, 1, None, &[]).unwrap();
+        assert_eq!(compiled.sql, "cards.name ILIKE $1");
+        assert_eq!(compiled.params, vec![QueryParam::Text("%50\\%'; DROP TABLE cards; --%".to_string())]);
+
+        // Conditions on the relationship replace the current-relationships default
+        let query = parse_card_query("incoming(*, relationship.valid_to < 2024-01-01)").unwrap();
+        let compiled = compile_card_query(&query, 1, None, &[]).unwrap();
+        assert!(!compiled.sql.contains("CURRENT_DATE"));
+        assert!(compiled.sql.contains("r1.valid_to < $1"));
+
+        let query = parse_card_query("attributes.cost > 1000").unwrap();
+        let compiled = compile_card_query(&query, 1, None, &[]).unwrap();
+        assert_eq!(compiled.params, vec![QueryParam::TextArray(vec!["cost".to_string()]), QueryParam::Number(1000.0)]);
+
+        let sort = card_sort("-updated, name, attributes.vendor", &[]).unwrap();
+        assert_eq!(sort.iter().map(|key| key.sql.as_str()).collect::<Vec<_>>(),
+                   vec!["updated_at", "name", "COALESCE(attributes #> '{vendor}', 'null'::jsonb)"]);
+        assert!(card_sort("name; DROP TABLE cards", &[]).is_err());
+        assert!(card_sort(",", &[]).is_err());
+
+        for bad in ["colour = red", "attributes.a'b = 1", "owner_id contains x", "created > yesterday", "relationship.valid_to exists"] {
+            assert!(compile_card_query(&parse_card_query(bad).unwrap(), 1, None, &[]).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_restricted_attributes_cannot_be_queried() {
+        let restricted = CardAccess::default().restricted_attributes;
+        for probe in [
+            "attributes.cost between 1000 and 2000",
+            "attributes.contract_value.amount > 10",
+            "NOT attributes.annual_cost exists",
+            "outgoing(reliesOn, attributes.license_cost >= 500)",
+        ] {
+            let query = parse_card_query(probe).unwrap();
+            assert!(
+                matches!(compile_card_query(&query, 1, None, &restricted), Err(AppError::Forbidden(_))),
+                "{}", probe
+            );
+            assert!(compile_card_query(&query, 1, None, &[]).is_ok(), "{}", probe);
+        }
+        let query = parse_card_query("attributes.hosting_type = SaaS AND attributes.costing_model exists").unwrap();
+        assert!(compile_card_query(&query, 1, None, &restricted).is_ok());
+
+        assert!(matches!(card_sort("-attributes.cost", &restricted), Err(AppError::Forbidden(_))));
+        assert!(card_sort("-attributes.cost", &[]).is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+/*!
+ * Card Search
+ *
+ * SQL fragments shared by ranked search and the `q` filter of card lists. A card matches
+ * when its full-text vector (migration 027) matches the web search query, or when its
+ * name or description is trigram-similar to the search text, which tolerates typos.
+ * Highlights come from `ts_headline` with control-character markers that are swapped
+ * for `<mark>` after the card text is HTML-escaped.
+ */
+
+const HIGHLIGHT_START: char = '\u{2}';
+const HIGHLIGHT_STOP: char = '\u{3}';
+
+/// Cards in `alias` matching the search text in parameter `q` (e.g. `$1`)
+pub fn search_match(q: &str, alias: &str) -> String {
+    format!(
+        "({a}search_vector @@ websearch_to_tsquery('english', {q}) OR {a}name % {q} OR {q} <% {a}name OR {q} <% {a}description)",
+        a = prefix(alias),
+        q = q
+    )
+}
+
+/// Relevance: full-text rank (0..1, weighted by field) plus trigram similarity of the name (0..1)
+pub fn search_score(q: &str, alias: &str) -> String {
+    format!(
+        "(ts_rank_cd({a}search_vector, websearch_to_tsquery('english', {q}), 32) \
+         + GREATEST(similarity({a}name, {q}), word_similarity({q}, {a}name)))::float8",
+        a = prefix(alias),
+        q = q
+    )
+}
+
+/// `ts_headline` of `column` with the matched words between highlight markers
+pub fn search_headline(q: &str, column: &str, whole_text: bool) -> String {
+    let options = if whole_text {
+        "HighlightAll=true"
+    } else {
+        "MaxWords=30, MinWords=10, MaxFragments=2, FragmentDelimiter=\" … \""
+    };
+    format!(
+        "ts_headline('english', {column}, websearch_to_tsquery('english', {q}), \
+         'StartSel=' || chr(2) || ', StopSel=' || chr(3) || ', {options}')"
+    )
+}
+
+/// Escape HTML in a headline and turn its markers into `<mark>` tags
+pub fn highlight(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for c in headline.chars() {
+        match c {
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_STOP => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(c),
+        }
+    }
+    html
+}
+
+fn prefix(alias: &str) -> String {
+    if alias.is_empty() { String::new() } else { format!("{}.", alias) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(
+            highlight("Legacy \u{2}billing\u{3} <script>alert('x')</script> & more"),
+            "Legacy <mark>billing</mark> &lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; more"
+        );
+        assert_eq!(highlight("no match"), "no match");
+    }
+
+    #[test]
+    fn test_search_match() {
+        assert_eq!(
+            search_match("$1", "c"),
+            "(c.search_vector @@ websearch_to_tsquery('english', $1) OR c.name % $1 OR $1 <% c.name OR $1 <% c.description)"
+        );
+        assert!(search_score("$2", "").starts_with("(ts_rank_cd(search_vector, websearch_to_tsquery('english', $2), 32)"));
+    }
+}
@@ -1,21 +1,92 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use chrono::Utc;
 
-use crate::models::card::{Card, CreateCardRequest, UpdateCardRequest, CardSearchParams};
+use crate::models::card::{Card, CardPage, CardType, CreateCardRequest, UpdateCardRequest, CardSearchParams};
+use crate::models::card_query::CardQuery;
+use crate::models::search::*;
+use crate::services::card_query::{card_sort, compile_card_query, parse_card_query};
+use crate::services::pagination::{Keyset, SortKey, SortKind};
+use crate::services::card_search::{highlight, search_headline, search_match, search_score};
+use crate::services::card_similarity::{similarity, suggest_relationships, Edge, Similarity, Suggestion};
+use crate::services::relationship_rule_service::{find_rule, rules_by_type, CURRENT_RELATIONSHIP};
+use crate::models::card_query::RelationshipDirection;
+use crate::models::relationship::RelationshipType;
+use crate::models::version::{CardVersion, VersionOperation};
+use crate::services::card_access::CardAccessPolicy;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::version_service::{current_actor, current_workspace, VersionService};
+use crate::services::RelationshipService;
+use crate::services::card_type_service::enforce_card_type_registered;
+use crate::services::metamodel_service::{enforce_attribute_rules, enforce_card_type_open, CURRENT_METAMODEL_VERSION_SQL};
+use crate::services::data_quality_service::{refresh_quality, spawn_recalculation};
+use crate::config::{CardAccess, DataQuality};
 use crate::error::AppError;
 
+const CARD_COLUMNS: &str = "id, name, type, lifecycle_phase, quality_score, description, owner_id, team_id, \
+                            workspace_id, metamodel_version, created_at, updated_at, attributes, tags, status";
+
+/// Cards scored when looking for similar cards
+const SIMILARITY_CANDIDATES: i64 = 500;
+
+/// Relationships suggested from similar cards
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Cards of workspace `$n` plus those shared into it; a NULL workspace matches every card
+fn visible_in_workspace(param: usize) -> String {
+    format!(
+        "(${0}::uuid IS NULL OR workspace_id = ${0}::uuid OR id IN (SELECT card_id FROM card_shares WHERE workspace_id = ${0}::uuid))",
+        param
+    )
+}
+
+/// Parameters $1 to $6 of every card search query
+struct SearchArgs {
+    q: String,
+    workspace_id: Option<Uuid>,
+    card_type: Option<String>,
+    lifecycle_phase: Option<String>,
+    tag: Option<String>,
+    owner_id: Option<Uuid>,
+}
+
+impl SearchArgs {
+    fn bind<'q>(&self, query: &'q str) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+        sqlx::query(query)
+            .bind(self.q.clone())
+            .bind(self.workspace_id)
+            .bind(self.card_type.clone())
+            .bind(self.lifecycle_phase.clone())
+            .bind(self.tag.clone())
+            .bind(self.owner_id)
+    }
+}
+
 pub struct CardService {
     pool: PgPool,
+    access: CardAccessPolicy,
+    quality: DataQuality,
 }
 
 impl CardService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, access: CardAccess) -> Self {
+        Self { pool, access: CardAccessPolicy::new(access), quality: DataQuality::default() }
+    }
+
+    /// Data quality settings used to rescore cards as they change
+    pub fn with_data_quality(mut self, quality: DataQuality) -> Self {
+        self.quality = quality;
+        self
     }
 
     pub async fn create(&self, req: CreateCardRequest) -> Result<Card, AppError> {
+        if let Some(actor) = current_actor() {
+            self.access.check_card_type(&actor, &req.card_type)?;
+        }
+
         let card_id = Uuid::new_v4();
         let now = Utc::now();
 
@@ -29,41 +100,65 @@ impl CardService {
             .trim_matches('"')
             .to_string();
 
-        sqlx::query(
+        let attributes = req.attributes.unwrap_or_else(|| serde_json::json!({}));
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        enforce_card_type_registered(&mut *tx, &req.card_type).await?;
+        enforce_card_type_open(&mut *tx, &card_type_str).await?;
+        enforce_attribute_rules(&mut tx, &card_type_str, &attributes).await?;
+
+        sqlx::query(&format!(
             r#"
-            INSERT INTO cards (id, name, type, lifecycle_phase, quality_score, description, owner_id, created_at, updated_at, attributes, tags, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            INSERT INTO cards (id, name, type, lifecycle_phase, description, owner_id, team_id, workspace_id, created_at, updated_at, attributes, tags, status, metamodel_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, {})
             "#,
-        )
+            CURRENT_METAMODEL_VERSION_SQL
+        ))
         .bind(card_id)
         .bind(&req.name)
         .bind(&card_type_str)
         .bind(&lifecycle_phase_str)
-        .bind(req.quality_score)
         .bind(&req.description)
         .bind(req.owner_id)
+        .bind(req.team_id)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
         .bind(now)
         .bind(now)
-        .bind(&req.attributes.unwrap_or_else(|| serde_json::json!({})))
+        .bind(&attributes)
         .bind(&req.tags.unwrap_or_default())
         .bind("active")
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create card: {}", e)))?;
 
-        self.get(card_id).await
+        refresh_quality(&mut tx, &[card_id], &self.quality).await?;
+        let card = self.fetch_locked(&mut tx, card_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card_id)))?;
+        VersionService::record_card(&mut tx, VersionOperation::Create, None, Some(&card)).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card: {}", e)))?;
+        self.policy_changed(&card.card_type);
+
+        Ok(self.redacted(card))
     }
 
+    /// Get a card as the current caller may see it
     pub async fn get(&self, id: Uuid) -> Result<Card, AppError> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, name, type, lifecycle_phase, quality_score, description, owner_id,
-                   created_at, updated_at, attributes, tags, status
-            FROM cards
-            WHERE id = $1 AND status = 'active'
-            "#,
-        )
+        self.get_unredacted(id).await.map(|card| self.redacted(card))
+    }
+
+    /// Get a card including restricted attributes, for snapshots taken by the saga
+    pub(crate) async fn get_unredacted(&self, id: Uuid) -> Result<Card, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM cards WHERE id = $1 AND status = 'active' AND {}",
+            CARD_COLUMNS,
+            visible_in_workspace(2)
+        ))
         .bind(id)
+        .bind(current_workspace())
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card: {}", e)))?
@@ -72,25 +167,65 @@ impl CardService {
         self.row_to_card(row)
     }
 
+    /// The visible cards among `ids`, in one query; missing or hidden ids are skipped
+    pub async fn get_many(&self, ids: &[Uuid]) -> Result<Vec<Card>, AppError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM cards WHERE id = ANY($1) AND status = 'active' AND {}",
+            CARD_COLUMNS,
+            visible_in_workspace(2)
+        ))
+        .bind(ids)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch cards: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_card(row).map(|card| self.redacted(card)))
+            .collect()
+    }
+
     pub async fn list(&self, params: CardSearchParams) -> Result<(Vec<Card>, i64), AppError> {
+        let page = self.list_page(params).await?;
+        Ok((page.cards, page.total))
+    }
+
+    /// A page of cards with the cursor of the next page
+    pub async fn list_page(&self, params: CardSearchParams) -> Result<CardPage, AppError> {
+        let query = params.query.as_deref().map(parse_card_query).transpose()?;
+        self.list_matching_page(params, query.as_ref()).await
+    }
+
+    /// List cards matching the search parameters and a boolean query AST
+    pub async fn list_matching(&self, params: CardSearchParams, query: Option<&CardQuery>) -> Result<(Vec<Card>, i64), AppError> {
+        let page = self.list_matching_page(params, query).await?;
+        Ok((page.cards, page.total))
+    }
+
+    /// A page of the cards matching the search parameters and a boolean query AST, with the
+    /// cursor of the next page. With `params.cursor` the page continues after that cursor
+    /// and `params.page` is ignored.
+    pub async fn list_matching_page(&self, params: CardSearchParams, query: Option<&CardQuery>) -> Result<CardPage, AppError> {
         let page = params.page.unwrap_or(1).max(1);
-        let page_size = params.page_size.unwrap_or(20).min(100);
+        let page_size = params.page_size.unwrap_or(20).clamp(1, 100);
         let offset = (page - 1) * page_size;
 
         // Build parameterized query to prevent SQL injection
-        let mut base_query = "SELECT id, name, type, lifecycle_phase, quality_score, description, owner_id, created_at, updated_at, attributes, tags, status FROM cards WHERE status = 'active'".to_string();
+        let mut base_query = format!("SELECT {} FROM cards WHERE status = 'active'", CARD_COLUMNS);
         let mut base_count = "SELECT COUNT(*) FROM cards WHERE status = 'active'".to_string();
         let mut conditions = Vec::new();
         let mut bind_params: Vec<String> = Vec::new();
 
-        // Search query parameter
-        let search_pattern = if let Some(q) = &params.q {
-            let param_num = bind_params.len() + 1;
-            let param_num2 = bind_params.len() + 2;
-            conditions.push(format!("(name ILIKE ${} OR description ILIKE ${})", param_num, param_num2));
-            bind_params.push(format!("%{}%", q));
-            bind_params.push(format!("%{}%", q));
-            Some((bind_params.len() - 2, bind_params.len() - 1))
+        // Search query parameter: full-text or fuzzy match, most relevant first
+        let search_order = if let Some(q) = &params.q {
+            let param = format!("${}", bind_params.len() + 1);
+            conditions.push(search_match(&param, ""));
+            bind_params.push(q.clone());
+            Some(search_score(&param, ""))
         } else {
             None
         };
@@ -137,6 +272,22 @@ impl CardService {
             None
         };
 
+        // Own and shared cards of the current workspace
+        if let Some(workspace_id) = current_workspace() {
+            conditions.push(visible_in_workspace(bind_params.len() + 1));
+            bind_params.push(workspace_id.to_string());
+        }
+
+        // Boolean query; its typed parameters are bound after the string ones
+        let query_params = match query {
+            Some(query) => {
+                let compiled = compile_card_query(query, bind_params.len() + 1, current_workspace(), &self.restricted_for_caller())?;
+                conditions.push(compiled.sql);
+                compiled.params
+            }
+            None => Vec::new(),
+        };
+
         // Build final queries with conditions
         if !conditions.is_empty() {
             base_query = format!("{} {}", base_query, "AND ".to_string() + &conditions.join(" AND "));
@@ -144,23 +295,44 @@ impl CardService {
         }
 
         // Build count query with parameters
-        let mut count_query = sqlx::query_as::<_, (i64,)>(&base_count);
+        let mut count_query = sqlx::query(&base_count);
 
         // Bind parameters for count query
         for param in &bind_params {
             count_query = count_query.bind(param);
         }
+        for param in &query_params {
+            count_query = param.bind(count_query);
+        }
 
         let count_row = count_query
             .fetch_one(&self.pool)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count cards: {}", e)))?;
 
-        // Build data query with parameters
-        let limit_param = bind_params.len() + 1;
-        let offset_param = bind_params.len() + 2;
-        let data_query = format!("{} ORDER BY created_at DESC LIMIT ${} OFFSET ${}",
-            base_query, limit_param, offset_param);
+        // Sort keys with the ID as tiebreaker; a cursor continues after its row instead of an offset
+        let keys = match (&params.sort, search_order) {
+            (Some(sort), _) => card_sort(sort, &self.restricted_for_caller())?,
+            (None, Some(score)) => vec![SortKey::new("relevance", score, SortKind::Float, true)],
+            (None, None) => vec![SortKey::new("created_at", "created_at".to_string(), SortKind::Timestamp, true)],
+        };
+        let keyset = Keyset::new(keys, "id");
+        let mut data_query = base_query.replacen(CARD_COLUMNS, &format!("{}, {}", CARD_COLUMNS, keyset.select()), 1);
+        let first_param = bind_params.len() + query_params.len() + 1;
+        let (after, cursor_params) = match &params.cursor {
+            Some(cursor) => {
+                let (condition, values) = keyset.after(cursor, first_param)?;
+                data_query = format!("{} AND {}", data_query, condition);
+                (true, values)
+            }
+            None => (false, Vec::new()),
+        };
+        let limit_param = first_param + cursor_params.len();
+        data_query = if after {
+            format!("{} ORDER BY {} LIMIT ${}", data_query, keyset.order_by(), limit_param)
+        } else {
+            format!("{} ORDER BY {} LIMIT ${} OFFSET ${}", data_query, keyset.order_by(), limit_param, limit_param + 1)
+        };
 
         let mut data_query_builder = sqlx::query(&data_query);
 
@@ -168,14 +340,330 @@ impl CardService {
         for param in &bind_params {
             data_query_builder = data_query_builder.bind(param);
         }
-        // Bind LIMIT and OFFSET
-        data_query_builder = data_query_builder.bind(page_size as i64).bind(offset as i64);
+        for param in &query_params {
+            data_query_builder = param.bind(data_query_builder);
+        }
+        for param in &cursor_params {
+            data_query_builder = data_query_builder.bind(param);
+        }
+        // One row more than the page tells whether there is a next page
+        data_query_builder = data_query_builder.bind(page_size as i64 + 1);
+        if !after {
+            data_query_builder = data_query_builder.bind(offset as i64);
+        }
 
-        let rows = data_query_builder
+        let mut rows = data_query_builder
             .fetch_all(&self.pool)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list cards: {}", e)))?;
 
+        let next_cursor = if rows.len() > page_size as usize {
+            rows.truncate(page_size as usize);
+            rows.last().map(|row| keyset.cursor_for(row)).transpose()?
+        } else {
+            None
+        };
+
+        let mut cards = Vec::new();
+        for row in rows {
+            match self.row_to_card(row) {
+                Ok(card) => cards.push(self.redacted(card)),
+                Err(e) => {
+                    tracing::warn!("Failed to parse card row: {:?}", e);
+                    continue;
+                }
+            }
+        }
+
+        let total: i64 = count_row.try_get(0)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count cards: {}", e)))?;
+        Ok(CardPage { cards, total, next_cursor })
+    }
+
+    /// Relevance-ranked search with highlights and facet counts over all matches
+    pub async fn search(&self, params: CardSearchQuery) -> Result<CardSearchResponse, AppError> {
+        let q = params.q.trim();
+        if q.is_empty() {
+            return Err(AppError::Validation("Search text is required".to_string()));
+        }
+        let page = params.page.unwrap_or(1).max(1);
+        let page_size = params.page_size.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * page_size;
+
+        // Every query binds the same six parameters; unset filters are NULL
+        let filter = format!(
+            "c.status = 'active' \
+             AND ($2::uuid IS NULL OR c.workspace_id = $2 OR c.id IN (SELECT card_id FROM card_shares WHERE workspace_id = $2)) \
+             AND {} AND ($3::text IS NULL OR c.type = $3) AND ($4::text IS NULL OR c.lifecycle_phase = $4) \
+             AND ($5::text IS NULL OR $5 = ANY(c.tags)) AND ($6::uuid IS NULL OR c.owner_id = $6)",
+            search_match("$1", "c"),
+        );
+        let lifecycle_phase = params.lifecycle_phase
+            .and_then(|phase| serde_json::to_value(phase).ok())
+            .and_then(|value| value.as_str().map(str::to_string));
+        let args = SearchArgs {
+            q: q.to_string(),
+            workspace_id: current_workspace(),
+            card_type: params.card_type.as_ref().map(|card_type| card_type.as_str().to_string()),
+            lifecycle_phase,
+            tag: params.tag.clone(),
+            owner_id: params.owner_id,
+        };
+
+        let rows = args.bind(&format!(
+            "SELECT {}, {} AS score, {} AS name_highlight, \
+             CASE WHEN COALESCE(description, '') = '' THEN NULL ELSE {} END AS snippet \
+             FROM cards c WHERE {} ORDER BY score DESC, name LIMIT $7 OFFSET $8",
+            CARD_COLUMNS,
+            search_score("$1", "c"),
+            search_headline("$1", "name", true),
+            search_headline("$1", "description", false),
+            filter
+        ))
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to search cards: {}", e)))?;
+
+        let mut data = Vec::with_capacity(rows.len());
+        for row in rows {
+            let score: f64 = row.try_get("score").map_err(|e| AppError::Internal(anyhow::anyhow!("Missing score: {}", e)))?;
+            let name_highlight: String = row.try_get("name_highlight")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing name_highlight: {}", e)))?;
+            let snippet: Option<String> = row.try_get("snippet")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing snippet: {}", e)))?;
+            let card = self.redacted(self.row_to_card(row)?);
+            data.push(CardSearchHit {
+                card,
+                score,
+                name_highlight: highlight(&name_highlight),
+                snippet: snippet.as_deref().map(highlight),
+            });
+        }
+
+        let total: i64 = args.bind(&format!("SELECT COUNT(*) FROM cards c WHERE {}", filter))
+            .fetch_one(&self.pool)
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count search results: {}", e)))?;
+
+        let facet = |select: &str, from: &str, group: &str, limit: i64| {
+            format!(
+                "SELECT {select}, COUNT(*) AS count FROM cards c {from} WHERE {filter} \
+                 GROUP BY {group} ORDER BY count DESC, 1 LIMIT {limit}"
+            )
+        };
+        let mut facets = Vec::with_capacity(4);
+        for query in [
+            facet("c.type AS value, NULL::text AS label", "", "c.type", 50),
+            facet("c.lifecycle_phase AS value, NULL::text AS label", "", "c.lifecycle_phase", 50),
+            facet("tag AS value, NULL::text AS label", "CROSS JOIN LATERAL unnest(c.tags) AS tag", "tag", 20),
+            facet("c.owner_id::text AS value, u.full_name AS label", "JOIN users u ON u.id = c.owner_id", "c.owner_id, u.full_name", 20),
+        ] {
+            let rows = args.bind(&query)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count search facets: {}", e)))?;
+            let counts = rows.iter()
+                .map(|row| Ok(FacetCount {
+                    value: row.try_get("value")?,
+                    label: row.try_get("label")?,
+                    count: row.try_get("count")?,
+                }))
+                .collect::<Result<Vec<_>, sqlx::Error>>()
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid search facet: {}", e)))?;
+            facets.push(counts);
+        }
+        let [types, lifecycle_phases, tags, owners]: [Vec<FacetCount>; 4] = facets.try_into()
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Missing search facets")))?;
+
+        Ok(CardSearchResponse {
+            data,
+            total,
+            page,
+            page_size,
+            facets: SearchFacets { types, lifecycle_phases, tags, owners },
+        })
+    }
+
+    /// Cards most similar to a card, and relationships its similar cards have that it lacks
+    pub async fn similar(&self, id: Uuid, params: SimilarCardsQuery) -> Result<SimilarCardsResponse, AppError> {
+        let limit = params.limit.unwrap_or(10).clamp(1, 50) as usize;
+        let card = self.get(id).await?;
+        let workspace_id = current_workspace();
+        let tags: Vec<String> = card.tags.iter().map(|tag| tag.trim().to_lowercase()).collect();
+
+        // Candidates share a tag, the type or a neighbour; those sharing most tags are scored first
+        let neighbours = format!(
+            "SELECT CASE WHEN r.from_card_id = $1 THEN r.to_card_id ELSE r.from_card_id END AS id \
+             FROM relationships r WHERE (r.from_card_id = $1 OR r.to_card_id = $1) AND {}",
+            CURRENT_RELATIONSHIP
+        );
+        let rows = sqlx::query(&format!(
+            "WITH neighbours AS ({neighbours}), \
+             second_neighbours AS ( \
+                 SELECT CASE WHEN n.id = r.from_card_id THEN r.to_card_id ELSE r.from_card_id END AS id \
+                 FROM relationships r JOIN neighbours n ON n.id IN (r.from_card_id, r.to_card_id) WHERE {current}) \
+             SELECT {columns} FROM cards c \
+             WHERE c.status = 'active' AND c.id <> $1 AND {visible} AND (NOT $5 OR c.type = $4) \
+             AND (ARRAY(SELECT lower(tag) FROM unnest(c.tags) AS tag) && $3 OR c.type = $4 \
+                  OR c.id IN (SELECT id FROM neighbours) OR c.id IN (SELECT id FROM second_neighbours)) \
+             ORDER BY cardinality(ARRAY(SELECT lower(tag) FROM unnest(c.tags) AS tag INTERSECT SELECT unnest($3::text[]))) DESC, \
+             c.updated_at DESC LIMIT {limit}",
+            current = CURRENT_RELATIONSHIP,
+            columns = CARD_COLUMNS,
+            visible = visible_in_workspace(2),
+            limit = SIMILARITY_CANDIDATES,
+        ))
+        .bind(id)
+        .bind(workspace_id)
+        .bind(&tags)
+        .bind(card.card_type.as_str())
+        .bind(params.same_type.unwrap_or(false))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch similar card candidates: {}", e)))?;
+        let candidates = rows.into_iter()
+            .map(|row| self.row_to_card(row).map(|card| self.redacted(card)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ids: Vec<Uuid> = candidates.iter().map(|candidate| candidate.id).collect();
+        ids.push(id);
+        let edge_rows = sqlx::query(&format!(
+            "SELECT r.from_card_id, r.to_card_id, r.relationship_type FROM relationships r \
+             WHERE (r.from_card_id = ANY($1) OR r.to_card_id = ANY($1)) AND {} \
+             AND ($2::uuid IS NULL OR r.workspace_id = $2)",
+            CURRENT_RELATIONSHIP
+        ))
+        .bind(&ids)
+        .bind(workspace_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card relationships: {}", e)))?;
+        let mut edges: HashMap<Uuid, Vec<Edge>> = HashMap::new();
+        for row in edge_rows {
+            let from: Uuid = row.try_get("from_card_id").map_err(|e| AppError::Internal(anyhow::anyhow!("Missing from_card_id: {}", e)))?;
+            let to: Uuid = row.try_get("to_card_id").map_err(|e| AppError::Internal(anyhow::anyhow!("Missing to_card_id: {}", e)))?;
+            let relationship_type: String = row.try_get("relationship_type")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing relationship_type: {}", e)))?;
+            edges.entry(from).or_default().push(Edge { relationship_type: relationship_type.clone(), direction: RelationshipDirection::Outgoing, other: to });
+            edges.entry(to).or_default().push(Edge { relationship_type, direction: RelationshipDirection::Incoming, other: from });
+        }
+        let no_edges = Vec::new();
+        let edges_of = |card_id: Uuid| edges.get(&card_id).unwrap_or(&no_edges);
+
+        let mut scored: Vec<(Card, Similarity)> = candidates.into_iter()
+            .map(|candidate| {
+                let score = similarity(&card, edges_of(id), &candidate, edges_of(candidate.id));
+                (candidate, score)
+            })
+            .filter(|(_, score)| score.score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then_with(|| a.0.name.cmp(&b.0.name)));
+        scored.truncate(limit);
+
+        let peers: Vec<(Uuid, f64, &[Edge])> = scored.iter()
+            .map(|(peer, score)| (peer.id, score.score, edges_of(peer.id).as_slice()))
+            .collect();
+        let suggestions = suggest_relationships(id, edges_of(id), &peers);
+        let suggested_relationships = self.describe_suggestions(&card, suggestions, peers.len()).await?;
+
+        Ok(SimilarCardsResponse {
+            card_id: id,
+            similar: scored.into_iter()
+                .map(|(card, score)| SimilarCard {
+                    card,
+                    score: score.score,
+                    tag_score: score.tag_score,
+                    attribute_score: score.attribute_score,
+                    graph_score: score.graph_score,
+                    shared_tags: score.shared_tags,
+                    shared_attributes: score.shared_attributes,
+                    shared_neighbours: score.shared_neighbours,
+                })
+                .collect(),
+            suggested_relationships,
+        })
+    }
+
+    /// Name the suggested cards, dropping those not visible here and relationships no rule allows
+    async fn describe_suggestions(&self, card: &Card, suggestions: Vec<Suggestion>, peers: usize) -> Result<Vec<SuggestedRelationship>, AppError> {
+        let ids: Vec<Uuid> = suggestions.iter().map(|suggestion| suggestion.edge.other).collect();
+        let targets: HashMap<Uuid, (String, String)> = sqlx::query_as::<_, (Uuid, String, String)>(&format!(
+            "SELECT id, name, type FROM cards WHERE id = ANY($1) AND status = 'active' AND {}",
+            visible_in_workspace(2)
+        ))
+        .bind(&ids)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch suggested cards: {}", e)))?
+        .into_iter()
+        .map(|(id, name, card_type)| (id, (name, card_type)))
+        .collect();
+        let rules = rules_by_type(&self.pool).await?;
+
+        let mut described = Vec::new();
+        for suggestion in suggestions {
+            let Some((name, target_type)) = targets.get(&suggestion.edge.other) else {
+                continue;
+            };
+            let (from_type, to_type) = match suggestion.edge.direction {
+                RelationshipDirection::Incoming => (target_type.as_str(), card.card_type.as_str()),
+                _ => (card.card_type.as_str(), target_type.as_str()),
+            };
+            if let Some(type_rules) = rules.get(&suggestion.edge.relationship_type) {
+                if find_rule(type_rules, from_type, to_type).is_none() {
+                    continue;
+                }
+            }
+            let Ok(relationship_type) = serde_json::from_value::<RelationshipType>(
+                serde_json::Value::String(suggestion.edge.relationship_type.clone()),
+            ) else {
+                continue;
+            };
+            described.push(SuggestedRelationship {
+                reason: format!(
+                    "{} of {} similar cards have a {} relationship with {}",
+                    suggestion.supporting_cards.len(), peers, suggestion.edge.relationship_type, name
+                ),
+                relationship_type,
+                direction: suggestion.edge.direction,
+                card_id: suggestion.edge.other,
+                card_name: name.clone(),
+                card_type: target_type.clone(),
+                confidence: suggestion.confidence,
+                supporting_cards: suggestion.supporting_cards,
+            });
+            if described.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        Ok(described)
+    }
+
+    /// List every active card in every workspace, without pagination or redaction (graph reconciliation)
+    pub async fn list_all(&self) -> Result<Vec<Card>, AppError> {
+        self.list_all_in(None).await
+    }
+
+    /// List every active card visible in the current workspace, without pagination or redaction (state snapshots)
+    pub async fn list_all_visible(&self) -> Result<Vec<Card>, AppError> {
+        self.list_all_in(current_workspace()).await
+    }
+
+    async fn list_all_in(&self, workspace_id: Option<Uuid>) -> Result<Vec<Card>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM cards WHERE status = 'active' AND {} ORDER BY created_at",
+            CARD_COLUMNS,
+            visible_in_workspace(1)
+        ))
+        .bind(workspace_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list all cards: {}", e)))?;
+
         let mut cards = Vec::new();
         for row in rows {
             match self.row_to_card(row) {
@@ -187,10 +675,10 @@ impl CardService {
             }
         }
 
-        Ok((cards, count_row.0))
+        Ok(cards)
     }
 
-    pub async fn update(&self, id: Uuid, req: UpdateCardRequest) -> Result<Card, AppError> {
+    pub async fn update(&self, id: Uuid, mut req: UpdateCardRequest) -> Result<Card, AppError> {
         let now = Utc::now();
 
         // Build dynamic UPDATE query
@@ -205,10 +693,6 @@ impl CardService {
             updates.push(format!("lifecycle_phase = ${}", param_idx));
             param_idx += 1;
         }
-        if req.quality_score.is_some() {
-            updates.push(format!("quality_score = ${}", param_idx));
-            param_idx += 1;
-        }
         if req.description.is_some() {
             updates.push(format!("description = ${}", param_idx));
             param_idx += 1;
@@ -221,7 +705,16 @@ impl CardService {
             updates.push(format!("tags = ${}", param_idx));
             param_idx += 1;
         }
+        if req.owner_id.is_some() {
+            updates.push(format!("owner_id = ${}", param_idx));
+            param_idx += 1;
+        }
+        if req.team_id.is_some() {
+            updates.push(format!("team_id = ${}", param_idx));
+            param_idx += 1;
+        }
 
+        // quality_score is computed, so a request carrying only that changes nothing
         if updates.is_empty() {
             return self.get(id).await;
         }
@@ -233,6 +726,27 @@ impl CardService {
             updates.join(", ")
         );
 
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", id)))?;
+        self.authorize_change(&mut tx, &before).await?;
+
+        // Callers who cannot see restricted attributes cannot change them either
+        if let (Some(actor), Some(attributes)) = (current_actor(), req.attributes.as_mut()) {
+            if self.access.redacts_for(&actor) {
+                self.access.preserve_restricted(&before.attributes, attributes);
+            }
+        }
+        if let Some(attributes) = &req.attributes {
+            let card_type_str = serde_json::to_string(&before.card_type)
+                .map_err(|e| anyhow::anyhow!("Failed to serialize card type: {}", e))?
+                .trim_matches('"')
+                .to_string();
+            enforce_attribute_rules(&mut tx, &card_type_str, attributes).await?;
+        }
+
         let mut query_builder = sqlx::query(&update_query).bind(id);
 
         if let Some(name) = &req.name {
@@ -245,9 +759,6 @@ impl CardService {
                 .to_string();
             query_builder = query_builder.bind(phase_str);
         }
-        if let Some(quality_score) = req.quality_score {
-            query_builder = query_builder.bind(quality_score);
-        }
         if let Some(description) = &req.description {
             query_builder = query_builder.bind(description);
         }
@@ -257,22 +768,115 @@ impl CardService {
         if let Some(tags) = &req.tags {
             query_builder = query_builder.bind(tags);
         }
+        if let Some(owner_id) = req.owner_id {
+            query_builder = query_builder.bind(owner_id);
+        }
+        if let Some(team_id) = req.team_id {
+            query_builder = query_builder.bind(team_id);
+        }
         query_builder = query_builder.bind(now);
 
         query_builder
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update card: {}", e)))?;
 
-        self.get(id).await
+        refresh_quality(&mut tx, &[id], &self.quality).await?;
+        let card = self.fetch_locked(&mut tx, id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", id)))?;
+        VersionService::record_card(&mut tx, VersionOperation::Update, Some(&before), Some(&card)).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card update: {}", e)))?;
+        self.policy_changed(&card.card_type);
+
+        Ok(self.redacted(card))
+    }
+
+    /// Write a card back exactly as given, keeping its original ID.
+    /// Used to restore earlier versions and to compensate failed deletes.
+    pub async fn restore(&self, card: &Card) -> Result<Card, AppError> {
+        let card_type_str = serde_json::to_string(&card.card_type)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize card type: {}", e))?
+            .trim_matches('"')
+            .to_string();
+
+        let lifecycle_phase_str = serde_json::to_string(&card.lifecycle_phase)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize lifecycle phase: {}", e))?
+            .trim_matches('"')
+            .to_string();
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, card.id).await?;
+        self.authorize_change(&mut tx, before.as_ref().unwrap_or(card)).await?;
+        enforce_card_type_registered(&mut *tx, &card.card_type).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO cards (id, name, type, lifecycle_phase, description, owner_id, team_id, created_at, updated_at, attributes, tags, workspace_id, metamodel_version, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, COALESCE($13, '1.0'), 'active')
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                lifecycle_phase = EXCLUDED.lifecycle_phase,
+                description = EXCLUDED.description,
+                owner_id = EXCLUDED.owner_id,
+                team_id = EXCLUDED.team_id,
+                updated_at = EXCLUDED.updated_at,
+                attributes = EXCLUDED.attributes,
+                tags = EXCLUDED.tags,
+                metamodel_version = EXCLUDED.metamodel_version,
+                status = 'active'
+            "#,
+        )
+        .bind(card.id)
+        .bind(&card.name)
+        .bind(&card_type_str)
+        .bind(&lifecycle_phase_str)
+        .bind(&card.description)
+        .bind(card.owner_id)
+        .bind(card.team_id)
+        .bind(card.created_at)
+        .bind(Utc::now())
+        .bind(&card.attributes)
+        .bind(&card.tags)
+        .bind(before.as_ref().and_then(|b| b.workspace_id).or(card.workspace_id)
+            .or_else(current_workspace).unwrap_or(DEFAULT_WORKSPACE_ID))
+        // The restored attributes follow the metamodel version they were written in
+        .bind(&card.metamodel_version)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to restore card: {}", e)))?;
+
+        refresh_quality(&mut tx, &[card.id], &self.quality).await?;
+        let restored = self.fetch_locked(&mut tx, card.id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card.id)))?;
+        VersionService::record_card(&mut tx, VersionOperation::Restore, before.as_ref(), Some(&restored)).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card restore: {}", e)))?;
+        self.policy_changed(&restored.card_type);
+
+        Ok(self.redacted(restored))
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, id).await?;
+        if let Some(card) = &before {
+            self.authorize_change(&mut tx, card).await?;
+        }
+        // Relationships are removed by ON DELETE CASCADE; capture them first so they get a delete version too
+        let relationships = RelationshipService::fetch_for_card_locked(&mut tx, id).await?;
+
         let result = sqlx::query(
             "DELETE FROM cards WHERE id = $1"
         )
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete card: {}", e)))?;
 
@@ -280,9 +884,98 @@ impl CardService {
             return Err(AppError::NotFound(format!("Card {} not found", id)));
         }
 
+        VersionService::record_card(&mut tx, VersionOperation::Delete, before.as_ref(), None).await?;
+        for relationship in &relationships {
+            VersionService::record_relationship(&mut tx, VersionOperation::Delete, Some(relationship), None).await?;
+        }
+        // Cards that relied on the deleted card lost a relationship
+        let sources: Vec<Uuid> = relationships.iter()
+            .filter(|relationship| relationship.from_card_id != id)
+            .map(|relationship| relationship.from_card_id)
+            .collect();
+        refresh_quality(&mut tx, &sources, &self.quality).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card delete: {}", e)))?;
+        if let Some(card) = &before {
+            self.policy_changed(&card.card_type);
+        }
+
         Ok(())
     }
 
+    /// Migrate the next `limit` active cards of `card_type` after `after_id` to metamodel `version`.
+    /// Cards `migrate` changes get a version entry; the rest are only stamped with the version.
+    /// Runs across every workspace. Returns the last card ID seen, cards processed and cards changed.
+    pub(crate) async fn migrate_batch(
+        &self,
+        card_type: &str,
+        after_id: Option<Uuid>,
+        limit: i64,
+        version: &str,
+        migrate: impl Fn(&Card) -> Option<Card>,
+    ) -> Result<(Option<Uuid>, usize, usize), AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM cards WHERE type = $1 AND status = 'active' AND ($2::uuid IS NULL OR id > $2) ORDER BY id LIMIT $3 FOR UPDATE",
+            CARD_COLUMNS
+        ))
+        .bind(card_type)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch cards to migrate: {}", e)))?;
+
+        let cards = rows.into_iter().map(|row| self.row_to_card(row)).collect::<Result<Vec<_>, _>>()?;
+        let mut changed = 0;
+
+        for card in &cards {
+            let Some(migrated) = migrate(card) else {
+                sqlx::query("UPDATE cards SET metamodel_version = $2 WHERE id = $1")
+                    .bind(card.id)
+                    .bind(version)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to stamp card version: {}", e)))?;
+                continue;
+            };
+
+            let card_type_str = serde_json::to_string(&migrated.card_type)
+                .map_err(|e| anyhow::anyhow!("Failed to serialize card type: {}", e))?
+                .trim_matches('"')
+                .to_string();
+
+            sqlx::query(
+                "UPDATE cards SET type = $2, attributes = $3, tags = $4, status = $5, metamodel_version = $6, updated_at = $7 WHERE id = $1"
+            )
+            .bind(card.id)
+            .bind(&card_type_str)
+            .bind(&migrated.attributes)
+            .bind(&migrated.tags)
+            .bind(&migrated.status)
+            .bind(version)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to migrate card: {}", e)))?;
+
+            let after = self.fetch_locked(&mut tx, card.id).await?
+                .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card.id)))?;
+            VersionService::record_card(&mut tx, VersionOperation::Update, Some(card), Some(&after)).await?;
+            changed += 1;
+        }
+        let ids: Vec<Uuid> = cards.iter().map(|card| card.id).collect();
+        refresh_quality(&mut tx, &ids, &self.quality).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card migration: {}", e)))?;
+
+        Ok((cards.last().map(|card| card.id), cards.len(), changed))
+    }
+
     /// Delete ALL cards from the database (for testing/cleanup purposes)
     pub async fn delete_all(&self) -> Result<u64, AppError> {
         let result = sqlx::query("DELETE FROM cards")
@@ -295,6 +988,101 @@ impl CardService {
 }
 
 impl CardService {
+    /// Policies score every card, so a policy change rescores them all in the background
+    fn policy_changed(&self, card_type: &CardType) {
+        if *card_type == CardType::ArchitecturePolicy {
+            spawn_recalculation(self.pool.clone(), self.quality.clone(), None);
+        }
+    }
+
+    /// Apply ownership and governance rules for the current caller to an existing card
+    async fn authorize_change(&self, conn: &mut sqlx::PgConnection, card: &Card) -> Result<(), AppError> {
+        let Some(actor) = current_actor() else {
+            return Ok(());
+        };
+        if let (Some(workspace_id), Some(home)) = (actor.workspace_id, card.workspace_id) {
+            if workspace_id != home {
+                return Err(self.foreign_card_error(&mut *conn, card.id, workspace_id).await?);
+            }
+        }
+        self.access.check_card_type(&actor, &card.card_type)?;
+
+        let is_team_member = match (card.team_id, actor.id) {
+            (Some(team_id), Some(user_id)) => {
+                sqlx::query_scalar::<_, bool>(
+                    "SELECT EXISTS(SELECT 1 FROM team_members WHERE team_id = $1 AND user_id = $2)",
+                )
+                .bind(team_id)
+                .bind(user_id)
+                .fetch_one(conn)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check team membership: {}", e)))?
+            }
+            _ => false,
+        };
+        self.access.check_ownership(&actor, card, is_team_member)
+    }
+
+    /// Cards shared into the workspace are read-only there; any other card does not exist for it
+    async fn foreign_card_error(&self, conn: &mut sqlx::PgConnection, card_id: Uuid, workspace_id: Uuid) -> Result<AppError, AppError> {
+        let shared = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM card_shares WHERE card_id = $1 AND workspace_id = $2)",
+        )
+        .bind(card_id)
+        .bind(workspace_id)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check card share: {}", e)))?;
+
+        Ok(if shared {
+            AppError::Forbidden(format!("Card {} is shared read-only with this workspace", card_id))
+        } else {
+            AppError::NotFound(format!("Card {} not found", card_id))
+        })
+    }
+
+    fn redacts_for_caller(&self) -> bool {
+        current_actor().is_some_and(|actor| self.access.redacts_for(&actor))
+    }
+
+    /// Attribute keys the current caller may not see, nor filter or sort by
+    fn restricted_for_caller(&self) -> Vec<String> {
+        current_actor().map(|actor| self.access.restricted_for(&actor).to_vec()).unwrap_or_default()
+    }
+
+    /// The access policy if the current caller gets restricted attributes removed, for
+    /// callers that redact serialized cards themselves
+    pub fn redaction_for_caller(&self) -> Option<&CardAccessPolicy> {
+        self.redacts_for_caller().then_some(&self.access)
+    }
+
+    /// Remove restricted attributes if the current caller may not see them
+    pub fn redacted(&self, mut card: Card) -> Card {
+        if self.redacts_for_caller() {
+            self.access.redact(&mut card);
+        }
+        card
+    }
+
+    /// Version history counterpart of `redacted`
+    pub fn redacted_versions(&self, mut versions: Vec<CardVersion>) -> Vec<CardVersion> {
+        if self.redacts_for_caller() {
+            versions.iter_mut().for_each(|version| self.access.redact_version(version));
+        }
+        versions
+    }
+
+    /// Read a card inside a transaction, locking the row until commit
+    async fn fetch_locked(&self, conn: &mut sqlx::PgConnection, id: Uuid) -> Result<Option<Card>, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM cards WHERE id = $1 FOR UPDATE", CARD_COLUMNS))
+            .bind(id)
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card: {}", e)))?;
+
+        row.map(|row| self.row_to_card(row)).transpose()
+    }
+
     fn row_to_card(&self, row: sqlx::postgres::PgRow) -> Result<Card, AppError> {
         let type_str: String = row.try_get("type")
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing type column: {}", e)))?;
@@ -318,6 +1106,9 @@ impl CardService {
             quality_score: row.try_get("quality_score").ok(),
             description: row.try_get("description").ok(),
             owner_id: row.try_get("owner_id").ok(),
+            team_id: row.try_get("team_id").ok(),
+            workspace_id: row.try_get("workspace_id").ok(),
+            metamodel_version: row.try_get("metamodel_version").ok(),
             created_at: row.try_get("created_at")
                 .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing created_at: {}", e)))?,
             updated_at: row.try_get("updated_at")
@@ -0,0 +1,224 @@
+/*!
+ * Card Similarity
+ *
+ * Scoring behind "Similar Cards" (FR-SEARCH-05 of docs/00-prd.md): shared tags weigh 70%,
+ * shared attribute values 20% and graph proximity 10%. Each part is a 0..1 overlap
+ * (Jaccard index) and the weighted sum is reported as 0-100. Cards that score high
+ * against each other are likely duplicates.
+ *
+ * Missing relationships are suggested from the card's most similar peers: a relationship
+ * many peers have, weighted by how similar each peer is, is one the card probably lacks.
+ */
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::card::Card;
+use crate::models::card_query::RelationshipDirection;
+
+pub const TAG_WEIGHT: f64 = 0.7;
+pub const ATTRIBUTE_WEIGHT: f64 = 0.2;
+pub const GRAPH_WEIGHT: f64 = 0.1;
+
+/// A current relationship seen from one of its cards
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub relationship_type: String,
+    pub direction: RelationshipDirection,
+    pub other: Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Similarity {
+    /// Weighted score, 0-100
+    pub score: f64,
+    pub tag_score: f64,
+    pub attribute_score: f64,
+    pub graph_score: f64,
+    pub shared_tags: Vec<String>,
+    pub shared_attributes: Vec<String>,
+    pub shared_neighbours: usize,
+}
+
+/// A relationship the similar cards have and the card does not
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub edge: Edge,
+    /// Share of the peers' similarity behind the suggestion, 0-100
+    pub confidence: f64,
+    pub supporting_cards: Vec<Uuid>,
+}
+
+/// Similarity of `other` to `card`, given the current relationships of each
+pub fn similarity(card: &Card, card_edges: &[Edge], other: &Card, other_edges: &[Edge]) -> Similarity {
+    let tags = |card: &Card| card.tags.iter().map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty()).collect::<BTreeSet<_>>();
+    let (card_tags, other_tags) = (tags(card), tags(other));
+    let shared_tags: Vec<String> = card_tags.intersection(&other_tags).cloned().collect();
+    let tag_score = jaccard(shared_tags.len(), card_tags.union(&other_tags).count());
+
+    let (card_attributes, other_attributes) = (attribute_values(&card.attributes), attribute_values(&other.attributes));
+    let shared_attributes: Vec<String> = card_attributes.iter()
+        .filter(|(key, value)| other_attributes.get(*key) == Some(value))
+        .map(|(key, _)| key.to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let attribute_keys = card_attributes.keys().chain(other_attributes.keys()).collect::<HashSet<_>>().len();
+    let attribute_score = jaccard(shared_attributes.len(), attribute_keys);
+
+    // Directly related cards are as close as cards can be; otherwise compare their neighbours
+    let neighbours = |edges: &[Edge], exclude: Uuid| edges.iter().map(|edge| edge.other).filter(|id| *id != exclude).collect::<HashSet<_>>();
+    let (card_neighbours, other_neighbours) = (neighbours(card_edges, other.id), neighbours(other_edges, card.id));
+    let shared_neighbours = card_neighbours.intersection(&other_neighbours).count();
+    let graph_score = if card_edges.iter().any(|edge| edge.other == other.id) {
+        1.0
+    } else {
+        jaccard(shared_neighbours, card_neighbours.union(&other_neighbours).count())
+    };
+
+    let score = 100.0 * (TAG_WEIGHT * tag_score + ATTRIBUTE_WEIGHT * attribute_score + GRAPH_WEIGHT * graph_score);
+    Similarity {
+        score: round(score),
+        tag_score: round(tag_score),
+        attribute_score: round(attribute_score),
+        graph_score: round(graph_score),
+        shared_tags,
+        shared_attributes,
+        shared_neighbours,
+    }
+}
+
+/// Relationships of the peers (`(peer id, similarity score, peer edges)`) that `card` lacks,
+/// most confident first
+pub fn suggest_relationships(card_id: Uuid, card_edges: &[Edge], peers: &[(Uuid, f64, &[Edge])]) -> Vec<Suggestion> {
+    let total: f64 = peers.iter().map(|(_, score, _)| score).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let existing: HashSet<&Edge> = card_edges.iter().collect();
+
+    let mut support: HashMap<&Edge, (f64, Vec<Uuid>)> = HashMap::new();
+    for (peer_id, score, edges) in peers {
+        for edge in edges.iter().collect::<HashSet<_>>() {
+            if edge.other == card_id || existing.contains(edge) {
+                continue;
+            }
+            let entry = support.entry(edge).or_default();
+            entry.0 += score;
+            entry.1.push(*peer_id);
+        }
+    }
+
+    let mut suggestions: Vec<Suggestion> = support.into_iter()
+        .map(|(edge, (weight, supporting_cards))| Suggestion {
+            edge: edge.clone(),
+            confidence: round(100.0 * weight / total),
+            supporting_cards,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.confidence.total_cmp(&a.confidence)
+            .then(b.supporting_cards.len().cmp(&a.supporting_cards.len()))
+            .then(a.edge.other.cmp(&b.edge.other))
+            .then(a.edge.relationship_type.cmp(&b.edge.relationship_type))
+    });
+    suggestions
+}
+
+/// Scalar attribute values by key, strings compared case-insensitively; nulls and empty strings are unset
+fn attribute_values(attributes: &Value) -> HashMap<&str, Value> {
+    let Some(attributes) = attributes.as_object() else {
+        return HashMap::new();
+    };
+    attributes.iter()
+        .filter_map(|(key, value)| match value {
+            Value::Null => None,
+            Value::String(text) if text.trim().is_empty() => None,
+            Value::String(text) => Some((key.as_str(), Value::String(text.trim().to_lowercase()))),
+            other => Some((key.as_str(), other.clone())),
+        })
+        .collect()
+}
+
+fn jaccard(shared: usize, union: usize) -> f64 {
+    if union == 0 { 0.0 } else { shared as f64 / union as f64 }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use crate::models::card::{CardType, LifecyclePhase};
+
+    fn card(name: &str, tags: &[&str], attributes: Value) -> Card {
+        Card {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            card_type: CardType::Application,
+            lifecycle_phase: LifecyclePhase::Active,
+            quality_score: None,
+            description: None,
+            owner_id: None,
+            team_id: None,
+            workspace_id: None,
+            metamodel_version: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            attributes,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            status: "active".to_string(),
+        }
+    }
+
+    fn relies_on(other: Uuid) -> Edge {
+        Edge { relationship_type: "reliesOn".to_string(), direction: RelationshipDirection::Outgoing, other }
+    }
+
+    #[test]
+    fn test_similarity_weights() {
+        let database = Uuid::new_v4();
+        let crm = card("CRM", &["sales", "Legacy"], json!({"vendor": "Acme", "hosting_type": "SaaS", "version": null}));
+        let crm_copy = card("CRM (copy)", &["legacy", "sales"], json!({"vendor": "acme ", "hosting_type": "OnPrem"}));
+
+        let scored = similarity(&crm, &[relies_on(database)], &crm_copy, &[relies_on(database)]);
+        assert_eq!(scored.shared_tags, vec!["legacy".to_string(), "sales".to_string()]);
+        assert_eq!(scored.shared_attributes, vec!["vendor".to_string()]);
+        assert_eq!((scored.tag_score, scored.attribute_score, scored.graph_score), (1.0, 0.5, 1.0));
+        assert_eq!(scored.shared_neighbours, 1);
+        assert_eq!(scored.score, 90.0);
+
+        let unrelated = card("Payroll", &["hr"], json!({}));
+        assert_eq!(similarity(&crm, &[], &unrelated, &[]).score, 0.0);
+
+        // A direct relationship counts as full graph proximity
+        let scored = similarity(&crm, &[relies_on(unrelated.id)], &unrelated, &[]);
+        assert_eq!(scored.graph_score, 1.0);
+        assert_eq!(scored.score, 10.0);
+    }
+
+    #[test]
+    fn test_suggest_relationships() {
+        let (card_id, database, queue, cache) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (peer_a, peer_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let peer_a_edges = vec![relies_on(database), relies_on(queue), relies_on(card_id)];
+        let peer_b_edges = vec![relies_on(database), relies_on(cache)];
+
+        let suggestions = suggest_relationships(
+            card_id,
+            &[relies_on(cache)],
+            &[(peer_a, 75.0, &peer_a_edges), (peer_b, 25.0, &peer_b_edges)],
+        );
+        let found: Vec<(Uuid, f64)> = suggestions.iter().map(|s| (s.edge.other, s.confidence)).collect();
+        assert_eq!(found, vec![(database, 100.0), (queue, 75.0)]);
+        assert_eq!(suggestions[0].supporting_cards.len(), 2);
+
+        assert!(suggest_relationships(card_id, &[], &[]).is_empty());
+    }
+}
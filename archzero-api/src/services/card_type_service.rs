@@ -0,0 +1,454 @@
+/*!
+ * Card Type Service
+ *
+ * Registry of card types. Built-in types are seeded by migration and can be restyled
+ * but not deleted; admins register further types (e.g. `DataObject`, `Organization`)
+ * at runtime. Each type carries its layer, an attribute schema checked on card writes,
+ * the relationship types its cards may take part in, and its display colour.
+ */
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde_json::Value;
+use sqlx::{PgExecutor, PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::CardType;
+use crate::models::card_type::*;
+use crate::models::metamodel::{AttributeViolation, ViolationDetails};
+use crate::models::relationship::RelationshipType;
+
+const CARD_TYPE_COLUMNS: &str = "name, label, layer, description, attribute_schema, allowed_relationship_types, \
+                                 color, built_in, created_by, created_at, updated_at";
+
+/// Colour of types missing from the registry
+pub const DEFAULT_CARD_COLOR: &str = "#64748b";
+
+pub struct CardTypeService {
+    pool: PgPool,
+}
+
+impl CardTypeService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, layer: Option<CardLayer>) -> Result<Vec<CardTypeDefinition>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM card_types WHERE ($1::text IS NULL OR layer = $1) ORDER BY built_in DESC, layer, name",
+            CARD_TYPE_COLUMNS
+        ))
+        .bind(layer.map(|layer| layer.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list card types: {}", e)))?;
+
+        rows.iter().map(row_to_card_type).collect()
+    }
+
+    pub async fn get(&self, name: &str) -> Result<CardTypeDefinition, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM card_types WHERE name = $1", CARD_TYPE_COLUMNS))
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card type: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Card type {} not found", name)))?;
+
+        row_to_card_type(&row)
+    }
+
+    /// Display colour of every registered type, by name
+    pub async fn colors(&self) -> Result<HashMap<String, String>, AppError> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT name, color FROM card_types")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load card type colours: {}", e)))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Register a new card type
+    pub async fn create(&self, req: CreateCardTypeRequest, created_by: Option<Uuid>) -> Result<CardTypeDefinition, AppError> {
+        let name_pattern = Regex::new(r"^[A-Z][A-Za-z0-9]{1,49}$").expect("valid card type name pattern");
+        if !name_pattern.is_match(&req.name) {
+            return Err(AppError::Validation(
+                "Card type names are PascalCase letters and digits, 2 to 50 characters".to_string(),
+            ));
+        }
+        if CardType::from_name(&req.name).is_built_in() {
+            return Err(AppError::Validation(format!("{} is a built-in card type", req.name)));
+        }
+        validate_definition(&req.color, &req.attribute_schema, &req.allowed_relationship_types)?;
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO card_types (name, label, layer, description, attribute_schema, allowed_relationship_types, color, created_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING {}",
+            CARD_TYPE_COLUMNS
+        ))
+        .bind(&req.name)
+        .bind(req.label.as_deref().unwrap_or(&req.name))
+        .bind(req.layer.as_str())
+        .bind(&req.description)
+        .bind(schema_json(&req.attribute_schema)?)
+        .bind(&req.allowed_relationship_types)
+        .bind(&req.color)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        row_to_card_type(&row)
+    }
+
+    pub async fn update(&self, name: &str, req: UpdateCardTypeRequest) -> Result<CardTypeDefinition, AppError> {
+        if req.label.trim().is_empty() {
+            return Err(AppError::Validation("Label is required".to_string()));
+        }
+        validate_definition(&req.color, &req.attribute_schema, &req.allowed_relationship_types)?;
+
+        let row = sqlx::query(&format!(
+            "UPDATE card_types SET label = $2, layer = $3, description = $4, attribute_schema = $5, \
+             allowed_relationship_types = $6, color = $7, updated_at = NOW() WHERE name = $1 RETURNING {}",
+            CARD_TYPE_COLUMNS
+        ))
+        .bind(name)
+        .bind(req.label.trim())
+        .bind(req.layer.as_str())
+        .bind(&req.description)
+        .bind(schema_json(&req.attribute_schema)?)
+        .bind(&req.allowed_relationship_types)
+        .bind(&req.color)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update card type: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Card type {} not found", name)))?;
+
+        row_to_card_type(&row)
+    }
+
+    /// Delete a custom type no card uses, with its attribute rules
+    pub async fn delete(&self, name: &str) -> Result<(), AppError> {
+        let definition = self.get(name).await?;
+        if definition.built_in {
+            return Err(AppError::Validation(format!("{} is a built-in card type and cannot be deleted", name)));
+        }
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let cards: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cards WHERE type = $1")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count cards: {}", e)))?;
+        if cards > 0 {
+            return Err(AppError::Validation(format!(
+                "{} cards still use card type {}; retire it with a metamodel migration instead",
+                cards, name
+            )));
+        }
+
+        sqlx::query("DELETE FROM metamodel_rules WHERE card_type = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete card type rules: {}", e)))?;
+        sqlx::query("DELETE FROM card_types WHERE name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete card type: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit card type delete: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Reject card types that are neither built in nor registered
+pub(crate) async fn enforce_card_type_registered<'e, E: PgExecutor<'e>>(
+    executor: E,
+    card_type: &CardType,
+) -> Result<(), AppError> {
+    if card_type.is_built_in() {
+        return Ok(());
+    }
+
+    let registered: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM card_types WHERE name = $1)")
+        .bind(card_type.as_str())
+        .fetch_one(executor)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check card type: {}", e)))?;
+
+    if registered {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Unknown card type: {}", card_type)))
+    }
+}
+
+/// Attribute schema of a card type; empty if the type is not registered
+pub(crate) async fn load_attribute_schema<'e, E: PgExecutor<'e>>(
+    executor: E,
+    card_type: &str,
+) -> Result<Vec<AttributeDefinition>, AppError> {
+    let schema: Option<Value> = sqlx::query_scalar("SELECT attribute_schema FROM card_types WHERE name = $1")
+        .bind(card_type)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load attribute schema: {}", e)))?;
+
+    schema
+        .map(|schema| {
+            serde_json::from_value(schema)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid attribute schema of {}: {}", card_type, e)))
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Reject a relationship type either endpoint's card type does not allow
+pub(crate) async fn enforce_relationship_allowed<'e, E: PgExecutor<'e>>(
+    executor: E,
+    from_card_id: Uuid,
+    to_card_id: Uuid,
+    relationship_type: &str,
+) -> Result<(), AppError> {
+    let rows = sqlx::query(
+        "SELECT c.type, t.allowed_relationship_types FROM cards c \
+         LEFT JOIN card_types t ON t.name = c.type WHERE c.id IN ($1, $2)",
+    )
+    .bind(from_card_id)
+    .bind(to_card_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load allowed relationship types: {}", e)))?;
+
+    for row in rows {
+        let card_type: String = row.try_get("type").map_err(missing("type"))?;
+        let allowed: Option<Vec<String>> = row.try_get("allowed_relationship_types").map_err(missing("allowed_relationship_types"))?;
+        if let Some(allowed) = allowed.filter(|allowed| !allowed.is_empty()) {
+            if !allowed.iter().any(|allowed| allowed == relationship_type) {
+                return Err(AppError::Validation(format!(
+                    "{} cards do not allow {} relationships (allowed: {})",
+                    card_type,
+                    relationship_type,
+                    allowed.join(", ")
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check attributes against a card type's schema; a JSON null counts as missing
+pub fn check_schema(schema: &[AttributeDefinition], attributes: &Value) -> Vec<AttributeViolation> {
+    let mut violations = Vec::new();
+
+    for definition in schema {
+        let field = format!("attributes.{}", definition.key);
+        let Some(value) = attributes.get(&definition.key).filter(|value| !value.is_null()) else {
+            if definition.required {
+                violations.push(AttributeViolation {
+                    field,
+                    message: "Missing required attribute".to_string(),
+                    details: ViolationDetails {
+                        allowed_values: definition.allowed_values.clone(),
+                        ..Default::default()
+                    },
+                });
+            }
+            continue;
+        };
+
+        let type_matches = match definition.data_type {
+            AttributeDataType::String => value.is_string(),
+            AttributeDataType::Integer => value.is_i64() || value.is_u64(),
+            AttributeDataType::Number => value.is_number(),
+            AttributeDataType::Boolean => value.is_boolean(),
+            AttributeDataType::Date => value
+                .as_str()
+                .is_some_and(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+                    || chrono::DateTime::parse_from_rfc3339(date).is_ok()),
+            AttributeDataType::Object => value.is_object(),
+            AttributeDataType::Array => value.is_array(),
+        };
+        if !type_matches {
+            violations.push(AttributeViolation {
+                field,
+                message: format!("Attribute must be {}", data_type_name(definition.data_type)),
+                details: ViolationDetails {
+                    provided_value: Some(value.clone()),
+                    ..Default::default()
+                },
+            });
+            continue;
+        }
+
+        if let Some(allowed) = &definition.allowed_values {
+            if !value.as_str().is_some_and(|value| allowed.iter().any(|allowed| allowed == value)) {
+                violations.push(AttributeViolation {
+                    field,
+                    message: "Invalid enum value".to_string(),
+                    details: ViolationDetails {
+                        provided_value: Some(value.clone()),
+                        allowed_values: Some(allowed.clone()),
+                        ..Default::default()
+                    },
+                });
+                continue;
+            }
+        }
+
+        if let Some(number) = value.as_f64() {
+            let below = definition.min.is_some_and(|min| number < min);
+            let above = definition.max.is_some_and(|max| number > max);
+            if below || above {
+                let bound = |bound: Option<f64>| bound.map_or("-".to_string(), |bound| bound.to_string());
+                violations.push(AttributeViolation {
+                    field,
+                    message: format!("Attribute must be between {} and {}", bound(definition.min), bound(definition.max)),
+                    details: ViolationDetails {
+                        provided_value: Some(value.clone()),
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+fn data_type_name(data_type: AttributeDataType) -> &'static str {
+    match data_type {
+        AttributeDataType::String => "a string",
+        AttributeDataType::Integer => "an integer",
+        AttributeDataType::Number => "a number",
+        AttributeDataType::Boolean => "a boolean",
+        AttributeDataType::Date => "an ISO 8601 date",
+        AttributeDataType::Object => "an object",
+        AttributeDataType::Array => "an array",
+    }
+}
+
+fn validate_definition(
+    color: &str,
+    schema: &[AttributeDefinition],
+    allowed_relationship_types: &[String],
+) -> Result<(), AppError> {
+    let color_pattern = Regex::new(r"^#[0-9a-fA-F]{6}$").expect("valid colour pattern");
+    if !color_pattern.is_match(color) {
+        return Err(AppError::Validation("Colour must be a hex colour like #3b82f6".to_string()));
+    }
+
+    for relationship_type in allowed_relationship_types {
+        serde_json::from_value::<RelationshipType>(Value::String(relationship_type.clone()))
+            .map_err(|_| AppError::Validation(format!("Unknown relationship type: {}", relationship_type)))?;
+    }
+
+    let mut keys = std::collections::HashSet::new();
+    for definition in schema {
+        if definition.key.trim().is_empty() {
+            return Err(AppError::Validation("Attribute keys must not be empty".to_string()));
+        }
+        if !keys.insert(definition.key.as_str()) {
+            return Err(AppError::Validation(format!("Attribute {} is defined twice", definition.key)));
+        }
+        if definition.allowed_values.as_ref().is_some_and(|values| values.is_empty()) {
+            return Err(AppError::Validation(format!("Allowed values of {} must not be empty", definition.key)));
+        }
+        if let (Some(min), Some(max)) = (definition.min, definition.max) {
+            if min > max {
+                return Err(AppError::Validation(format!("Minimum of {} is above its maximum", definition.key)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn schema_json(schema: &[AttributeDefinition]) -> Result<Value, AppError> {
+    serde_json::to_value(schema)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize attribute schema: {}", e)))
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_card_type(row: &sqlx::postgres::PgRow) -> Result<CardTypeDefinition, AppError> {
+    let layer: String = row.try_get("layer").map_err(missing("layer"))?;
+    let schema: Value = row.try_get("attribute_schema").map_err(missing("attribute_schema"))?;
+
+    Ok(CardTypeDefinition {
+        name: row.try_get("name").map_err(missing("name"))?,
+        label: row.try_get("label").map_err(missing("label"))?,
+        layer: CardLayer::parse(&layer)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid card layer: {}", layer)))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        attribute_schema: serde_json::from_value(schema)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid attribute schema: {}", e)))?,
+        allowed_relationship_types: row.try_get("allowed_relationship_types").map_err(missing("allowed_relationship_types"))?,
+        color: row.try_get("color").map_err(missing("color"))?,
+        built_in: row.try_get("built_in").map_err(missing("built_in"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn attribute(key: &str, data_type: AttributeDataType, required: bool) -> AttributeDefinition {
+        AttributeDefinition {
+            key: key.to_string(),
+            data_type,
+            required,
+            allowed_values: None,
+            min: None,
+            max: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_check_schema() {
+        let mut org_type = attribute("org_type", AttributeDataType::String, true);
+        org_type.allowed_values = Some(vec!["Department".to_string(), "Vendor".to_string()]);
+        let mut headcount = attribute("headcount", AttributeDataType::Integer, false);
+        headcount.min = Some(0.0);
+        let schema = vec![org_type, headcount, attribute("founded", AttributeDataType::Date, false)];
+
+        assert!(check_schema(&schema, &json!({"org_type": "Vendor", "headcount": 45, "founded": "2001-04-01"})).is_empty());
+
+        let violations = check_schema(&schema, &json!({"headcount": -1, "founded": "April 2001"}));
+        assert_eq!(violations.len(), 3);
+        assert_eq!(violations[0].field, "attributes.org_type");
+        assert_eq!(violations[0].message, "Missing required attribute");
+        assert_eq!(violations[1].message, "Attribute must be between 0 and -");
+        assert_eq!(violations[2].message, "Attribute must be an ISO 8601 date");
+
+        let violations = check_schema(&schema, &json!({"org_type": "Partner", "headcount": 4.5}));
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].message, "Invalid enum value");
+        assert_eq!(violations[1].message, "Attribute must be an integer");
+    }
+
+    #[test]
+    fn test_validate_definition() {
+        assert!(validate_definition("#3b82f6", &[], &["dependsOn".to_string()]).is_ok());
+        assert!(validate_definition("blue", &[], &[]).is_err());
+        assert!(validate_definition("#3b82f6", &[], &["ownedBy".to_string()]).is_err());
+
+        let twice = vec![
+            attribute("owner", AttributeDataType::String, false),
+            attribute("owner", AttributeDataType::String, true),
+        ];
+        assert!(validate_definition("#3b82f6", &twice, &[]).is_err());
+    }
+}
@@ -1,120 +1,136 @@
 /**
  * CSRF Token Service
  *
- * Provides Cross-Site Request Forgery protection using token-based validation.
- * Tokens are generated using cryptographically secure random bytes and
- * stored with expiration times for validation.
+ * Provides Cross-Site Request Forgery protection using signed double-submit tokens.
+ * A token is `<nonce>.<expires_at_ms>.<signature>`, where the signature is an
+ * HMAC-SHA256 over the nonce and expiry. The token is issued in a cookie and echoed
+ * back by the browser in the `X-CSRF-Token` header; a cross-site page can make the
+ * browser send the cookie but cannot read it to set the header.
+ *
+ * Tokens are validated from their signature alone, so every replica sharing the
+ * secret accepts them without shared storage.
  */
 
-use std::collections::HashMap;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use uuid::Uuid;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
 
-#[derive(Clone, Debug)]
-pub struct CsrfToken {
-    pub token: String,
-    pub expires_at: Instant,
-}
+use crate::config::Csrf;
+
+type HmacSha256 = Hmac<Sha256>;
 
 pub struct CsrfService {
-    tokens: RwLock<HashMap<String, CsrfToken>>,
+    key: Vec<u8>,
     default_ttl: Duration,
+    cookie_name: String,
+    session_cookies: Vec<String>,
+    enabled: bool,
+    secure_cookie: bool,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
 }
 
 impl CsrfService {
-    /// Create a new CSRF service
-    pub fn new() -> Self {
+    /// Create a CSRF service signing with `config.secret`, or `fallback_secret` if unset
+    pub fn new(config: Csrf, fallback_secret: &str) -> Self {
+        let secret = config.secret.as_deref().unwrap_or(fallback_secret);
         Self {
-            tokens: RwLock::new(HashMap::new()),
-            default_ttl: Duration::from_secs(3600), // 1 hour default
+            // Domain-separated so a CSRF signature is never a valid signature elsewhere
+            key: format!("archzero-csrf:{}", secret).into_bytes(),
+            default_ttl: Duration::from_secs(config.token_ttl_seconds),
+            cookie_name: config.cookie_name,
+            session_cookies: config.session_cookies,
+            enabled: config.enabled,
+            secure_cookie: false,
         }
     }
 
     /// Create a new CSRF service with custom TTL
-    pub fn with_ttl(ttl: Duration) -> Self {
-        Self {
-            tokens: RwLock::new(HashMap::new()),
-            default_ttl: ttl,
-        }
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
     }
 
-    /// Generate a new CSRF token
-    pub async fn generate_token(&self) -> String {
-        let token = Uuid::new_v4().to_string();
-        let csrf_token = CsrfToken {
-            token: token.clone(),
-            expires_at: Instant::now() + self.default_ttl,
-        };
-
-        self.tokens.write().await.insert(token.clone(), csrf_token);
-        token
+    /// Mark the token cookie `Secure` (HTTPS only)
+    pub fn with_secure_cookie(mut self, secure: bool) -> Self {
+        self.secure_cookie = secure;
+        self
     }
 
-    /// Generate a new CSRF token with custom expiration
-    pub async fn generate_token_with_ttl(&self, ttl: Duration) -> String {
-        let token = Uuid::new_v4().to_string();
-        let csrf_token = CsrfToken {
-            token: token.clone(),
-            expires_at: Instant::now() + ttl,
-        };
-
-        self.tokens.write().await.insert(token.clone(), csrf_token);
-        token
+    /// Whether the middleware checks requests at all
+    pub fn enabled(&self) -> bool {
+        self.enabled
     }
 
-    /// Validate a CSRF token
-    pub async fn validate_token(&self, token: &str) -> bool {
-        let tokens = self.tokens.read().await;
-
-        if let Some(csrf_token) = tokens.get(token) {
-            // Check if token has expired
-            if csrf_token.expires_at > Instant::now() {
-                return true;
-            }
-        }
-
-        false
+    pub fn cookie_name(&self) -> &str {
+        &self.cookie_name
     }
 
-    /// Validate and consume a CSRF token (one-time use)
-    pub async fn validate_and_consume_token(&self, token: &str) -> bool {
-        let tokens = self.tokens.write().await;
-
-        if let Some(csrf_token) = tokens.get(token) {
-            // Check if token has expired
-            if csrf_token.expires_at > Instant::now() {
-                // Remove token after successful validation (one-time use)
-                drop(tokens);
-                self.tokens.write().await.remove(token);
-                return true;
-            }
-        }
+    /// Cookies that make a request cookie-authenticated, and so subject to the check
+    pub fn session_cookies(&self) -> &[String] {
+        &self.session_cookies
+    }
 
-        false
+    pub fn ttl(&self) -> Duration {
+        self.default_ttl
     }
 
-    /// Clean up expired tokens (should be called periodically)
-    pub async fn cleanup_expired_tokens(&self) -> usize {
-        let mut tokens = self.tokens.write().await;
-        let now = Instant::now();
+    fn sign(&self, payload: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    }
 
-        let before_count = tokens.len();
-        tokens.retain(|_, csrf_token| csrf_token.expires_at > now);
-        let after_count = tokens.len();
+    /// Generate a new CSRF token
+    pub fn generate_token(&self) -> String {
+        self.generate_token_with_ttl(self.default_ttl)
+    }
 
-        before_count - after_count
+    /// Generate a new CSRF token with custom expiration
+    pub fn generate_token_with_ttl(&self, ttl: Duration) -> String {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let payload = format!("{}.{}", hex::encode(nonce), now_ms() + ttl.as_millis() as u64);
+        let signature = self.sign(&payload);
+        format!("{}.{}", payload, signature)
     }
 
-    /// Get the number of active tokens
-    pub async fn active_token_count(&self) -> usize {
-        self.tokens.read().await.len()
+    /// Validate a CSRF token's signature and expiry
+    pub fn validate_token(&self, token: &str) -> bool {
+        let Some((payload, signature)) = token.rsplit_once('.') else {
+            return false;
+        };
+        let Some(expires_at) = payload.split_once('.').and_then(|(_, expiry)| expiry.parse::<u64>().ok()) else {
+            return false;
+        };
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+            return false;
+        };
+
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).is_ok() && expires_at > now_ms()
     }
-}
 
-impl Default for CsrfService {
-    fn default() -> Self {
-        Self::new()
+    /// `Set-Cookie` value issuing `token`. Not `HttpOnly`: the frontend reads it to set the header.
+    pub fn cookie(&self, token: &str) -> String {
+        let mut cookie = format!(
+            "{}={}; Path=/; Max-Age={}; SameSite=Strict",
+            self.cookie_name,
+            token,
+            self.default_ttl.as_secs()
+        );
+        if self.secure_cookie {
+            cookie.push_str("; Secure");
+        }
+        cookie
     }
 }
 
@@ -122,58 +138,57 @@ impl Default for CsrfService {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_generate_and_validate_token() {
-        let service = CsrfService::new();
-        let token = service.generate_token().await;
+    fn service() -> CsrfService {
+        CsrfService::new(Csrf::default(), "test-secret")
+    }
+
+    #[test]
+    fn test_generate_and_validate_token() {
+        let service = service();
+        let token = service.generate_token();
 
-        assert!(service.validate_token(&token).await);
+        assert!(service.validate_token(&token));
     }
 
-    #[tokio::test]
-    async fn test_validate_and_consume_token() {
-        let service = CsrfService::new();
-        let token = service.generate_token().await;
+    #[test]
+    fn test_token_valid_across_instances_sharing_secret() {
+        let token = service().generate_token();
 
-        // First validation should succeed and consume the token
-        assert!(service.validate_and_consume_token(&token).await);
-        // Second validation should fail
-        assert!(!service.validate_and_consume_token(&token).await);
+        // Another replica with the same secret accepts it; one with a different secret does not
+        assert!(service().validate_token(&token));
+        assert!(!CsrfService::new(Csrf::default(), "other-secret").validate_token(&token));
     }
 
-    #[tokio::test]
-    async fn test_invalid_token() {
-        let service = CsrfService::new();
-        assert!(!service.validate_token("invalid-token").await);
+    #[test]
+    fn test_invalid_token() {
+        let service = service();
+        assert!(!service.validate_token("invalid-token"));
+
+        // Tampering with the expiry breaks the signature
+        let token = service.generate_token();
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let extended = (now_ms() + 86_400_000).to_string();
+        parts[1] = &extended;
+        assert!(!service.validate_token(&parts.join(".")));
     }
 
     #[tokio::test]
     async fn test_expired_token() {
-        let service = CsrfService::with_ttl(Duration::from_millis(100));
-        let token = service.generate_token().await;
+        let service = service().with_ttl(Duration::from_millis(100));
+        let token = service.generate_token();
 
         // Wait for token to expire
         tokio::time::sleep(Duration::from_millis(150)).await;
 
-        assert!(!service.validate_token(&token).await);
+        assert!(!service.validate_token(&token));
     }
 
-    #[tokio::test]
-    async fn test_cleanup_expired_tokens() {
-        let service = CsrfService::with_ttl(Duration::from_millis(100));
-
-        // Generate some tokens
-        let _token1 = service.generate_token().await;
-        let _token2 = service.generate_token().await;
-
-        assert_eq!(service.active_token_count().await, 2);
-
-        // Wait for tokens to expire
-        tokio::time::sleep(Duration::from_millis(150)).await;
-
-        // Cleanup expired tokens
-        let cleaned = service.cleanup_expired_tokens().await;
-        assert_eq!(cleaned, 2);
-        assert_eq!(service.active_token_count().await, 0);
+    #[test]
+    fn test_cookie_attributes() {
+        let cookie = service().with_secure_cookie(true).cookie("abc");
+        assert!(cookie.starts_with("csrf_token=abc;"));
+        assert!(cookie.contains("SameSite=Strict"));
+        assert!(cookie.contains("Secure"));
+        assert!(!cookie.contains("HttpOnly"));
     }
 }
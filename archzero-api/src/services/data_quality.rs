@@ -0,0 +1,213 @@
+/*!
+ * Data Quality Scoring
+ *
+ * Computes `Card.quality_score` (FR-CORE-05 of docs/00-prd.md) from five factors, each
+ * 0..1: completeness of the attributes the metamodel requires and recommends (35%),
+ * freshness since the last update or review (20%), ownership (15%), the relationships
+ * cards of the type are expected to have (15%) and compliance with the architecture
+ * policies (15%). A factor with nothing to check counts as met. The weighted sum is
+ * reported as 0-100, together with the issues that cost points.
+ */
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+pub const COMPLETENESS_WEIGHT: f64 = 0.35;
+pub const FRESHNESS_WEIGHT: f64 = 0.2;
+pub const OWNERSHIP_WEIGHT: f64 = 0.15;
+pub const RELATIONSHIP_WEIGHT: f64 = 0.15;
+pub const COMPLIANCE_WEIGHT: f64 = 0.15;
+
+/// A missing required attribute costs twice as much as a missing recommended one
+const REQUIRED_FACTOR: f64 = 2.0;
+
+/// Everything a card's score depends on
+#[derive(Debug, Clone)]
+pub struct QualityFacts<'a> {
+    pub attributes: &'a Value,
+    pub description: Option<&'a str>,
+    /// Attribute keys the card type's schema or metamodel rules require
+    pub required: &'a [String],
+    /// Attribute keys they define without requiring
+    pub recommended: &'a [String],
+    pub owner_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    /// Days since the card was last updated or reviewed
+    pub days_since_review: i64,
+    pub stale_after_days: i64,
+    /// Outgoing relationship types cards of the type should have
+    pub expected_relationships: &'a [String],
+    /// Outgoing relationship types the card has
+    pub relationship_types: &'a HashSet<String>,
+    /// Name of each policy checked and whether the card complies
+    pub policy_results: &'a [(String, bool)],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityScore {
+    /// Weighted score, 0-100
+    pub score: i32,
+    pub completeness: i32,
+    pub freshness: i32,
+    pub ownership: i32,
+    pub relationships: i32,
+    pub compliance: i32,
+    /// What cost points, in factor order
+    pub issues: Vec<String>,
+}
+
+pub fn quality_score(facts: &QualityFacts) -> QualityScore {
+    let mut issues = Vec::new();
+
+    // Completeness; the description counts as a recommended field of every card
+    let missing_required: Vec<&String> = facts.required.iter().filter(|key| !is_filled(facts.attributes.get(key.as_str()))).collect();
+    let missing_recommended: Vec<&String> = facts.recommended.iter().filter(|key| !is_filled(facts.attributes.get(key.as_str()))).collect();
+    let has_description = facts.description.is_some_and(|text| !text.trim().is_empty());
+    let possible = REQUIRED_FACTOR * facts.required.len() as f64 + facts.recommended.len() as f64 + 1.0;
+    let missing = REQUIRED_FACTOR * missing_required.len() as f64 + missing_recommended.len() as f64 + if has_description { 0.0 } else { 1.0 };
+    let completeness = 1.0 - missing / possible;
+    issues.extend(missing_required.iter().map(|key| format!("Missing required attribute '{}'", key)));
+    issues.extend(missing_recommended.iter().map(|key| format!("Missing recommended attribute '{}'", key)));
+    if !has_description {
+        issues.push("No description".to_string());
+    }
+
+    // Freshness: full until the card goes stale, then none after as long again
+    let stale_after = facts.stale_after_days.max(1);
+    let overdue = facts.days_since_review - stale_after;
+    let freshness = (1.0 - overdue.max(0) as f64 / stale_after as f64).max(0.0);
+    if overdue > 0 {
+        issues.push(format!("Not updated or reviewed for {} days", facts.days_since_review));
+    }
+
+    let ownership = match (facts.owner_id, facts.team_id) {
+        (Some(_), _) => 1.0,
+        (None, Some(_)) => {
+            issues.push("Owned by a team but no person".to_string());
+            0.5
+        }
+        (None, None) => {
+            issues.push("No owner".to_string());
+            0.0
+        }
+    };
+
+    let missing_relationships: Vec<&String> = facts.expected_relationships.iter()
+        .filter(|relationship_type| !facts.relationship_types.contains(*relationship_type))
+        .collect();
+    let relationships = share(facts.expected_relationships.len() - missing_relationships.len(), facts.expected_relationships.len());
+    issues.extend(missing_relationships.iter().map(|relationship_type| format!("No {} relationship", relationship_type)));
+
+    let violated: Vec<&String> = facts.policy_results.iter().filter(|(_, compliant)| !compliant).map(|(name, _)| name).collect();
+    let compliance = share(facts.policy_results.len() - violated.len(), facts.policy_results.len());
+    issues.extend(violated.iter().map(|name| format!("Violates policy '{}'", name)));
+
+    let score = COMPLETENESS_WEIGHT * completeness
+        + FRESHNESS_WEIGHT * freshness
+        + OWNERSHIP_WEIGHT * ownership
+        + RELATIONSHIP_WEIGHT * relationships
+        + COMPLIANCE_WEIGHT * compliance;
+
+    QualityScore {
+        score: percent(score),
+        completeness: percent(completeness),
+        freshness: percent(freshness),
+        ownership: percent(ownership),
+        relationships: percent(relationships),
+        compliance: percent(compliance),
+        issues,
+    }
+}
+
+/// Nulls, blank strings and empty arrays and objects count as missing
+fn is_filled(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::String(text)) => !text.trim().is_empty(),
+        Some(Value::Array(values)) => !values.is_empty(),
+        Some(Value::Object(values)) => !values.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn share(met: usize, total: usize) -> f64 {
+    if total == 0 { 1.0 } else { met as f64 / total as f64 }
+}
+
+fn percent(value: f64) -> i32 {
+    (value * 100.0).round().clamp(0.0, 100.0) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_quality_score() {
+        let attributes = json!({"vendor": "Acme", "hosting_type": " ", "version": null});
+        let (required, recommended) = (keys(&["vendor"]), keys(&["hosting_type", "version"]));
+        let expected = keys(&["reliesOn"]);
+        let relationship_types: HashSet<String> = HashSet::new();
+        let policy_results = vec![("Cloud first".to_string(), true), ("Supported runtime".to_string(), false)];
+
+        let facts = QualityFacts {
+            attributes: &attributes,
+            description: Some("Customer relationship management"),
+            required: &required,
+            recommended: &recommended,
+            owner_id: None,
+            team_id: Some(Uuid::new_v4()),
+            days_since_review: 270,
+            stale_after_days: 180,
+            expected_relationships: &expected,
+            relationship_types: &relationship_types,
+            policy_results: &policy_results,
+        };
+        let scored = quality_score(&facts);
+        // 2 of 5 completeness points are missing, freshness is half gone
+        assert_eq!(
+            (scored.completeness, scored.freshness, scored.ownership, scored.relationships, scored.compliance),
+            (60, 50, 50, 0, 50)
+        );
+        assert_eq!(scored.score, 46);
+        assert_eq!(scored.issues, vec![
+            "Missing recommended attribute 'hosting_type'".to_string(),
+            "Missing recommended attribute 'version'".to_string(),
+            "Not updated or reviewed for 270 days".to_string(),
+            "Owned by a team but no person".to_string(),
+            "No reliesOn relationship".to_string(),
+            "Violates policy 'Supported runtime'".to_string(),
+        ]);
+
+        // Nothing to check counts as met
+        let relationship_types: HashSet<String> = ["reliesOn".to_string()].into_iter().collect();
+        let complete = QualityFacts {
+            attributes: &json!({"vendor": "Acme"}),
+            description: Some("CRM"),
+            required: &required,
+            recommended: &[],
+            owner_id: Some(Uuid::new_v4()),
+            team_id: None,
+            days_since_review: 3,
+            stale_after_days: 180,
+            expected_relationships: &expected,
+            relationship_types: &relationship_types,
+            policy_results: &[],
+        };
+        let scored = quality_score(&complete);
+        assert_eq!(scored.score, 100);
+        assert!(scored.issues.is_empty());
+
+        let bare = QualityFacts { attributes: &json!({}), description: None, owner_id: None, days_since_review: 400, ..complete };
+        let scored = quality_score(&bare);
+        assert_eq!((scored.completeness, scored.freshness, scored.ownership), (0, 0, 0));
+        assert_eq!(scored.score, 30);
+    }
+}
@@ -0,0 +1,509 @@
+/*!
+ * Data Quality Service
+ *
+ * Keeps `cards.quality_score` computed (see `data_quality`). Card and relationship
+ * writes recalculate the cards they touch in their own transaction; changes to metamodel
+ * rules, card types and policies recalculate the affected cards in the background; and
+ * a nightly run recalculates every card, so freshness keeps decaying for untouched cards.
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+use crate::config::DataQuality;
+use crate::error::AppError;
+use crate::handlers::policies::evaluate_policy_rules;
+use crate::models::card::CardType;
+use crate::models::data_quality::*;
+use crate::models::policies::ComplianceStatus;
+use crate::services::card_type_service::load_attribute_schema;
+use crate::services::data_quality::{quality_score, QualityFacts};
+use crate::services::relationship_rule_service::CURRENT_RELATIONSHIP;
+use crate::services::version_service::current_workspace;
+
+/// Cards recalculated per transaction by full runs
+const BATCH_SIZE: i64 = 500;
+
+const QUALITY_COLUMNS: &str = "card_id, score, completeness, freshness, ownership, relationships, compliance, \
+                               issues, last_reviewed_at, calculated_at";
+
+pub struct DataQualityService {
+    pool: PgPool,
+    settings: DataQuality,
+}
+
+impl DataQualityService {
+    pub fn new(pool: PgPool, settings: DataQuality) -> Self {
+        Self { pool, settings }
+    }
+
+    /// Quality of a card, calculated now if it never was
+    pub async fn card_quality(&self, card_id: Uuid) -> Result<CardQuality, AppError> {
+        if let Some(quality) = self.fetch(card_id).await? {
+            return Ok(quality);
+        }
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to acquire connection: {}", e)))?;
+        refresh_quality(&mut conn, &[card_id], &self.settings).await?;
+        self.fetch(card_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card_id)))
+    }
+
+    /// Confirm a card is current; restores its freshness like an update would
+    pub async fn review(&self, card_id: Uuid) -> Result<CardQuality, AppError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+        sqlx::query(
+            "INSERT INTO card_quality (card_id, last_reviewed_at) VALUES ($1, NOW()) \
+             ON CONFLICT (card_id) DO UPDATE SET last_reviewed_at = NOW()",
+        )
+        .bind(card_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record review: {}", e)))?;
+        refresh_quality(&mut tx, &[card_id], &self.settings).await?;
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit review: {}", e)))?;
+
+        self.fetch(card_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card_id)))
+    }
+
+    /// Recalculate every active card, or those of one type, in every workspace
+    pub async fn recalculate(&self, card_type: Option<&str>) -> Result<QualityRecalculation, AppError> {
+        recalculate(&self.pool, &self.settings, card_type).await
+    }
+
+    /// Recalculate the cards of a type, or all of them, without waiting for it
+    pub fn recalculate_in_background(&self, card_type: Option<String>) {
+        spawn_recalculation(self.pool.clone(), self.settings.clone(), card_type);
+    }
+
+    /// Recalculate every card once a day at `hour` (UTC) in a background task
+    pub fn start_nightly(self: Arc<Self>, hour: u32) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(until_next(Utc::now(), hour)).await;
+                match self.recalculate(None).await {
+                    Ok(result) => tracing::info!("Nightly data quality run recalculated {} cards", result.cards),
+                    Err(e) => tracing::error!("Nightly data quality run failed: {}", e),
+                }
+            }
+        });
+
+        tracing::info!("Nightly data quality job started (daily at {:02}:00 UTC)", hour);
+    }
+
+    /// Averages, score bands and the worst cards of the current workspace, overall and by type and owner
+    pub async fn dashboard(&self, params: DataQualityParams) -> Result<DataQualityDashboard, AppError> {
+        let limit = params.limit.unwrap_or(10).clamp(1, 100) as i64;
+        let filter = "c.status = 'active' AND ($1::uuid IS NULL OR c.workspace_id = $1) \
+                      AND ($2::text IS NULL OR c.type = $2) AND ($3::uuid IS NULL OR c.owner_id = $3)";
+        let from = "FROM cards c JOIN card_quality q ON q.card_id = c.id LEFT JOIN users u ON u.id = c.owner_id";
+        let failed = |e: sqlx::Error| AppError::Internal(anyhow::anyhow!("Failed to load data quality: {}", e));
+
+        let totals = filtered(&format!(
+            "SELECT COUNT(*) AS card_count, COALESCE(AVG(q.score), 0)::float8 AS average_score, \
+             COUNT(*) FILTER (WHERE q.score >= 90) AS excellent, \
+             COUNT(*) FILTER (WHERE q.score >= 75 AND q.score < 90) AS good, \
+             COUNT(*) FILTER (WHERE q.score >= 60 AND q.score < 75) AS fair, \
+             COUNT(*) FILTER (WHERE q.score < 60) AS poor {} WHERE {}",
+            from, filter
+        ), &params)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(failed)?;
+
+        // Worst cards overall, per type and per owner in one pass
+        let ranked = filtered(&format!(
+            "SELECT * FROM (SELECT c.id, c.name, c.type, c.owner_id, u.full_name AS owner_name, q.score, q.issues, \
+             ROW_NUMBER() OVER (ORDER BY q.score, c.name, c.id) AS overall_rank, \
+             ROW_NUMBER() OVER (PARTITION BY c.type ORDER BY q.score, c.name, c.id) AS type_rank, \
+             ROW_NUMBER() OVER (PARTITION BY c.owner_id ORDER BY q.score, c.name, c.id) AS owner_rank \
+             {} WHERE {}) ranked \
+             WHERE overall_rank <= $4 OR type_rank <= $4 OR owner_rank <= $4 ORDER BY score, name, id",
+            from, filter
+        ), &params)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed)?;
+
+        let mut worst_cards = Vec::new();
+        let mut worst_by_type: HashMap<String, Vec<CardQualitySummary>> = HashMap::new();
+        let mut worst_by_owner: HashMap<Option<Uuid>, Vec<CardQualitySummary>> = HashMap::new();
+        for row in &ranked {
+            let summary = CardQualitySummary {
+                card_id: row.try_get("id").map_err(missing("id"))?,
+                name: row.try_get("name").map_err(missing("name"))?,
+                card_type: row.try_get("type").map_err(missing("type"))?,
+                owner_id: row.try_get("owner_id").map_err(missing("owner_id"))?,
+                owner_name: row.try_get("owner_name").map_err(missing("owner_name"))?,
+                score: row.try_get("score").map_err(missing("score"))?,
+                issues: issues(row.try_get("issues").map_err(missing("issues"))?),
+            };
+            if row.try_get::<i64, _>("overall_rank").map_err(missing("overall_rank"))? <= limit {
+                worst_cards.push(summary.clone());
+            }
+            if row.try_get::<i64, _>("type_rank").map_err(missing("type_rank"))? <= limit {
+                worst_by_type.entry(summary.card_type.clone()).or_default().push(summary.clone());
+            }
+            if row.try_get::<i64, _>("owner_rank").map_err(missing("owner_rank"))? <= limit {
+                worst_by_owner.entry(summary.owner_id).or_default().push(summary);
+            }
+        }
+
+        let by_type = filtered(&format!(
+            "SELECT c.type, COUNT(*) AS card_count, AVG(q.score)::float8 AS average_score {} WHERE {} \
+             GROUP BY c.type ORDER BY average_score, c.type",
+            from, filter
+        ), &params)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed)?
+        .iter()
+        .map(|row| {
+            let card_type: String = row.try_get("type").map_err(missing("type"))?;
+            Ok(TypeQuality {
+                worst_cards: worst_by_type.remove(&card_type).unwrap_or_default(),
+                card_type,
+                card_count: row.try_get("card_count").map_err(missing("card_count"))?,
+                average_score: round(row.try_get("average_score").map_err(missing("average_score"))?),
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+        let by_owner = filtered(&format!(
+            "SELECT c.owner_id, MAX(u.full_name) AS owner_name, COUNT(*) AS card_count, AVG(q.score)::float8 AS average_score \
+             {} WHERE {} GROUP BY c.owner_id ORDER BY average_score, c.owner_id",
+            from, filter
+        ), &params)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed)?
+        .iter()
+        .map(|row| {
+            let owner_id: Option<Uuid> = row.try_get("owner_id").map_err(missing("owner_id"))?;
+            Ok(OwnerQuality {
+                worst_cards: worst_by_owner.remove(&owner_id).unwrap_or_default(),
+                owner_id,
+                owner_name: row.try_get("owner_name").map_err(missing("owner_name"))?,
+                card_count: row.try_get("card_count").map_err(missing("card_count"))?,
+                average_score: round(row.try_get("average_score").map_err(missing("average_score"))?),
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok(DataQualityDashboard {
+            card_count: totals.try_get("card_count").map_err(missing("card_count"))?,
+            average_score: round(totals.try_get("average_score").map_err(missing("average_score"))?),
+            bands: QualityBands {
+                excellent: totals.try_get("excellent").map_err(missing("excellent"))?,
+                good: totals.try_get("good").map_err(missing("good"))?,
+                fair: totals.try_get("fair").map_err(missing("fair"))?,
+                poor: totals.try_get("poor").map_err(missing("poor"))?,
+            },
+            worst_cards,
+            by_type,
+            by_owner,
+        })
+    }
+
+    async fn fetch(&self, card_id: Uuid) -> Result<Option<CardQuality>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM card_quality q JOIN cards c ON c.id = q.card_id \
+             WHERE q.card_id = $1 AND c.status = 'active' AND q.calculated_at >= c.updated_at",
+            QUALITY_COLUMNS
+        ))
+        .bind(card_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card quality: {}", e)))?;
+
+        row.as_ref().map(row_to_quality).transpose()
+    }
+}
+
+/// Recalculate and store the quality of `card_ids`; inactive and unknown cards are skipped
+pub(crate) async fn refresh_quality(conn: &mut PgConnection, card_ids: &[Uuid], settings: &DataQuality) -> Result<(), AppError> {
+    if card_ids.is_empty() {
+        return Ok(());
+    }
+    let failed = |e: sqlx::Error| AppError::Internal(anyhow::anyhow!("Failed to recalculate data quality: {}", e));
+
+    let cards = sqlx::query(
+        "SELECT c.id, c.type, c.owner_id, c.team_id, c.description, c.attributes, c.workspace_id, \
+         GREATEST(c.updated_at, q.last_reviewed_at) AS reviewed_at \
+         FROM cards c LEFT JOIN card_quality q ON q.card_id = c.id \
+         WHERE c.id = ANY($1) AND c.status = 'active'",
+    )
+    .bind(card_ids)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(failed)?;
+    if cards.is_empty() {
+        return Ok(());
+    }
+
+    // Required and recommended attributes per card type, from its schema and metamodel rules
+    let types: Vec<String> = cards.iter()
+        .map(|row| row.try_get("type").map_err(missing("type")))
+        .collect::<Result<HashSet<_>, _>>()?
+        .into_iter()
+        .collect();
+    let mut attribute_keys: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+    for card_type in &types {
+        let keys = attribute_keys.entry(card_type.clone()).or_default();
+        for definition in load_attribute_schema(&mut *conn, card_type).await? {
+            add_key(keys, definition.key, definition.required);
+        }
+    }
+    let rules = sqlx::query("SELECT card_type, attribute_key, is_required FROM metamodel_rules WHERE card_type = ANY($1) ORDER BY attribute_key")
+        .bind(&types)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(failed)?;
+    for rule in &rules {
+        let card_type: String = rule.try_get("card_type").map_err(missing("card_type"))?;
+        if let Some(keys) = attribute_keys.get_mut(&card_type) {
+            add_key(keys, rule.try_get("attribute_key").map_err(missing("attribute_key"))?, rule.try_get("is_required").map_err(missing("is_required"))?);
+        }
+    }
+
+    let mut relationship_types: HashMap<Uuid, HashSet<String>> = HashMap::new();
+    let relationships = sqlx::query(&format!(
+        "SELECT r.from_card_id, r.relationship_type FROM relationships r WHERE r.from_card_id = ANY($1) AND {}",
+        CURRENT_RELATIONSHIP
+    ))
+    .bind(card_ids)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(failed)?;
+    for row in &relationships {
+        relationship_types.entry(row.try_get("from_card_id").map_err(missing("from_card_id"))?)
+            .or_default()
+            .insert(row.try_get("relationship_type").map_err(missing("relationship_type"))?);
+    }
+
+    // Policies apply within their workspace
+    let policies = sqlx::query(
+        "SELECT name, attributes->'rule_json' AS rule_json, workspace_id FROM cards \
+         WHERE type = 'ArchitecturePolicy' AND status = 'active' AND attributes ? 'rule_json' ORDER BY name",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(failed)?
+    .iter()
+    .map(|row| Ok((
+        row.try_get::<String, _>("name").map_err(missing("name"))?,
+        row.try_get::<Value, _>("rule_json").map_err(missing("rule_json"))?,
+        row.try_get::<Option<Uuid>, _>("workspace_id").map_err(missing("workspace_id"))?,
+    )))
+    .collect::<Result<Vec<_>, AppError>>()?;
+
+    let no_relationships = HashSet::new();
+    let now = Utc::now();
+    for card in &cards {
+        let id: Uuid = card.try_get("id").map_err(missing("id"))?;
+        let card_type: String = card.try_get("type").map_err(missing("type"))?;
+        let attributes: Value = card.try_get("attributes").map_err(missing("attributes"))?;
+        let description: Option<String> = card.try_get("description").map_err(missing("description"))?;
+        let workspace_id: Option<Uuid> = card.try_get("workspace_id").map_err(missing("workspace_id"))?;
+        let reviewed_at: DateTime<Utc> = card.try_get("reviewed_at").map_err(missing("reviewed_at"))?;
+        let (required, recommended) = attribute_keys.get(&card_type).cloned().unwrap_or_default();
+        let expected: Vec<String> = settings.expected_relationships.iter()
+            .filter(|expected| expected.card_type == card_type)
+            .map(|expected| expected.relationship_type.clone())
+            .collect();
+
+        let parsed = CardType::from_name(&card_type);
+        let policy_results: Vec<(String, bool)> = if parsed == CardType::ArchitecturePolicy {
+            Vec::new()
+        } else {
+            policies.iter()
+                .filter(|(_, rule_json, policy_workspace)| *policy_workspace == workspace_id && policy_applies(rule_json, &card_type))
+                .map(|(name, rule_json, _)| {
+                    let result = evaluate_policy_rules(rule_json, &attributes, &parsed);
+                    (name.clone(), result.status == ComplianceStatus::Compliant)
+                })
+                .collect()
+        };
+
+        let scored = quality_score(&QualityFacts {
+            attributes: &attributes,
+            description: description.as_deref(),
+            required: &required,
+            recommended: &recommended,
+            owner_id: card.try_get("owner_id").map_err(missing("owner_id"))?,
+            team_id: card.try_get("team_id").map_err(missing("team_id"))?,
+            days_since_review: (now - reviewed_at).num_days(),
+            stale_after_days: settings.stale_after_days,
+            expected_relationships: &expected,
+            relationship_types: relationship_types.get(&id).unwrap_or(&no_relationships),
+            policy_results: &policy_results,
+        });
+
+        // A derived value: no version entry and no new `updated_at`
+        sqlx::query("UPDATE cards SET quality_score = $2 WHERE id = $1")
+            .bind(id)
+            .bind(scored.score)
+            .execute(&mut *conn)
+            .await
+            .map_err(failed)?;
+        sqlx::query(
+            "INSERT INTO card_quality (card_id, score, completeness, freshness, ownership, relationships, compliance, issues, calculated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW()) \
+             ON CONFLICT (card_id) DO UPDATE SET score = EXCLUDED.score, completeness = EXCLUDED.completeness, \
+             freshness = EXCLUDED.freshness, ownership = EXCLUDED.ownership, relationships = EXCLUDED.relationships, \
+             compliance = EXCLUDED.compliance, issues = EXCLUDED.issues, calculated_at = EXCLUDED.calculated_at",
+        )
+        .bind(id)
+        .bind(scored.score)
+        .bind(scored.completeness)
+        .bind(scored.freshness)
+        .bind(scored.ownership)
+        .bind(scored.relationships)
+        .bind(scored.compliance)
+        .bind(serde_json::json!(scored.issues))
+        .execute(&mut *conn)
+        .await
+        .map_err(failed)?;
+    }
+    Ok(())
+}
+
+/// Recalculate the cards of a type, or all of them, in a background task
+pub(crate) fn spawn_recalculation(pool: PgPool, settings: DataQuality, card_type: Option<String>) {
+    tokio::spawn(async move {
+        if let Err(e) = recalculate(&pool, &settings, card_type.as_deref()).await {
+            tracing::error!("Data quality recalculation failed: {}", e);
+        }
+    });
+}
+
+async fn recalculate(pool: &PgPool, settings: &DataQuality, card_type: Option<&str>) -> Result<QualityRecalculation, AppError> {
+    let mut cards = 0;
+    let mut after: Option<Uuid> = None;
+    loop {
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM cards WHERE status = 'active' AND ($1::text IS NULL OR type = $1) \
+             AND ($2::uuid IS NULL OR id > $2) ORDER BY id LIMIT $3",
+        )
+        .bind(card_type)
+        .bind(after)
+        .bind(BATCH_SIZE)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list cards: {}", e)))?;
+        let Some(last) = ids.last().copied() else {
+            break;
+        };
+
+        let mut tx = pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+        refresh_quality(&mut tx, &ids, settings).await?;
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit data quality: {}", e)))?;
+
+        cards += ids.len() as u64;
+        after = Some(last);
+    }
+    Ok(QualityRecalculation { cards, completed_at: Utc::now() })
+}
+
+/// Dashboard query bound to the current workspace and the type and owner filters ($1-$3)
+fn filtered<'q>(sql: &'q str, params: &DataQualityParams) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    sqlx::query(sql)
+        .bind(current_workspace())
+        .bind(params.card_type.clone())
+        .bind(params.owner_id)
+}
+
+/// Add an attribute key as required or recommended; required wins when both define it
+fn add_key((required, recommended): &mut (Vec<String>, Vec<String>), key: String, is_required: bool) {
+    if is_required {
+        recommended.retain(|existing| *existing != key);
+        if !required.contains(&key) {
+            required.push(key);
+        }
+    } else if !required.contains(&key) && !recommended.contains(&key) {
+        recommended.push(key);
+    }
+}
+
+/// Policies without `applicable_to` apply to every card type
+fn policy_applies(rule_json: &Value, card_type: &str) -> bool {
+    match rule_json.get("applicable_to").and_then(Value::as_array) {
+        Some(types) => types.iter().any(|applicable| applicable.as_str() == Some(card_type)),
+        None => true,
+    }
+}
+
+/// Time from `now` until the next `hour`:00 UTC
+fn until_next(now: DateTime<Utc>, hour: u32) -> Duration {
+    let today = now.date_naive().and_hms_opt(hour.min(23), 0, 0).unwrap_or_default().and_utc();
+    let next = if today > now { today } else { today + chrono::Duration::days(1) };
+    (next - now).to_std().unwrap_or_default()
+}
+
+fn issues(value: Value) -> Vec<String> {
+    serde_json::from_value(value).unwrap_or_default()
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_quality(row: &sqlx::postgres::PgRow) -> Result<CardQuality, AppError> {
+    Ok(CardQuality {
+        card_id: row.try_get("card_id").map_err(missing("card_id"))?,
+        score: row.try_get("score").map_err(missing("score"))?,
+        completeness: row.try_get("completeness").map_err(missing("completeness"))?,
+        freshness: row.try_get("freshness").map_err(missing("freshness"))?,
+        ownership: row.try_get("ownership").map_err(missing("ownership"))?,
+        relationships: row.try_get("relationships").map_err(missing("relationships"))?,
+        compliance: row.try_get("compliance").map_err(missing("compliance"))?,
+        issues: issues(row.try_get("issues").map_err(missing("issues"))?),
+        last_reviewed_at: row.try_get("last_reviewed_at").map_err(missing("last_reviewed_at"))?,
+        calculated_at: row.try_get("calculated_at").map_err(missing("calculated_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_attribute_keys_and_policy_scope() {
+        let mut keys = (Vec::new(), Vec::new());
+        add_key(&mut keys, "vendor".to_string(), false);
+        add_key(&mut keys, "hosting_type".to_string(), false);
+        add_key(&mut keys, "vendor".to_string(), true);
+        add_key(&mut keys, "vendor".to_string(), false);
+        assert_eq!(keys, (vec!["vendor".to_string()], vec!["hosting_type".to_string()]));
+
+        assert!(policy_applies(&json!({"applicable_to": ["Application", "ITComponent"]}), "Application"));
+        assert!(!policy_applies(&json!({"applicable_to": ["ITComponent"]}), "Application"));
+        assert!(policy_applies(&json!({"required_attributes": ["owner"]}), "Application"));
+    }
+
+    #[test]
+    fn test_until_next() {
+        let before = Utc.with_ymd_and_hms(2026, 3, 1, 1, 30, 0).unwrap();
+        assert_eq!(until_next(before, 2), Duration::from_secs(30 * 60));
+        let after = Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap();
+        assert_eq!(until_next(after, 2), Duration::from_secs(24 * 3600));
+    }
+}
@@ -0,0 +1,334 @@
+/*!
+ * Embedded Graph Service
+ *
+ * In-process graph backend for installs without Neo4j. PostgreSQL already holds
+ * every card and relationship, so node and edge writes are no-ops and traversals
+ * are computed over an adjacency index built from the relationships table.
+ */
+
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::Card;
+use crate::models::graph_sync::{GraphCardNode, GraphRelationshipEdge};
+use crate::models::relationship::Relationship;
+use crate::services::graph_backend::GraphBackend;
+use crate::services::neo4j_service::{edge_label, graph_label};
+use crate::services::version_service::current_workspace;
+use crate::services::{CardService, RelationshipService};
+
+pub struct EmbeddedGraphService {
+    card_service: Arc<CardService>,
+    relationship_service: Arc<RelationshipService>,
+}
+
+impl EmbeddedGraphService {
+    pub fn new(card_service: Arc<CardService>, relationship_service: Arc<RelationshipService>) -> Self {
+        Self {
+            card_service,
+            relationship_service,
+        }
+    }
+
+    /// Adjacency index over the relationships of the current workspace
+    async fn load_graph(&self) -> Result<AdjacencyGraph, AppError> {
+        let workspace_id = current_workspace();
+        let edges = self.list_relationship_edges().await?
+            .into_iter()
+            .filter(|edge| workspace_id.is_none() || edge.workspace_id == workspace_id)
+            .collect();
+        Ok(AdjacencyGraph::from_edges(edges))
+    }
+}
+
+#[async_trait]
+impl GraphBackend for EmbeddedGraphService {
+    fn name(&self) -> &'static str {
+        "embedded"
+    }
+
+    async fn create_card_node(&self, _card: &Card) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn update_card_node(&self, _card: &Card) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn delete_card_node(&self, _card_id: Uuid) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn merge_card_node(&self, _card: &Card) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn create_relationship(&self, _relationship: &Relationship) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn update_relationship(&self, _relationship: &Relationship) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn delete_relationship(&self, _relationship_id: Uuid, _edge_type: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn list_card_nodes(&self) -> Result<Vec<GraphCardNode>, AppError> {
+        self.card_service
+            .list_all()
+            .await?
+            .into_iter()
+            .map(|card| {
+                Ok(GraphCardNode {
+                    id: card.id,
+                    card_type: graph_label(&card.card_type)?,
+                    lifecycle_phase: graph_label(&card.lifecycle_phase)?,
+                    name: card.name,
+                    workspace_id: card.workspace_id,
+                })
+            })
+            .collect()
+    }
+
+    async fn list_relationship_edges(&self) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        self.relationship_service
+            .list_all()
+            .await?
+            .into_iter()
+            .map(|relationship| {
+                Ok(GraphRelationshipEdge {
+                    id: Some(relationship.id),
+                    from_card_id: relationship.from_card_id,
+                    to_card_id: relationship.to_card_id,
+                    edge_type: edge_label(&relationship.relationship_type)?,
+                    valid_from: relationship.valid_from,
+                    valid_to: relationship.valid_to,
+                    confidence: relationship.confidence,
+                    attributes: relationship.attributes,
+                    workspace_id: relationship.workspace_id,
+                })
+            })
+            .collect()
+    }
+
+    async fn delete_untracked_edges(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn clear_graph(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn dependents(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        Ok(self.load_graph().await?.dependents(card_id))
+    }
+
+    async fn dependencies(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        Ok(self.load_graph().await?.dependencies(card_id))
+    }
+
+    async fn critical_paths(&self, threshold: u32) -> Result<Vec<(Uuid, u32)>, AppError> {
+        Ok(self.load_graph().await?.critical_paths(threshold))
+    }
+
+    async fn neighbourhood(&self, card_id: Uuid, depth: u32) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        Ok(self.load_graph().await?.neighbourhood(card_id, depth))
+    }
+}
+
+/// Directed edge list indexed by source and target card
+#[derive(Debug, Default)]
+pub struct AdjacencyGraph {
+    edges: Vec<GraphRelationshipEdge>,
+    outgoing: HashMap<Uuid, Vec<usize>>,
+    incoming: HashMap<Uuid, Vec<usize>>,
+}
+
+impl AdjacencyGraph {
+    pub fn from_edges(edges: Vec<GraphRelationshipEdge>) -> Self {
+        let mut outgoing: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        let mut incoming: HashMap<Uuid, Vec<usize>> = HashMap::new();
+
+        for (idx, edge) in edges.iter().enumerate() {
+            outgoing.entry(edge.from_card_id).or_default().push(idx);
+            incoming.entry(edge.to_card_id).or_default().push(idx);
+        }
+
+        Self { edges, outgoing, incoming }
+    }
+
+    pub fn dependents(&self, card_id: Uuid) -> Vec<Uuid> {
+        self.distinct(self.incoming.get(&card_id), |edge| edge.from_card_id)
+    }
+
+    pub fn dependencies(&self, card_id: Uuid) -> Vec<Uuid> {
+        self.distinct(self.outgoing.get(&card_id), |edge| edge.to_card_id)
+    }
+
+    pub fn critical_paths(&self, threshold: u32) -> Vec<(Uuid, u32)> {
+        let mut paths: Vec<(Uuid, u32)> = self.incoming
+            .keys()
+            .map(|id| (*id, self.dependents(*id).len() as u32))
+            .filter(|(_, fan_in)| *fan_in >= threshold)
+            .collect();
+
+        paths.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        paths
+    }
+
+    pub fn neighbourhood(&self, card_id: Uuid, depth: u32) -> Vec<GraphRelationshipEdge> {
+        let mut visited = HashSet::from([card_id]);
+        let mut seen_edges = HashSet::new();
+        let mut queue = VecDeque::from([(card_id, 0u32)]);
+        let mut result = Vec::new();
+
+        while let Some((current, level)) = queue.pop_front() {
+            if level >= depth {
+                continue;
+            }
+
+            let touching = self.outgoing.get(&current).into_iter()
+                .chain(self.incoming.get(&current))
+                .flatten();
+
+            for &idx in touching {
+                if !seen_edges.insert(idx) {
+                    continue;
+                }
+                let edge = &self.edges[idx];
+                result.push(edge.clone());
+
+                let other = if edge.from_card_id == current { edge.to_card_id } else { edge.from_card_id };
+                if visited.insert(other) {
+                    queue.push_back((other, level + 1));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Number of weakly connected components among `node_ids`; isolated nodes count as one each
+    pub fn connected_components(&self, node_ids: &[Uuid]) -> u32 {
+        let mut visited = HashSet::new();
+        let mut components = 0;
+
+        for &start in node_ids {
+            if !visited.insert(start) {
+                continue;
+            }
+            components += 1;
+
+            let mut queue = VecDeque::from([start]);
+            while let Some(current) = queue.pop_front() {
+                let neighbours = self.dependents(current).into_iter().chain(self.dependencies(current));
+                for other in neighbours {
+                    if visited.insert(other) {
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Longest shortest path (in hops) along edge direction, starting from cards with no incoming edges
+    pub fn max_depth(&self) -> u32 {
+        let sources: Vec<Uuid> = self.outgoing.keys()
+            .filter(|id| !self.incoming.contains_key(id))
+            .copied()
+            .collect();
+
+        let mut max_depth = 0;
+        for source in sources {
+            let mut visited = HashSet::from([source]);
+            let mut queue = VecDeque::from([(source, 0u32)]);
+
+            while let Some((current, level)) = queue.pop_front() {
+                max_depth = max_depth.max(level);
+                for other in self.dependencies(current) {
+                    if visited.insert(other) {
+                        queue.push_back((other, level + 1));
+                    }
+                }
+            }
+        }
+
+        max_depth
+    }
+
+    fn distinct(
+        &self,
+        indices: Option<&Vec<usize>>,
+        endpoint: impl Fn(&GraphRelationshipEdge) -> Uuid,
+    ) -> Vec<Uuid> {
+        let mut seen = HashSet::new();
+        indices
+            .into_iter()
+            .flatten()
+            .map(|&idx| endpoint(&self.edges[idx]))
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: Uuid, to: Uuid) -> GraphRelationshipEdge {
+        GraphRelationshipEdge {
+            id: Some(Uuid::new_v4()),
+            from_card_id: from,
+            to_card_id: to,
+            edge_type: "RELIESON".to_string(),
+            valid_from: "2024-01-01".to_string(),
+            valid_to: None,
+            confidence: Some(1.0),
+            attributes: serde_json::json!({}),
+            workspace_id: None,
+        }
+    }
+
+    #[test]
+    fn test_dependents_and_dependencies_are_distinct() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let graph = AdjacencyGraph::from_edges(vec![edge(a, c), edge(b, c), edge(a, c)]);
+
+        let mut dependents = graph.dependents(c);
+        dependents.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+
+        assert_eq!(dependents, expected);
+        assert_eq!(graph.dependencies(a), vec![c]);
+        assert!(graph.dependencies(c).is_empty());
+        assert_eq!(graph.critical_paths(2), vec![(c, 2)]);
+    }
+
+    #[test]
+    fn test_neighbourhood_respects_depth() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let graph = AdjacencyGraph::from_edges(vec![edge(a, b), edge(c, b), edge(c, d)]);
+
+        assert!(graph.neighbourhood(b, 0).is_empty());
+        assert_eq!(graph.neighbourhood(b, 1).len(), 2);
+        assert_eq!(graph.neighbourhood(b, 2).len(), 3);
+        assert_eq!(graph.neighbourhood(a, 5).len(), 3);
+    }
+
+    #[test]
+    fn test_components_and_depth() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let graph = AdjacencyGraph::from_edges(vec![edge(a, b), edge(b, c)]);
+
+        assert_eq!(graph.connected_components(&[a, b, c, d]), 2);
+        assert_eq!(graph.max_depth(), 2);
+    }
+}
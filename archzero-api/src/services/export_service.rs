@@ -7,6 +7,9 @@ use crate::models::export::{
     ExportHistoryResponse, PaginationMetadata
 };
 use crate::error::AppError;
+use crate::models::card::Card;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::version_service::current_workspace;
 
 pub struct ExportService {
     pool: PgPool,
@@ -30,10 +33,37 @@ impl ExportService {
         &self,
         request: ExportRequest,
         user_id: Uuid,
+    ) -> Result<ExportHistoryItem, AppError> {
+        // Fetch cards from database
+        let cards = self.fetch_cards(&request.filters).await?;
+
+        self.write_cards_export(&cards, request.format, user_id).await
+    }
+
+    /// Export cards already selected by the caller, e.g. the results of a saved search
+    pub async fn export_card_list(
+        &self,
+        cards: &[Card],
+        format: ExportFormat,
+        user_id: Uuid,
+    ) -> Result<ExportHistoryItem, AppError> {
+        let cards = cards.iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize cards: {}", e)))?;
+
+        self.write_cards_export(&cards, format, user_id).await
+    }
+
+    async fn write_cards_export(
+        &self,
+        cards: &[serde_json::Value],
+        format: ExportFormat,
+        user_id: Uuid,
     ) -> Result<ExportHistoryItem, AppError> {
         let export_id = Uuid::new_v4();
         let now = chrono::Utc::now();
-        let file_extension = match request.format {
+        let file_extension = match format {
             ExportFormat::Csv => "csv",
             ExportFormat::Excel => "xlsx",
             ExportFormat::Json => "json",
@@ -42,19 +72,16 @@ impl ExportService {
         let file_name = format!("cards_{}.{}", now.format("%Y%m%d_%H%M%S"), file_extension);
         let file_path = self.export_dir.join(&file_name);
 
-        // Fetch cards from database
-        let cards = self.fetch_cards(&request.filters).await?;
-
         // Generate export file
-        match request.format {
+        match format {
             ExportFormat::Csv => {
-                self.generate_csv(&cards, &file_path).await?;
+                self.generate_csv(cards, &file_path).await?;
             }
             ExportFormat::Excel => {
                 return Err(AppError::Internal(anyhow::anyhow!("Excel export not yet implemented - requires additional system dependencies")));
             }
             ExportFormat::Json => {
-                self.generate_json(&cards, &file_path).await?;
+                self.generate_json(cards, &file_path).await?;
             }
         }
 
@@ -62,7 +89,7 @@ impl ExportService {
         let export = self.create_export_history(
             export_id,
             "cards".to_string(),
-            request.format,
+            format,
             ExportStatus::Completed,
             Some(file_path.to_string_lossy().to_string()),
             Some(format!("/exports/{}", file_name)),
@@ -131,9 +158,10 @@ impl ExportService {
     ) -> Result<ExportHistoryResponse, AppError> {
         // Get total count
         let total: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM exports WHERE created_by = $1"
+            "SELECT COUNT(*) FROM exports WHERE created_by = $1 AND ($2::uuid IS NULL OR workspace_id = $2)"
         )
         .bind(user_id)
+        .bind(current_workspace())
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count exports: {}", e)))?;
@@ -144,7 +172,7 @@ impl ExportService {
             SELECT id, export_type, format, status, file_path, file_url,
                    error_message, created_at, created_by
             FROM exports
-            WHERE created_by = $1
+            WHERE created_by = $1 AND ($4::uuid IS NULL OR workspace_id = $4)
             ORDER BY created_at DESC
             LIMIT $2 OFFSET $3
             "#
@@ -152,6 +180,7 @@ impl ExportService {
         .bind(user_id)
         .bind(limit as i64)
         .bind(offset as i64)
+        .bind(current_workspace())
         .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch export history: {}", e)))?;
@@ -268,8 +297,8 @@ impl ExportService {
         let export = sqlx::query_as::<_, ExportHistoryItem>(
             r#"
             INSERT INTO exports (id, export_type, format, status, file_path, file_url,
-                                 error_message, created_by, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+                                 error_message, created_by, created_at, updated_at, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $10)
             RETURNING id, export_type, format, status, file_path, file_url,
                       error_message, created_at, created_by
             "#
@@ -283,6 +312,7 @@ impl ExportService {
         .bind(&error_message)
         .bind(created_by)
         .bind(now)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
         .fetch_one(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create export history: {}", e)))?;
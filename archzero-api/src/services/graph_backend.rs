@@ -0,0 +1,92 @@
+/*!
+ * Graph Backend
+ *
+ * Graph operations used by the SAGA orchestrator, topology analysis, reconciliation
+ * and the graph handlers. `Neo4jService` is the external implementation;
+ * `EmbeddedGraphService` computes the same traversals in-process from PostgreSQL.
+ */
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::{GraphBackendKind, Settings};
+use crate::error::AppError;
+use crate::models::card::Card;
+use crate::models::graph_sync::{GraphCardNode, GraphRelationshipEdge};
+use crate::models::relationship::Relationship;
+use crate::services::{CardService, EmbeddedGraphService, Neo4jService, RelationshipService};
+
+#[async_trait]
+pub trait GraphBackend: Send + Sync {
+    /// Short backend name for logs and health output
+    fn name(&self) -> &'static str;
+
+    async fn create_card_node(&self, card: &Card) -> Result<(), AppError>;
+    async fn update_card_node(&self, card: &Card) -> Result<(), AppError>;
+    async fn delete_card_node(&self, card_id: Uuid) -> Result<(), AppError>;
+    /// Create or refresh a card node, keyed by card ID
+    async fn merge_card_node(&self, card: &Card) -> Result<(), AppError>;
+
+    async fn create_relationship(&self, relationship: &Relationship) -> Result<(), AppError>;
+    async fn update_relationship(&self, relationship: &Relationship) -> Result<(), AppError>;
+    /// Delete the edge with this relationship ID; `edge_type` is its label as stored in the graph
+    async fn delete_relationship(&self, relationship_id: Uuid, edge_type: &str) -> Result<(), AppError>;
+
+    async fn list_card_nodes(&self) -> Result<Vec<GraphCardNode>, AppError>;
+    async fn list_relationship_edges(&self) -> Result<Vec<GraphRelationshipEdge>, AppError>;
+    /// Remove edges that carry no relationship ID
+    async fn delete_untracked_edges(&self) -> Result<(), AppError>;
+    /// Remove every card node and edge
+    async fn clear_graph(&self) -> Result<(), AppError>;
+
+    /// Distinct cards with an edge pointing at `card_id`
+    async fn dependents(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError>;
+    /// Distinct cards `card_id` has an edge pointing to
+    async fn dependencies(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError>;
+    /// Cards with at least `threshold` dependents, highest fan-in first
+    async fn critical_paths(&self, threshold: u32) -> Result<Vec<(Uuid, u32)>, AppError>;
+    /// Edges reachable from `card_id` within `depth` hops, ignoring direction
+    async fn neighbourhood(&self, card_id: Uuid, depth: u32) -> Result<Vec<GraphRelationshipEdge>, AppError>;
+}
+
+/// Build the configured graph backend.
+/// If Neo4j is selected but unreachable, startup fails unless `graph.fallback_to_embedded` is set.
+pub async fn connect_graph_backend(
+    settings: &Settings,
+    card_service: Arc<CardService>,
+    relationship_service: Arc<RelationshipService>,
+) -> Result<Arc<dyn GraphBackend>, AppError> {
+    let embedded = || -> Arc<dyn GraphBackend> {
+        Arc::new(EmbeddedGraphService::new(card_service.clone(), relationship_service.clone()))
+    };
+
+    match settings.graph.backend {
+        GraphBackendKind::Embedded => {
+            tracing::info!("Using embedded graph backend");
+            Ok(embedded())
+        }
+        GraphBackendKind::Neo4j => {
+            match Neo4jService::new(
+                &settings.database.neo4j_uri,
+                &settings.database.neo4j_user,
+                &settings.database.neo4j_password,
+            )
+            .await
+            {
+                Ok(neo4j) => {
+                    tracing::info!("Connected to Neo4j");
+                    Ok(Arc::new(neo4j))
+                }
+                Err(e) if settings.graph.fallback_to_embedded => {
+                    tracing::warn!("Neo4j unavailable ({}), falling back to embedded graph backend", e);
+                    Ok(embedded())
+                }
+                Err(e) => Err(AppError::Internal(anyhow::anyhow!(
+                    "Neo4j unavailable ({}); set graph.backend = \"embedded\" or graph.fallback_to_embedded = true to run without it",
+                    e
+                ))),
+            }
+        }
+    }
+}
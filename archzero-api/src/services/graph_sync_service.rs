@@ -0,0 +1,474 @@
+/*!
+ * Graph Reconciliation Service
+ *
+ * Detects and repairs drift between PostgreSQL (source of truth) and the graph backend.
+ * Cards are matched on node `id`, relationships on the edge `id` property.
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::models::card::Card;
+use crate::models::graph_sync::*;
+use crate::models::relationship::Relationship;
+use crate::services::{CardService, RelationshipService, GraphBackend};
+use crate::services::neo4j_service::{graph_label, edge_label};
+use crate::error::AppError;
+
+pub struct GraphSyncService {
+    card_service: Arc<CardService>,
+    relationship_service: Arc<RelationshipService>,
+    graph: Arc<dyn GraphBackend>,
+}
+
+impl GraphSyncService {
+    pub fn new(
+        card_service: Arc<CardService>,
+        relationship_service: Arc<RelationshipService>,
+        graph: Arc<dyn GraphBackend>,
+    ) -> Self {
+        Self {
+            card_service,
+            relationship_service,
+            graph,
+        }
+    }
+
+    /// Compare both stores without writing anything
+    pub async fn diff(&self) -> Result<ReconciliationReport, AppError> {
+        let cards = self.card_service.list_all().await?;
+        let relationships = self.relationship_service.list_all().await?;
+        let nodes = self.graph.list_card_nodes().await?;
+        let edges = self.graph.list_relationship_edges().await?;
+
+        diff_stores(&cards, &relationships, &nodes, &edges)
+    }
+
+    /// Diff both stores and, depending on the mode, repair the graph or rebuild it from scratch
+    pub async fn reconcile(&self, mode: ReconcileMode) -> Result<ReconciliationResult, AppError> {
+        let cards = self.card_service.list_all().await?;
+        let relationships = self.relationship_service.list_all().await?;
+        let nodes = self.graph.list_card_nodes().await?;
+        let edges = self.graph.list_relationship_edges().await?;
+
+        let report = diff_stores(&cards, &relationships, &nodes, &edges)?;
+
+        let mut result = ReconciliationResult {
+            mode,
+            report,
+            cards_written: 0,
+            cards_removed: 0,
+            relationships_written: 0,
+            relationships_removed: 0,
+            errors: Vec::new(),
+            completed_at: Utc::now(),
+        };
+
+        match mode {
+            ReconcileMode::Report => {}
+            ReconcileMode::Repair => self.repair(&cards, &relationships, &edges, &mut result).await,
+            ReconcileMode::Rebuild => self.rebuild(&cards, &relationships, &mut result).await?,
+        }
+
+        result.completed_at = Utc::now();
+        Ok(result)
+    }
+
+    /// Run reconciliation on a fixed interval in a background task
+    pub fn start_periodic(self: Arc<Self>, interval: Duration, mode: ReconcileMode) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; skip it so startup isn't slowed down
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+
+                match self.reconcile(mode).await {
+                    Ok(result) if result.report.in_sync() => {
+                        tracing::debug!("Graph reconciliation: stores are in sync");
+                    }
+                    Ok(result) => {
+                        tracing::warn!(
+                            "Graph reconciliation ({:?}): {} missing / {} orphaned / {} stale cards, {} missing / {} orphaned / {} stale relationships, {} errors",
+                            mode,
+                            result.report.missing_cards.len(),
+                            result.report.orphaned_cards.len(),
+                            result.report.stale_cards.len(),
+                            result.report.missing_relationships.len(),
+                            result.report.orphaned_relationships.len(),
+                            result.report.stale_relationships.len(),
+                            result.errors.len(),
+                        );
+                    }
+                    Err(e) => tracing::error!("Graph reconciliation failed: {}", e),
+                }
+            }
+        });
+
+        tracing::info!("Graph reconciliation job started (every {:?}, mode {:?})", interval, mode);
+    }
+
+    async fn repair(
+        &self,
+        cards: &[Card],
+        relationships: &[Relationship],
+        edges: &[GraphRelationshipEdge],
+        result: &mut ReconciliationResult,
+    ) {
+        let report = result.report.clone();
+        let cards_by_id: HashMap<_, _> = cards.iter().map(|c| (c.id, c)).collect();
+        let relationships_by_id: HashMap<_, _> = relationships.iter().map(|r| (r.id, r)).collect();
+        // Edges are deleted by the label they carry in the graph, which may differ from PostgreSQL
+        let mut edge_labels: HashMap<_, HashSet<&str>> = HashMap::new();
+        for edge in edges {
+            if let Some(id) = edge.id {
+                edge_labels.entry(id).or_default().insert(edge.edge_type.as_str());
+            }
+        }
+
+        // Cards first so relationship edges have both endpoints to attach to
+        let card_ids = report.missing_cards.iter().chain(report.stale_cards.iter().map(|d| &d.id));
+        for id in card_ids {
+            if let Some(card) = cards_by_id.get(id) {
+                match self.graph.merge_card_node(card).await {
+                    Ok(()) => result.cards_written += 1,
+                    Err(e) => result.errors.push(format!("Card {}: {}", id, e)),
+                }
+            }
+        }
+
+        for id in &report.orphaned_cards {
+            match self.graph.delete_card_node(*id).await {
+                Ok(()) => result.cards_removed += 1,
+                Err(e) => result.errors.push(format!("Card {}: {}", id, e)),
+            }
+        }
+
+        if report.untracked_edges > 0 {
+            match self.graph.delete_untracked_edges().await {
+                Ok(()) => result.relationships_removed += report.untracked_edges,
+                Err(e) => result.errors.push(format!("Untracked edges: {}", e)),
+            }
+        }
+
+        for id in &report.orphaned_relationships {
+            match self.delete_edges(*id, &edge_labels).await {
+                Ok(()) => result.relationships_removed += 1,
+                Err(e) => result.errors.push(format!("Relationship {}: {}", id, e)),
+            }
+        }
+
+        // Stale edges are replaced rather than patched, since the edge label itself may differ
+        for drift in &report.stale_relationships {
+            if let Err(e) = self.delete_edges(drift.id, &edge_labels).await {
+                result.errors.push(format!("Relationship {}: {}", drift.id, e));
+                continue;
+            }
+            if let Some(relationship) = relationships_by_id.get(&drift.id) {
+                match self.graph.create_relationship(relationship).await {
+                    Ok(()) => result.relationships_written += 1,
+                    Err(e) => result.errors.push(format!("Relationship {}: {}", drift.id, e)),
+                }
+            }
+        }
+
+        for id in &report.missing_relationships {
+            if let Some(relationship) = relationships_by_id.get(id) {
+                match self.graph.create_relationship(relationship).await {
+                    Ok(()) => result.relationships_written += 1,
+                    Err(e) => result.errors.push(format!("Relationship {}: {}", id, e)),
+                }
+            }
+        }
+    }
+
+    /// Delete every copy of a relationship's edge, whatever label each copy carries
+    async fn delete_edges(&self, id: Uuid, edge_labels: &HashMap<Uuid, HashSet<&str>>) -> Result<(), AppError> {
+        for edge_type in edge_labels.get(&id).into_iter().flatten() {
+            self.graph.delete_relationship(id, edge_type).await?;
+        }
+        Ok(())
+    }
+
+    async fn rebuild(
+        &self,
+        cards: &[Card],
+        relationships: &[Relationship],
+        result: &mut ReconciliationResult,
+    ) -> Result<(), AppError> {
+        self.graph.clear_graph().await?;
+        result.cards_removed = result.report.graph_cards;
+        result.relationships_removed = result.report.graph_relationships;
+
+        for card in cards {
+            match self.graph.merge_card_node(card).await {
+                Ok(()) => result.cards_written += 1,
+                Err(e) => result.errors.push(format!("Card {}: {}", card.id, e)),
+            }
+        }
+
+        for relationship in relationships {
+            match self.graph.create_relationship(relationship).await {
+                Ok(()) => result.relationships_written += 1,
+                Err(e) => result.errors.push(format!("Relationship {}: {}", relationship.id, e)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute the drift between PostgreSQL rows and the graph contents
+pub fn diff_stores(
+    cards: &[Card],
+    relationships: &[Relationship],
+    nodes: &[GraphCardNode],
+    edges: &[GraphRelationshipEdge],
+) -> Result<ReconciliationReport, AppError> {
+    let nodes_by_id: HashMap<_, _> = nodes.iter().map(|n| (n.id, n)).collect();
+    let card_ids: HashSet<_> = cards.iter().map(|c| c.id).collect();
+
+    let mut missing_cards = Vec::new();
+    let mut stale_cards = Vec::new();
+    for card in cards {
+        let Some(node) = nodes_by_id.get(&card.id) else {
+            missing_cards.push(card.id);
+            continue;
+        };
+
+        let mut fields = Vec::new();
+        if node.name != card.name {
+            fields.push("name".to_string());
+        }
+        if node.card_type != graph_label(&card.card_type)? {
+            fields.push("type".to_string());
+        }
+        if node.lifecycle_phase != graph_label(&card.lifecycle_phase)? {
+            fields.push("lifecyclePhase".to_string());
+        }
+        if node.workspace_id != card.workspace_id {
+            fields.push("workspaceId".to_string());
+        }
+        if !fields.is_empty() {
+            stale_cards.push(DriftedEntity { id: card.id, fields });
+        }
+    }
+
+    let orphaned_cards: Vec<_> = nodes.iter()
+        .map(|n| n.id)
+        .filter(|id| !card_ids.contains(id))
+        .collect();
+
+    let mut edges_by_id: HashMap<_, Vec<&GraphRelationshipEdge>> = HashMap::new();
+    let mut untracked_edges = 0;
+    for edge in edges {
+        match edge.id {
+            Some(id) => edges_by_id.entry(id).or_default().push(edge),
+            None => untracked_edges += 1,
+        }
+    }
+    let relationship_ids: HashSet<_> = relationships.iter().map(|r| r.id).collect();
+
+    let mut missing_relationships = Vec::new();
+    let mut stale_relationships = Vec::new();
+    for relationship in relationships {
+        let Some(copies) = edges_by_id.get(&relationship.id) else {
+            missing_relationships.push(relationship.id);
+            continue;
+        };
+
+        let edge = copies[0];
+        let mut fields = Vec::new();
+        if copies.len() > 1 {
+            fields.push("duplicate".to_string());
+        }
+        if edge.from_card_id != relationship.from_card_id || edge.to_card_id != relationship.to_card_id {
+            fields.push("endpoints".to_string());
+        }
+        if edge.edge_type != edge_label(&relationship.relationship_type)? {
+            fields.push("relationshipType".to_string());
+        }
+        if edge.valid_from != relationship.valid_from {
+            fields.push("validFrom".to_string());
+        }
+        if edge.valid_to.as_deref().unwrap_or_default() != relationship.valid_to.as_deref().unwrap_or_default() {
+            fields.push("validTo".to_string());
+        }
+        if edge.confidence.unwrap_or(1.0) != relationship.confidence.unwrap_or(1.0) {
+            fields.push("confidence".to_string());
+        }
+        if !same_attributes(&edge.attributes, &relationship.attributes) {
+            fields.push("attributes".to_string());
+        }
+        if edge.workspace_id != relationship.workspace_id {
+            fields.push("workspaceId".to_string());
+        }
+        if !fields.is_empty() {
+            stale_relationships.push(DriftedEntity { id: relationship.id, fields });
+        }
+    }
+
+    let orphaned_relationships: Vec<_> = edges_by_id.keys()
+        .copied()
+        .filter(|id| !relationship_ids.contains(id))
+        .collect();
+
+    Ok(ReconciliationReport {
+        checked_at: Utc::now(),
+        postgres_cards: cards.len(),
+        graph_cards: nodes.len(),
+        postgres_relationships: relationships.len(),
+        graph_relationships: edges.len(),
+        missing_cards,
+        orphaned_cards,
+        stale_cards,
+        missing_relationships,
+        orphaned_relationships,
+        stale_relationships,
+        untracked_edges,
+    })
+}
+
+/// Edges written before attributes were synced have none; treat that the same as `{}`
+fn same_attributes(graph: &serde_json::Value, postgres: &serde_json::Value) -> bool {
+    let empty = |v: &serde_json::Value| v.is_null() || v.as_object().is_some_and(|o| o.is_empty());
+    graph == postgres || (empty(graph) && empty(postgres))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use crate::models::card::{CardType, LifecyclePhase};
+    use crate::models::relationship::RelationshipType;
+
+    fn card(name: &str) -> Card {
+        Card {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            card_type: CardType::Application,
+            lifecycle_phase: LifecyclePhase::Active,
+            quality_score: None,
+            description: None,
+            owner_id: None,
+            team_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            attributes: serde_json::json!({}),
+            tags: vec![],
+            status: "active".to_string(),
+            workspace_id: None,
+            metamodel_version: None,
+        }
+    }
+
+    fn node(card: &Card) -> GraphCardNode {
+        GraphCardNode {
+            id: card.id,
+            name: card.name.clone(),
+            card_type: "Application".to_string(),
+            lifecycle_phase: "Active".to_string(),
+            workspace_id: card.workspace_id,
+        }
+    }
+
+    fn relationship(from: &Card, to: &Card) -> Relationship {
+        Relationship {
+            id: Uuid::new_v4(),
+            from_card_id: from.id,
+            to_card_id: to.id,
+            relationship_type: RelationshipType::ReliesOn,
+            valid_from: "2026-01-01".to_string(),
+            valid_to: None,
+            attributes: serde_json::json!({}),
+            confidence: None,
+            created_at: Utc::now(),
+            workspace_id: from.workspace_id,
+        }
+    }
+
+    fn edge(relationship: &Relationship) -> GraphRelationshipEdge {
+        GraphRelationshipEdge {
+            id: Some(relationship.id),
+            from_card_id: relationship.from_card_id,
+            to_card_id: relationship.to_card_id,
+            edge_type: "RELIESON".to_string(),
+            valid_from: relationship.valid_from.clone(),
+            valid_to: None,
+            confidence: Some(1.0),
+            attributes: serde_json::json!({}),
+            workspace_id: relationship.workspace_id,
+        }
+    }
+
+    #[test]
+    fn test_in_sync_stores_report_no_drift() {
+        let a = card("CRM");
+        let b = card("Postgres");
+        let rel = relationship(&a, &b);
+
+        let report = diff_stores(
+            &[a.clone(), b.clone()],
+            &[rel.clone()],
+            &[node(&a), node(&b)],
+            &[edge(&rel)],
+        ).unwrap();
+
+        assert!(report.in_sync());
+    }
+
+    #[test]
+    fn test_detects_missing_orphaned_and_stale_cards() {
+        let kept = card("CRM");
+        let missing = card("ERP");
+        let orphan = card("Legacy");
+
+        let mut stale_node = node(&kept);
+        stale_node.name = "Old CRM".to_string();
+
+        let report = diff_stores(
+            &[kept.clone(), missing.clone()],
+            &[],
+            &[stale_node, node(&orphan)],
+            &[],
+        ).unwrap();
+
+        assert_eq!(report.missing_cards, vec![missing.id]);
+        assert_eq!(report.orphaned_cards, vec![orphan.id]);
+        assert_eq!(report.stale_cards, vec![DriftedEntity { id: kept.id, fields: vec!["name".to_string()] }]);
+    }
+
+    #[test]
+    fn test_detects_relationship_drift() {
+        let a = card("CRM");
+        let b = card("Postgres");
+        let missing = relationship(&a, &b);
+        let mut changed = relationship(&b, &a);
+        changed.confidence = Some(0.5);
+        changed.attributes = serde_json::json!({"protocol": "jdbc"});
+        let orphan = relationship(&a, &b);
+
+        let mut untracked = edge(&orphan);
+        untracked.id = None;
+
+        let report = diff_stores(
+            &[a.clone(), b.clone()],
+            &[missing.clone(), changed.clone()],
+            &[node(&a), node(&b)],
+            &[edge(&changed), edge(&orphan), untracked],
+        ).unwrap();
+
+        assert_eq!(report.missing_relationships, vec![missing.id]);
+        assert_eq!(report.orphaned_relationships, vec![orphan.id]);
+        assert_eq!(report.stale_relationships, vec![DriftedEntity {
+            id: changed.id,
+            fields: vec!["confidence".to_string(), "attributes".to_string()],
+        }]);
+        assert_eq!(report.untracked_edges, 1);
+        assert!(!report.in_sync());
+    }
+}
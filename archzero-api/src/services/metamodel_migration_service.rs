@@ -0,0 +1,587 @@
+/*!
+ * Metamodel Migration Service
+ *
+ * Versions the metamodel and migrates existing cards to it (docs/01-metamodel-spec.md §1.6):
+ * renaming an attribute key, backfilling an attribute before it becomes required, and
+ * retiring a card type. Each migration is a new version; breaking changes bump the major
+ * number, additive ones the minor. Migrations run in the background in batches, with
+ * progress on the migration row, and every migrated card records the version it is at.
+ */
+
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::{Card, CardType};
+use crate::models::metamodel::*;
+use crate::services::metamodel_service::validate_card_type;
+use crate::services::version_service::{current_actor, CURRENT_ACTOR};
+use crate::services::CardService;
+
+const MIGRATION_COLUMNS: &str = "id, version, description, operation, status, total_cards, processed_cards, \
+                                 changed_cards, error, created_by, created_at, started_at, completed_at";
+
+/// Cards migrated per transaction
+const BATCH_SIZE: i64 = 200;
+
+/// Most affected cards listed in a dry run
+const DRY_RUN_SAMPLES: usize = 50;
+
+/// Tag put on cards of a type retired in `Tag` mode
+const DEPRECATED_TAG: &str = "DEPRECATED";
+
+pub struct MetamodelMigrationService {
+    pool: PgPool,
+    card_service: Arc<CardService>,
+}
+
+impl MetamodelMigrationService {
+    pub fn new(pool: PgPool, card_service: Arc<CardService>) -> Self {
+        Self { pool, card_service }
+    }
+
+    /// Latest version whose migration completed
+    pub async fn current_version(&self) -> Result<String, AppError> {
+        let version: Option<String> = sqlx::query_scalar(
+            "SELECT version FROM metamodel_migrations WHERE status = 'completed' ORDER BY major DESC, minor DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch metamodel version: {}", e)))?;
+
+        Ok(version.unwrap_or_else(|| INITIAL_METAMODEL_VERSION.to_string()))
+    }
+
+    pub async fn version_info(&self) -> Result<MetamodelVersionInfo, AppError> {
+        let retired_card_types: Vec<String> =
+            sqlx::query_scalar("SELECT card_type FROM metamodel_retired_card_types ORDER BY card_type")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list retired card types: {}", e)))?;
+
+        Ok(MetamodelVersionInfo {
+            version: self.current_version().await?,
+            retired_card_types,
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<MetamodelMigration>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM metamodel_migrations ORDER BY major DESC, minor DESC",
+            MIGRATION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list metamodel migrations: {}", e)))?;
+
+        rows.iter().map(row_to_migration).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<MetamodelMigration, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM metamodel_migrations WHERE id = $1", MIGRATION_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch metamodel migration: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Metamodel migration {} not found", id)))?;
+
+        row_to_migration(&row)
+    }
+
+    /// What `operation` would change, without changing anything
+    pub async fn dry_run(&self, operation: &MetamodelOperation) -> Result<MetamodelDryRun, AppError> {
+        validate_operation(&self.pool, operation).await?;
+
+        let mut affected_cards = 0;
+        let mut samples = Vec::new();
+        let cards = self.card_service.list_all().await?;
+        for card in cards.iter().filter(|card| card.card_type.as_str() == operation.card_type()) {
+            let Some(migrated) = apply_operation(operation, card) else {
+                continue;
+            };
+            affected_cards += 1;
+            if samples.len() < DRY_RUN_SAMPLES {
+                let (before, after) = changed_fields(card, &migrated);
+                samples.push(MetamodelDryRunSample {
+                    card_id: card.id,
+                    name: card.name.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+
+        Ok(MetamodelDryRun {
+            version: self.next_version(operation).await?.0,
+            affected_cards,
+            samples,
+            rule_changes: self.rule_changes(operation).await?,
+        })
+    }
+
+    /// Record a new version and migrate existing cards to it in the background
+    pub async fn start(
+        self: Arc<Self>,
+        req: CreateMetamodelMigrationRequest,
+        created_by: Option<Uuid>,
+    ) -> Result<MetamodelMigration, AppError> {
+        let operation = req.operation;
+        validate_operation(&self.pool, &operation).await?;
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        // One migration at a time; versions are assigned in order
+        sqlx::query("LOCK TABLE metamodel_migrations IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to lock metamodel migrations: {}", e)))?;
+        let in_progress: Option<String> = sqlx::query_scalar(
+            "SELECT version FROM metamodel_migrations WHERE status IN ('pending', 'running') LIMIT 1",
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check running migrations: {}", e)))?;
+        if let Some(version) = in_progress {
+            return Err(AppError::Validation(format!(
+                "Metamodel migration to version {} is still in progress",
+                version
+            )));
+        }
+
+        let (version, major, minor) = self.next_version(&operation).await?;
+        let total_cards: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cards WHERE type = $1 AND status = 'active'")
+            .bind(operation.card_type())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count cards: {}", e)))?;
+
+        let operation_json = serde_json::to_value(&operation)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize metamodel operation: {}", e))?;
+        let row = sqlx::query(&format!(
+            "INSERT INTO metamodel_migrations (version, major, minor, description, operation, status, total_cards, created_by) \
+             VALUES ($1, $2, $3, $4, $5, 'pending', $6, $7) RETURNING {}",
+            MIGRATION_COLUMNS
+        ))
+        .bind(&version)
+        .bind(major)
+        .bind(minor)
+        .bind(&req.description)
+        .bind(&operation_json)
+        .bind(total_cards as i32)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+        let migration = row_to_migration(&row)?;
+
+        // New cards of a retired type are rejected as soon as the migration is accepted
+        if let MetamodelOperation::RetireCardType { card_type, replacement, .. } = &operation {
+            sqlx::query(
+                "INSERT INTO metamodel_retired_card_types (card_type, replacement_type, retired_in_version) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT (card_type) DO UPDATE SET replacement_type = EXCLUDED.replacement_type, \
+                 retired_in_version = EXCLUDED.retired_in_version, retired_at = NOW()",
+            )
+            .bind(card_type)
+            .bind(replacement)
+            .bind(&version)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to retire card type: {}", e)))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit metamodel migration: {}", e)))?;
+
+        self.spawn(migration.id, version, operation);
+        Ok(migration)
+    }
+
+    /// Restart migrations interrupted by a shutdown; migrating a card twice changes nothing
+    pub async fn resume_interrupted(self: Arc<Self>) -> Result<(), AppError> {
+        for migration in self.list().await? {
+            if matches!(migration.status, MetamodelMigrationStatus::Pending | MetamodelMigrationStatus::Running) {
+                tracing::info!("Resuming metamodel migration to version {}", migration.version);
+                self.clone().spawn(migration.id, migration.version, migration.operation);
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a migration in a background task, as the caller so card versions name them
+    fn spawn(self: Arc<Self>, id: Uuid, version: String, operation: MetamodelOperation) {
+        let actor = current_actor();
+        tokio::spawn(async move {
+            let run = self.run(id, &version, &operation);
+            let result = match actor {
+                Some(actor) => CURRENT_ACTOR.scope(actor, run).await,
+                None => run.await,
+            };
+            if let Err(e) = result {
+                tracing::error!("Metamodel migration to version {} failed: {:?}", version, e);
+            }
+        });
+    }
+
+    async fn run(&self, id: Uuid, version: &str, operation: &MetamodelOperation) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE metamodel_migrations SET status = 'running', processed_cards = 0, changed_cards = 0, \
+             started_at = NOW(), error = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start metamodel migration: {}", e)))?;
+
+        match self.migrate_cards(id, version, operation).await {
+            Ok(()) => {
+                sqlx::query("UPDATE metamodel_migrations SET status = 'completed', completed_at = NOW() WHERE id = $1")
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to complete metamodel migration: {}", e)))?;
+                Ok(())
+            }
+            Err(e) => {
+                sqlx::query("UPDATE metamodel_migrations SET status = 'failed', error = $2, completed_at = NOW() WHERE id = $1")
+                    .bind(id)
+                    .bind(e.to_string())
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record metamodel migration failure: {}", e)))?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn migrate_cards(&self, id: Uuid, version: &str, operation: &MetamodelOperation) -> Result<(), AppError> {
+        let mut after_id = None;
+        let (mut processed, mut changed) = (0i32, 0i32);
+
+        loop {
+            let (last_id, batch_processed, batch_changed) = self.card_service
+                .migrate_batch(operation.card_type(), after_id, BATCH_SIZE, version, |card| apply_operation(operation, card))
+                .await?;
+            processed += batch_processed as i32;
+            changed += batch_changed as i32;
+
+            sqlx::query("UPDATE metamodel_migrations SET processed_cards = $2, changed_cards = $3 WHERE id = $1")
+                .bind(id)
+                .bind(processed)
+                .bind(changed)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record migration progress: {}", e)))?;
+
+            if (batch_processed as i64) < BATCH_SIZE {
+                break;
+            }
+            after_id = last_id;
+        }
+
+        self.apply_rule_changes(operation).await
+    }
+
+    /// Rules follow renamed keys; backfilled attributes become required once every card has them
+    async fn apply_rule_changes(&self, operation: &MetamodelOperation) -> Result<(), AppError> {
+        match operation {
+            MetamodelOperation::RenameAttribute { card_type, from, to } => {
+                sqlx::query(
+                    "UPDATE metamodel_rules SET attribute_key = $3, updated_at = NOW() \
+                     WHERE card_type = $1 AND attribute_key = $2 \
+                     AND NOT EXISTS (SELECT 1 FROM metamodel_rules WHERE card_type = $1 AND attribute_key = $3)",
+                )
+                .bind(card_type)
+                .bind(from)
+                .bind(to)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to rename metamodel rule: {}", e)))?;
+            }
+            MetamodelOperation::BackfillAttribute { card_type, attribute_key, make_required: true, .. } => {
+                sqlx::query(
+                    "INSERT INTO metamodel_rules (card_type, attribute_key, is_required) VALUES ($1, $2, TRUE) \
+                     ON CONFLICT (card_type, attribute_key) DO UPDATE SET is_required = TRUE, updated_at = NOW()",
+                )
+                .bind(card_type)
+                .bind(attribute_key)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to require attribute: {}", e)))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Rule and registry changes `operation` makes, for dry runs
+    async fn rule_changes(&self, operation: &MetamodelOperation) -> Result<Vec<String>, AppError> {
+        let rule_keys: Vec<String> = sqlx::query_scalar("SELECT attribute_key FROM metamodel_rules WHERE card_type = $1")
+            .bind(operation.card_type())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load metamodel rules: {}", e)))?;
+
+        let mut changes = Vec::new();
+        match operation {
+            MetamodelOperation::RenameAttribute { card_type, from, to } => {
+                if rule_keys.contains(from) && rule_keys.contains(to) {
+                    changes.push(format!("{} already has a rule for '{}'; the rule for '{}' is kept unchanged", card_type, to, from));
+                } else if rule_keys.contains(from) {
+                    changes.push(format!("The {} rule for '{}' moves to '{}'", card_type, from, to));
+                }
+            }
+            MetamodelOperation::BackfillAttribute { card_type, attribute_key, make_required: true, .. } => {
+                changes.push(format!("'{}' becomes required for {}", attribute_key, card_type));
+            }
+            MetamodelOperation::BackfillAttribute { .. } => {}
+            MetamodelOperation::RetireCardType { card_type, replacement, .. } => {
+                changes.push(match replacement {
+                    Some(replacement) => format!("New {} cards are rejected in favour of {}", card_type, replacement),
+                    None => format!("New {} cards are rejected", card_type),
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Version `operation` would create: the next major for breaking changes, else the next minor.
+    /// Counts failed migrations too, so a version number is never reused.
+    async fn next_version(&self, operation: &MetamodelOperation) -> Result<(String, i32, i32), AppError> {
+        let latest: Option<(i32, i32)> =
+            sqlx::query_as("SELECT major, minor FROM metamodel_migrations ORDER BY major DESC, minor DESC LIMIT 1")
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch metamodel version: {}", e)))?;
+
+        let (major, minor) = latest.unwrap_or((1, 0));
+        let (major, minor) = if operation.is_breaking() { (major + 1, 0) } else { (major, minor + 1) };
+        Ok((format!("{}.{}", major, minor), major, minor))
+    }
+}
+
+/// `card` after `operation`, or None if the operation leaves it unchanged
+pub fn apply_operation(operation: &MetamodelOperation, card: &Card) -> Option<Card> {
+    if card.card_type.as_str() != operation.card_type() {
+        return None;
+    }
+
+    let mut migrated = card.clone();
+    match operation {
+        MetamodelOperation::RenameAttribute { from, to, .. } => {
+            let attributes = migrated.attributes.as_object_mut()?;
+            let value = attributes.remove(from)?;
+            // Where both keys exist the new one already holds the current value
+            if attributes.get(to).is_none_or(Value::is_null) {
+                attributes.insert(to.clone(), value);
+            }
+        }
+        MetamodelOperation::BackfillAttribute { attribute_key, default_value, .. } => {
+            if !migrated.attributes.is_object() {
+                migrated.attributes = Value::Object(Map::new());
+            }
+            let attributes = migrated.attributes.as_object_mut()?;
+            if !attributes.get(attribute_key).is_none_or(Value::is_null) {
+                return None;
+            }
+            attributes.insert(attribute_key.clone(), default_value.clone());
+        }
+        MetamodelOperation::RetireCardType { replacement, mode, .. } => match mode {
+            RetireMode::Tag => {
+                if migrated.tags.iter().any(|tag| tag == DEPRECATED_TAG) {
+                    return None;
+                }
+                migrated.tags.push(DEPRECATED_TAG.to_string());
+            }
+            RetireMode::Convert => {
+                migrated.card_type = CardType::from_name(replacement.as_deref()?);
+            }
+            RetireMode::Archive => {
+                migrated.status = "archived".to_string();
+            }
+        },
+    }
+    Some(migrated)
+}
+
+async fn validate_operation(pool: &PgPool, operation: &MetamodelOperation) -> Result<(), AppError> {
+    validate_card_type(pool, operation.card_type()).await?;
+
+    match operation {
+        MetamodelOperation::RenameAttribute { from, to, .. } => {
+            if from.trim().is_empty() || to.trim().is_empty() {
+                return Err(AppError::Validation("Both attribute keys are required".to_string()));
+            }
+            if from == to {
+                return Err(AppError::Validation("The new attribute key must differ from the old one".to_string()));
+            }
+        }
+        MetamodelOperation::BackfillAttribute { attribute_key, default_value, .. } => {
+            if attribute_key.trim().is_empty() {
+                return Err(AppError::Validation("Attribute key is required".to_string()));
+            }
+            if default_value.is_null() {
+                return Err(AppError::Validation("A backfill needs a non-null default value".to_string()));
+            }
+        }
+        MetamodelOperation::RetireCardType { card_type, replacement, mode } => {
+            if let Some(replacement) = replacement {
+                validate_card_type(pool, replacement).await?;
+                if replacement == card_type {
+                    return Err(AppError::Validation("A card type cannot replace itself".to_string()));
+                }
+            } else if *mode == RetireMode::Convert {
+                return Err(AppError::Validation("Converting cards needs a replacement card type".to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The fields a migration changes, before and after
+fn changed_fields(before: &Card, after: &Card) -> (Value, Value) {
+    let mut old = Map::new();
+    let mut new = Map::new();
+
+    if before.card_type != after.card_type {
+        old.insert("type".to_string(), Value::String(before.card_type.to_string()));
+        new.insert("type".to_string(), Value::String(after.card_type.to_string()));
+    }
+    if before.status != after.status {
+        old.insert("status".to_string(), Value::String(before.status.clone()));
+        new.insert("status".to_string(), Value::String(after.status.clone()));
+    }
+    if before.tags != after.tags {
+        old.insert("tags".to_string(), serde_json::json!(before.tags));
+        new.insert("tags".to_string(), serde_json::json!(after.tags));
+    }
+
+    let empty = Map::new();
+    let old_attributes = before.attributes.as_object().unwrap_or(&empty);
+    let new_attributes = after.attributes.as_object().unwrap_or(&empty);
+    let (mut old_changed, mut new_changed) = (Map::new(), Map::new());
+    for key in old_attributes.keys().chain(new_attributes.keys()) {
+        if old_attributes.get(key) != new_attributes.get(key) {
+            old_changed.insert(key.clone(), old_attributes.get(key).cloned().unwrap_or(Value::Null));
+            new_changed.insert(key.clone(), new_attributes.get(key).cloned().unwrap_or(Value::Null));
+        }
+    }
+    if !old_changed.is_empty() {
+        old.insert("attributes".to_string(), Value::Object(old_changed));
+        new.insert("attributes".to_string(), Value::Object(new_changed));
+    }
+
+    (Value::Object(old), Value::Object(new))
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_migration(row: &sqlx::postgres::PgRow) -> Result<MetamodelMigration, AppError> {
+    let operation: Value = row.try_get("operation").map_err(missing("operation"))?;
+    let status: String = row.try_get("status").map_err(missing("status"))?;
+
+    Ok(MetamodelMigration {
+        id: row.try_get("id").map_err(missing("id"))?,
+        version: row.try_get("version").map_err(missing("version"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        operation: serde_json::from_value(operation)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid metamodel operation: {}", e)))?,
+        status: MetamodelMigrationStatus::parse(&status)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid migration status: {}", status)))?,
+        total_cards: row.try_get("total_cards").map_err(missing("total_cards"))?,
+        processed_cards: row.try_get("processed_cards").map_err(missing("processed_cards"))?,
+        changed_cards: row.try_get("changed_cards").map_err(missing("changed_cards"))?,
+        error: row.try_get("error").map_err(missing("error"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        started_at: row.try_get("started_at").map_err(missing("started_at"))?,
+        completed_at: row.try_get("completed_at").map_err(missing("completed_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::card::LifecyclePhase;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn card(attributes: Value) -> Card {
+        Card {
+            id: Uuid::new_v4(),
+            name: "CRM".to_string(),
+            card_type: CardType::Application,
+            lifecycle_phase: LifecyclePhase::Active,
+            quality_score: None,
+            description: None,
+            owner_id: None,
+            team_id: None,
+            workspace_id: None,
+            metamodel_version: Some(INITIAL_METAMODEL_VERSION.to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            attributes,
+            tags: vec![],
+            status: "active".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_operation() {
+        let rename = MetamodelOperation::RenameAttribute {
+            card_type: "Application".to_string(),
+            from: "cost".to_string(),
+            to: "annual_cost".to_string(),
+        };
+        let migrated = apply_operation(&rename, &card(json!({"cost": 100}))).unwrap();
+        assert_eq!(migrated.attributes, json!({"annual_cost": 100}));
+        // The new key wins where both exist; cards without the old key are untouched
+        let migrated = apply_operation(&rename, &card(json!({"cost": 100, "annual_cost": 120}))).unwrap();
+        assert_eq!(migrated.attributes, json!({"annual_cost": 120}));
+        assert!(apply_operation(&rename, &card(json!({"annual_cost": 120}))).is_none());
+
+        let backfill = MetamodelOperation::BackfillAttribute {
+            card_type: "Application".to_string(),
+            attribute_key: "hosting_type".to_string(),
+            default_value: json!("On-Premise"),
+            make_required: true,
+        };
+        let migrated = apply_operation(&backfill, &card(json!({"hosting_type": null}))).unwrap();
+        assert_eq!(migrated.attributes, json!({"hosting_type": "On-Premise"}));
+        assert!(apply_operation(&backfill, &card(json!({"hosting_type": "SaaS"}))).is_none());
+
+        let retire = |mode| MetamodelOperation::RetireCardType {
+            card_type: "Application".to_string(),
+            replacement: Some("Platform".to_string()),
+            mode,
+        };
+        let tagged = apply_operation(&retire(RetireMode::Tag), &card(json!({}))).unwrap();
+        assert_eq!(tagged.tags, vec![DEPRECATED_TAG.to_string()]);
+        assert!(apply_operation(&retire(RetireMode::Tag), &tagged).is_none());
+        let converted = apply_operation(&retire(RetireMode::Convert), &card(json!({}))).unwrap();
+        assert_eq!(converted.card_type, CardType::Platform);
+        let archived = apply_operation(&retire(RetireMode::Archive), &card(json!({}))).unwrap();
+        assert_eq!(archived.status, "archived");
+
+        // Other card types are never touched
+        let mut interface = card(json!({"cost": 100}));
+        interface.card_type = CardType::Interface;
+        assert!(apply_operation(&rename, &interface).is_none());
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let before = card(json!({"cost": 100, "owner": "Sales"}));
+        let mut after = before.clone();
+        after.attributes = json!({"annual_cost": 100, "owner": "Sales"});
+
+        let (old, new) = changed_fields(&before, &after);
+        assert_eq!(old, json!({"attributes": {"cost": 100, "annual_cost": null}}));
+        assert_eq!(new, json!({"attributes": {"cost": null, "annual_cost": 100}}));
+    }
+}
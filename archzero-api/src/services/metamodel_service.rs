@@ -0,0 +1,335 @@
+/*!
+ * Metamodel Service
+ *
+ * Registry of per card type attribute rules (required keys, regex patterns, allowed
+ * values), managed by admins. `CardService` checks card attributes against it, and
+ * against the attribute schema of the card type, on every create and attribute update,
+ * which covers imports and bulk updates too.
+ */
+
+use regex::Regex;
+use serde_json::Value;
+use sqlx::{PgConnection, PgExecutor, PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::CardType;
+use crate::models::metamodel::*;
+use crate::services::card_type_service::{check_schema, enforce_card_type_registered, load_attribute_schema};
+
+const RULE_COLUMNS: &str = "id, card_type, attribute_key, is_required, regex_pattern, allowed_values, \
+                            description, example_value, created_by, created_at, updated_at";
+
+pub struct MetamodelService {
+    pool: PgPool,
+}
+
+impl MetamodelService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, card_type: Option<&str>) -> Result<Vec<MetamodelRule>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM metamodel_rules WHERE ($1::text IS NULL OR card_type = $1) ORDER BY card_type, attribute_key",
+            RULE_COLUMNS
+        ))
+        .bind(card_type)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list metamodel rules: {}", e)))?;
+
+        rows.iter().map(row_to_rule).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<MetamodelRule, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM metamodel_rules WHERE id = $1", RULE_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch metamodel rule: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Metamodel rule {} not found", id)))?;
+
+        row_to_rule(&row)
+    }
+
+    /// Add a rule; one per card type and attribute key
+    pub async fn create(&self, req: CreateMetamodelRuleRequest, created_by: Option<Uuid>) -> Result<MetamodelRule, AppError> {
+        validate_card_type(&self.pool, &req.card_type).await?;
+        let attribute_key = req.attribute_key.trim();
+        if attribute_key.is_empty() {
+            return Err(AppError::Validation("Attribute key is required".to_string()));
+        }
+        validate_rule(req.regex_pattern.as_deref(), req.allowed_values.as_deref())?;
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO metamodel_rules (card_type, attribute_key, is_required, regex_pattern, allowed_values, description, example_value, created_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING {}",
+            RULE_COLUMNS
+        ))
+        .bind(&req.card_type)
+        .bind(attribute_key)
+        .bind(req.is_required)
+        .bind(&req.regex_pattern)
+        .bind(&req.allowed_values)
+        .bind(&req.description)
+        .bind(&req.example_value)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        row_to_rule(&row)
+    }
+
+    pub async fn update(&self, id: Uuid, req: UpdateMetamodelRuleRequest) -> Result<MetamodelRule, AppError> {
+        validate_rule(req.regex_pattern.as_deref(), req.allowed_values.as_deref())?;
+
+        let row = sqlx::query(&format!(
+            "UPDATE metamodel_rules SET is_required = $2, regex_pattern = $3, allowed_values = $4, \
+             description = $5, example_value = $6, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .bind(req.is_required)
+        .bind(&req.regex_pattern)
+        .bind(&req.allowed_values)
+        .bind(&req.description)
+        .bind(&req.example_value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update metamodel rule: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Metamodel rule {} not found", id)))?;
+
+        row_to_rule(&row)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM metamodel_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete metamodel rule: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Metamodel rule {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Violations of the card type's schema and rules, without writing anything
+    pub async fn check(&self, card_type: &str, attributes: &Value) -> Result<Vec<AttributeViolation>, AppError> {
+        validate_card_type(&self.pool, card_type).await?;
+        let mut violations = check_schema(&load_attribute_schema(&self.pool, card_type).await?, attributes);
+        let rules = self.list(Some(card_type)).await?;
+        violations.extend(check_attributes(&rules, attributes));
+        Ok(violations)
+    }
+}
+
+/// Reject attributes breaking the card type's schema or one of its rules, with every violation listed
+pub(crate) async fn enforce_attribute_rules(
+    conn: &mut PgConnection,
+    card_type: &str,
+    attributes: &Value,
+) -> Result<(), AppError> {
+    let schema = load_attribute_schema(&mut *conn, card_type).await?;
+    let rows = sqlx::query(&format!("SELECT {} FROM metamodel_rules WHERE card_type = $1", RULE_COLUMNS))
+        .bind(card_type)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load metamodel rules: {}", e)))?;
+    let rules = rows.iter().map(row_to_rule).collect::<Result<Vec<_>, _>>()?;
+
+    let mut violations = check_schema(&schema, attributes);
+    violations.extend(check_attributes(&rules, attributes));
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::AttributeValidation(violations))
+    }
+}
+
+/// Latest completed metamodel version, as an SQL expression stamped on written cards
+pub(crate) const CURRENT_METAMODEL_VERSION_SQL: &str = "COALESCE((SELECT version FROM metamodel_migrations \
+     WHERE status = 'completed' ORDER BY major DESC, minor DESC LIMIT 1), '1.0')";
+
+/// Reject new cards of a retired card type
+pub(crate) async fn enforce_card_type_open<'e, E: PgExecutor<'e>>(executor: E, card_type: &str) -> Result<(), AppError> {
+    let retired: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT replacement_type FROM metamodel_retired_card_types WHERE card_type = $1",
+    )
+    .bind(card_type)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check retired card types: {}", e)))?;
+
+    match retired {
+        None => Ok(()),
+        Some(Some(replacement)) => Err(AppError::Validation(format!(
+            "Card type '{}' is retired. Use '{}' instead.",
+            card_type, replacement
+        ))),
+        Some(None) => Err(AppError::Validation(format!("Card type '{}' is retired", card_type))),
+    }
+}
+
+/// Check attributes against rules; a JSON null counts as missing
+pub fn check_attributes(rules: &[MetamodelRule], attributes: &Value) -> Vec<AttributeViolation> {
+    let mut violations = Vec::new();
+
+    for rule in rules {
+        let field = format!("attributes.{}", rule.attribute_key);
+        let value = attributes.get(&rule.attribute_key).filter(|value| !value.is_null());
+
+        let Some(value) = value else {
+            if rule.is_required {
+                violations.push(AttributeViolation {
+                    field,
+                    message: "Missing required attribute".to_string(),
+                    details: ViolationDetails {
+                        required_by_rule: Some(rule.id),
+                        allowed_values: rule.allowed_values.clone(),
+                        example_valid_value: rule.example_value.clone(),
+                        ..Default::default()
+                    },
+                });
+            }
+            continue;
+        };
+
+        // Patterns and allowed values compare the value as text
+        let text = match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        };
+
+        if let Some(allowed) = &rule.allowed_values {
+            if !text.as_ref().is_some_and(|text| allowed.contains(text)) {
+                violations.push(AttributeViolation {
+                    field,
+                    message: "Invalid enum value".to_string(),
+                    details: ViolationDetails {
+                        provided_value: Some(value.clone()),
+                        allowed_values: Some(allowed.clone()),
+                        ..Default::default()
+                    },
+                });
+                continue;
+            }
+        }
+
+        if let Some(pattern) = &rule.regex_pattern {
+            // Patterns are checked when the rule is saved, so an invalid one is skipped here
+            let matches = match Regex::new(pattern) {
+                Ok(regex) => text.as_ref().is_some_and(|text| regex.is_match(text)),
+                Err(_) => true,
+            };
+            if !matches {
+                violations.push(AttributeViolation {
+                    field,
+                    message: "Attribute value does not match required pattern".to_string(),
+                    details: ViolationDetails {
+                        provided_value: Some(value.clone()),
+                        required_pattern: Some(pattern.clone()),
+                        example_valid_value: rule.example_value.clone(),
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Reject names that are neither built-in nor registered card types
+pub(crate) async fn validate_card_type<'e, E: PgExecutor<'e>>(executor: E, card_type: &str) -> Result<(), AppError> {
+    if card_type.is_empty() {
+        return Err(AppError::Validation("Card type is required".to_string()));
+    }
+    enforce_card_type_registered(executor, &CardType::from_name(card_type)).await
+}
+
+fn validate_rule(regex_pattern: Option<&str>, allowed_values: Option<&[String]>) -> Result<(), AppError> {
+    if let Some(pattern) = regex_pattern {
+        Regex::new(pattern).map_err(|e| AppError::Validation(format!("Invalid regex pattern: {}", e)))?;
+    }
+    if allowed_values.is_some_and(|values| values.is_empty()) {
+        return Err(AppError::Validation("Allowed values must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_rule(row: &sqlx::postgres::PgRow) -> Result<MetamodelRule, AppError> {
+    Ok(MetamodelRule {
+        id: row.try_get("id").map_err(missing("id"))?,
+        card_type: row.try_get("card_type").map_err(missing("card_type"))?,
+        attribute_key: row.try_get("attribute_key").map_err(missing("attribute_key"))?,
+        is_required: row.try_get("is_required").map_err(missing("is_required"))?,
+        regex_pattern: row.try_get("regex_pattern").map_err(missing("regex_pattern"))?,
+        allowed_values: row.try_get("allowed_values").map_err(missing("allowed_values"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        example_value: row.try_get("example_value").map_err(missing("example_value"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn rule(key: &str, required: bool, pattern: Option<&str>, allowed: Option<&[&str]>) -> MetamodelRule {
+        MetamodelRule {
+            id: Uuid::new_v4(),
+            card_type: "Application".to_string(),
+            attribute_key: key.to_string(),
+            is_required: required,
+            regex_pattern: pattern.map(str::to_string),
+            allowed_values: allowed.map(|values| values.iter().map(|v| v.to_string()).collect()),
+            description: None,
+            example_value: Some("CC-FIN-01".to_string()),
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_check_attributes() {
+        let rules = vec![
+            rule("hosting_type", true, None, Some(&["SaaS", "PaaS", "IaaS", "On-Premise"])),
+            rule("cost_center", false, Some(r"^CC-[A-Z]{2,4}-\d{2}$"), None),
+        ];
+
+        assert!(check_attributes(&rules, &json!({"hosting_type": "SaaS", "cost_center": "CC-FIN-01"})).is_empty());
+        // Optional attributes may be left out
+        assert!(check_attributes(&rules, &json!({"hosting_type": "IaaS"})).is_empty());
+
+        let violations = check_attributes(&rules, &json!({"cost_center": "SALES-001"}));
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].field, "attributes.hosting_type");
+        assert_eq!(violations[0].message, "Missing required attribute");
+        assert_eq!(violations[0].details.required_by_rule, Some(rules[0].id));
+        assert_eq!(violations[1].field, "attributes.cost_center");
+        assert_eq!(violations[1].details.provided_value, Some(json!("SALES-001")));
+        assert_eq!(violations[1].details.required_pattern.as_deref(), Some(r"^CC-[A-Z]{2,4}-\d{2}$"));
+
+        let violations = check_attributes(&rules, &json!({"hosting_type": "Hybrid Cloud"}));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "Invalid enum value");
+        assert_eq!(violations[0].details.allowed_values.as_ref().map(Vec::len), Some(4));
+
+        // Null counts as missing
+        assert_eq!(check_attributes(&rules, &json!({"hosting_type": null})).len(), 1);
+    }
+}
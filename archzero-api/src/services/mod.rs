@@ -1,41 +1,82 @@
+pub mod account_sender;
+pub mod api_token_service;
 pub mod arb_audit_service;
 pub mod arb_notification_service;
 pub mod arb_template_service;
+pub mod architecture_state_service;
 pub mod auth_service;
 pub mod bia_service;
 pub mod cache;
+pub mod card_access;
+pub mod card_query;
+pub mod card_search;
+pub mod card_similarity;
 pub mod card_service;
+pub mod card_type_service;
 pub mod cached_card_service;
 pub mod csrf;
+pub mod data_quality;
+pub mod data_quality_service;
 pub mod db_service;
+pub mod embedded_graph_service;
 pub mod export_scheduler;
 pub mod export_service;
+pub mod graph_backend;
+pub mod graph_sync_service;
+pub mod metamodel_migration_service;
+pub mod metamodel_service;
 pub mod migration_service;
 pub mod relationship_service;
+pub mod relationship_rule_service;
 pub mod neo4j_service;
+pub mod oidc_service;
+pub mod pagination;
 pub mod report_service;
 pub mod saga_service;
+pub mod saved_search_service;
+pub mod scim_service;
 pub mod topology_service;
 pub mod tco_service;
+pub mod team_service;
 pub mod rate_limit;
+pub mod version_service;
+pub mod user_service;
+pub mod workspace_service;
 
+pub use api_token_service::ApiTokenService;
 pub use arb_audit_service::ARBAuditService;
 pub use arb_notification_service::ARBNotificationService;
 pub use arb_template_service::ArbTemplateService;
+pub use architecture_state_service::ArchitectureStateService;
 pub use auth_service::AuthService;
 pub use bia_service::BIAService;
 pub use cache::CacheService;
 pub use card_service::CardService;
+pub use card_type_service::CardTypeService;
 pub use cached_card_service::CachedCardService;
 pub use csrf::CsrfService;
+pub use data_quality_service::DataQualityService;
 pub use db_service::{DatabaseService, PgPool};
+pub use embedded_graph_service::EmbeddedGraphService;
 pub use export_scheduler::ExportScheduler;
 pub use export_service::ExportService;
+pub use graph_backend::GraphBackend;
+pub use graph_sync_service::GraphSyncService;
+pub use metamodel_migration_service::MetamodelMigrationService;
+pub use metamodel_service::MetamodelService;
 pub use migration_service::MigrationService;
 pub use relationship_service::RelationshipService;
+pub use relationship_rule_service::RelationshipRuleService;
 pub use neo4j_service::Neo4jService;
+pub use oidc_service::OidcService;
 pub use report_service::ReportService;
 pub use saga_service::SagaOrchestrator;
+pub use saved_search_service::SavedSearchService;
+pub use scim_service::ScimService;
 pub use topology_service::TopologyService;
 pub use tco_service::TCOService;
+pub use team_service::TeamService;
 pub use rate_limit::RateLimitService;
+pub use version_service::VersionService;
+pub use user_service::UserService;
+pub use workspace_service::WorkspaceService;
@@ -1,10 +1,14 @@
+use async_trait::async_trait;
 use neo4rs::Graph;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::models::card::Card;
-use crate::models::relationship::Relationship;
+use crate::models::graph_sync::{GraphCardNode, GraphRelationshipEdge};
+use crate::models::relationship::{Relationship, RelationshipType};
+use crate::services::graph_backend::GraphBackend;
+use crate::services::version_service::current_workspace;
 use crate::error::AppError;
 
 #[derive(Clone)]
@@ -25,9 +29,36 @@ impl Neo4jService {
         let graph = Graph::connect(config).await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to connect to Neo4j: {}", e)))?;
 
-        Ok(Self {
+        let service = Self {
             graph: Arc::new(RwLock::new(graph)),
-        })
+        };
+        service.ensure_schema().await?;
+
+        Ok(service)
+    }
+
+    /// Create the card ID uniqueness constraint and one edge ID index per relationship type,
+    /// so lookups by ID do not scan the whole graph. Safe to run on every startup.
+    async fn ensure_schema(&self) -> Result<(), AppError> {
+        let mut statements = vec![
+            "CREATE CONSTRAINT card_id_unique IF NOT EXISTS FOR (c:Card) REQUIRE c.id IS UNIQUE".to_string(),
+        ];
+        for relationship_type in &RelationshipType::ALL {
+            let label = edge_label(relationship_type)?;
+            statements.push(format!(
+                "CREATE INDEX {INDEX}_id IF NOT EXISTS FOR ()-[r:{REL_TYPE}]-() ON (r.id)",
+                INDEX = label.to_lowercase(),
+                REL_TYPE = label
+            ));
+        }
+
+        let graph = self.graph.write().await;
+        for statement in statements {
+            graph.run(neo4rs::query(&statement)).await
+                .map_err(|e| AppError::Neo4j(format!("Failed to create graph schema: {}", e)))?;
+        }
+
+        Ok(())
     }
 
     /// Execute a generic Neo4j query
@@ -54,7 +85,8 @@ impl Neo4jService {
             r#"
             CREATE (c:Card {{id: $id, name: $name, type: $type, lifecyclePhase: $lifecyclePhase,
                                qualityScore: $qualityScore, description: $description,
-                               status: $status, createdAt: $createdAt, updatedAt: $updatedAt}})
+                               status: $status, workspaceId: $workspaceId,
+                               createdAt: $createdAt, updatedAt: $updatedAt}})
             "#,
         );
 
@@ -66,6 +98,7 @@ impl Neo4jService {
             .param("qualityScore", card.quality_score.unwrap_or(0))
             .param("description", card.description.clone().unwrap_or_default())
             .param("status", card.status.clone())
+            .param("workspaceId", workspace_param(card.workspace_id))
             .param("createdAt", card.created_at.to_rfc3339())
             .param("updatedAt", card.updated_at.to_rfc3339());
 
@@ -94,7 +127,7 @@ impl Neo4jService {
             MATCH (c:Card {{id: $id}})
             SET c.name = $name, c.type = $type, c.lifecyclePhase = $lifecyclePhase,
                 c.qualityScore = $qualityScore, c.description = $description,
-                c.status = $status, c.updatedAt = $updatedAt
+                c.status = $status, c.workspaceId = $workspaceId, c.updatedAt = $updatedAt
             "#,
         );
 
@@ -106,6 +139,7 @@ impl Neo4jService {
             .param("qualityScore", card.quality_score.unwrap_or(0))
             .param("description", card.description.clone().unwrap_or_default())
             .param("status", card.status.clone())
+            .param("workspaceId", workspace_param(card.workspace_id))
             .param("updatedAt", card.updated_at.to_rfc3339());
 
         graph.run(query).await
@@ -131,34 +165,30 @@ impl Neo4jService {
 
     /// Create a relationship between two cards in Neo4j
     pub async fn create_relationship(&self, relationship: &Relationship) -> Result<(), AppError> {
-        let relationship_type_str = serde_json::to_string(&relationship.relationship_type)
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize relationship type: {}", e)))?
-            .trim_matches('"')
-            .to_string();
+        // Use the relationship type as the edge label (validated against injection)
+        let edge_type = edge_label(&relationship.relationship_type)?;
 
         let graph = self.graph.write().await;
 
-        // Use the relationship type as the edge label (must be alphanumeric)
-        // Validate to prevent Neo4j injection - only allow alphanumeric and underscore
-        let edge_type = relationship_type_str.replace(" ", "");
-        if !edge_type.chars().all(|c| c.is_alphanumeric() || c == '_') {
-            return Err(AppError::Internal(anyhow::anyhow!("Invalid relationship type: {}", edge_type)));
-        }
-
         let query = format!(
             r#"
             MATCH (from:Card {{id: $fromId}}), (to:Card {{id: $toId}})
-            CREATE (from)-[r:{REL_TYPE} {{validFrom: $validFrom, validTo: $validTo, confidence: $confidence}}]->(to)
+            CREATE (from)-[r:{REL_TYPE} {{id: $id, validFrom: $validFrom, validTo: $validTo,
+                                           confidence: $confidence, attributes: $attributes,
+                                           workspaceId: $workspaceId}}]->(to)
             "#,
-            REL_TYPE = edge_type.to_uppercase()
+            REL_TYPE = edge_type
         );
 
         let query = neo4rs::query(&query)
+            .param("id", relationship.id.to_string())
             .param("fromId", relationship.from_card_id.to_string())
             .param("toId", relationship.to_card_id.to_string())
             .param("validFrom", relationship.valid_from.clone())
             .param("validTo", relationship.valid_to.clone().unwrap_or_default())
-            .param("confidence", relationship.confidence.unwrap_or(1.0));
+            .param("confidence", relationship.confidence.unwrap_or(1.0))
+            .param("attributes", relationship.attributes.to_string())
+            .param("workspaceId", workspace_param(relationship.workspace_id));
 
         graph.run(query).await
             .map_err(|e| AppError::Neo4j(format!("Failed to create relationship: {}", e)))?;
@@ -166,11 +196,55 @@ impl Neo4jService {
         Ok(())
     }
 
-    /// Delete a relationship from Neo4j
-    pub async fn delete_relationship(&self, relationship_id: Uuid) -> Result<(), AppError> {
-        let graph = self.graph.write().await;
+    /// Update the properties of a relationship edge, matched by relationship ID.
+    /// If the edge does not exist yet (e.g. it was never synced) it is created.
+    pub async fn update_relationship(&self, relationship: &Relationship) -> Result<(), AppError> {
+        let query = format!(
+            r#"
+            MATCH (:Card)-[r:{REL_TYPE} {{id: $id}}]->(:Card)
+            SET r.validFrom = $validFrom, r.validTo = $validTo,
+                r.confidence = $confidence, r.attributes = $attributes, r.workspaceId = $workspaceId
+            RETURN count(r) as updated
+            "#,
+            REL_TYPE = edge_label(&relationship.relationship_type)?
+        );
+
+        let query = neo4rs::query(&query)
+        .param("id", relationship.id.to_string())
+        .param("validFrom", relationship.valid_from.clone())
+        .param("validTo", relationship.valid_to.clone().unwrap_or_default())
+        .param("confidence", relationship.confidence.unwrap_or(1.0))
+        .param("attributes", relationship.attributes.to_string())
+        .param("workspaceId", workspace_param(relationship.workspace_id));
+
+        let updated = {
+            let graph = self.graph.write().await;
+            let mut result = graph.execute(query).await
+                .map_err(|e| AppError::Neo4j(format!("Failed to update relationship: {}", e)))?;
+
+            match result.next().await {
+                Ok(Some(row)) => row.get::<i64>("updated").unwrap_or(0),
+                Ok(None) => 0,
+                Err(e) => return Err(AppError::Neo4j(format!("Failed to update relationship: {}", e))),
+            }
+        };
+
+        if updated == 0 {
+            tracing::warn!("Relationship edge {} missing in Neo4j, creating it", relationship.id);
+            self.create_relationship(relationship).await?;
+        }
+
+        Ok(())
+    }
 
-        let query = "MATCH ()-[r]->() WHERE elementId(r).toString() CONTAINS $id DELETE r";
+    /// Delete a relationship from Neo4j, matched by edge label and relationship ID
+    pub async fn delete_relationship(&self, relationship_id: Uuid, edge_type: &str) -> Result<(), AppError> {
+        let query = format!(
+            "MATCH (:Card)-[r:{REL_TYPE} {{id: $id}}]->(:Card) DELETE r",
+            REL_TYPE = checked_label(edge_type)?
+        );
+
+        let graph = self.graph.write().await;
 
         let query = neo4rs::query(&query)
             .param("id", relationship_id.to_string());
@@ -195,4 +269,341 @@ impl Neo4jService {
 
         Ok(())
     }
+
+    /// Create or refresh a card node, keyed by card ID
+    pub async fn merge_card_node(&self, card: &Card) -> Result<(), AppError> {
+        let card_type_str = graph_label(&card.card_type)?;
+        let lifecycle_phase_str = graph_label(&card.lifecycle_phase)?;
+
+        let graph = self.graph.write().await;
+
+        let query = r#"
+            MERGE (c:Card {id: $id})
+            SET c.name = $name, c.type = $type, c.lifecyclePhase = $lifecyclePhase,
+                c.qualityScore = $qualityScore, c.description = $description,
+                c.status = $status, c.workspaceId = $workspaceId,
+                c.createdAt = $createdAt, c.updatedAt = $updatedAt
+            "#;
+
+        let query = neo4rs::query(query)
+            .param("id", card.id.to_string())
+            .param("name", card.name.clone())
+            .param("type", card_type_str)
+            .param("lifecyclePhase", lifecycle_phase_str)
+            .param("qualityScore", card.quality_score.unwrap_or(0))
+            .param("description", card.description.clone().unwrap_or_default())
+            .param("status", card.status.clone())
+            .param("workspaceId", workspace_param(card.workspace_id))
+            .param("createdAt", card.created_at.to_rfc3339())
+            .param("updatedAt", card.updated_at.to_rfc3339());
+
+        graph.run(query).await
+            .map_err(|e| AppError::Neo4j(format!("Failed to merge card node: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// List every card node currently stored in Neo4j
+    pub async fn list_card_nodes(&self) -> Result<Vec<GraphCardNode>, AppError> {
+        let query = neo4rs::query(
+            "MATCH (c:Card) RETURN c.id as id, c.name as name, c.type as type, c.lifecyclePhase as lifecyclePhase, \
+             c.workspaceId as workspaceId",
+        );
+
+        let mut result = self.execute_query(query).await?;
+
+        let mut nodes = Vec::new();
+        while let Some(row) = result.next().await
+            .map_err(|e| AppError::Neo4j(format!("Failed to read card nodes: {}", e)))?
+        {
+            let Some(id) = row.get::<String>("id").and_then(|s| Uuid::parse_str(&s).ok()) else {
+                tracing::warn!("Skipping card node without a valid id");
+                continue;
+            };
+
+            nodes.push(GraphCardNode {
+                id,
+                name: row.get::<String>("name").unwrap_or_default(),
+                card_type: row.get::<String>("type").unwrap_or_default(),
+                lifecycle_phase: row.get::<String>("lifecyclePhase").unwrap_or_default(),
+                workspace_id: row.get::<String>("workspaceId").and_then(|s| Uuid::parse_str(&s).ok()),
+            });
+        }
+
+        Ok(nodes)
+    }
+
+    /// List every relationship edge between card nodes in Neo4j
+    pub async fn list_relationship_edges(&self) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        let query = neo4rs::query(
+            r#"
+            MATCH (from:Card)-[r]->(to:Card)
+            RETURN r.id as id, from.id as fromId, to.id as toId, type(r) as relType,
+                   r.validFrom as validFrom, r.validTo as validTo, r.confidence as confidence,
+                   r.attributes as attributes, r.workspaceId as workspaceId
+            "#,
+        );
+
+        let mut result = self.execute_query(query).await?;
+
+        let mut edges = Vec::new();
+        while let Some(row) = result.next().await
+            .map_err(|e| AppError::Neo4j(format!("Failed to read relationship edges: {}", e)))?
+        {
+            match row_to_edge(&row) {
+                Some(edge) => edges.push(edge),
+                None => tracing::warn!("Skipping relationship edge between nodes without valid ids"),
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Delete relationship edges that carry no relationship ID.
+    /// These were written before edges were keyed by ID and cannot be matched back to PostgreSQL.
+    pub async fn delete_untracked_edges(&self) -> Result<(), AppError> {
+        let graph = self.graph.write().await;
+
+        let query = neo4rs::query("MATCH (:Card)-[r]->(:Card) WHERE r.id IS NULL DELETE r");
+
+        graph.run(query).await
+            .map_err(|e| AppError::Neo4j(format!("Failed to delete untracked edges: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Remove every card node and edge from the graph
+    pub async fn clear_graph(&self) -> Result<(), AppError> {
+        let graph = self.graph.write().await;
+
+        let query = neo4rs::query("MATCH (c:Card) DETACH DELETE c");
+
+        graph.run(query).await
+            .map_err(|e| AppError::Neo4j(format!("Failed to clear graph: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Distinct cards with an edge of the current workspace pointing at the card
+    pub async fn dependents(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let query = neo4rs::query(
+            "MATCH (c:Card {id: $card_id})<-[r]-(other:Card) \
+             WHERE $workspaceId = '' OR r.workspaceId = $workspaceId \
+             RETURN DISTINCT other.id as id",
+        )
+        .param("card_id", card_id.to_string())
+        .param("workspaceId", workspace_param(current_workspace()));
+
+        self.collect_ids(query, "id").await
+    }
+
+    /// Distinct cards the card has an edge of the current workspace pointing to
+    pub async fn dependencies(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let query = neo4rs::query(
+            "MATCH (c:Card {id: $card_id})-[r]->(other:Card) \
+             WHERE $workspaceId = '' OR r.workspaceId = $workspaceId \
+             RETURN DISTINCT other.id as id",
+        )
+        .param("card_id", card_id.to_string())
+        .param("workspaceId", workspace_param(current_workspace()));
+
+        self.collect_ids(query, "id").await
+    }
+
+    /// Cards with at least `threshold` distinct dependents, highest fan-in first
+    pub async fn critical_paths(&self, threshold: u32) -> Result<Vec<(Uuid, u32)>, AppError> {
+        let query = neo4rs::query(
+            r#"
+            MATCH (c:Card)<-[r]-(other:Card)
+            WHERE $workspaceId = '' OR r.workspaceId = $workspaceId
+            WITH c, count(DISTINCT other) as fan_in
+            WHERE fan_in >= $threshold
+            RETURN c.id as card_id, fan_in
+            ORDER BY fan_in DESC
+            "#,
+        )
+        .param("threshold", threshold as i64)
+        .param("workspaceId", workspace_param(current_workspace()));
+
+        let mut result = self.execute_query(query).await?;
+
+        let mut critical_paths = Vec::new();
+        while let Some(row) = result.next().await
+            .map_err(|e| AppError::Neo4j(format!("Failed to read critical paths: {}", e)))?
+        {
+            if let Some(id) = row.get::<String>("card_id").and_then(|s| Uuid::parse_str(&s).ok()) {
+                let fan_in: i64 = row.get("fan_in").unwrap_or(0);
+                critical_paths.push((id, fan_in as u32));
+            }
+        }
+
+        Ok(critical_paths)
+    }
+
+    /// Edges of the current workspace reachable from the card within `depth` hops, in either direction
+    pub async fn neighbourhood(&self, card_id: Uuid, depth: u32) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        if depth == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Variable-length bounds cannot be parameterised; depth is numeric so interpolation is safe
+        let query = format!(
+            r#"
+            MATCH p = (c:Card {{id: $card_id}})-[*1..{DEPTH}]-(:Card)
+            WHERE $workspaceId = '' OR all(rel IN relationships(p) WHERE rel.workspaceId = $workspaceId)
+            UNWIND relationships(p) AS r
+            WITH DISTINCT r
+            RETURN r.id as id, startNode(r).id as fromId, endNode(r).id as toId, type(r) as relType,
+                   r.validFrom as validFrom, r.validTo as validTo, r.confidence as confidence,
+                   r.attributes as attributes, r.workspaceId as workspaceId
+            "#,
+            DEPTH = depth
+        );
+
+        let query = neo4rs::query(&query)
+            .param("card_id", card_id.to_string())
+            .param("workspaceId", workspace_param(current_workspace()));
+        let mut result = self.execute_query(query).await?;
+
+        let mut edges = Vec::new();
+        while let Some(row) = result.next().await
+            .map_err(|e| AppError::Neo4j(format!("Failed to read neighbourhood: {}", e)))?
+        {
+            if let Some(edge) = row_to_edge(&row) {
+                edges.push(edge);
+            }
+        }
+
+        Ok(edges)
+    }
+
+    async fn collect_ids(&self, query: neo4rs::Query, column: &str) -> Result<Vec<Uuid>, AppError> {
+        let mut result = self.execute_query(query).await?;
+
+        let mut ids = Vec::new();
+        while let Some(row) = result.next().await
+            .map_err(|e| AppError::Neo4j(format!("Failed to read card ids: {}", e)))?
+        {
+            if let Some(id) = row.get::<String>(column).and_then(|s| Uuid::parse_str(&s).ok()) {
+                ids.push(id);
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+#[async_trait]
+impl GraphBackend for Neo4jService {
+    fn name(&self) -> &'static str {
+        "neo4j"
+    }
+
+    async fn create_card_node(&self, card: &Card) -> Result<(), AppError> {
+        Neo4jService::create_card_node(self, card).await
+    }
+
+    async fn update_card_node(&self, card: &Card) -> Result<(), AppError> {
+        Neo4jService::update_card_node(self, card).await
+    }
+
+    async fn delete_card_node(&self, card_id: Uuid) -> Result<(), AppError> {
+        Neo4jService::delete_card_node(self, card_id).await
+    }
+
+    async fn merge_card_node(&self, card: &Card) -> Result<(), AppError> {
+        Neo4jService::merge_card_node(self, card).await
+    }
+
+    async fn create_relationship(&self, relationship: &Relationship) -> Result<(), AppError> {
+        Neo4jService::create_relationship(self, relationship).await
+    }
+
+    async fn update_relationship(&self, relationship: &Relationship) -> Result<(), AppError> {
+        Neo4jService::update_relationship(self, relationship).await
+    }
+
+    async fn delete_relationship(&self, relationship_id: Uuid, edge_type: &str) -> Result<(), AppError> {
+        Neo4jService::delete_relationship(self, relationship_id, edge_type).await
+    }
+
+    async fn list_card_nodes(&self) -> Result<Vec<GraphCardNode>, AppError> {
+        Neo4jService::list_card_nodes(self).await
+    }
+
+    async fn list_relationship_edges(&self) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        Neo4jService::list_relationship_edges(self).await
+    }
+
+    async fn delete_untracked_edges(&self) -> Result<(), AppError> {
+        Neo4jService::delete_untracked_edges(self).await
+    }
+
+    async fn clear_graph(&self) -> Result<(), AppError> {
+        Neo4jService::clear_graph(self).await
+    }
+
+    async fn dependents(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        Neo4jService::dependents(self, card_id).await
+    }
+
+    async fn dependencies(&self, card_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        Neo4jService::dependencies(self, card_id).await
+    }
+
+    async fn critical_paths(&self, threshold: u32) -> Result<Vec<(Uuid, u32)>, AppError> {
+        Neo4jService::critical_paths(self, threshold).await
+    }
+
+    async fn neighbourhood(&self, card_id: Uuid, depth: u32) -> Result<Vec<GraphRelationshipEdge>, AppError> {
+        Neo4jService::neighbourhood(self, card_id, depth).await
+    }
+}
+
+/// Read an edge returned as `id, fromId, toId, relType, validFrom, validTo, confidence, attributes, workspaceId`
+fn row_to_edge(row: &neo4rs::Row) -> Option<GraphRelationshipEdge> {
+    let from_card_id = row.get::<String>("fromId").and_then(|s| Uuid::parse_str(&s).ok())?;
+    let to_card_id = row.get::<String>("toId").and_then(|s| Uuid::parse_str(&s).ok())?;
+
+    Some(GraphRelationshipEdge {
+        id: row.get::<String>("id").and_then(|s| Uuid::parse_str(&s).ok()),
+        from_card_id,
+        to_card_id,
+        edge_type: row.get::<String>("relType").unwrap_or_default(),
+        valid_from: row.get::<String>("validFrom").unwrap_or_default(),
+        valid_to: row.get::<String>("validTo").filter(|s| !s.is_empty()),
+        confidence: row.get::<f64>("confidence"),
+        attributes: row.get::<String>("attributes")
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_else(|| serde_json::json!({})),
+        workspace_id: row.get::<String>("workspaceId").and_then(|s| Uuid::parse_str(&s).ok()),
+    })
+}
+
+/// Workspace ID as stored on nodes and edges; empty matches every workspace in traversals
+fn workspace_param(workspace_id: Option<Uuid>) -> String {
+    workspace_id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+/// Serialize a unit enum the same way it is stored on graph nodes and edges
+pub fn graph_label<T: serde::Serialize>(value: &T) -> Result<String, AppError> {
+    Ok(serde_json::to_string(value)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize graph label: {}", e)))?
+        .trim_matches('"')
+        .to_string())
+}
+
+/// Neo4j edge label for a relationship type, e.g. `reliesOn` becomes `RELIESON`.
+/// Only alphanumerics and underscores are allowed since the label is interpolated into Cypher.
+pub fn edge_label(relationship_type: &RelationshipType) -> Result<String, AppError> {
+    let edge_type = graph_label(relationship_type)?.replace(' ', "");
+    Ok(checked_label(&edge_type)?.to_uppercase())
+}
+
+/// Reject labels that are unsafe to interpolate into Cypher
+fn checked_label(edge_type: &str) -> Result<&str, AppError> {
+    if edge_type.is_empty() || !edge_type.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(AppError::Internal(anyhow::anyhow!("Invalid relationship type: {}", edge_type)));
+    }
+    Ok(edge_type)
 }
@@ -0,0 +1,673 @@
+/*!
+ * OpenID Connect Single Sign-On
+ *
+ * Authorization code flow with PKCE against the configured IdP:
+ * 1. `begin_login` stores a state/nonce/code-verifier and returns the IdP authorization URL,
+ *    plus a short-lived cookie binding the login to the browser that started it
+ * 2. `complete_login` checks that cookie (so an attacker cannot hand a victim the callback of
+ *    a login they started), consumes the state, exchanges the code (with the verifier) for an
+ *    ID token, validates it against the IdP's JWKS (or the client secret for HS* tokens),
+ *    provisions or links the user just in time and starts a normal session; an existing
+ *    account is only linked by email when the IdP verified the address and the account is
+ *    not bound to another IdP identity
+ *
+ * IdP groups map to roles through `[oidc] role_mappings`; the first match wins and is
+ * re-applied on every login, so the IdP stays the source of truth for roles.
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::{role_for_groups, Oidc};
+use crate::error::AppError;
+use crate::models::user::{LoginResponse, SessionClient, User, UserRole};
+use crate::services::user_service::{generate_token, hash_token};
+use crate::services::AuthService;
+
+/// Cookie binding a login in progress to the browser that started it; holds the state hash
+pub const LOGIN_COOKIE: &str = "oidc_login";
+
+/// Path the login cookie is sent to: the login and callback endpoints
+const LOGIN_COOKIE_PATH: &str = "/api/v1/auth/oidc";
+
+/// Subset of the IdP's discovery document
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// ID token claims used for provisioning; everything else is kept in `extra`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub name: Option<String>,
+    pub preferred_username: Option<String>,
+    pub nonce: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// A verified IdP identity
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: String,
+    /// The IdP asserted `email_verified: true`; required to link an existing account by email
+    pub email_verified: bool,
+    pub full_name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+pub struct OidcService {
+    pool: PgPool,
+    auth_service: Arc<AuthService>,
+    config: Oidc,
+    http: reqwest::Client,
+    metadata: RwLock<Option<ProviderMetadata>>,
+    jwks: RwLock<Option<JwkSet>>,
+    secure_cookie: bool,
+}
+
+impl OidcService {
+    pub fn new(pool: PgPool, auth_service: Arc<AuthService>, config: Oidc) -> Self {
+        Self {
+            pool,
+            auth_service,
+            config,
+            http: reqwest::Client::new(),
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+            secure_cookie: false,
+        }
+    }
+
+    /// Mark the login cookie `Secure` (HTTPS only)
+    pub fn with_secure_cookie(mut self, secure: bool) -> Self {
+        self.secure_cookie = secure;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Frontend page the callback redirects to
+    pub fn post_login_redirect(&self) -> &str {
+        &self.config.post_login_redirect
+    }
+
+    /// Start a login: remember state, nonce and PKCE verifier, and return the IdP URL with the
+    /// `Set-Cookie` value binding the login to this browser
+    pub async fn begin_login(&self) -> Result<(String, String), AppError> {
+        self.ensure_enabled()?;
+
+        let state = generate_token();
+        let nonce = generate_token();
+        let code_verifier = pkce_verifier();
+
+        // Abandoned logins are cleaned up as new ones start
+        sqlx::query("DELETE FROM oidc_login_requests WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to clean up login requests: {}", e)))?;
+
+        sqlx::query(
+            "INSERT INTO oidc_login_requests (state_hash, code_verifier, nonce, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(hash_token(&state))
+        .bind(&code_verifier)
+        .bind(&nonce)
+        .bind(Utc::now() + Duration::minutes(self.config.login_timeout_minutes))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to store login request: {}", e)))?;
+
+        let url = self.authorization_url(&state, &nonce, &pkce_challenge(&code_verifier)).await?;
+        Ok((url, self.login_cookie(&hash_token(&state))))
+    }
+
+    /// `Set-Cookie` value for the login cookie. `HttpOnly`, and `SameSite=Lax` so it still
+    /// accompanies the top-level redirect back from the IdP.
+    pub fn login_cookie(&self, value: &str) -> String {
+        let max_age = if value.is_empty() { 0 } else { self.config.login_timeout_minutes * 60 };
+        let mut cookie = format!(
+            "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite=Lax",
+            LOGIN_COOKIE, value, LOGIN_COOKIE_PATH, max_age
+        );
+        if self.secure_cookie {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+
+    /// `Set-Cookie` value removing the login cookie once the callback is handled
+    pub fn clear_login_cookie(&self) -> String {
+        self.login_cookie("")
+    }
+
+    /// Finish a login from the IdP callback and start a session. `login_cookie` is the value of
+    /// the cookie set by `begin_login`; it must belong to `state`.
+    pub async fn complete_login(
+        &self,
+        code: &str,
+        state: &str,
+        login_cookie: Option<&str>,
+        client: SessionClient,
+    ) -> Result<LoginResponse, AppError> {
+        self.ensure_enabled()?;
+        if login_cookie != Some(hash_token(state).as_str()) {
+            return Err(AppError::Auth("Login was not started in this browser".to_string()));
+        }
+
+        let request = sqlx::query(
+            "DELETE FROM oidc_login_requests WHERE state_hash = $1 AND expires_at > NOW()
+             RETURNING code_verifier, nonce",
+        )
+        .bind(hash_token(state))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch login request: {}", e)))?
+        .ok_or_else(|| AppError::Auth("Login request is invalid or has expired".to_string()))?;
+
+        let code_verifier: String = request.try_get("code_verifier")
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing code_verifier: {}", e)))?;
+        let nonce: String = request.try_get("nonce")
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing nonce: {}", e)))?;
+
+        let identity = self.exchange_code(code, &code_verifier, &nonce).await?;
+        let user = self.provision_user(&identity).await?;
+
+        tracing::info!("Single sign-on login for {} ({})", user.email, identity.subject);
+        self.auth_service.start_session(user, &client).await
+    }
+
+    /// IdP authorization URL for the given state, nonce and PKCE challenge
+    pub async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, AppError> {
+        let metadata = self.metadata().await?;
+
+        let url = url::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", self.config.scopes.join(" ").as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid authorization endpoint: {}", e)))?;
+
+        Ok(url.into())
+    }
+
+    /// Exchange an authorization code for a validated identity
+    pub async fn exchange_code(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<OidcIdentity, AppError> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self.http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            tracing::warn!("IdP rejected authorization code ({}): {}", status, body);
+            return Err(AppError::Auth("Single sign-on failed: authorization code was rejected".to_string()));
+        }
+
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid token response: {}", e)))?;
+
+        let claims = self.validate_id_token(&tokens.id_token, &metadata).await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(AppError::Auth("Single sign-on failed: nonce mismatch".to_string()));
+        }
+
+        self.identity_from_claims(&metadata.issuer, claims)
+    }
+
+    /// Role for a user's IdP groups; `None` if no mapping matches
+    pub fn role_for_groups(&self, groups: &[String]) -> Option<UserRole> {
+        role_for_groups(&self.config.role_mappings, groups)
+    }
+
+    /// Role applied at login: the mapped role, or the default role once the user's groups no
+    /// longer match any mapping. `None` (keep the stored role) when no mappings are configured.
+    pub fn login_role(&self, groups: &[String]) -> Option<UserRole> {
+        if self.config.role_mappings.is_empty() {
+            return None;
+        }
+        Some(self.role_for_groups(groups).unwrap_or_else(|| self.config.default_role.clone()))
+    }
+
+    /// Find the user by IdP identity, link an existing account by email, or create one
+    async fn provision_user(&self, identity: &OidcIdentity) -> Result<User, AppError> {
+        let login_role = self.login_role(&identity.groups);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let existing = sqlx::query(
+            "SELECT id, is_active, oidc_issuer, oidc_subject FROM users
+             WHERE (oidc_issuer = $1 AND oidc_subject = $2) OR LOWER(email) = LOWER($3)
+             ORDER BY (oidc_issuer = $1 AND oidc_subject = $2) DESC NULLS LAST
+             LIMIT 1
+             FOR UPDATE",
+        )
+        .bind(&identity.issuer)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to look up user: {}", e)))?;
+
+        let user_id: Uuid = match existing {
+            Some(row) => {
+                let user_id: Uuid = row.try_get("id")
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing id: {}", e)))?;
+                let is_active: bool = row.try_get("is_active")
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing is_active: {}", e)))?;
+                if !is_active {
+                    return Err(AppError::Auth("Account deactivated".to_string()));
+                }
+                let linked_issuer: Option<String> = row.try_get("oidc_issuer")
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing oidc_issuer: {}", e)))?;
+                let linked_subject: Option<String> = row.try_get("oidc_subject")
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing oidc_subject: {}", e)))?;
+                check_account_link(identity, linked_issuer.as_deref(), linked_subject.as_deref())?;
+
+                sqlx::query(
+                    "UPDATE users SET oidc_issuer = $2, oidc_subject = $3,
+                            full_name = COALESCE($4, full_name),
+                            role = COALESCE($5, role),
+                            failed_login_attempts = 0, locked_until = NULL, updated_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(user_id)
+                .bind(&identity.issuer)
+                .bind(&identity.subject)
+                .bind(&identity.full_name)
+                .bind(login_role.as_ref().map(|role| role.as_str()))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update user: {}", e)))?;
+
+                user_id
+            }
+            None => {
+                let role = login_role.unwrap_or_else(|| self.config.default_role.clone());
+                sqlx::query_scalar(
+                    "INSERT INTO users (email, full_name, role, password_hash, oidc_issuer, oidc_subject, failed_login_attempts)
+                     VALUES ($1, $2, $3, NULL, $4, $5, 0)
+                     RETURNING id",
+                )
+                .bind(&identity.email)
+                .bind(&identity.full_name)
+                .bind(role.as_str())
+                .bind(&identity.issuer)
+                .bind(&identity.subject)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        self.auth_service.get_user(user_id).await
+    }
+
+    async fn validate_id_token(&self, id_token: &str, metadata: &ProviderMetadata) -> Result<IdTokenClaims, AppError> {
+        let invalid = |reason: String| AppError::Auth(format!("Single sign-on failed: invalid ID token ({})", reason));
+
+        let header = decode_header(id_token).map_err(|e| invalid(e.to_string()))?;
+        let key = match header.alg {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = self.config.client_secret.as_ref()
+                    .ok_or_else(|| invalid("HMAC-signed token without a client secret".to_string()))?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => self.signing_key(header.kid.as_deref(), metadata).await?,
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| invalid(e.to_string()))
+    }
+
+    /// Key from the IdP's JWKS; refetched once when the key ID is unknown (key rotation)
+    async fn signing_key(&self, kid: Option<&str>, metadata: &ProviderMetadata) -> Result<DecodingKey, AppError> {
+        for refresh in [false, true] {
+            if refresh || self.jwks.read().await.is_none() {
+                let jwks: JwkSet = self.fetch_json(&metadata.jwks_uri).await?;
+                *self.jwks.write().await = Some(jwks);
+            }
+
+            let jwks = self.jwks.read().await;
+            let jwk = jwks.as_ref().and_then(|set| match kid {
+                Some(kid) => set.find(kid),
+                None => set.keys.first(),
+            });
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk)
+                    .map_err(|e| AppError::Auth(format!("Single sign-on failed: unusable signing key ({})", e)));
+            }
+        }
+
+        Err(AppError::Auth("Single sign-on failed: unknown signing key".to_string()))
+    }
+
+    fn identity_from_claims(&self, issuer: &str, claims: IdTokenClaims) -> Result<OidcIdentity, AppError> {
+        if claims.email_verified == Some(false) {
+            return Err(AppError::Auth("Single sign-on failed: email address is not verified".to_string()));
+        }
+
+        let email = claims.email.clone()
+            .or_else(|| claims.preferred_username.clone().filter(|name| name.contains('@')))
+            .ok_or_else(|| AppError::Auth("Single sign-on failed: the IdP did not provide an email address".to_string()))?;
+
+        // Groups may be a list or a single string
+        let groups = match claims.extra.get(&self.config.groups_claim) {
+            Some(Value::Array(values)) => values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+            Some(Value::String(group)) => vec![group.clone()],
+            _ => Vec::new(),
+        };
+
+        Ok(OidcIdentity {
+            issuer: issuer.to_string(),
+            subject: claims.sub,
+            email,
+            email_verified: claims.email_verified == Some(true),
+            full_name: claims.name,
+            groups,
+        })
+    }
+
+    async fn metadata(&self) -> Result<ProviderMetadata, AppError> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.config.issuer_url.trim_end_matches('/'));
+        let metadata: ProviderMetadata = self.fetch_json(&url).await?;
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn fetch_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, AppError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to reach the IdP at {}: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid response from the IdP at {}: {}", url, e)))
+    }
+
+    fn ensure_enabled(&self) -> Result<(), AppError> {
+        if self.config.enabled {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Single sign-on is not configured".to_string()))
+        }
+    }
+}
+
+/// Whether `identity` may sign in as the account currently bound to `linked_issuer`/`linked_subject`.
+/// An account found by email is only linked when the IdP verified the address and the account
+/// is not already bound to another IdP identity.
+fn check_account_link(
+    identity: &OidcIdentity,
+    linked_issuer: Option<&str>,
+    linked_subject: Option<&str>,
+) -> Result<(), AppError> {
+    if linked_issuer == Some(identity.issuer.as_str()) && linked_subject == Some(identity.subject.as_str()) {
+        return Ok(());
+    }
+    if !identity.email_verified {
+        return Err(AppError::Auth(
+            "Single sign-on failed: the IdP did not verify the email address of an existing account".to_string(),
+        ));
+    }
+    if linked_subject.is_some() {
+        return Err(AppError::Auth(
+            "Single sign-on failed: the account is linked to a different single sign-on identity".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// PKCE code verifier: 32 random bytes, base64url (43 characters)
+fn pkce_verifier() -> String {
+    let bytes = hex::decode(generate_token()).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// S256 code challenge for a verifier
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::{get, post}, Form, Json, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    use crate::config::GroupRoleMapping;
+
+    const MOCK_IDP_KEY: &str = include_str!("../../tests/fixtures/mock_idp_rsa.pem");
+    const MOCK_IDP_MODULUS: &str = "xMSYJqYSPL_VCuahia90LEX0uttoSFGqYR-Q7wj2fUZmhn9Qf1GKsP0KZO2Mr3gVr0ewNJMsY2ILd1oCLf0VYB5TdICIJuytABI6iPwU-qxTxLKNODfLVMWzaeSWgzDKkJEbzH27rhoEqWOlCiC4c-KWMabL0mrfh9OY-L8ZKHLSMG2QBwf-2m4xOdLtqQk3WfKQjhDlTw2iQpcyY8ZaJUTYSQHpHoBiOGI1qbHyETVgS61J4sN9FphwjkUoqBV3BnVSGbHnGnhw4930tI18QLvH2JBnltVvsjO6KuQo8v1fT1KnHi-sAitpX_P9bhh-ABkgCrc3iTqa0onDcHiPwQ";
+
+    /// Issued authorization codes: code -> (PKCE challenge, nonce)
+    #[derive(Clone)]
+    struct MockIdp {
+        issuer: String,
+        codes: Arc<Mutex<HashMap<String, (String, String)>>>,
+    }
+
+    /// A minimal IdP: discovery, JWKS and a token endpoint that checks PKCE
+    async fn start_mock_idp() -> MockIdp {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let idp = MockIdp {
+            issuer: format!("http://{}", listener.local_addr().unwrap()),
+            codes: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(|State(idp): State<MockIdp>| async move {
+                Json(json!({
+                    "issuer": idp.issuer,
+                    "authorization_endpoint": format!("{}/authorize", idp.issuer),
+                    "token_endpoint": format!("{}/token", idp.issuer),
+                    "jwks_uri": format!("{}/jwks", idp.issuer),
+                }))
+            }))
+            .route("/jwks", get(|| async {
+                Json(json!({"keys": [{"kty": "RSA", "kid": "test-key", "alg": "RS256", "use": "sig", "n": MOCK_IDP_MODULUS, "e": "AQAB"}]}))
+            }))
+            .route("/token", post(|State(idp): State<MockIdp>, Form(form): Form<HashMap<String, String>>| async move {
+                let (challenge, nonce) = idp.codes.lock().unwrap().remove(&form["code"]).expect("unknown code");
+                assert_eq!(pkce_challenge(&form["code_verifier"]), challenge, "PKCE verifier does not match");
+
+                let claims = json!({
+                    "iss": idp.issuer,
+                    "aud": "archzero",
+                    "sub": "user-42",
+                    "email": "jane@example.com",
+                    "email_verified": true,
+                    "name": "Jane Doe",
+                    "nonce": nonce,
+                    "groups": ["staff", "archzero-architects"],
+                    "exp": Utc::now().timestamp() + 300,
+                });
+                let mut header = Header::new(Algorithm::RS256);
+                header.kid = Some("test-key".to_string());
+                let id_token = encode(&header, &claims, &EncodingKey::from_rsa_pem(MOCK_IDP_KEY.as_bytes()).unwrap()).unwrap();
+                Json(json!({"access_token": "opaque", "token_type": "Bearer", "id_token": id_token}))
+            }))
+            .with_state(idp.clone());
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        idp
+    }
+
+    fn service(issuer: &str) -> OidcService {
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let auth_service = Arc::new(AuthService::new(pool.clone(), "secret".to_string(), 900, 30));
+        OidcService::new(pool, auth_service, Oidc {
+            enabled: true,
+            issuer_url: issuer.to_string(),
+            client_id: "archzero".to_string(),
+            role_mappings: vec![
+                GroupRoleMapping { group: "archzero-admins".to_string(), role: UserRole::Admin },
+                GroupRoleMapping { group: "archzero-architects".to_string(), role: UserRole::Architect },
+            ],
+            ..Oidc::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_code_exchange_against_mock_idp() {
+        let idp = start_mock_idp().await;
+        let oidc = service(&idp.issuer);
+
+        let verifier = pkce_verifier();
+        let url = oidc.authorization_url("state-1", "nonce-1", &pkce_challenge(&verifier)).await.unwrap();
+        assert!(url.starts_with(&format!("{}/authorize?", idp.issuer)));
+        assert!(url.contains("code_challenge_method=S256"));
+
+        idp.codes.lock().unwrap().insert("code-1".to_string(), (pkce_challenge(&verifier), "nonce-1".to_string()));
+        let identity = oidc.exchange_code("code-1", &verifier, "nonce-1").await.unwrap();
+
+        assert_eq!(identity.issuer, idp.issuer);
+        assert_eq!(identity.subject, "user-42");
+        assert_eq!(identity.email, "jane@example.com");
+        assert!(identity.email_verified);
+        assert_eq!(oidc.role_for_groups(&identity.groups), Some(UserRole::Architect));
+        assert_eq!(oidc.role_for_groups(&["staff".to_string()]), None);
+    }
+
+    #[tokio::test]
+    async fn test_login_role_falls_back_to_default_when_groups_no_longer_match() {
+        let oidc = service("http://idp.invalid");
+        assert_eq!(oidc.login_role(&["archzero-admins".to_string()]), Some(UserRole::Admin));
+        // Removed from every mapped group: demoted rather than keeping the old role
+        assert_eq!(oidc.login_role(&["staff".to_string()]), Some(Oidc::default().default_role));
+
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let auth_service = Arc::new(AuthService::new(pool.clone(), "secret".to_string(), 900, 30));
+        let unmapped = OidcService::new(pool, auth_service, Oidc { enabled: true, ..Oidc::default() });
+        assert_eq!(unmapped.login_role(&["staff".to_string()]), None);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_mismatch_is_rejected() {
+        let idp = start_mock_idp().await;
+        let oidc = service(&idp.issuer);
+
+        let verifier = pkce_verifier();
+        idp.codes.lock().unwrap().insert("code-2".to_string(), (pkce_challenge(&verifier), "other-nonce".to_string()));
+
+        let result = oidc.exchange_code("code-2", &verifier, "nonce-2").await;
+        assert!(matches!(result, Err(AppError::Auth(message)) if message.contains("nonce")));
+    }
+
+    #[tokio::test]
+    async fn test_callback_requires_the_login_cookie_of_its_state() {
+        let oidc = service("http://idp.invalid").with_secure_cookie(true);
+        let client = || SessionClient { user_agent: None, ip_address: None };
+
+        // A callback forwarded to another browser carries no cookie, or one for another login
+        for cookie in [None, Some(hash_token("attacker-state"))] {
+            let result = oidc.complete_login("code", "victim-state", cookie.as_deref(), client()).await;
+            assert!(matches!(result, Err(AppError::Auth(message)) if message.contains("not started in this browser")));
+        }
+
+        let cookie = oidc.login_cookie(&hash_token("state-1"));
+        assert!(cookie.starts_with(&format!("{}={};", LOGIN_COOKIE, hash_token("state-1"))));
+        assert!(cookie.contains("HttpOnly") && cookie.contains("SameSite=Lax") && cookie.contains("Secure"));
+        assert!(oidc.clear_login_cookie().contains("Max-Age=0"));
+    }
+
+    #[test]
+    fn test_accounts_are_linked_by_verified_email_only() {
+        let identity = OidcIdentity {
+            issuer: "https://idp.example.com".to_string(),
+            subject: "user-42".to_string(),
+            email: "jane@example.com".to_string(),
+            email_verified: true,
+            full_name: None,
+            groups: Vec::new(),
+        };
+
+        // Same identity, or an account never linked to SSO
+        assert!(check_account_link(&identity, Some("https://idp.example.com"), Some("user-42")).is_ok());
+        assert!(check_account_link(&identity, None, None).is_ok());
+
+        // Bound to another subject or another IdP
+        assert!(matches!(
+            check_account_link(&identity, Some("https://idp.example.com"), Some("user-7")),
+            Err(AppError::Auth(_))
+        ));
+        assert!(matches!(
+            check_account_link(&identity, Some("https://other.example.com"), Some("user-42")),
+            Err(AppError::Auth(_))
+        ));
+
+        // Unverified (or unasserted) email cannot claim an existing account
+        let unverified = OidcIdentity { email_verified: false, ..identity.clone() };
+        assert!(matches!(check_account_link(&unverified, None, None), Err(AppError::Auth(_))));
+        assert!(check_account_link(&unverified, Some("https://idp.example.com"), Some("user-42")).is_ok());
+    }
+}
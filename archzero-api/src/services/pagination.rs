@@ -0,0 +1,271 @@
+/*!
+ * Keyset Pagination
+ *
+ * Sorting and cursors shared by card, relationship, risk, policy and ARB listings.
+ * A sort is a comma-separated list of whitelisted fields, `-` for descending, and may
+ * use JSONB attribute paths (`attributes.vendor`). The row ID is always the last sort
+ * key, so every row has a unique position.
+ *
+ * A cursor holds the sort and the sort key values of the last row of a page, base64url
+ * encoded. The next page continues after that row with a `WHERE` on the sort keys
+ * instead of `OFFSET`, so deep pages stay fast and rows inserted or deleted meanwhile
+ * do not shift pages. Cursors are only valid for the sort they were issued for.
+ */
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::error::AppError;
+
+/// SQL type of a sort key, used to cast cursor values back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKind {
+    Text,
+    Integer,
+    Float,
+    Timestamp,
+    Uuid,
+    Json,
+}
+
+impl SortKind {
+    fn sql_type(&self) -> &'static str {
+        match self {
+            SortKind::Text => "text",
+            SortKind::Integer => "bigint",
+            SortKind::Float => "float8",
+            SortKind::Timestamp => "timestamptz",
+            SortKind::Uuid => "uuid",
+            SortKind::Json => "jsonb",
+        }
+    }
+
+    /// Whether a cursor value casts to this type
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            SortKind::Text => true,
+            SortKind::Integer => value.parse::<i64>().is_ok(),
+            SortKind::Float => value.parse::<f64>().is_ok(),
+            SortKind::Timestamp => chrono::DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z").is_ok(),
+            SortKind::Uuid => uuid::Uuid::parse_str(value).is_ok(),
+            SortKind::Json => serde_json::from_str::<serde_json::Value>(value).is_ok(),
+        }
+    }
+}
+
+/// A field a listing can be sorted by. `sql` must never be NULL, so nullable columns are
+/// wrapped in `COALESCE`.
+#[derive(Debug, Clone, Copy)]
+pub struct SortField {
+    pub name: &'static str,
+    pub sql: &'static str,
+    pub kind: SortKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub name: String,
+    pub sql: String,
+    pub kind: SortKind,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn new(name: &str, sql: String, kind: SortKind, descending: bool) -> Self {
+        Self { name: name.to_string(), sql, kind, descending }
+    }
+}
+
+/// Parse a sort such as `-updated_at,name,attributes.vendor` against a whitelist.
+/// `json_column` enables `attributes.<key>` paths into that JSONB column, except under the
+/// `restricted` keys: ordering by a value the caller cannot see would reveal it.
+pub fn parse_sort(
+    sort: &str,
+    fields: &[SortField],
+    json_column: Option<&str>,
+    restricted: &[String],
+) -> Result<Vec<SortKey>, AppError> {
+    let keys = sort.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| {
+            let (name, descending) = match key.strip_prefix('-') {
+                Some(name) => (name, true),
+                None => (key.strip_prefix('+').unwrap_or(key), false),
+            };
+            if let Some(field) = fields.iter().find(|field| field.name == name) {
+                return Ok(SortKey::new(name, field.sql.to_string(), field.kind, descending));
+            }
+            match (json_column, name.strip_prefix("attributes.")) {
+                (Some(column), Some(path)) if is_attribute_path(path) => {
+                    if path.split('.').next().is_some_and(|key| restricted.iter().any(|restricted| restricted == key)) {
+                        return Err(AppError::Forbidden(format!("Cannot sort by restricted attribute '{}'", name)));
+                    }
+                    let path = path.split('.').collect::<Vec<_>>().join(",");
+                    Ok(SortKey::new(name, format!("COALESCE({} #> '{{{}}}', 'null'::jsonb)", column, path), SortKind::Json, descending))
+                }
+                _ => Err(AppError::Validation(format!("Cannot sort by '{}'", name))),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if keys.is_empty() {
+        return Err(AppError::Validation("Sort is empty".to_string()));
+    }
+    Ok(keys)
+}
+
+/// Attribute keys and nested keys made of letters, digits, `_` and `-`; they are inlined into SQL
+fn is_attribute_path(path: &str) -> bool {
+    path.split('.').all(|key| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    after: Vec<String>,
+}
+
+/// A sort with the unique ID tiebreaker, producing `ORDER BY`, cursor conditions and cursors
+#[derive(Debug, Clone)]
+pub struct Keyset {
+    keys: Vec<SortKey>,
+}
+
+impl Keyset {
+    /// `id_column` breaks ties, in the direction of the first key
+    pub fn new(mut keys: Vec<SortKey>, id_column: &str) -> Self {
+        let descending = keys.first().map(|key| key.descending).unwrap_or(false);
+        keys.push(SortKey::new("id", id_column.to_string(), SortKind::Uuid, descending));
+        Self { keys }
+    }
+
+    /// Canonical sort the cursors are tied to
+    pub fn spec(&self) -> String {
+        self.keys.iter()
+            .map(|key| format!("{}{}", if key.descending { "-" } else { "" }, key.name))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn order_by(&self) -> String {
+        self.keys.iter()
+            .map(|key| format!("{} {}", key.sql, if key.descending { "DESC" } else { "ASC" }))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Sort key values to select with each row, read back by `cursor_for`
+    pub fn select(&self) -> String {
+        self.keys.iter()
+            .enumerate()
+            .map(|(i, key)| format!("({})::text AS sort_key_{}", key.sql, i))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Condition for the rows after `cursor`, with its text parameters numbered from `first_param`
+    pub fn after(&self, cursor: &str, first_param: usize) -> Result<(String, Vec<String>), AppError> {
+        let invalid = || AppError::Validation("Invalid cursor".to_string());
+        let decoded = URL_SAFE_NO_PAD.decode(cursor.trim()).map_err(|_| invalid())?;
+        let cursor: Cursor = serde_json::from_slice(&decoded).map_err(|_| invalid())?;
+        if cursor.sort != self.spec() {
+            return Err(AppError::Validation("Cursor was issued for a different sort".to_string()));
+        }
+        if cursor.after.len() != self.keys.len()
+            || !self.keys.iter().zip(&cursor.after).all(|(key, value)| key.kind.accepts(value))
+        {
+            return Err(invalid());
+        }
+
+        let value = |i: usize| format!("CAST(${} AS {})", first_param + i, self.keys[i].kind.sql_type());
+        let branches = (0..self.keys.len())
+            .map(|i| {
+                let mut terms: Vec<String> = (0..i).map(|j| format!("{} = {}", self.keys[j].sql, value(j))).collect();
+                let op = if self.keys[i].descending { "<" } else { ">" };
+                terms.push(format!("{} {} {}", self.keys[i].sql, op, value(i)));
+                format!("({})", terms.join(" AND "))
+            })
+            .collect::<Vec<_>>();
+        Ok((format!("({})", branches.join(" OR ")), cursor.after))
+    }
+
+    /// Cursor continuing after `row`, which selected `select()`
+    pub fn cursor_for(&self, row: &sqlx::postgres::PgRow) -> Result<String, AppError> {
+        let after = (0..self.keys.len())
+            .map(|i| row.try_get::<String, _>(format!("sort_key_{}", i).as_str()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing sort key: {}", e)))?;
+        Ok(self.encode(after))
+    }
+
+    fn encode(&self, after: Vec<String>) -> String {
+        let cursor = Cursor { sort: self.spec(), after };
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&cursor).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[SortField] = &[
+        SortField { name: "name", sql: "name", kind: SortKind::Text },
+        SortField { name: "created_at", sql: "created_at", kind: SortKind::Timestamp },
+        SortField { name: "quality_score", sql: "COALESCE(quality_score, -1)", kind: SortKind::Integer },
+    ];
+
+    #[test]
+    fn test_parse_sort() {
+        let keys = parse_sort("-quality_score, name,attributes.vendor", FIELDS, Some("attributes"), &[]).unwrap();
+        assert_eq!(keys.len(), 3);
+        assert!(keys[0].descending && !keys[1].descending);
+        assert_eq!(keys[2].sql, "COALESCE(attributes #> '{vendor}', 'null'::jsonb)");
+        assert_eq!(
+            parse_sort("attributes.costs.annual", FIELDS, Some("attributes"), &[]).unwrap()[0].sql,
+            "COALESCE(attributes #> '{costs,annual}', 'null'::jsonb)"
+        );
+
+        assert!(parse_sort("attributes.vendor", FIELDS, None, &[]).is_err());
+        assert!(parse_sort("attributes.x'; DROP TABLE cards; --", FIELDS, Some("attributes"), &[]).is_err());
+        assert!(parse_sort("password", FIELDS, None, &[]).is_err());
+        assert!(parse_sort(" , ", FIELDS, None, &[]).is_err());
+
+        // Redacted callers cannot order by values they cannot see
+        let restricted = vec!["cost".to_string()];
+        assert!(matches!(parse_sort("name,-attributes.cost", FIELDS, Some("attributes"), &restricted), Err(AppError::Forbidden(_))));
+        assert!(matches!(parse_sort("attributes.cost.amount", FIELDS, Some("attributes"), &restricted), Err(AppError::Forbidden(_))));
+        assert!(parse_sort("attributes.costing_model", FIELDS, Some("attributes"), &restricted).is_ok());
+    }
+
+    #[test]
+    fn test_keyset_cursor_round_trip() {
+        let keyset = Keyset::new(parse_sort("-created_at,name", FIELDS, None, &[]).unwrap(), "id");
+        assert_eq!(keyset.spec(), "-created_at,name,-id");
+        assert_eq!(keyset.order_by(), "created_at DESC, name ASC, id DESC");
+
+        let cursor = keyset.encode(vec![
+            "2026-01-02 10:00:00.123456+00".to_string(),
+            "CRM".to_string(),
+            "6f1c2b8e-0a7d-4a53-9a57-6d2f0b1c9e11".to_string(),
+        ]);
+        let (condition, params) = keyset.after(&cursor, 3).unwrap();
+        assert_eq!(
+            condition,
+            "((created_at < CAST($3 AS timestamptz)) \
+             OR (created_at = CAST($3 AS timestamptz) AND name > CAST($4 AS text)) \
+             OR (created_at = CAST($3 AS timestamptz) AND name = CAST($4 AS text) AND id < CAST($5 AS uuid)))"
+        );
+        assert_eq!(params[1], "CRM");
+
+        // Cursors only work for their own sort and with values of the right type
+        let by_name = Keyset::new(parse_sort("name", FIELDS, None, &[]).unwrap(), "id");
+        assert!(matches!(by_name.after(&cursor, 1), Err(AppError::Validation(_))));
+        let tampered = keyset.encode(vec!["yesterday".to_string(), "CRM".to_string(), "x".to_string()]);
+        assert!(matches!(keyset.after(&tampered, 1), Err(AppError::Validation(_))));
+        assert!(matches!(keyset.after("not a cursor", 1), Err(AppError::Validation(_))));
+    }
+}
@@ -1,20 +1,50 @@
 /**
  * Rate Limiting Service
  *
- * Provides distributed rate limiting using Redis.
- * Supports multiple rate limit strategies:
- * - Token bucket (default)
- * - Fixed window
- * - Sliding window log
+ * Sliding-window rate limiting. Each key keeps a log of request timestamps; a request
+ * is allowed while fewer than `max_requests` fall inside the last `window_seconds`.
  *
- * Rate limits can be applied per-IP, per-user, or per-endpoint.
+ * Counters live in Redis (a sorted set per key, updated atomically by a Lua script) so
+ * limits hold across replicas. The in-memory store is for tests and single-process use.
+ *
+ * Limits come from the `rate_limit` settings: one per route group, optionally replaced
+ * per user, API token or IP address, with an allow-list of clients and paths.
  */
 
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, RwLock};
 use uuid::Uuid;
 
+use crate::config::RateLimit;
+
+/// Trims the key's log to the window, then records the request if the limit allows it.
+/// Returns {allowed, requests in window, oldest timestamp in window (ms)}.
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local key = KEYS[1]
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+redis.call('ZREMRANGEBYSCORE', key, '-inf', now - window)
+local count = redis.call('ZCARD', key)
+local allowed = 0
+if count < limit then
+    redis.call('ZADD', key, now, ARGV[4])
+    count = count + 1
+    allowed = 1
+end
+redis.call('PEXPIRE', key, window)
+local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+local oldest_at = now
+if oldest[2] then
+    oldest_at = tonumber(oldest[2])
+end
+return {allowed, count, oldest_at}
+"#;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
     pub max_requests: u32,
@@ -54,6 +84,10 @@ impl RateLimitConfig {
     pub fn per_hour(limit: u32) -> Self {
         Self::new(limit, 3600)
     }
+
+    fn window_ms(&self) -> u64 {
+        self.window_seconds as u64 * 1000
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,72 +98,247 @@ pub struct RateLimitResult {
     pub retry_after: Option<u32>, // Seconds until retry allowed
 }
 
-pub struct RateLimitService {
-    // Note: In production, this would use Redis
-    // For now, we'll use an in-memory implementation
-    // that can be easily swapped for Redis
-    counters: Arc<tokio::sync::RwLock<std::collections::HashMap<String, RateLimitCounter>>>,
+impl RateLimitResult {
+    /// Result for `count` requests in the window, the oldest made at `oldest_ms`
+    fn from_window(allowed: bool, count: u32, oldest_ms: u64, config: &RateLimitConfig) -> Self {
+        let reset_ms = oldest_ms + config.window_ms();
+        let retry_after = (!allowed).then(|| {
+            let wait_ms = reset_ms.saturating_sub(now_ms());
+            (wait_ms.div_ceil(1000) as u32).max(1)
+        });
+        Self {
+            allowed,
+            remaining: config.max_requests.saturating_sub(count),
+            reset_at: reset_ms.div_ceil(1000),
+            retry_after,
+        }
+    }
 }
 
+/// The limit a request is checked against
 #[derive(Debug, Clone)]
-struct RateLimitCounter {
-    count: u32,
-    window_start: std::time::Instant,
+pub struct RateLimitRule {
+    /// Route group name, or "default"
+    pub group: String,
+    pub config: RateLimitConfig,
+    /// Allow the request if the counters cannot be reached
+    pub fail_open: bool,
+}
+
+/// A trusted reverse proxy: an address, or a CIDR range when `prefix` is shorter than the address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrustedProxy {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl TrustedProxy {
+    /// Parse `10.0.0.1`, `10.0.0.0/8` or `fd00::/8`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (value.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+        (prefix <= bits).then_some(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let masked = |bits: u128, width: u8| if self.prefix == 0 { 0 } else { bits >> (width - self.prefix) };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => masked(u32::from(net) as u128, 32) == masked(u32::from(ip) as u128, 32),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => masked(u128::from(net), 128) == masked(u128::from(ip), 128),
+            _ => false,
+        }
+    }
+}
+
+enum CounterStore {
+    Memory(RwLock<HashMap<String, VecDeque<u64>>>),
+    Redis {
+        client: redis::Client,
+        connection: OnceCell<ConnectionManager>,
+    },
+}
+
+pub struct RateLimitService {
+    store: CounterStore,
+    policy: RateLimit,
+    trusted_proxies: Vec<TrustedProxy>,
+    enabled: bool,
+}
+
+fn parse_trusted_proxies(entries: &[String]) -> Vec<TrustedProxy> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let proxy = TrustedProxy::parse(entry);
+            if proxy.is_none() {
+                tracing::error!("Ignoring invalid rate_limit.trusted_proxies entry '{}'", entry);
+            }
+            proxy
+        })
+        .collect()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
 }
 
 impl RateLimitService {
-    /// Create a new rate limiting service
+    /// Create an in-memory rate limiting service; counters are not shared between replicas
     pub fn new() -> Self {
         Self {
-            counters: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            store: CounterStore::Memory(RwLock::new(HashMap::new())),
+            policy: RateLimit::default(),
+            trusted_proxies: Vec::new(),
+            enabled: false,
         }
     }
 
+    /// Create a Redis-backed service applying the configured limits. Redis is
+    /// connected on first use, so the API starts even while Redis is down.
+    pub fn with_redis(redis_url: &str, policy: RateLimit, enabled: bool) -> Result<Self, redis::RedisError> {
+        Ok(Self {
+            store: CounterStore::Redis {
+                client: redis::Client::open(redis_url)?,
+                connection: OnceCell::new(),
+            },
+            trusted_proxies: parse_trusted_proxies(&policy.trusted_proxies),
+            policy,
+            enabled,
+        })
+    }
+
+    /// Whether the middleware should limit requests at all
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Proxies whose forwarded headers identify the client
+    pub fn trusted_proxies(&self) -> &[TrustedProxy] {
+        &self.trusted_proxies
+    }
+
+    /// Whether the client or path is on the allow-list
+    pub fn is_allow_listed(&self, client: &str, path: &str) -> bool {
+        self.policy
+            .allow_list
+            .iter()
+            .any(|entry| entry == client || (entry.starts_with('/') && path.starts_with(entry.as_str())))
+    }
+
+    /// Limit for a client on a path: the client's own limit for the path's group (or for
+    /// every group) if configured, otherwise the group's limit
+    pub fn rule_for(&self, client: &str, path: &str) -> RateLimitRule {
+        let group = self.policy.groups.iter().find(|group| {
+            group.path_prefixes.iter().any(|prefix| path.starts_with(prefix.as_str()))
+        });
+        let group_name = group.map_or("default", |group| group.name.as_str());
+
+        let client_limit = self
+            .policy
+            .clients
+            .iter()
+            .filter(|limit| limit.client == client)
+            .find(|limit| limit.group.as_deref() == Some(group_name))
+            .or_else(|| {
+                self.policy
+                    .clients
+                    .iter()
+                    .find(|limit| limit.client == client && limit.group.is_none())
+            });
+
+        let config = match (client_limit, group) {
+            (Some(limit), _) => RateLimitConfig::new(limit.max_requests, limit.window_seconds),
+            (None, Some(group)) => RateLimitConfig::new(group.max_requests, group.window_seconds),
+            (None, None) => RateLimitConfig::new(self.policy.default_max_requests, self.policy.default_window_seconds),
+        };
+
+        RateLimitRule {
+            group: group_name.to_string(),
+            config,
+            fail_open: group.and_then(|group| group.fail_open).unwrap_or(self.policy.fail_open),
+        }
+    }
+
+    /// Check a client's request against its rule, counting it if allowed
+    pub async fn check_client(&self, client: &str, rule: &RateLimitRule) -> RateLimitResult {
+        self.check_window(&format!("{}:{}", rule.group, client), &rule.config, rule.fail_open).await
+    }
+
     /// Check if a request should be rate limited
     pub async fn check_rate_limit(
         &self,
         key: &str,
         config: &RateLimitConfig,
     ) -> RateLimitResult {
-        let mut counters = self.counters.write().await;
-        let now = std::time::Instant::now();
-        let window_duration = Duration::from_secs(config.window_seconds as u64);
-
-        // Get or create counter for this key
-        let counter = counters.entry(key.to_string()).or_insert_with(|| RateLimitCounter {
-            count: 0,
-            window_start: now,
-        });
-
-        // Check if we need to reset the window (fixed window strategy)
-        if now.duration_since(counter.window_start) >= window_duration {
-            counter.count = 0;
-            counter.window_start = now;
-        }
-
-        // Check if request is allowed
-        let allowed = counter.count < config.max_requests;
+        self.check_window(key, config, self.policy.fail_open).await
+    }
 
-        if allowed {
-            counter.count += 1;
+    async fn check_window(&self, key: &str, config: &RateLimitConfig, fail_open: bool) -> RateLimitResult {
+        match &self.store {
+            CounterStore::Memory(counters) => {
+                let mut counters = counters.write().await;
+                let now = now_ms();
+                let log = counters.entry(key.to_string()).or_default();
+
+                // Drop requests that have slid out of the window
+                let window_start = now.saturating_sub(config.window_ms());
+                while log.front().is_some_and(|&at| at <= window_start) {
+                    log.pop_front();
+                }
+
+                let allowed = (log.len() as u32) < config.max_requests;
+                if allowed {
+                    log.push_back(now);
+                }
+                let oldest = log.front().copied().unwrap_or(now);
+                RateLimitResult::from_window(allowed, log.len() as u32, oldest, config)
+            }
+            CounterStore::Redis { client, connection } => {
+                match self.check_redis(client, connection, key, config).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        tracing::warn!("Rate limit check failed, Redis unavailable: {}", e);
+                        let allowed = fail_open;
+                        RateLimitResult {
+                            allowed,
+                            remaining: if allowed { config.max_requests } else { 0 },
+                            reset_at: (now_ms() + config.window_ms()).div_ceil(1000),
+                            retry_after: (!allowed).then_some(config.window_seconds),
+                        }
+                    }
+                }
+            }
         }
+    }
 
-        let remaining = config.max_requests.saturating_sub(counter.count);
-        let reset_at = counter.window_start
-            .duration_since(now)
-            .as_secs()
-            + config.window_seconds as u64;
-
-        RateLimitResult {
-            allowed,
-            remaining,
-            reset_at,
-            retry_after: if allowed {
-                None
-            } else {
-                Some(config.window_seconds)
-            },
-        }
+    async fn check_redis(
+        &self,
+        client: &redis::Client,
+        connection: &OnceCell<ConnectionManager>,
+        key: &str,
+        config: &RateLimitConfig,
+    ) -> Result<RateLimitResult, redis::RedisError> {
+        let mut conn = connection
+            .get_or_try_init(|| client.get_connection_manager())
+            .await?
+            .clone();
+        let now = now_ms();
+        let (allowed, count, oldest): (i64, i64, i64) = redis::Script::new(SLIDING_WINDOW_SCRIPT)
+            .key(format!("{}:{}", self.policy.key_prefix, key))
+            .arg(now)
+            .arg(config.window_ms())
+            .arg(config.max_requests)
+            .arg(format!("{}-{}", now, Uuid::new_v4()))
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(RateLimitResult::from_window(allowed == 1, count as u32, oldest as u64, config))
     }
 
     /// Check rate limit for an IP address
@@ -183,24 +392,27 @@ impl RateLimitService {
         }
     }
 
-    /// Clean up expired counters (should be called periodically)
+    /// Clean up in-memory counters idle for an hour (Redis keys expire on their own)
     pub async fn cleanup_expired_counters(&self) -> usize {
-        let mut counters = self.counters.write().await;
-        let now = std::time::Instant::now();
-        let max_age = Duration::from_secs(3600); // 1 hour
+        let CounterStore::Memory(counters) = &self.store else {
+            return 0;
+        };
+        let mut counters = counters.write().await;
+        let cutoff = now_ms().saturating_sub(Duration::from_secs(3600).as_millis() as u64);
 
         let before_count = counters.len();
-        counters.retain(|_, counter| {
-            now.duration_since(counter.window_start) < max_age
-        });
+        counters.retain(|_, log| log.back().is_some_and(|&at| at > cutoff));
         let after_count = counters.len();
 
         before_count - after_count
     }
 
-    /// Get the number of active counters
+    /// Get the number of active in-memory counters
     pub async fn active_counter_count(&self) -> usize {
-        self.counters.read().await.len()
+        match &self.store {
+            CounterStore::Memory(counters) => counters.read().await.len(),
+            CounterStore::Redis { .. } => 0,
+        }
     }
 }
 
@@ -288,4 +500,66 @@ mod tests {
         ).await;
         assert!(!result.allowed);
     }
+
+    #[test]
+    fn test_rule_resolution_and_allow_list() {
+        use crate::config::{ClientLimit, RateLimit};
+
+        let policy = RateLimit {
+            clients: vec![
+                ClientLimit {
+                    client: "token:az_1a2b3c4d".to_string(),
+                    group: Some("bulk".to_string()),
+                    max_requests: 500,
+                    window_seconds: 60,
+                },
+                ClientLimit {
+                    client: "token:az_1a2b3c4d".to_string(),
+                    group: None,
+                    max_requests: 1000,
+                    window_seconds: 60,
+                },
+            ],
+            allow_list: vec!["/api/v1/health".to_string(), "ip:10.0.0.5".to_string()],
+            ..RateLimit::default()
+        };
+        let service = RateLimitService::with_redis("redis://127.0.0.1:6379", policy, true).unwrap();
+
+        let rule = service.rule_for("ip:192.0.2.1", "/api/v1/auth/login");
+        assert_eq!(rule.group, "auth");
+        assert_eq!(rule.config.max_requests, 20);
+        assert!(!rule.fail_open, "login limits must hold while Redis is down");
+        assert!(service.rule_for("ip:192.0.2.1", "/api/v1/cards").fail_open);
+
+        let rule = service.rule_for("ip:192.0.2.1", "/api/v1/cards");
+        assert_eq!(rule.group, "default");
+        assert_eq!(rule.config.max_requests, 300);
+
+        // A group-specific client limit wins over the client's catch-all limit
+        assert_eq!(service.rule_for("token:az_1a2b3c4d", "/api/v1/import/cards").config.max_requests, 500);
+        assert_eq!(service.rule_for("token:az_1a2b3c4d", "/api/v1/cards").config.max_requests, 1000);
+
+        assert!(service.is_allow_listed("ip:192.0.2.1", "/api/v1/health"));
+        assert!(service.is_allow_listed("ip:10.0.0.5", "/api/v1/cards"));
+        assert!(!service.is_allow_listed("ip:192.0.2.1", "/api/v1/cards"));
+    }
+
+    #[test]
+    fn test_trusted_proxy_ranges() {
+        let ip = |value: &str| value.parse::<IpAddr>().unwrap();
+
+        let range = TrustedProxy::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(ip("10.1.2.3")));
+        assert!(!range.contains(ip("11.0.0.1")));
+        assert!(!range.contains(ip("::ffff:10.1.2.3")));
+
+        let single = TrustedProxy::parse("192.0.2.10").unwrap();
+        assert!(single.contains(ip("192.0.2.10")));
+        assert!(!single.contains(ip("192.0.2.11")));
+
+        assert!(TrustedProxy::parse("fd00::/8").unwrap().contains(ip("fd12::1")));
+        assert!(TrustedProxy::parse("0.0.0.0/0").unwrap().contains(ip("203.0.113.7")));
+        assert_eq!(TrustedProxy::parse("10.0.0.0/33"), None);
+        assert_eq!(TrustedProxy::parse("proxy.internal"), None);
+    }
 }
@@ -0,0 +1,485 @@
+/*!
+ * Relationship Rule Service
+ *
+ * The relationship matrix of docs/02-relationship-spec.md §2: which card types each
+ * relationship type may connect, in which direction and with what cardinality.
+ * Relationship types without rules connect any cards. `RelationshipService::create`
+ * checks new relationships against the rules, which covers the API, sagas and
+ * relationship imports; existing relationships are checked by the violations report.
+ */
+
+use std::collections::HashMap;
+
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::relationship::*;
+use crate::services::card_type_service::enforce_card_type_registered;
+use crate::models::card::CardType;
+use crate::services::version_service::current_workspace;
+
+const RULE_COLUMNS: &str = "id, relationship_type, source_type, target_type, cardinality, bidirectional, \
+                            description, created_by, created_at, updated_at";
+
+/// Matches any card type in a rule
+pub const ANY_CARD_TYPE: &str = "*";
+
+/// Relationships still in effect today; `valid_to` is an ISO date string
+pub(crate) const CURRENT_RELATIONSHIP: &str = "(r.valid_to IS NULL OR r.valid_to >= to_char(CURRENT_DATE, 'YYYY-MM-DD'))";
+
+pub struct RelationshipRuleService {
+    pool: PgPool,
+}
+
+impl RelationshipRuleService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, relationship_type: Option<&RelationshipType>) -> Result<Vec<RelationshipRule>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM relationship_rules WHERE ($1::text IS NULL OR relationship_type = $1) \
+             ORDER BY relationship_type, source_type, target_type",
+            RULE_COLUMNS
+        ))
+        .bind(relationship_type.map(relationship_type_name))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list relationship rules: {}", e)))?;
+
+        rows.iter().map(row_to_rule).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<RelationshipRule, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM relationship_rules WHERE id = $1", RULE_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship rule: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Relationship rule {} not found", id)))?;
+
+        row_to_rule(&row)
+    }
+
+    /// Allow a relationship type between two card types
+    pub async fn create(&self, req: CreateRelationshipRuleRequest, created_by: Option<Uuid>) -> Result<RelationshipRule, AppError> {
+        for card_type in [&req.source_type, &req.target_type] {
+            if card_type != ANY_CARD_TYPE {
+                enforce_card_type_registered(&self.pool, &CardType::from_name(card_type)).await?;
+            }
+        }
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO relationship_rules (relationship_type, source_type, target_type, cardinality, bidirectional, description, created_by) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {}",
+            RULE_COLUMNS
+        ))
+        .bind(relationship_type_name(&req.relationship_type))
+        .bind(&req.source_type)
+        .bind(&req.target_type)
+        .bind(req.cardinality.unwrap_or(RelationshipCardinality::ManyToMany).as_str())
+        .bind(req.bidirectional)
+        .bind(&req.description)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        row_to_rule(&row)
+    }
+
+    pub async fn update(&self, id: Uuid, req: UpdateRelationshipRuleRequest) -> Result<RelationshipRule, AppError> {
+        let row = sqlx::query(&format!(
+            "UPDATE relationship_rules SET cardinality = $2, bidirectional = $3, description = $4, updated_at = NOW() \
+             WHERE id = $1 RETURNING {}",
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .bind(req.cardinality.as_str())
+        .bind(req.bidirectional)
+        .bind(&req.description)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update relationship rule: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Relationship rule {} not found", id)))?;
+
+        row_to_rule(&row)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM relationship_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete relationship rule: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Relationship rule {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Current relationships of the current workspace that break a rule
+    pub async fn violations(&self) -> Result<RelationshipViolationReport, AppError> {
+        let rules = rules_by_type(&self.pool).await?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT r.id, r.relationship_type, f.id AS from_id, f.name AS from_name, f.type AS from_type, \
+             t.id AS to_id, t.name AS to_name, t.type AS to_type \
+             FROM relationships r JOIN cards f ON f.id = r.from_card_id JOIN cards t ON t.id = r.to_card_id \
+             WHERE {} AND ($1::uuid IS NULL OR r.workspace_id = $1) ORDER BY r.created_at",
+            CURRENT_RELATIONSHIP
+        ))
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load relationships: {}", e)))?;
+
+        let relationships = rows
+            .iter()
+            .map(|row| {
+                Ok(RelationshipFacts {
+                    id: row.try_get("id").map_err(missing("id"))?,
+                    relationship_type: row.try_get("relationship_type").map_err(missing("relationship_type"))?,
+                    from: RelationshipEndpoint {
+                        card_id: row.try_get("from_id").map_err(missing("from_id"))?,
+                        name: row.try_get("from_name").map_err(missing("from_name"))?,
+                        card_type: row.try_get("from_type").map_err(missing("from_type"))?,
+                    },
+                    to: RelationshipEndpoint {
+                        card_id: row.try_get("to_id").map_err(missing("to_id"))?,
+                        name: row.try_get("to_name").map_err(missing("to_name"))?,
+                        card_type: row.try_get("to_type").map_err(missing("to_type"))?,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok(RelationshipViolationReport {
+            checked_relationships: relationships.len(),
+            violations: find_violations(&rules, &relationships),
+        })
+    }
+}
+
+/// Every rule, keyed by relationship type as stored
+pub(crate) async fn rules_by_type(pool: &PgPool) -> Result<HashMap<String, Vec<RelationshipRule>>, AppError> {
+    let rows = sqlx::query(&format!("SELECT {} FROM relationship_rules ORDER BY source_type, target_type", RULE_COLUMNS))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load relationship rules: {}", e)))?;
+
+    let mut rules: HashMap<String, Vec<RelationshipRule>> = HashMap::new();
+    for rule in rows.iter().map(row_to_rule) {
+        let rule = rule?;
+        rules.entry(relationship_type_name(&rule.relationship_type)).or_default().push(rule);
+    }
+    Ok(rules)
+}
+
+/// Reject a new relationship no rule of its type allows, or one exceeding the rule's cardinality
+pub(crate) async fn enforce_relationship_rules(
+    conn: &mut PgConnection,
+    from_card_id: Uuid,
+    to_card_id: Uuid,
+    relationship_type: &str,
+) -> Result<(), AppError> {
+    let rows = sqlx::query(&format!("SELECT {} FROM relationship_rules WHERE relationship_type = $1", RULE_COLUMNS))
+        .bind(relationship_type)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load relationship rules: {}", e)))?;
+    let rules = rows.iter().map(row_to_rule).collect::<Result<Vec<_>, _>>()?;
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    // Lock both cards so concurrent creates cannot both pass the cardinality check
+    let cards: Vec<(Uuid, String, String)> =
+        sqlx::query_as("SELECT id, name, type FROM cards WHERE id IN ($1, $2) ORDER BY id FOR UPDATE")
+            .bind(from_card_id)
+            .bind(to_card_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship cards: {}", e)))?;
+    let card = |id: Uuid| {
+        cards.iter().find(|card| card.0 == id)
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", id)))
+    };
+    let (from, to) = (card(from_card_id)?, card(to_card_id)?);
+
+    let (rule, reversed) = find_rule(&rules, &from.2, &to.2)
+        .ok_or_else(|| AppError::Validation(not_allowed_message(relationship_type, &from.2, &to.2, &rules)))?;
+    let (source, target) = if reversed { (to, from) } else { (from, to) };
+
+    if rule.cardinality.single_target() {
+        let count = count_related(&mut *conn, source.0, target.0, relationship_type, &rule.target_type, true, rule.bidirectional).await?;
+        if count > 0 {
+            return Err(AppError::Validation(format!(
+                "{} already has a {} relationship to a {} card; the rule allows one",
+                source.1, relationship_type, type_label(&rule.target_type)
+            )));
+        }
+    }
+    if rule.cardinality.single_source() {
+        let count = count_related(&mut *conn, target.0, source.0, relationship_type, &rule.source_type, rule.bidirectional, true).await?;
+        if count > 0 {
+            return Err(AppError::Validation(format!(
+                "{} already has a {} relationship from a {} card; the rule allows one",
+                target.1, relationship_type, type_label(&rule.source_type)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Current relationships of a type between `card_id` and cards of `counterpart_type`, other than `except_id`
+async fn count_related(
+    conn: &mut PgConnection,
+    card_id: Uuid,
+    except_id: Uuid,
+    relationship_type: &str,
+    counterpart_type: &str,
+    outgoing: bool,
+    incoming: bool,
+) -> Result<i64, AppError> {
+    sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM relationships r \
+         JOIN cards c ON c.id = CASE WHEN r.from_card_id = $1 THEN r.to_card_id ELSE r.from_card_id END \
+         WHERE r.relationship_type = $2 AND (($3 AND r.from_card_id = $1) OR ($4 AND r.to_card_id = $1)) \
+         AND ($5 = '*' OR c.type = $5) AND c.id <> $6 AND {}",
+        CURRENT_RELATIONSHIP
+    ))
+    .bind(card_id)
+    .bind(relationship_type)
+    .bind(outgoing)
+    .bind(incoming)
+    .bind(counterpart_type)
+    .bind(except_id)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count relationships: {}", e)))
+}
+
+/// A relationship with the names and types of its cards
+#[derive(Debug, Clone)]
+pub struct RelationshipFacts {
+    pub id: Uuid,
+    pub relationship_type: String,
+    pub from: RelationshipEndpoint,
+    pub to: RelationshipEndpoint,
+}
+
+/// The most specific rule allowing `from_type` → `to_type`, and whether it matched reversed.
+/// `rules` are the rules of one relationship type.
+pub fn find_rule<'a>(rules: &'a [RelationshipRule], from_type: &str, to_type: &str) -> Option<(&'a RelationshipRule, bool)> {
+    let matches = |pattern: &str, card_type: &str| pattern == ANY_CARD_TYPE || pattern == card_type;
+    let specificity = |rule: &RelationshipRule| {
+        (rule.source_type != ANY_CARD_TYPE) as u8 + (rule.target_type != ANY_CARD_TYPE) as u8
+    };
+
+    let forward = rules.iter()
+        .filter(|rule| matches(&rule.source_type, from_type) && matches(&rule.target_type, to_type))
+        .map(|rule| (rule, false));
+    let reverse = rules.iter()
+        .filter(|rule| rule.bidirectional && matches(&rule.source_type, to_type) && matches(&rule.target_type, from_type))
+        .map(|rule| (rule, true));
+
+    // Forward matches come first, so they win ties
+    forward.chain(reverse).fold(None, |best: Option<(&RelationshipRule, bool)>, candidate| match best {
+        Some(best) if specificity(best.0) >= specificity(candidate.0) => Some(best),
+        _ => Some(candidate),
+    })
+}
+
+/// Relationships no rule allows, and those beyond a rule's cardinality
+pub fn find_violations(
+    rules: &HashMap<String, Vec<RelationshipRule>>,
+    relationships: &[RelationshipFacts],
+) -> Vec<RelationshipRuleViolation> {
+    let mut violations = Vec::new();
+    // (rule, card, side) -> relationships counted against the card's limit
+    let mut groups: HashMap<(Uuid, Uuid, bool), Vec<&RelationshipFacts>> = HashMap::new();
+
+    for relationship in relationships {
+        let Some(type_rules) = rules.get(&relationship.relationship_type).filter(|rules| !rules.is_empty()) else {
+            continue;
+        };
+        let Some((rule, reversed)) = find_rule(type_rules, &relationship.from.card_type, &relationship.to.card_type) else {
+            violations.push(violation(
+                relationship,
+                None,
+                not_allowed_message(&relationship.relationship_type, &relationship.from.card_type, &relationship.to.card_type, type_rules),
+            ));
+            continue;
+        };
+
+        let (source, target) = if reversed { (&relationship.to, &relationship.from) } else { (&relationship.from, &relationship.to) };
+        if rule.cardinality.single_target() {
+            groups.entry((rule.id, source.card_id, true)).or_default().push(relationship);
+        }
+        if rule.cardinality.single_source() {
+            groups.entry((rule.id, target.card_id, false)).or_default().push(relationship);
+        }
+    }
+
+    let mut over_limit: Vec<_> = groups.into_iter().filter(|(_, members)| members.len() > 1).collect();
+    over_limit.sort_by_key(|((rule_id, card_id, _), _)| (*rule_id, *card_id));
+    for ((rule_id, card_id, single_target), members) in over_limit {
+        let card_name = members.iter()
+            .flat_map(|member| [&member.from, &member.to])
+            .find(|endpoint| endpoint.card_id == card_id)
+            .map(|endpoint| endpoint.name.clone())
+            .unwrap_or_default();
+        let direction = if single_target { "to" } else { "from" };
+        for member in &members {
+            violations.push(violation(
+                member,
+                Some(rule_id),
+                format!(
+                    "{} has {} {} relationships {} cards of this rule; it allows one",
+                    card_name, members.len(), member.relationship_type, direction
+                ),
+            ));
+        }
+    }
+
+    violations
+}
+
+fn violation(relationship: &RelationshipFacts, rule_id: Option<Uuid>, message: String) -> RelationshipRuleViolation {
+    RelationshipRuleViolation {
+        relationship_id: relationship.id,
+        relationship_type: relationship.relationship_type.clone(),
+        from: relationship.from.clone(),
+        to: relationship.to.clone(),
+        rule_id,
+        message,
+    }
+}
+
+fn not_allowed_message(relationship_type: &str, from_type: &str, to_type: &str, rules: &[RelationshipRule]) -> String {
+    let allowed: Vec<String> = rules.iter()
+        .map(|rule| {
+            let arrow = if rule.bidirectional { "<->" } else { "->" };
+            format!("{} {} {}", type_label(&rule.source_type), arrow, type_label(&rule.target_type))
+        })
+        .collect();
+    format!(
+        "{} relationships cannot go from {} to {} (allowed: {})",
+        relationship_type, from_type, to_type, allowed.join(", ")
+    )
+}
+
+fn type_label(card_type: &str) -> &str {
+    if card_type == ANY_CARD_TYPE { "any" } else { card_type }
+}
+
+fn relationship_type_name(relationship_type: &RelationshipType) -> String {
+    serde_json::to_value(relationship_type)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_rule(row: &sqlx::postgres::PgRow) -> Result<RelationshipRule, AppError> {
+    let relationship_type: String = row.try_get("relationship_type").map_err(missing("relationship_type"))?;
+    let cardinality: String = row.try_get("cardinality").map_err(missing("cardinality"))?;
+
+    Ok(RelationshipRule {
+        id: row.try_get("id").map_err(missing("id"))?,
+        relationship_type: serde_json::from_value(serde_json::Value::String(relationship_type.clone()))
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalid relationship type: {}", relationship_type)))?,
+        source_type: row.try_get("source_type").map_err(missing("source_type"))?,
+        target_type: row.try_get("target_type").map_err(missing("target_type"))?,
+        cardinality: RelationshipCardinality::parse(&cardinality)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid cardinality: {}", cardinality)))?,
+        bidirectional: row.try_get("bidirectional").map_err(missing("bidirectional"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn rule(relationship_type: RelationshipType, source: &str, target: &str, cardinality: RelationshipCardinality) -> RelationshipRule {
+        RelationshipRule {
+            id: Uuid::new_v4(),
+            relationship_type,
+            source_type: source.to_string(),
+            target_type: target.to_string(),
+            cardinality,
+            bidirectional: false,
+            description: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn endpoint(name: &str, card_type: &str) -> RelationshipEndpoint {
+        RelationshipEndpoint { card_id: Uuid::new_v4(), name: name.to_string(), card_type: card_type.to_string() }
+    }
+
+    fn relationship(relationship_type: &str, from: &RelationshipEndpoint, to: &RelationshipEndpoint) -> RelationshipFacts {
+        RelationshipFacts { id: Uuid::new_v4(), relationship_type: relationship_type.to_string(), from: from.clone(), to: to.clone() }
+    }
+
+    #[test]
+    fn test_find_rule() {
+        let any = rule(RelationshipType::AppliesTo, "ArchitecturePolicy", "*", RelationshipCardinality::ManyToMany);
+        let exact = rule(RelationshipType::AppliesTo, "ArchitecturePolicy", "Application", RelationshipCardinality::OneToOne);
+        let rules = vec![any.clone(), exact.clone()];
+
+        // The most specific rule wins
+        assert_eq!(find_rule(&rules, "ArchitecturePolicy", "Application").map(|(rule, _)| rule.id), Some(exact.id));
+        assert_eq!(find_rule(&rules, "ArchitecturePolicy", "Platform").map(|(rule, _)| rule.id), Some(any.id));
+        // Rules are directed unless bidirectional
+        assert!(find_rule(&rules, "Application", "ArchitecturePolicy").is_none());
+
+        let mut successor = rule(RelationshipType::DependsOn, "Initiative", "Objective", RelationshipCardinality::ManyToMany);
+        successor.bidirectional = true;
+        let rules = vec![successor];
+        assert_eq!(find_rule(&rules, "Objective", "Initiative").map(|(_, reversed)| reversed), Some(true));
+    }
+
+    #[test]
+    fn test_find_violations() {
+        let threatens = rule(RelationshipType::Threatens, "Risk", "*", RelationshipCardinality::ManyToMany);
+        let exempts = rule(RelationshipType::ExemptsFrom, "Exception", "ArchitecturePolicy", RelationshipCardinality::ManyToOne);
+        let rules = HashMap::from([
+            ("threatens".to_string(), vec![threatens]),
+            ("exemptsFrom".to_string(), vec![exempts.clone()]),
+        ]);
+
+        let platform = endpoint("AWS", "Platform");
+        let objective = endpoint("Cut costs", "Objective");
+        let exception = endpoint("Legacy DB", "Exception");
+        let policy_a = endpoint("Encryption", "ArchitecturePolicy");
+        let policy_b = endpoint("Cloud first", "ArchitecturePolicy");
+        let relationships = vec![
+            relationship("threatens", &platform, &objective),
+            relationship("exemptsFrom", &exception, &policy_a),
+            relationship("exemptsFrom", &exception, &policy_b),
+            // No rules for the type, so anything goes
+            relationship("dependsOn", &platform, &objective),
+        ];
+
+        let violations = find_violations(&rules, &relationships);
+        assert_eq!(violations.len(), 3);
+        assert_eq!(violations[0].relationship_id, relationships[0].id);
+        assert_eq!(violations[0].rule_id, None);
+        assert_eq!(violations[0].message, "threatens relationships cannot go from Platform to Objective (allowed: Risk -> any)");
+        assert!(violations[1..].iter().all(|violation| violation.rule_id == Some(exempts.id)));
+        assert_eq!(violations[1].message, "Legacy DB has 2 exemptsFrom relationships to cards of this rule; it allows one");
+    }
+}
@@ -3,16 +3,40 @@ use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use chrono::Utc;
 
+use crate::config::DataQuality;
 use crate::models::relationship::{Relationship, CreateRelationshipRequest, UpdateRelationshipRequest};
+use crate::models::version::VersionOperation;
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::card_type_service::enforce_relationship_allowed;
+use crate::services::data_quality_service::refresh_quality;
+use crate::services::pagination::{parse_sort, Keyset, SortField, SortKey, SortKind};
+use crate::services::relationship_rule_service::enforce_relationship_rules;
+use crate::services::version_service::{current_workspace, VersionService};
 use crate::error::AppError;
 
+/// Relationship fields a listing can be sorted by, besides `attributes.<key>`
+pub const RELATIONSHIP_SORT_FIELDS: &[SortField] = &[
+    SortField { name: "created_at", sql: "COALESCE(created_at, 'epoch'::timestamptz)", kind: SortKind::Timestamp },
+    SortField { name: "relationship_type", sql: "relationship_type", kind: SortKind::Text },
+    SortField { name: "valid_from", sql: "COALESCE(valid_from, '')", kind: SortKind::Text },
+    SortField { name: "valid_to", sql: "COALESCE(valid_to, '')", kind: SortKind::Text },
+    SortField { name: "confidence", sql: "COALESCE(confidence, -1)::float8", kind: SortKind::Float },
+];
+
 pub struct RelationshipService {
     pool: PgPool,
+    quality: DataQuality,
 }
 
 impl RelationshipService {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self { pool, quality: DataQuality::default() }
+    }
+
+    /// Data quality settings used to rescore cards whose relationships change
+    pub fn with_data_quality(mut self, quality: DataQuality) -> Self {
+        self.quality = quality;
+        self
     }
 
     pub async fn create(&self, req: CreateRelationshipRequest) -> Result<Relationship, AppError> {
@@ -25,10 +49,17 @@ impl RelationshipService {
             .trim_matches('"')
             .to_string();
 
-        sqlx::query(
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let workspace_id = Self::endpoint_workspace(&mut tx, req.from_card_id, req.to_card_id).await?;
+        enforce_relationship_allowed(&mut *tx, req.from_card_id, req.to_card_id, &relationship_type_str).await?;
+        enforce_relationship_rules(&mut tx, req.from_card_id, req.to_card_id, &relationship_type_str).await?;
+
+        let result = sqlx::query(
             r#"
-            INSERT INTO relationships (id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO relationships (id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             ON CONFLICT (from_card_id, to_card_id, relationship_type, valid_from) DO NOTHING
             "#,
         )
@@ -41,47 +72,59 @@ impl RelationshipService {
         .bind(&req.attributes.unwrap_or_else(|| serde_json::json!({})))
         .bind(req.confidence)
         .bind(now)
-        .execute(&self.pool)
+        .bind(workspace_id)
+        .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create relationship: {}", e)))?;
 
+        if result.rows_affected() > 0 {
+            let relationship = self.fetch_locked(&mut tx, relationship_id).await?;
+            VersionService::record_relationship(&mut tx, VersionOperation::Create, None, relationship.as_ref()).await?;
+            refresh_quality(&mut tx, &[req.from_card_id], &self.quality).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit relationship: {}", e)))?;
+
         self.get(relationship_id).await
     }
 
     pub async fn get(&self, id: Uuid) -> Result<Relationship, AppError> {
         let row = sqlx::query(
             r#"
-            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at
+            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id
             FROM relationships
-            WHERE id = $1
+            WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)
             "#,
         )
         .bind(id)
+        .bind(current_workspace())
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship: {}", e)))?
         .ok_or_else(|| AppError::NotFound(format!("Relationship {} not found", id)))?;
 
-        self.row_to_relationship(row)
+        Self::row_to_relationship(row)
     }
 
     pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<Relationship>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at
+            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id
             FROM relationships
-            WHERE from_card_id = $1 OR to_card_id = $1
+            WHERE (from_card_id = $1 OR to_card_id = $1) AND ($2::uuid IS NULL OR workspace_id = $2)
             ORDER BY created_at DESC
             "#,
         )
         .bind(card_id)
+        .bind(current_workspace())
         .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list relationships: {}", e)))?;
 
         let mut relationships = Vec::new();
         for row in rows {
-            match self.row_to_relationship(row) {
+            match Self::row_to_relationship(row) {
                 Ok(rel) => relationships.push(rel),
                 Err(e) => {
                     tracing::warn!("Failed to parse relationship row: {:?}", e);
@@ -93,21 +136,99 @@ impl RelationshipService {
         Ok(relationships)
     }
 
+    /// Relationships of the current workspace, only those of `card_id` if given, in `sort`
+    /// order (newest first by default). With a `limit`, also returns the cursor of the next page.
+    pub async fn list_page(
+        &self,
+        card_id: Option<Uuid>,
+        sort: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<(Vec<Relationship>, Option<String>), AppError> {
+        let keys = match sort {
+            Some(sort) => parse_sort(sort, RELATIONSHIP_SORT_FIELDS, Some("attributes"), &[])?,
+            None => vec![SortKey::new("created_at", RELATIONSHIP_SORT_FIELDS[0].sql.to_string(), SortKind::Timestamp, true)],
+        };
+        let keyset = Keyset::new(keys, "id");
+        let (after, after_params) = match cursor {
+            Some(cursor) => {
+                let (condition, params) = keyset.after(cursor, 3)?;
+                (format!("AND {}", condition), params)
+            }
+            None => (String::new(), Vec::new()),
+        };
+        let limit = limit.map(|limit| limit.clamp(1, 1000));
+
+        let sql = format!(
+            "SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id, {} \
+             FROM relationships \
+             WHERE ($1::uuid IS NULL OR from_card_id = $1 OR to_card_id = $1) AND ($2::uuid IS NULL OR workspace_id = $2) {} \
+             ORDER BY {} LIMIT ${}",
+            keyset.select(),
+            after,
+            keyset.order_by(),
+            after_params.len() + 3
+        );
+        let mut query = sqlx::query(&sql).bind(card_id).bind(current_workspace());
+        for param in &after_params {
+            query = query.bind(param);
+        }
+        // One row more than the page tells whether there is a next page; no limit lists all
+        let mut rows = query
+            .bind(limit.map(|limit| limit as i64 + 1))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list relationships: {}", e)))?;
+
+        let next_cursor = match limit {
+            Some(limit) if rows.len() > limit as usize => {
+                rows.truncate(limit as usize);
+                rows.last().map(|row| keyset.cursor_for(row)).transpose()?
+            }
+            _ => None,
+        };
+
+        let mut relationships = Vec::new();
+        for row in rows {
+            match Self::row_to_relationship(row) {
+                Ok(rel) => relationships.push(rel),
+                Err(e) => {
+                    tracing::warn!("Failed to parse relationship row: {:?}", e);
+                    continue;
+                }
+            }
+        }
+
+        Ok((relationships, next_cursor))
+    }
+
+    /// Relationships of every workspace (graph reconciliation)
     pub async fn list_all(&self) -> Result<Vec<Relationship>, AppError> {
+        self.list_all_in(None).await
+    }
+
+    /// Relationships of the current workspace
+    pub async fn list_all_visible(&self) -> Result<Vec<Relationship>, AppError> {
+        self.list_all_in(current_workspace()).await
+    }
+
+    async fn list_all_in(&self, workspace_id: Option<Uuid>) -> Result<Vec<Relationship>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at
+            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id
             FROM relationships
+            WHERE $1::uuid IS NULL OR workspace_id = $1
             ORDER BY created_at DESC
             "#,
         )
+        .bind(workspace_id)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list all relationships: {}", e)))?;
 
         let mut relationships = Vec::new();
         for row in rows {
-            match self.row_to_relationship(row) {
+            match Self::row_to_relationship(row) {
                 Ok(rel) => relationships.push(rel),
                 Err(e) => {
                     tracing::warn!("Failed to parse relationship row: {:?}", e);
@@ -149,6 +270,12 @@ impl RelationshipService {
             updates.join(", ")
         );
 
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Relationship {} not found", id)))?;
+
         let mut query_builder = sqlx::query(&update_query).bind(id);
 
         if let Some(valid_from) = &req.valid_from {
@@ -165,17 +292,82 @@ impl RelationshipService {
         }
 
         query_builder
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update relationship: {}", e)))?;
 
-        self.get(id).await
+        let relationship = self.fetch_locked(&mut tx, id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Relationship {} not found", id)))?;
+        VersionService::record_relationship(&mut tx, VersionOperation::Update, Some(&before), Some(&relationship)).await?;
+        // Validity dates decide whether the relationship is current
+        refresh_quality(&mut tx, &[relationship.from_card_id], &self.quality).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit relationship update: {}", e)))?;
+
+        Ok(relationship)
+    }
+
+    /// Write a relationship back exactly as given, keeping its original ID.
+    /// Used to compensate failed updates and deletes.
+    pub async fn restore(&self, relationship: &Relationship) -> Result<Relationship, AppError> {
+        let relationship_type_str = serde_json::to_string(&relationship.relationship_type)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize relationship type: {}", e))?
+            .trim_matches('"')
+            .to_string();
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, relationship.id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO relationships (id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO UPDATE SET
+                valid_from = EXCLUDED.valid_from,
+                valid_to = EXCLUDED.valid_to,
+                attributes = EXCLUDED.attributes,
+                confidence = EXCLUDED.confidence
+            WHERE relationships.workspace_id = EXCLUDED.workspace_id
+            "#,
+        )
+        .bind(relationship.id)
+        .bind(relationship.from_card_id)
+        .bind(relationship.to_card_id)
+        .bind(&relationship_type_str)
+        .bind(&relationship.valid_from)
+        .bind(&relationship.valid_to)
+        .bind(&relationship.attributes)
+        .bind(relationship.confidence)
+        .bind(relationship.created_at)
+        .bind(relationship.workspace_id.or_else(current_workspace).unwrap_or(DEFAULT_WORKSPACE_ID))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to restore relationship: {}", e)))?;
+
+        let restored = self.fetch_locked(&mut tx, relationship.id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Relationship {} not found", relationship.id)))?;
+        VersionService::record_relationship(&mut tx, VersionOperation::Restore, before.as_ref(), Some(&restored)).await?;
+        refresh_quality(&mut tx, &[restored.from_card_id], &self.quality).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit relationship restore: {}", e)))?;
+
+        Ok(restored)
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM relationships WHERE id = $1")
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let before = self.fetch_locked(&mut tx, id).await?;
+
+        let result = sqlx::query("DELETE FROM relationships WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)")
             .bind(id)
-            .execute(&self.pool)
+            .bind(current_workspace())
+            .execute(&mut *tx)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete relationship: {}", e)))?;
 
@@ -183,12 +375,103 @@ impl RelationshipService {
             return Err(AppError::NotFound(format!("Relationship {} not found", id)));
         }
 
+        VersionService::record_relationship(&mut tx, VersionOperation::Delete, before.as_ref(), None).await?;
+        if let Some(relationship) = &before {
+            refresh_quality(&mut tx, &[relationship.from_card_id], &self.quality).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit relationship delete: {}", e)))?;
+
         Ok(())
     }
 }
 
 impl RelationshipService {
-    fn row_to_relationship(&self, row: sqlx::postgres::PgRow) -> Result<Relationship, AppError> {
+    /// Read a relationship of the current workspace inside a transaction, locking the row until commit
+    async fn fetch_locked(&self, conn: &mut sqlx::PgConnection, id: Uuid) -> Result<Option<Relationship>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id
+            FROM relationships
+            WHERE id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(current_workspace())
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship: {}", e)))?;
+
+        row.map(Self::row_to_relationship).transpose()
+    }
+
+    /// Workspace a new relationship belongs to: its source card's. Within a request the source card must
+    /// belong to the current workspace and the target card must belong or be shared to it.
+    async fn endpoint_workspace(conn: &mut sqlx::PgConnection, from_card_id: Uuid, to_card_id: Uuid) -> Result<Uuid, AppError> {
+        let current = current_workspace();
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.workspace_id,
+                   EXISTS(SELECT 1 FROM card_shares s WHERE s.card_id = c.id AND s.workspace_id = $3) AS shared
+            FROM cards c
+            WHERE c.id IN ($1, $2)
+            "#,
+        )
+        .bind(from_card_id)
+        .bind(to_card_id)
+        .bind(current)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship cards: {}", e)))?;
+
+        let mut from_workspace = None;
+        let mut to_visible = false;
+        for row in rows {
+            let id: Uuid = row.try_get("id")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing id: {}", e)))?;
+            let workspace_id: Uuid = row.try_get("workspace_id")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing workspace_id: {}", e)))?;
+            let shared: bool = row.try_get("shared")
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing shared: {}", e)))?;
+            let visible = current.is_none_or(|current| current == workspace_id);
+
+            if id == from_card_id && visible {
+                from_workspace = Some(workspace_id);
+            }
+            if id == to_card_id {
+                to_visible = visible || shared;
+            }
+        }
+
+        let from_workspace = from_workspace
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", from_card_id)))?;
+        if !to_visible {
+            return Err(AppError::NotFound(format!("Card {} not found", to_card_id)));
+        }
+        Ok(from_workspace)
+    }
+
+    /// Lock every relationship touching a card, e.g. before the card delete cascades to them
+    pub(crate) async fn fetch_for_card_locked(conn: &mut sqlx::PgConnection, card_id: Uuid) -> Result<Vec<Relationship>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, from_card_id, to_card_id, relationship_type, valid_from, valid_to, attributes, confidence, created_at, workspace_id
+            FROM relationships
+            WHERE from_card_id = $1 OR to_card_id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card relationships: {}", e)))?;
+
+        rows.into_iter().map(Self::row_to_relationship).collect()
+    }
+
+    fn row_to_relationship(row: sqlx::postgres::PgRow) -> Result<Relationship, AppError> {
         let type_str: String = row.try_get("relationship_type")
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing relationship_type: {}", e)))?;
 
@@ -212,6 +495,7 @@ impl RelationshipService {
             confidence: row.try_get("confidence").ok(),
             created_at: row.try_get("created_at")
                 .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing created_at: {}", e)))?,
+            workspace_id: row.try_get("workspace_id").ok(),
         })
     }
 }
@@ -2,36 +2,37 @@ use uuid::Uuid;
 use std::sync::Arc;
 
 use crate::models::card::{Card, CreateCardRequest, UpdateCardRequest};
-use crate::models::relationship::{Relationship, CreateRelationshipRequest};
-use crate::services::{CardService, RelationshipService, Neo4jService};
+use crate::models::relationship::{Relationship, CreateRelationshipRequest, UpdateRelationshipRequest};
+use crate::services::{CardService, RelationshipService, GraphBackend};
+use crate::services::neo4j_service::edge_label;
 use crate::error::AppError;
 
-/// SAGA orchestrator for dual-write operations between PostgreSQL and Neo4j
+/// SAGA orchestrator for dual-write operations between PostgreSQL and the graph backend
 /// Implements the SAGA pattern to ensure data consistency across databases
 #[derive(Clone)]
 pub struct SagaOrchestrator {
     card_service: Arc<CardService>,
     relationship_service: Arc<RelationshipService>,
-    neo4j_service: Arc<Neo4jService>,
+    graph: Arc<dyn GraphBackend>,
 }
 
 impl SagaOrchestrator {
     pub fn new(
         card_service: Arc<CardService>,
         relationship_service: Arc<RelationshipService>,
-        neo4j_service: Arc<Neo4jService>,
+        graph: Arc<dyn GraphBackend>,
     ) -> Self {
         Self {
             card_service,
             relationship_service,
-            neo4j_service,
+            graph,
         }
     }
 
     /// Create card with SAGA pattern:
     /// 1. Create in PostgreSQL (primary)
-    /// 2. Create in Neo4j (secondary)
-    /// 3. If Neo4j fails, compensate by deleting from PostgreSQL
+    /// 2. Create in the graph (secondary)
+    /// 3. If the graph write fails, compensate by deleting from PostgreSQL
     pub async fn create_card(&self, req: CreateCardRequest) -> Result<Card, AppError> {
         // Step 1: Create card in PostgreSQL
         let card = match self.card_service.create(req).await {
@@ -39,9 +40,9 @@ impl SagaOrchestrator {
             Err(e) => return Err(e),
         };
 
-        // Step 2: Create card node in Neo4j
-        if let Err(e) = self.neo4j_service.create_card_node(&card).await {
-            tracing::error!("Neo4j sync failed: {}", e);
+        // Step 2: Create card node in the graph
+        if let Err(e) = self.graph.create_card_node(&card).await {
+            tracing::error!("Graph sync failed: {}", e);
 
             // Compensating transaction: Delete from PostgreSQL
             if let Err(compensate_err) = self.card_service.delete(card.id).await {
@@ -49,7 +50,7 @@ impl SagaOrchestrator {
             }
 
             return Err(AppError::Internal(anyhow::anyhow!(
-                "Failed to sync card to Neo4j, rolled back PostgreSQL: {}", e
+                "Failed to sync card to the graph, rolled back PostgreSQL: {}", e
             )));
         }
 
@@ -58,11 +59,11 @@ impl SagaOrchestrator {
 
     /// Update card with SAGA pattern:
     /// 1. Update in PostgreSQL
-    /// 2. Update in Neo4j
-    /// 3. If Neo4j fails, compensate by reverting PostgreSQL update (snapshot approach)
+    /// 2. Update in the graph
+    /// 3. If the graph write fails, compensate by reverting PostgreSQL update (snapshot approach)
     pub async fn update_card(&self, id: Uuid, req: UpdateCardRequest) -> Result<Card, AppError> {
         // Snapshot current state for potential compensation
-        let snapshot = self.card_service.get(id).await?;
+        let snapshot = self.card_service.get_unredacted(id).await?;
 
         // Step 1: Update in PostgreSQL
         let card = match self.card_service.update(id, req.clone()).await {
@@ -70,9 +71,9 @@ impl SagaOrchestrator {
             Err(e) => return Err(e),
         };
 
-        // Step 2: Update in Neo4j
-        if let Err(e) = self.neo4j_service.update_card_node(&card).await {
-            tracing::error!("Neo4j sync failed: {}", e);
+        // Step 2: Update in the graph
+        if let Err(e) = self.graph.update_card_node(&card).await {
+            tracing::error!("Graph sync failed: {}", e);
 
             // Compensating transaction: Revert to snapshot
             if let Err(compensate_err) = self.revert_card_update(snapshot).await {
@@ -80,7 +81,7 @@ impl SagaOrchestrator {
             }
 
             return Err(AppError::Internal(anyhow::anyhow!(
-                "Failed to sync card update to Neo4j, rolled back: {}", e
+                "Failed to sync card update to the graph, rolled back: {}", e
             )));
         }
 
@@ -89,20 +90,20 @@ impl SagaOrchestrator {
 
     /// Delete card with SAGA pattern:
     /// 1. Soft delete in PostgreSQL
-    /// 2. Delete from Neo4j
-    /// 3. If Neo4j fails, compensate by restoring in PostgreSQL
+    /// 2. Delete from the graph
+    /// 3. If the graph write fails, compensate by restoring in PostgreSQL
     pub async fn delete_card(&self, id: Uuid) -> Result<(), AppError> {
         // Snapshot current state
-        let snapshot = self.card_service.get(id).await?;
+        let snapshot = self.card_service.get_unredacted(id).await?;
 
         // Step 1: Soft delete in PostgreSQL
         if let Err(e) = self.card_service.delete(id).await {
             return Err(e);
         }
 
-        // Step 2: Delete from Neo4j
-        if let Err(e) = self.neo4j_service.delete_card_node(id).await {
-            tracing::error!("Neo4j sync failed: {}", e);
+        // Step 2: Delete from the graph
+        if let Err(e) = self.graph.delete_card_node(id).await {
+            tracing::error!("Graph sync failed: {}", e);
 
             // Compensating transaction: Restore in PostgreSQL
             if let Err(compensate_err) = self.restore_card(snapshot).await {
@@ -110,13 +111,48 @@ impl SagaOrchestrator {
             }
 
             return Err(AppError::Internal(anyhow::anyhow!(
-                "Failed to sync card deletion to Neo4j, rolled back: {}", e
+                "Failed to sync card deletion to the graph, rolled back: {}", e
             )));
         }
 
         Ok(())
     }
 
+    /// Restore a card to a previous version with SAGA pattern:
+    /// 1. Write the snapshot back to PostgreSQL, keeping the card ID (undeletes if needed)
+    /// 2. Merge the node into the graph
+    /// 3. If the graph write fails, compensate by putting back the current state
+    pub async fn restore_card_version(&self, snapshot: Card) -> Result<Card, AppError> {
+        let current = match self.card_service.get_unredacted(snapshot.id).await {
+            Ok(card) => Some(card),
+            Err(AppError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        // Step 1: Restore in PostgreSQL
+        let card = self.card_service.restore(&snapshot).await?;
+
+        // Step 2: Merge in the graph
+        if let Err(e) = self.graph.merge_card_node(&card).await {
+            tracing::error!("Graph sync failed: {}", e);
+
+            // Compensating transaction: Put back the current state
+            let compensation = match current {
+                Some(current) => self.card_service.restore(&current).await.map(|_| ()),
+                None => self.card_service.delete(card.id).await,
+            };
+            if let Err(compensate_err) = compensation {
+                tracing::error!("Failed to compensate PostgreSQL restore: {}", compensate_err);
+            }
+
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Failed to sync card restore to the graph, rolled back: {}", e
+            )));
+        }
+
+        Ok(card)
+    }
+
     /// Create relationship with SAGA pattern
     pub async fn create_relationship(&self, req: CreateRelationshipRequest) -> Result<Relationship, AppError> {
         // Step 1: Create in PostgreSQL
@@ -125,9 +161,9 @@ impl SagaOrchestrator {
             Err(e) => return Err(e),
         };
 
-        // Step 2: Create in Neo4j
-        if let Err(e) = self.neo4j_service.create_relationship(&relationship).await {
-            tracing::error!("Neo4j sync failed: {}", e);
+        // Step 2: Create in the graph
+        if let Err(e) = self.graph.create_relationship(&relationship).await {
+            tracing::error!("Graph sync failed: {}", e);
 
             // Compensating transaction: Delete from PostgreSQL
             if let Err(compensate_err) = self.relationship_service.delete(relationship.id).await {
@@ -135,13 +171,70 @@ impl SagaOrchestrator {
             }
 
             return Err(AppError::Internal(anyhow::anyhow!(
-                "Failed to sync relationship to Neo4j, rolled back: {}", e
+                "Failed to sync relationship to the graph, rolled back: {}", e
+            )));
+        }
+
+        Ok(relationship)
+    }
+
+    /// Update relationship with SAGA pattern:
+    /// 1. Update in PostgreSQL
+    /// 2. Update the edge properties in the graph
+    /// 3. If the graph write fails, compensate by restoring the PostgreSQL snapshot
+    pub async fn update_relationship(&self, id: Uuid, req: UpdateRelationshipRequest) -> Result<Relationship, AppError> {
+        // Snapshot current state for potential compensation
+        let snapshot = self.relationship_service.get(id).await?;
+
+        // Step 1: Update in PostgreSQL
+        let relationship = self.relationship_service.update(id, req).await?;
+
+        // Step 2: Update in the graph
+        if let Err(e) = self.graph.update_relationship(&relationship).await {
+            tracing::error!("Graph sync failed: {}", e);
+
+            // Compensating transaction: Revert to snapshot
+            if let Err(compensate_err) = self.relationship_service.restore(&snapshot).await {
+                tracing::error!("Failed to compensate PostgreSQL revert: {}", compensate_err);
+            }
+
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Failed to sync relationship update to the graph, rolled back: {}", e
             )));
         }
 
         Ok(relationship)
     }
 
+    /// Delete relationship with SAGA pattern:
+    /// 1. Delete from PostgreSQL
+    /// 2. Delete the edge from the graph
+    /// 3. If the graph write fails, compensate by re-inserting the snapshot with its original ID
+    pub async fn delete_relationship(&self, id: Uuid) -> Result<(), AppError> {
+        // Snapshot current state
+        let snapshot = self.relationship_service.get(id).await?;
+        let edge_type = edge_label(&snapshot.relationship_type)?;
+
+        // Step 1: Delete from PostgreSQL
+        self.relationship_service.delete(id).await?;
+
+        // Step 2: Delete from the graph
+        if let Err(e) = self.graph.delete_relationship(id, &edge_type).await {
+            tracing::error!("Graph sync failed: {}", e);
+
+            // Compensating transaction: Restore in PostgreSQL
+            if let Err(compensate_err) = self.relationship_service.restore(&snapshot).await {
+                tracing::error!("Failed to compensate PostgreSQL restore: {}", compensate_err);
+            }
+
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Failed to sync relationship deletion to the graph, rolled back: {}", e
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Compensating transaction: Revert card to snapshot state
     async fn revert_card_update(&self, snapshot: Card) -> Result<(), AppError> {
         let revert_req = UpdateCardRequest {
@@ -151,6 +244,8 @@ impl SagaOrchestrator {
             description: snapshot.description.clone(),
             attributes: Some(snapshot.attributes.clone()),
             tags: Some(snapshot.tags.clone()),
+            owner_id: snapshot.owner_id,
+            team_id: snapshot.team_id,
         };
 
         self.card_service
@@ -161,23 +256,10 @@ impl SagaOrchestrator {
         Ok(())
     }
 
-    /// Compensating transaction: Restore deleted card
+    /// Compensating transaction: Restore deleted card with its original ID
     async fn restore_card(&self, snapshot: Card) -> Result<(), AppError> {
-        // This is a simplified restore - in production you'd store the full snapshot
-        // For now, we'll create a new card with the snapshot data
-        let create_req = CreateCardRequest {
-            name: snapshot.name,
-            card_type: snapshot.card_type,
-            lifecycle_phase: snapshot.lifecycle_phase,
-            quality_score: snapshot.quality_score,
-            description: snapshot.description,
-            owner_id: snapshot.owner_id,
-            attributes: Some(snapshot.attributes),
-            tags: Some(snapshot.tags),
-        };
-
         self.card_service
-            .create(create_req)
+            .restore(&snapshot)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to restore card: {}", e)))?;
 
@@ -0,0 +1,348 @@
+/*!
+ * Saved Search Service
+ *
+ * Named card searches: the filters, boolean query and sort of `GET /api/v1/cards` plus
+ * the columns to show. A search is personal, or shared with a team its owner belongs
+ * to; pins are per user. Running a search goes through `CardService::list_matching_page`,
+ * the same path as the card list, so exports and reports that take a saved search as
+ * their filter see exactly the cards the search shows.
+ */
+
+use std::sync::Arc;
+
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::{Card, CardPage, CardSearchParams};
+use crate::models::saved_search::*;
+use crate::models::user::{Claims, UserRole};
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+use crate::services::card_query::{card_sort, compile_card_query, parse_card_query};
+use crate::services::card_service::CardService;
+use crate::services::version_service::current_workspace;
+
+const SAVED_SEARCH_COLUMNS: &str = "s.id, s.name, s.description, s.owner_id, s.team_id, s.query, s.columns, \
+                                    s.created_at, s.updated_at, \
+                                    EXISTS(SELECT 1 FROM saved_search_pins p WHERE p.saved_search_id = s.id AND p.user_id = $1) AS pinned";
+
+/// Searches owned by the caller (`$1`) or shared with one of their teams, in the workspace `$2`
+const VISIBLE: &str = "(s.owner_id = $1 OR s.team_id IN (SELECT team_id FROM team_members WHERE user_id = $1)) \
+                       AND ($2::uuid IS NULL OR s.workspace_id = $2)";
+
+/// Card fields that can be shown as columns, as named in card responses
+const CARD_COLUMNS: &[&str] = &[
+    "id", "name", "type", "lifecyclePhase", "qualityScore", "description", "ownerId", "teamId",
+    "workspaceId", "tags", "createdAt", "updatedAt",
+];
+
+/// Largest page `CardService::list_matching_page` returns
+const RUN_PAGE_SIZE: u32 = 100;
+
+pub struct SavedSearchService {
+    pool: PgPool,
+    card_service: Arc<CardService>,
+}
+
+impl SavedSearchService {
+    pub fn new(pool: PgPool, card_service: Arc<CardService>) -> Self {
+        Self { pool, card_service }
+    }
+
+    /// Searches visible to the caller, pinned first
+    pub async fn list(&self, claims: &Claims) -> Result<Vec<SavedSearch>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM saved_searches s WHERE {} ORDER BY pinned DESC, s.name",
+            SAVED_SEARCH_COLUMNS, VISIBLE
+        ))
+        .bind(caller(claims)?)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list saved searches: {}", e)))?;
+
+        rows.iter().map(row_to_saved_search).collect()
+    }
+
+    /// A search the caller owns or that is shared with one of their teams
+    pub async fn get(&self, id: Uuid, claims: &Claims) -> Result<SavedSearch, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM saved_searches s WHERE s.id = $3 AND {}",
+            SAVED_SEARCH_COLUMNS, VISIBLE
+        ))
+        .bind(caller(claims)?)
+        .bind(current_workspace())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch saved search: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Saved search {} not found", id)))?;
+
+        row_to_saved_search(&row)
+    }
+
+    pub async fn create(&self, req: CreateSavedSearchRequest, claims: &Claims) -> Result<SavedSearch, AppError> {
+        let owner_id = caller(claims)?;
+        let query = resolve_query(req.query, req.query_text.as_deref())?;
+        validate(&req.name, &query, &req.columns)?;
+        if let Some(team_id) = req.team_id {
+            self.check_team(team_id, claims).await?;
+        }
+
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO saved_searches (name, description, owner_id, team_id, workspace_id, query, columns) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
+        )
+        .bind(req.name.trim())
+        .bind(&req.description)
+        .bind(owner_id)
+        .bind(req.team_id)
+        .bind(current_workspace().unwrap_or(DEFAULT_WORKSPACE_ID))
+        .bind(query_json(&query)?)
+        .bind(&req.columns)
+        .fetch_one(&self.pool)
+        .await?;
+
+        self.get(id, claims).await
+    }
+
+    /// Replace a search; only its owner or an admin can change it
+    pub async fn update(&self, id: Uuid, req: UpdateSavedSearchRequest, claims: &Claims) -> Result<SavedSearch, AppError> {
+        let existing = self.get(id, claims).await?;
+        require_owner(&existing, claims)?;
+        let query = resolve_query(req.query, req.query_text.as_deref())?;
+        validate(&req.name, &query, &req.columns)?;
+        if let Some(team_id) = req.team_id.filter(|team_id| existing.team_id != Some(*team_id)) {
+            self.check_team(team_id, claims).await?;
+        }
+
+        sqlx::query(
+            "UPDATE saved_searches SET name = $2, description = $3, team_id = $4, query = $5, columns = $6, \
+             updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(req.name.trim())
+        .bind(&req.description)
+        .bind(req.team_id)
+        .bind(query_json(&query)?)
+        .bind(&req.columns)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update saved search: {}", e)))?;
+
+        self.get(id, claims).await
+    }
+
+    /// Delete a search; only its owner or an admin can delete it
+    pub async fn delete(&self, id: Uuid, claims: &Claims) -> Result<(), AppError> {
+        let existing = self.get(id, claims).await?;
+        require_owner(&existing, claims)?;
+
+        sqlx::query("DELETE FROM saved_searches WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete saved search: {}", e)))?;
+        Ok(())
+    }
+
+    /// Pin a visible search for the caller
+    pub async fn pin(&self, id: Uuid, claims: &Claims) -> Result<SavedSearch, AppError> {
+        self.get(id, claims).await?;
+        sqlx::query("INSERT INTO saved_search_pins (saved_search_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(id)
+            .bind(caller(claims)?)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to pin saved search: {}", e)))?;
+
+        self.get(id, claims).await
+    }
+
+    pub async fn unpin(&self, id: Uuid, claims: &Claims) -> Result<SavedSearch, AppError> {
+        self.get(id, claims).await?;
+        sqlx::query("DELETE FROM saved_search_pins WHERE saved_search_id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(caller(claims)?)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to unpin saved search: {}", e)))?;
+
+        self.get(id, claims).await
+    }
+
+    /// One page of the cards a search matches, exactly as `GET /api/v1/cards` would list them
+    pub async fn run(&self, id: Uuid, params: &SavedSearchRunParams, claims: &Claims) -> Result<CardPage, AppError> {
+        let search = self.get(id, claims).await?;
+        let mut card_params = search_params(&search.query, params.page, params.page_size);
+        card_params.cursor = params.cursor.clone();
+        self.card_service.list_matching_page(card_params, search.query.query.as_ref()).await
+    }
+
+    /// Every card a search matches, in its sort order; the filter source of exports and reports
+    pub async fn cards(&self, id: Uuid, claims: &Claims) -> Result<Vec<Card>, AppError> {
+        let search = self.get(id, claims).await?;
+        let mut cards = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut params = search_params(&search.query, None, Some(RUN_PAGE_SIZE));
+            params.cursor = cursor;
+            let page = self.card_service.list_matching_page(params, search.query.query.as_ref()).await?;
+            cards.extend(page.cards);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(cards)
+    }
+
+    /// Only members of a team (or admins) can share a search with it
+    async fn check_team(&self, team_id: Uuid, claims: &Claims) -> Result<(), AppError> {
+        let (exists, is_member): (bool, bool) = sqlx::query_as(
+            "SELECT EXISTS(SELECT 1 FROM teams WHERE id = $1), \
+             EXISTS(SELECT 1 FROM team_members WHERE team_id = $1 AND user_id = $2)",
+        )
+        .bind(team_id)
+        .bind(caller(claims)?)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check team membership: {}", e)))?;
+
+        if !exists {
+            return Err(AppError::NotFound(format!("Team {} not found", team_id)));
+        }
+        if !is_member && claims.role != UserRole::Admin {
+            return Err(AppError::Forbidden("Searches can only be shared with your own teams".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Card list parameters of a saved search
+pub fn search_params(query: &SavedSearchQuery, page: Option<u32>, page_size: Option<u32>) -> CardSearchParams {
+    CardSearchParams {
+        q: query.q.clone(),
+        card_type: query.card_type.clone(),
+        lifecycle_phase: query.lifecycle_phase.clone(),
+        tags: query.tags.clone(),
+        query: None,
+        sort: query.sort.clone(),
+        cursor: None,
+        page,
+        page_size,
+    }
+}
+
+/// Take the boolean query from the text syntax when no AST is given
+fn resolve_query(mut query: SavedSearchQuery, query_text: Option<&str>) -> Result<SavedSearchQuery, AppError> {
+    if query.query.is_none() {
+        if let Some(text) = query_text.filter(|text| !text.trim().is_empty()) {
+            query.query = Some(parse_card_query(text)?);
+        }
+    }
+    Ok(query)
+}
+
+/// Reject searches that would fail when run
+fn validate(name: &str, query: &SavedSearchQuery, columns: &[String]) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation("Saved search name is required".to_string()));
+    }
+    // Restricted attributes are checked for whoever runs the search
+    if let Some(sort) = &query.sort {
+        card_sort(sort, &[])?;
+    }
+    if let Some(card_query) = &query.query {
+        compile_card_query(card_query, 1, None, &[])?;
+    }
+    for column in columns {
+        let known = CARD_COLUMNS.contains(&column.as_str())
+            || column.strip_prefix("attributes.").is_some_and(|key| !key.is_empty());
+        if !known {
+            return Err(AppError::Validation(format!("Unknown column '{}'", column)));
+        }
+    }
+    Ok(())
+}
+
+fn require_owner(search: &SavedSearch, claims: &Claims) -> Result<(), AppError> {
+    if search.owner_id != caller(claims)? && claims.role != UserRole::Admin {
+        return Err(AppError::Forbidden("Only the owner can change a saved search".to_string()));
+    }
+    Ok(())
+}
+
+fn caller(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Auth("Invalid token subject".to_string()))
+}
+
+fn query_json(query: &SavedSearchQuery) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(query)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize saved search query: {}", e)))
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_saved_search(row: &sqlx::postgres::PgRow) -> Result<SavedSearch, AppError> {
+    let query: serde_json::Value = row.try_get("query").map_err(missing("query"))?;
+
+    Ok(SavedSearch {
+        id: row.try_get("id").map_err(missing("id"))?,
+        name: row.try_get("name").map_err(missing("name"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        owner_id: row.try_get("owner_id").map_err(missing("owner_id"))?,
+        team_id: row.try_get("team_id").map_err(missing("team_id"))?,
+        query: serde_json::from_value(query)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid saved search query: {}", e)))?,
+        columns: row.try_get("columns").map_err(missing("columns"))?,
+        pinned: row.try_get("pinned").map_err(missing("pinned"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::card::CardType;
+
+    #[test]
+    fn test_resolve_query_and_validate() {
+        let query = resolve_query(
+            SavedSearchQuery { sort: Some("-updated_at,name".to_string()), ..Default::default() },
+            Some("type = Application AND tags contains legacy"),
+        )
+        .unwrap();
+        assert!(query.query.is_some());
+        assert!(validate("Legacy apps", &query, &["name".to_string(), "attributes.vendor".to_string()]).is_ok());
+
+        assert!(validate(" ", &query, &[]).is_err());
+        assert!(validate("Legacy apps", &query, &["password".to_string()]).is_err());
+        assert!(validate("Legacy apps", &query, &["attributes.".to_string()]).is_err());
+        let bad_sort = SavedSearchQuery { sort: Some("owner_id; DROP TABLE cards".to_string()), ..Default::default() };
+        assert!(validate("Bad sort", &bad_sort, &[]).is_err());
+    }
+
+    #[test]
+    fn test_search_params_and_stored_query() {
+        let query = SavedSearchQuery {
+            card_type: Some(CardType::Application),
+            tags: Some(vec!["legacy".to_string()]),
+            sort: Some("name".to_string()),
+            ..Default::default()
+        };
+        let params = search_params(&query, Some(2), Some(50));
+        assert_eq!(params.sort.as_deref(), Some("name"));
+        assert_eq!(params.page, Some(2));
+        assert!(params.query.is_none());
+
+        let stored = query_json(&query).unwrap();
+        assert_eq!(stored["type"], "Application");
+        assert!(stored.get("q").is_none());
+        let restored: SavedSearchQuery = serde_json::from_value(stored).unwrap();
+        assert_eq!(restored, query);
+    }
+}
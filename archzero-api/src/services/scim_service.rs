@@ -0,0 +1,879 @@
+/*!
+ * SCIM 2.0 Provisioning
+ *
+ * Lets an identity platform keep users and teams in sync (RFC 7643/7644):
+ * - SCIM Users map onto `users` (userName is the email; accounts are deactivated, never deleted)
+ * - SCIM Groups map onto teams and team membership
+ * - provisioned users get their role from their groups through `[scim] role_mappings`,
+ *   recalculated whenever their memberships change
+ *
+ * Filters support `eq`, `ne`, `co`, `sw`, `ew` and `pr` joined with `and`, compiled
+ * to parameterised SQL over a whitelist of attributes.
+ */
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::config::{role_for_groups, Scim};
+use crate::error::AppError;
+use crate::models::scim::*;
+use crate::services::{AuthService, UserService};
+
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Filterable user attributes: (lowercased SCIM attribute, SQL text expression, case-insensitive)
+const USER_ATTRIBUTES: &[(&str, &str, bool)] = &[
+    ("id", "u.id::text", false),
+    ("username", "u.email", true),
+    ("emails.value", "u.email", true),
+    ("emails", "u.email", true),
+    ("externalid", "u.scim_external_id", false),
+    ("displayname", "u.full_name", true),
+    ("name.formatted", "u.full_name", true),
+    ("active", "u.is_active::text", true),
+];
+
+const GROUP_ATTRIBUTES: &[(&str, &str, bool)] = &[
+    ("id", "t.id::text", false),
+    ("displayname", "t.name", true),
+    ("externalid", "t.scim_external_id", false),
+];
+
+const USER_SELECT: &str = "SELECT u.id, u.email, u.full_name, u.is_active, u.scim_external_id, u.created_at, u.updated_at, \
+     COALESCE((SELECT json_agg(json_build_object('value', t.id::text, 'display', t.name) ORDER BY t.name) \
+               FROM team_members m JOIN teams t ON t.id = m.team_id WHERE m.user_id = u.id), '[]') AS groups \
+     FROM users u WHERE NOT u.is_service_account";
+
+const GROUP_SELECT: &str = "SELECT t.id, t.name, t.scim_external_id, t.created_at, \
+     COALESCE((SELECT json_agg(json_build_object('value', u.id::text, 'display', COALESCE(u.full_name, u.email)) ORDER BY u.email) \
+               FROM team_members m JOIN users u ON u.id = m.user_id WHERE m.team_id = t.id), '[]') AS members \
+     FROM teams t WHERE TRUE";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Pr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterClause {
+    /// Lowercased attribute path, e.g. `username` or `emails.value`
+    pub attribute: String,
+    pub op: FilterOp,
+    pub value: Option<String>,
+}
+
+/// Parse a SCIM filter: `attr op value` clauses joined with `and`
+pub fn parse_filter(filter: &str) -> Result<Vec<FilterClause>, AppError> {
+    let invalid = |reason: &str| AppError::Validation(format!("Invalid filter: {}", reason));
+    let tokens = tokenize(filter).map_err(|reason| invalid(&reason))?;
+
+    let mut clauses = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    loop {
+        let attribute = match tokens.next() {
+            Some(Token::Word(word)) => normalize_attribute(&word),
+            _ => return Err(invalid("expected an attribute")),
+        };
+        let op = match tokens.next() {
+            Some(Token::Word(word)) => match word.to_lowercase().as_str() {
+                "eq" => FilterOp::Eq,
+                "ne" => FilterOp::Ne,
+                "co" => FilterOp::Co,
+                "sw" => FilterOp::Sw,
+                "ew" => FilterOp::Ew,
+                "pr" => FilterOp::Pr,
+                other => return Err(invalid(&format!("unsupported operator '{}'", other))),
+            },
+            _ => return Err(invalid("expected an operator")),
+        };
+        let value = if op == FilterOp::Pr {
+            None
+        } else {
+            match tokens.next() {
+                Some(Token::Quoted(value)) => Some(value),
+                Some(Token::Word(word)) if matches!(word.as_str(), "true" | "false") => Some(word),
+                _ => return Err(invalid("expected a quoted value")),
+            }
+        };
+        clauses.push(FilterClause { attribute, op, value });
+
+        match tokens.next() {
+            None => return Ok(clauses),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("and") => continue,
+            Some(_) => return Err(invalid("only 'and' is supported between expressions")),
+        }
+    }
+}
+
+/// Compile clauses to a SQL condition over `attributes`, with parameters numbered from `first_param`
+pub fn compile_filter(
+    clauses: &[FilterClause],
+    attributes: &[(&str, &str, bool)],
+    first_param: usize,
+) -> Result<(String, Vec<String>), AppError> {
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+
+    for clause in clauses {
+        let (_, column, case_insensitive) = attributes
+            .iter()
+            .find(|(name, _, _)| *name == clause.attribute)
+            .ok_or_else(|| AppError::Validation(format!("Invalid filter: unsupported attribute '{}'", clause.attribute)))?;
+
+        let Some(value) = &clause.value else {
+            conditions.push(format!("{} IS NOT NULL", column));
+            continue;
+        };
+
+        params.push(value.clone());
+        let param = format!("${}", first_param + params.len() - 1);
+        let (column, param) = if *case_insensitive {
+            (format!("LOWER({})", column), format!("LOWER({})", param))
+        } else {
+            (column.to_string(), param)
+        };
+
+        conditions.push(match clause.op {
+            FilterOp::Eq => format!("{} = {}", column, param),
+            FilterOp::Ne => format!("{} IS DISTINCT FROM {}", column, param),
+            FilterOp::Co => format!("POSITION({} IN {}) > 0", param, column),
+            FilterOp::Sw => format!("STARTS_WITH({}, {})", column, param),
+            FilterOp::Ew => format!("RIGHT({}, LENGTH({})) = {}", column, param, param),
+            FilterOp::Pr => unreachable!("presence has no value"),
+        });
+    }
+
+    Ok((conditions.join(" AND "), params))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => value.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+            tokens.push(Token::Quoted(value));
+        } else if c == '(' || c == ')' {
+            return Err("grouping is not supported".to_string());
+        } else {
+            let mut word = String::new();
+            let mut depth = 0;
+            while let Some(&c) = chars.peek() {
+                // Value paths such as emails[type eq "work"].value stay one token
+                match c {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    c if c.is_whitespace() && depth == 0 => break,
+                    _ => {}
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Lowercase, drop the core schema URN prefix and any value-path filter (`emails[...].value`)
+fn normalize_attribute(attribute: &str) -> String {
+    let attribute = attribute
+        .strip_prefix(&format!("{}:", USER_SCHEMA))
+        .or_else(|| attribute.strip_prefix(&format!("{}:", GROUP_SCHEMA)))
+        .unwrap_or(attribute);
+    let attribute = match (attribute.find('['), attribute.find(']')) {
+        (Some(open), Some(close)) if open < close => format!("{}{}", &attribute[..open], &attribute[close + 1..]),
+        _ => attribute.to_string(),
+    };
+    attribute.to_lowercase()
+}
+
+/// User fields set by a PATCH request
+#[derive(Debug, Default, PartialEq)]
+pub struct UserChanges {
+    pub user_name: Option<String>,
+    pub name: Option<ScimName>,
+    pub display_name: Option<Option<String>>,
+    pub external_id: Option<Option<String>>,
+    pub active: Option<bool>,
+}
+
+impl UserChanges {
+    /// Full name to store: displayName wins over name parts
+    pub fn full_name(&self) -> Option<Option<String>> {
+        match (&self.display_name, &self.name) {
+            (Some(display_name), _) => Some(display_name.clone()),
+            (None, Some(name)) => Some(name.full_name()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Fold PATCH operations into user changes
+pub fn apply_user_patch(operations: &[ScimPatchOperation]) -> Result<UserChanges, AppError> {
+    let mut changes = UserChanges::default();
+
+    for operation in operations {
+        let op = operation.op.to_lowercase();
+        if !matches!(op.as_str(), "add" | "replace" | "remove") {
+            return Err(AppError::Validation(format!("Unsupported patch operation '{}'", operation.op)));
+        }
+        let value = if op == "remove" { Value::Null } else { operation.value.clone().unwrap_or(Value::Null) };
+
+        match &operation.path {
+            Some(path) => set_user_attribute(&mut changes, &normalize_attribute(path), &value)?,
+            // Without a path the value is an object of attributes
+            None => {
+                let Value::Object(attributes) = value else {
+                    return Err(AppError::Validation("Patch operation without a path needs an object value".to_string()));
+                };
+                for (key, value) in attributes {
+                    set_user_attribute(&mut changes, &normalize_attribute(&key), &value)?;
+                }
+            }
+        }
+    }
+    Ok(changes)
+}
+
+fn set_user_attribute(changes: &mut UserChanges, attribute: &str, value: &Value) -> Result<(), AppError> {
+    let text = || value.as_str().map(str::to_string);
+    match attribute {
+        "username" => {
+            changes.user_name = Some(text().ok_or_else(|| AppError::Validation("userName must be a string".to_string()))?)
+        }
+        "displayname" => changes.display_name = Some(text()),
+        "externalid" => changes.external_id = Some(text()),
+        "active" => {
+            // Some clients send booleans as strings ("False")
+            let active = match value {
+                Value::Bool(active) => *active,
+                Value::String(active) if active.eq_ignore_ascii_case("true") => true,
+                Value::String(active) if active.eq_ignore_ascii_case("false") => false,
+                _ => return Err(AppError::Validation("active must be a boolean".to_string())),
+            };
+            changes.active = Some(active);
+        }
+        "name" => {
+            changes.name = Some(
+                serde_json::from_value(value.clone())
+                    .map_err(|e| AppError::Validation(format!("Invalid name: {}", e)))?,
+            )
+        }
+        "name.formatted" => changes.name.get_or_insert_with(ScimName::default).formatted = text(),
+        "name.givenname" => changes.name.get_or_insert_with(ScimName::default).given_name = text(),
+        "name.familyname" => changes.name.get_or_insert_with(ScimName::default).family_name = text(),
+        // userName is the login email; other attributes are not stored
+        _ => tracing::debug!("Ignoring SCIM user attribute '{}'", attribute),
+    }
+    Ok(())
+}
+
+/// Group fields and membership changes set by a PATCH request
+#[derive(Debug, Default, PartialEq)]
+pub struct GroupChanges {
+    pub display_name: Option<String>,
+    pub external_id: Option<Option<String>>,
+    pub replace_members: Option<Vec<Uuid>>,
+    pub add_members: Vec<Uuid>,
+    pub remove_members: Vec<Uuid>,
+}
+
+/// Fold PATCH operations into group changes
+pub fn apply_group_patch(operations: &[ScimPatchOperation]) -> Result<GroupChanges, AppError> {
+    let mut changes = GroupChanges::default();
+
+    for operation in operations {
+        let op = operation.op.to_lowercase();
+        let value = operation.value.clone().unwrap_or(Value::Null);
+        let path = operation.path.as_deref().map(str::trim);
+
+        match (op.as_str(), path) {
+            // remove with a value filter: members[value eq "<id>"]
+            ("remove", Some(path)) if path.to_lowercase().starts_with("members[") => {
+                let clauses = parse_filter(path.get(8..path.len().saturating_sub(1)).unwrap_or_default())?;
+                for clause in clauses {
+                    if clause.attribute != "value" || clause.op != FilterOp::Eq {
+                        return Err(AppError::Validation("Only members[value eq \"...\"] is supported".to_string()));
+                    }
+                    changes.remove_members.push(parse_member_id(clause.value.as_deref().unwrap_or_default())?);
+                }
+            }
+            ("remove", Some(path)) if path.eq_ignore_ascii_case("members") => {
+                if value.is_null() {
+                    changes.replace_members = Some(Vec::new());
+                    changes.add_members.clear();
+                } else {
+                    changes.remove_members.extend(member_ids(&value)?);
+                }
+            }
+            ("add", Some(path)) if path.eq_ignore_ascii_case("members") => {
+                changes.add_members.extend(member_ids(&value)?);
+            }
+            ("replace", Some(path)) if path.eq_ignore_ascii_case("members") => {
+                changes.replace_members = Some(member_ids(&value)?);
+                changes.add_members.clear();
+                changes.remove_members.clear();
+            }
+            ("add" | "replace", Some(path)) if path.eq_ignore_ascii_case("displayName") => {
+                changes.display_name = Some(display_name(&value)?);
+            }
+            ("add" | "replace", Some(path)) if path.eq_ignore_ascii_case("externalId") => {
+                changes.external_id = Some(value.as_str().map(str::to_string));
+            }
+            ("remove", Some(path)) if path.eq_ignore_ascii_case("externalId") => {
+                changes.external_id = Some(None);
+            }
+            ("add" | "replace", None) => {
+                let Value::Object(attributes) = value else {
+                    return Err(AppError::Validation("Patch operation without a path needs an object value".to_string()));
+                };
+                for (key, value) in attributes {
+                    match key.to_lowercase().as_str() {
+                        "displayname" => changes.display_name = Some(display_name(&value)?),
+                        "externalid" => changes.external_id = Some(value.as_str().map(str::to_string)),
+                        "members" if op == "add" => changes.add_members.extend(member_ids(&value)?),
+                        "members" => changes.replace_members = Some(member_ids(&value)?),
+                        other => tracing::debug!("Ignoring SCIM group attribute '{}'", other),
+                    }
+                }
+            }
+            _ => {
+                return Err(AppError::Validation(format!(
+                    "Unsupported patch operation '{}' on '{}'",
+                    operation.op,
+                    path.unwrap_or("")
+                )))
+            }
+        }
+    }
+    Ok(changes)
+}
+
+fn display_name(value: &Value) -> Result<String, AppError> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Validation("displayName must be a non-empty string".to_string()))
+}
+
+fn member_ids(value: &Value) -> Result<Vec<Uuid>, AppError> {
+    let members: Vec<ScimMember> = match value {
+        Value::Array(_) => serde_json::from_value(value.clone()),
+        _ => serde_json::from_value(Value::Array(vec![value.clone()])),
+    }
+    .map_err(|e| AppError::Validation(format!("Invalid members: {}", e)))?;
+
+    members.iter().map(|member| parse_member_id(&member.value)).collect()
+}
+
+fn parse_member_id(value: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::Validation(format!("Unknown member '{}'", value)))
+}
+
+/// Pick the login email: userName if it is an address, else the primary email
+fn login_email(user_name: &str, emails: &[ScimEmail]) -> Result<String, AppError> {
+    if user_name.contains('@') {
+        return Ok(user_name.trim().to_string());
+    }
+    emails
+        .iter()
+        .find(|email| email.primary)
+        .or_else(|| emails.first())
+        .map(|email| email.value.trim().to_string())
+        .ok_or_else(|| AppError::Validation("userName or emails must contain an email address".to_string()))
+}
+
+pub struct ScimService {
+    pool: PgPool,
+    user_service: Arc<UserService>,
+    config: Scim,
+}
+
+impl ScimService {
+    pub fn new(pool: PgPool, user_service: Arc<UserService>, config: Scim) -> Self {
+        Self { pool, user_service, config }
+    }
+
+    pub fn ensure_enabled(&self) -> Result<(), AppError> {
+        if self.config.enabled {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("SCIM provisioning is not enabled".to_string()))
+        }
+    }
+
+    pub async fn list_users(&self, query: &ScimListQuery) -> Result<ScimListResponse<ScimUser>, AppError> {
+        let (rows, total, start_index) = self.list(USER_SELECT, USER_ATTRIBUTES, "u.created_at, u.id", query).await?;
+        let users = rows.iter().map(row_to_user).collect::<Result<Vec<_>, _>>()?;
+        Ok(ScimListResponse::new(total, start_index, users))
+    }
+
+    pub async fn get_user(&self, id: Uuid) -> Result<ScimUser, AppError> {
+        let row = sqlx::query(&format!("{} AND u.id = $1", USER_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch user: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+
+        row_to_user(&row)
+    }
+
+    /// Provision a user; it has no password and signs in with single sign-on
+    pub async fn create_user(&self, req: ScimCreateUserRequest) -> Result<ScimUser, AppError> {
+        let email = login_email(&req.user_name, &req.emails)?;
+        let full_name = req.display_name.clone().or_else(|| req.name.full_name());
+
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (email, full_name, role, password_hash, is_active, scim_managed, scim_external_id, failed_login_attempts)
+             VALUES ($1, $2, $3, NULL, $4, TRUE, $5, 0)
+             RETURNING id",
+        )
+        .bind(&email)
+        .bind(&full_name)
+        .bind(self.config.default_role.as_str())
+        .bind(req.active)
+        .bind(&req.external_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!("SCIM provisioned user {}", email);
+        self.get_user(id).await
+    }
+
+    /// Apply PATCH operations; `active: false` deactivates the user and revokes their sessions
+    pub async fn patch_user(&self, id: Uuid, operations: &[ScimPatchOperation]) -> Result<ScimUser, AppError> {
+        let current = self.get_user(id).await?;
+        let changes = apply_user_patch(operations)?;
+
+        let email = match &changes.user_name {
+            Some(user_name) => Some(login_email(user_name, &current.emails)?),
+            None => None,
+        };
+        let full_name = changes.full_name();
+
+        // The user is adopted by the provisioning client from here on
+        sqlx::query(
+            "UPDATE users SET
+                email = COALESCE($2, email),
+                full_name = CASE WHEN $3 THEN $4 ELSE full_name END,
+                scim_external_id = CASE WHEN $5 THEN $6 ELSE scim_external_id END,
+                scim_managed = TRUE,
+                updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&email)
+        .bind(full_name.is_some())
+        .bind(full_name.flatten())
+        .bind(changes.external_id.is_some())
+        .bind(changes.external_id.clone().flatten())
+        .execute(&self.pool)
+        .await?;
+
+        match changes.active {
+            Some(false) if current.active => {
+                self.user_service.deactivate(id, None).await?;
+                tracing::info!("SCIM deactivated user {}", current.user_name);
+            }
+            Some(true) if !current.active => {
+                self.user_service.reactivate(id).await?;
+            }
+            _ => {}
+        }
+
+        self.get_user(id).await
+    }
+
+    /// SCIM DELETE: deactivate, keeping the row for audit and version history
+    pub async fn delete_user(&self, id: Uuid) -> Result<(), AppError> {
+        let current = self.get_user(id).await?;
+        if current.active {
+            self.user_service.deactivate(id, None).await?;
+            tracing::info!("SCIM deactivated user {}", current.user_name);
+        }
+        Ok(())
+    }
+
+    pub async fn list_groups(&self, query: &ScimListQuery) -> Result<ScimListResponse<ScimGroup>, AppError> {
+        let (rows, total, start_index) = self.list(GROUP_SELECT, GROUP_ATTRIBUTES, "t.name, t.id", query).await?;
+        let groups = rows.iter().map(row_to_group).collect::<Result<Vec<_>, _>>()?;
+        Ok(ScimListResponse::new(total, start_index, groups))
+    }
+
+    pub async fn get_group(&self, id: Uuid) -> Result<ScimGroup, AppError> {
+        let row = sqlx::query(&format!("{} AND t.id = $1", GROUP_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch group: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Group {} not found", id)))?;
+
+        row_to_group(&row)
+    }
+
+    pub async fn create_group(&self, req: ScimCreateGroupRequest) -> Result<ScimGroup, AppError> {
+        let name = display_name(&Value::String(req.display_name))?;
+        let members = req.members.iter().map(|member| parse_member_id(&member.value)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let id: Uuid = sqlx::query_scalar("INSERT INTO teams (name, scim_external_id) VALUES ($1, $2) RETURNING id")
+            .bind(&name)
+            .bind(&req.external_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        add_members(&mut tx, id, &members).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        self.sync_roles(&members).await?;
+        self.get_group(id).await
+    }
+
+    /// Apply PATCH operations, then recalculate the roles of everyone whose membership changed
+    pub async fn patch_group(&self, id: Uuid, operations: &[ScimPatchOperation]) -> Result<ScimGroup, AppError> {
+        let current = self.get_group(id).await?;
+        let changes = apply_group_patch(operations)?;
+        let before: HashSet<Uuid> = current.members.iter().filter_map(|m| Uuid::parse_str(&m.value).ok()).collect();
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE teams SET
+                name = COALESCE($2, name),
+                scim_external_id = CASE WHEN $3 THEN $4 ELSE scim_external_id END
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&changes.display_name)
+        .bind(changes.external_id.is_some())
+        .bind(changes.external_id.clone().flatten())
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(members) = &changes.replace_members {
+            sqlx::query("DELETE FROM team_members WHERE team_id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to replace members: {}", e)))?;
+            add_members(&mut tx, id, members).await?;
+        }
+        add_members(&mut tx, id, &changes.add_members).await?;
+        if !changes.remove_members.is_empty() {
+            sqlx::query("DELETE FROM team_members WHERE team_id = $1 AND user_id = ANY($2)")
+                .bind(id)
+                .bind(&changes.remove_members)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to remove members: {}", e)))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        let group = self.get_group(id).await?;
+        let after: HashSet<Uuid> = group.members.iter().filter_map(|m| Uuid::parse_str(&m.value).ok()).collect();
+
+        // A rename can change which mapping matches, so every member is affected
+        let affected: Vec<Uuid> = if changes.display_name.is_some() {
+            before.union(&after).copied().collect()
+        } else {
+            before.symmetric_difference(&after).copied().collect()
+        };
+        self.sync_roles(&affected).await?;
+
+        Ok(group)
+    }
+
+    /// Delete the team; cards keep their owner but lose the team
+    pub async fn delete_group(&self, id: Uuid) -> Result<(), AppError> {
+        let current = self.get_group(id).await?;
+        sqlx::query("DELETE FROM teams WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete group: {}", e)))?;
+
+        let members: Vec<Uuid> = current.members.iter().filter_map(|m| Uuid::parse_str(&m.value).ok()).collect();
+        self.sync_roles(&members).await
+    }
+
+    /// Recalculate the roles of provisioned users from their groups. Users whose role changes
+    /// lose their sessions so the new role applies immediately.
+    async fn sync_roles(&self, user_ids: &[Uuid]) -> Result<(), AppError> {
+        if self.config.role_mappings.is_empty() || user_ids.is_empty() {
+            return Ok(());
+        }
+
+        let rows = sqlx::query(
+            "SELECT u.id, u.role, COALESCE(ARRAY_AGG(t.name) FILTER (WHERE t.name IS NOT NULL), '{}') AS groups
+             FROM users u
+             LEFT JOIN team_members m ON m.user_id = u.id
+             LEFT JOIN teams t ON t.id = m.team_id
+             WHERE u.id = ANY($1) AND u.scim_managed
+             GROUP BY u.id",
+        )
+        .bind(user_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to load group memberships: {}", e)))?;
+
+        for row in rows {
+            let user_id: Uuid = row.try_get("id").map_err(missing("id"))?;
+            let current: String = row.try_get("role").map_err(missing("role"))?;
+            let groups: Vec<String> = row.try_get("groups").map_err(missing("groups"))?;
+
+            let role = role_for_groups(&self.config.role_mappings, &groups)
+                .unwrap_or_else(|| self.config.default_role.clone());
+            if role.as_str() == current {
+                continue;
+            }
+
+            let mut tx = self.pool.begin().await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+            sqlx::query("UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1")
+                .bind(user_id)
+                .bind(role.as_str())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update role: {}", e)))?;
+            AuthService::revoke_user_sessions(&mut tx, user_id, None).await?;
+            tx.commit().await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+            tracing::info!("SCIM changed role of user {} from {} to {}", user_id, current, role.as_str());
+        }
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        select: &str,
+        attributes: &[(&str, &str, bool)],
+        order_by: &str,
+        query: &ScimListQuery,
+    ) -> Result<(Vec<sqlx::postgres::PgRow>, i64, i64), AppError> {
+        let (condition, params) = match query.filter.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+            Some(filter) => compile_filter(&parse_filter(filter)?, attributes, 1)?,
+            None => ("TRUE".to_string(), Vec::new()),
+        };
+        let start_index = query.start_index.unwrap_or(1).max(1);
+        let count = query.count.unwrap_or(100).clamp(0, MAX_PAGE_SIZE);
+
+        let count_sql = format!("SELECT COUNT(*) FROM ({} AND {}) AS matches", select, condition);
+        let mut total_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        for param in &params {
+            total_query = total_query.bind(param);
+        }
+        let total = total_query
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count resources: {}", e)))?;
+
+        let page_sql = format!(
+            "{} AND {} ORDER BY {} LIMIT {} OFFSET {}",
+            select, condition, order_by, count, start_index - 1
+        );
+        let mut page_query = sqlx::query(&page_sql);
+        for param in &params {
+            page_query = page_query.bind(param);
+        }
+        let rows = page_query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list resources: {}", e)))?;
+
+        Ok((rows, total, start_index))
+    }
+}
+
+async fn add_members(conn: &mut sqlx::PgConnection, team_id: Uuid, user_ids: &[Uuid]) -> Result<(), AppError> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    // Unknown users are skipped rather than failing the whole request
+    sqlx::query(
+        "INSERT INTO team_members (team_id, user_id)
+         SELECT $1, id FROM users WHERE id = ANY($2)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(team_id)
+    .bind(user_ids)
+    .execute(conn)
+    .await
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to add members: {}", e)))?;
+    Ok(())
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_user(row: &sqlx::postgres::PgRow) -> Result<ScimUser, AppError> {
+    let id: Uuid = row.try_get("id").map_err(missing("id"))?;
+    let email: String = row.try_get("email").map_err(missing("email"))?;
+    let full_name: Option<String> = row.try_get("full_name").map_err(missing("full_name"))?;
+    let groups: Value = row.try_get("groups").map_err(missing("groups"))?;
+
+    Ok(ScimUser {
+        schemas: vec![USER_SCHEMA.to_string()],
+        id,
+        external_id: row.try_get("scim_external_id").map_err(missing("scim_external_id"))?,
+        user_name: email.clone(),
+        name: ScimName {
+            formatted: full_name.clone(),
+            ..ScimName::default()
+        },
+        display_name: full_name,
+        emails: vec![ScimEmail {
+            value: email,
+            primary: true,
+            kind: Some("work".to_string()),
+        }],
+        active: row.try_get("is_active").map_err(missing("is_active"))?,
+        groups: serde_json::from_value(groups)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid groups: {}", e)))?,
+        meta: ScimMeta {
+            resource_type: "User".to_string(),
+            created: row.try_get("created_at").map_err(missing("created_at"))?,
+            last_modified: row.try_get("updated_at").map_err(missing("updated_at"))?,
+            location: format!("/scim/v2/Users/{}", id),
+        },
+    })
+}
+
+fn row_to_group(row: &sqlx::postgres::PgRow) -> Result<ScimGroup, AppError> {
+    let id: Uuid = row.try_get("id").map_err(missing("id"))?;
+    let created = row.try_get("created_at").map_err(missing("created_at"))?;
+    let members: Value = row.try_get("members").map_err(missing("members"))?;
+
+    Ok(ScimGroup {
+        schemas: vec![GROUP_SCHEMA.to_string()],
+        id,
+        external_id: row.try_get("scim_external_id").map_err(missing("scim_external_id"))?,
+        display_name: row.try_get("name").map_err(missing("name"))?,
+        members: serde_json::from_value(members)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid members: {}", e)))?,
+        meta: ScimMeta {
+            resource_type: "Group".to_string(),
+            created,
+            last_modified: created,
+            location: format!("/scim/v2/Groups/{}", id),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operations(value: Value) -> Vec<ScimPatchOperation> {
+        serde_json::from_value::<ScimPatchRequest>(value).unwrap().operations
+    }
+
+    #[test]
+    fn test_filter_compiles_to_parameterised_sql() {
+        let clauses = parse_filter(r#"userName eq "Jane@Example.com" and emails[type eq "work"].value pr"#).unwrap();
+        assert_eq!(clauses.len(), 2);
+        assert_eq!(clauses[1].attribute, "emails.value");
+
+        let (sql, params) = compile_filter(&clauses, USER_ATTRIBUTES, 1).unwrap();
+        assert_eq!(sql, "LOWER(u.email) = LOWER($1) AND u.email IS NOT NULL");
+        assert_eq!(params, vec!["Jane@Example.com"]);
+
+        let (sql, params) = compile_filter(&parse_filter(r#"externalId eq "a\"b" and active eq true"#).unwrap(), USER_ATTRIBUTES, 3).unwrap();
+        assert_eq!(sql, "u.scim_external_id = $3 AND LOWER(u.is_active::text) = LOWER($4)");
+        assert_eq!(params, vec!["a\"b", "true"]);
+
+        assert!(parse_filter(r#"userName eq "a" or userName eq "b""#).is_err());
+        assert!(parse_filter(r#"(userName eq "a")"#).is_err());
+        assert!(compile_filter(&parse_filter(r#"password eq "x""#).unwrap(), USER_ATTRIBUTES, 1).is_err());
+    }
+
+    #[test]
+    fn test_user_patch_handles_paths_and_string_booleans() {
+        let changes = apply_user_patch(&operations(json!({
+            "schemas": [PATCH_OP_SCHEMA],
+            "Operations": [
+                {"op": "Replace", "path": "active", "value": "False"},
+                {"op": "replace", "path": "name.givenName", "value": "Jane"},
+                {"op": "replace", "path": "name.familyName", "value": "Doe"},
+                {"op": "remove", "path": "externalId"}
+            ]
+        })))
+        .unwrap();
+        assert_eq!(changes.active, Some(false));
+        assert_eq!(changes.full_name(), Some(Some("Jane Doe".to_string())));
+        assert_eq!(changes.external_id, Some(None));
+
+        let changes = apply_user_patch(&operations(json!({
+            "Operations": [{"op": "replace", "value": {"active": true, "userName": "jane@example.com"}}]
+        })))
+        .unwrap();
+        assert_eq!(changes.active, Some(true));
+        assert_eq!(changes.user_name.as_deref(), Some("jane@example.com"));
+    }
+
+    #[test]
+    fn test_group_patch_membership_operations() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let changes = apply_group_patch(&operations(json!({
+            "Operations": [
+                {"op": "add", "path": "members", "value": [{"value": a.to_string()}]},
+                {"op": "remove", "path": format!("members[value eq \"{}\"]", b)},
+                {"op": "replace", "path": "displayName", "value": "ArchZero Architects"}
+            ]
+        })))
+        .unwrap();
+        assert_eq!(changes.add_members, vec![a]);
+        assert_eq!(changes.remove_members, vec![b]);
+        assert_eq!(changes.display_name.as_deref(), Some("ArchZero Architects"));
+
+        let changes = apply_group_patch(&operations(json!({
+            "Operations": [{"op": "replace", "path": "members", "value": [{"value": b.to_string()}]}]
+        })))
+        .unwrap();
+        assert_eq!(changes.replace_members, Some(vec![b]));
+
+        assert!(apply_group_patch(&operations(json!({
+            "Operations": [{"op": "add", "path": "members", "value": [{"value": "not-a-user"}]}]
+        })))
+        .is_err());
+    }
+}
@@ -0,0 +1,117 @@
+/*!
+ * Team Service
+ *
+ * Teams and their members. A card's `team_id` lets every member edit it
+ * (see `card_access`).
+ */
+
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::team::{CreateTeamRequest, Team};
+
+const TEAM_SELECT: &str = "SELECT t.id, t.name, t.description, t.created_at, \
+     COALESCE(ARRAY_AGG(m.user_id) FILTER (WHERE m.user_id IS NOT NULL), '{}') AS member_ids \
+     FROM teams t LEFT JOIN team_members m ON m.team_id = t.id";
+
+pub struct TeamService {
+    pool: PgPool,
+}
+
+impl TeamService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Team>, AppError> {
+        let rows = sqlx::query(&format!("{} GROUP BY t.id ORDER BY t.name", TEAM_SELECT))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list teams: {}", e)))?;
+
+        rows.into_iter().map(row_to_team).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Team, AppError> {
+        let row = sqlx::query(&format!("{} WHERE t.id = $1 GROUP BY t.id", TEAM_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch team: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Team {} not found", id)))?;
+
+        row_to_team(row)
+    }
+
+    pub async fn create(&self, req: CreateTeamRequest) -> Result<Team, AppError> {
+        if req.name.trim().is_empty() {
+            return Err(AppError::Validation("Team name is required".to_string()));
+        }
+
+        let id: Uuid = sqlx::query_scalar("INSERT INTO teams (name, description) VALUES ($1, $2) RETURNING id")
+            .bind(req.name.trim())
+            .bind(&req.description)
+            .fetch_one(&self.pool)
+            .await?;
+
+        self.get(id).await
+    }
+
+    /// Delete a team; its cards keep their owner but lose the team
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM teams WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete team: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Team {} not found", id)));
+        }
+        Ok(())
+    }
+
+    pub async fn add_member(&self, team_id: Uuid, user_id: Uuid) -> Result<Team, AppError> {
+        self.get(team_id).await?;
+
+        sqlx::query("INSERT INTO team_members (team_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(team_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.code().as_deref() == Some("23503") => {
+                    AppError::NotFound(format!("User {} not found", user_id))
+                }
+                e => AppError::Internal(anyhow::anyhow!("Failed to add team member: {}", e)),
+            })?;
+
+        self.get(team_id).await
+    }
+
+    pub async fn remove_member(&self, team_id: Uuid, user_id: Uuid) -> Result<Team, AppError> {
+        sqlx::query("DELETE FROM team_members WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to remove team member: {}", e)))?;
+
+        self.get(team_id).await
+    }
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_team(row: sqlx::postgres::PgRow) -> Result<Team, AppError> {
+    Ok(Team {
+        id: row.try_get("id").map_err(missing("id"))?,
+        name: row.try_get("name").map_err(missing("name"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        member_ids: row.try_get("member_ids").map_err(missing("member_ids"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+    })
+}
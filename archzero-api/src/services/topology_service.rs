@@ -3,26 +3,24 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::models::bia::{TopologyMetrics, CriticalityLevel, EnhancedCriticality};
-use crate::services::Neo4jService;
+use crate::services::GraphBackend;
 
 pub struct TopologyService {
-    neo4j: Arc<Neo4jService>,
+    graph: Arc<dyn GraphBackend>,
 }
 
 impl TopologyService {
-    pub fn new(neo4j: Arc<Neo4jService>) -> Self {
-        Self { neo4j }
+    pub fn new(graph: Arc<dyn GraphBackend>) -> Self {
+        Self { graph }
     }
 
     /// Calculate topology metrics for a card (fan-in, fan-out)
     pub async fn calculate_topology_metrics(&self, card_id: Uuid) -> Result<TopologyMetrics> {
-        let card_id_str = card_id.to_string();
-
         // Calculate fan-in (number of cards that depend on this card)
-        let fan_in = self.count_fan_in(&card_id_str).await?;
+        let fan_in = self.count_fan_in(card_id).await?;
 
         // Calculate fan-out (number of cards this card depends on)
-        let fan_out = self.count_fan_out(&card_id_str).await?;
+        let fan_out = self.count_fan_out(card_id).await?;
 
         let total_connections = fan_in + fan_out;
 
@@ -47,89 +45,23 @@ impl TopologyService {
     }
 
     /// Count fan-in (incoming dependencies)
-    async fn count_fan_in(&self, card_id: &str) -> Result<u32> {
-        let query = "
-            MATCH (c:Card {id: $card_id})<-[:RELATED_TO]-(other:Card)
-            RETURN count(other) as fan_in
-        ";
-
-        let mut result = self.neo4j
-            .execute_query(neo4rs::query(query).param("card_id", card_id))
-            .await?;
-
-        if let Ok(Some(row)) = result.next().await {
-            let count: i64 = row.get("fan_in").unwrap_or(0);
-            Ok(count as u32)
-        } else {
-            Ok(0)
-        }
+    async fn count_fan_in(&self, card_id: Uuid) -> Result<u32> {
+        Ok(self.graph.dependents(card_id).await?.len() as u32)
     }
 
     /// Count fan-out (outgoing dependencies)
-    async fn count_fan_out(&self, card_id: &str) -> Result<u32> {
-        let query = "
-            MATCH (c:Card {id: $card_id})-[:RELATED_TO]->(other:Card)
-            RETURN count(other) as fan_out
-        ";
-
-        let mut result = self.neo4j
-            .execute_query(neo4rs::query(query).param("card_id", card_id))
-            .await?;
-
-        if let Ok(Some(row)) = result.next().await {
-            let count: i64 = row.get("fan_out").unwrap_or(0);
-            Ok(count as u32)
-        } else {
-            Ok(0)
-        }
+    async fn count_fan_out(&self, card_id: Uuid) -> Result<u32> {
+        Ok(self.graph.dependencies(card_id).await?.len() as u32)
     }
 
     /// Get all dependent cards (cards that depend on this card)
     pub async fn get_dependents(&self, card_id: Uuid) -> Result<Vec<Uuid>> {
-        let card_id_str = card_id.to_string();
-        let query = "
-            MATCH (c:Card {id: $card_id})<-[:RELATED_TO]-(other:Card)
-            RETURN other.id as id
-        ";
-
-        let mut result = self.neo4j
-            .execute_query(neo4rs::query(query).param("card_id", card_id_str))
-            .await?;
-
-        let mut dependents = Vec::new();
-        while let Ok(Some(row)) = result.next().await {
-            if let Some(id_str) = row.get::<String>("id") {
-                if let Ok(id) = Uuid::parse_str(&id_str) {
-                    dependents.push(id);
-                }
-            }
-        }
-
-        Ok(dependents)
+        Ok(self.graph.dependents(card_id).await?)
     }
 
     /// Get all dependencies (cards this card depends on)
     pub async fn get_dependencies(&self, card_id: Uuid) -> Result<Vec<Uuid>> {
-        let card_id_str = card_id.to_string();
-        let query = "
-            MATCH (c:Card {id: $card_id})-[:RELATED_TO]->(other:Card)
-            RETURN other.id as id
-        ";
-
-        let mut result = self.neo4j
-            .execute_query(neo4rs::query(query).param("card_id", card_id_str))
-            .await?;
-
-        let mut dependencies = Vec::new();
-        while let Ok(Some(row)) = result.next().await {
-            if let Some(id_str) = row.get::<String>("id") {
-                if let Ok(id) = Uuid::parse_str(&id_str) {
-                    dependencies.push(id);
-                }
-            }
-        }
-
-        Ok(dependencies)
+        Ok(self.graph.dependencies(card_id).await?)
     }
 
     /// Calculate enhanced criticality combining BIA score and topology
@@ -198,48 +130,15 @@ impl TopologyService {
 
     /// Find critical paths (cards with high fan-in that are dependencies for many cards)
     pub async fn find_critical_paths(&self, threshold: u32) -> Result<Vec<(Uuid, u32)>> {
-        let query = "
-            MATCH (c:Card)<-[:RELATED_TO]-(other:Card)
-            WITH c, count(other) as fan_in
-            WHERE fan_in >= $threshold
-            RETURN c.id as card_id, fan_in
-            ORDER BY fan_in DESC
-        ";
-
-        let mut result = self.neo4j
-            .execute_query(neo4rs::query(query).param("threshold", threshold))
-            .await?;
-
-        let mut critical_paths = Vec::new();
-        while let Ok(Some(row)) = result.next().await {
-            if let Some(id_str) = row.get::<String>("card_id") {
-                if let Ok(id) = Uuid::parse_str(&id_str) {
-                    let fan_in: i64 = row.get("fan_in").unwrap_or(0);
-                    critical_paths.push((id, fan_in as u32));
-                }
-            }
-        }
-
-        Ok(critical_paths)
+        Ok(self.graph.critical_paths(threshold).await?)
     }
 
     /// Get topology metrics for all cards
     pub async fn get_all_topology_metrics(&self) -> Result<Vec<TopologyMetrics>> {
-        let query = "
-            MATCH (c:Card)
-            RETURN c.id as card_id
-        ";
-
-        let mut result = self.neo4j.execute_query(neo4rs::query(query)).await?;
-
-        let mut card_ids = Vec::new();
-        while let Ok(Some(row)) = result.next().await {
-            if let Some(id_str) = row.get::<String>("card_id") {
-                if let Ok(id) = Uuid::parse_str(&id_str) {
-                    card_ids.push(id);
-                }
-            }
-        }
+        let card_ids = self.graph.list_card_nodes().await?
+            .into_iter()
+            .map(|node| node.id)
+            .collect();
 
         self.calculate_bulk_topology_metrics(card_ids).await
     }
@@ -0,0 +1,617 @@
+/*!
+ * User Management Service
+ *
+ * Admin-driven user CRUD, deactivation, invitations, password reset/change and
+ * account unlock. Accounts are never hard-deleted so that version history and audit
+ * rows keep pointing at a real user. Invitation and reset tokens are random, single-use
+ * and stored only as SHA-256 hashes.
+ */
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::config::{Accounts, PasswordPolicy};
+use crate::error::AppError;
+use crate::models::user::*;
+use crate::services::account_sender::{AccountMessage, AccountMessageKind, AccountMessageSender};
+use crate::services::AuthService;
+
+const USER_COLUMNS: &str = "id, email, full_name, role, is_active, COALESCE(failed_login_attempts, 0) AS failed_login_attempts, \
+     locked_until, deactivated_at, password_changed_at, created_at, updated_at";
+
+const INVITATION_COLUMNS: &str =
+    "id, email, full_name, role, invited_by, user_id, expires_at, accepted_at, revoked_at, created_at";
+
+pub struct UserService {
+    pool: PgPool,
+    auth_service: Arc<AuthService>,
+    sender: Arc<dyn AccountMessageSender>,
+    accounts: Accounts,
+    password_policy: PasswordPolicy,
+}
+
+impl UserService {
+    pub fn new(
+        pool: PgPool,
+        auth_service: Arc<AuthService>,
+        sender: Arc<dyn AccountMessageSender>,
+        accounts: Accounts,
+        password_policy: PasswordPolicy,
+    ) -> Self {
+        Self {
+            pool,
+            auth_service,
+            sender,
+            accounts,
+            password_policy,
+        }
+    }
+
+    pub async fn list(&self, include_inactive: bool) -> Result<Vec<UserAccount>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM users WHERE is_active OR $1 ORDER BY created_at DESC",
+            USER_COLUMNS
+        ))
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list users: {}", e)))?;
+
+        rows.into_iter().map(row_to_account).collect()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<UserAccount, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch user: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        row_to_account(row)
+    }
+
+    /// Create an account with an admin-chosen password
+    pub async fn create(&self, req: RegisterRequest) -> Result<UserAccount, AppError> {
+        validate_password(&self.password_policy, &req.password)?;
+        let user = self.auth_service.register(req).await?;
+        self.get(user.id).await
+    }
+
+    /// Update email, name or role. The last active admin cannot be demoted, and a role
+    /// change revokes the user's sessions so tokens carrying the old role stop working.
+    pub async fn update(&self, id: Uuid, req: UpdateUserRequest) -> Result<UserAccount, AppError> {
+        let current = self.get(id).await?;
+
+        if let Some(role) = &req.role {
+            if current.role == UserRole::Admin && *role != UserRole::Admin {
+                self.ensure_other_admin(id).await?;
+            }
+        }
+        let role_changed = req.role.as_ref().is_some_and(|role| *role != current.role);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE users SET email = COALESCE($2, email), full_name = COALESCE($3, full_name),
+                    role = COALESCE($4, role), updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&req.email)
+        .bind(&req.full_name)
+        .bind(req.role.as_ref().map(UserRole::as_str))
+        .execute(&mut *tx)
+        .await?;
+
+        if role_changed {
+            AuthService::revoke_user_sessions(&mut tx, id, None).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        self.get(id).await
+    }
+
+    /// Soft delete: the account can no longer log in and pending reset tokens are voided
+    pub async fn deactivate(&self, id: Uuid, acting_user: Option<Uuid>) -> Result<UserAccount, AppError> {
+        if acting_user == Some(id) {
+            return Err(AppError::Validation("You cannot deactivate your own account".to_string()));
+        }
+
+        let current = self.get(id).await?;
+        if !current.is_active {
+            return Ok(current);
+        }
+        if current.role == UserRole::Admin {
+            self.ensure_other_admin(id).await?;
+        }
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("UPDATE users SET is_active = FALSE, deactivated_at = NOW(), updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to deactivate user: {}", e)))?;
+
+        sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to void reset tokens: {}", e)))?;
+
+        AuthService::revoke_user_sessions(&mut tx, id, None).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        self.get(id).await
+    }
+
+    pub async fn reactivate(&self, id: Uuid) -> Result<UserAccount, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET is_active = TRUE, deactivated_at = NULL, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to reactivate user: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        self.get(id).await
+    }
+
+    /// Clear a lockout set by repeated failed logins
+    pub async fn unlock(&self, id: Uuid) -> Result<UserAccount, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET failed_login_attempts = 0, locked_until = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to unlock user: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        self.get(id).await
+    }
+
+    /// Create an invitation and send its link. Any open invitation for the same email is revoked.
+    pub async fn invite(&self, req: CreateInvitationRequest, invited_by: Option<Uuid>) -> Result<Invitation, AppError> {
+        self.ensure_password_login()?;
+        let existing: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM users WHERE email = $1")
+            .bind(&req.email)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to check email: {}", e)))?;
+        if existing.is_some() {
+            return Err(AppError::Validation(format!("A user with email {} already exists", req.email)));
+        }
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::hours(self.accounts.invitation_expiry_hours);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE user_invitations SET revoked_at = NOW()
+             WHERE email = $1 AND accepted_at IS NULL AND revoked_at IS NULL",
+        )
+        .bind(&req.email)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke previous invitation: {}", e)))?;
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO user_invitations (email, full_name, role, token_hash, invited_by, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING {}",
+            INVITATION_COLUMNS
+        ))
+        .bind(&req.email)
+        .bind(&req.full_name)
+        .bind(req.role.as_str())
+        .bind(hash_token(&token))
+        .bind(invited_by)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create invitation: {}", e)))?;
+        let invitation = row_to_invitation(row)?;
+
+        // Only keep the invitation if the link was actually delivered
+        let link = format!("{}/accept-invitation?token={}", self.accounts.link_base_url.trim_end_matches('/'), token);
+        self.sender
+            .send(&AccountMessage {
+                kind: AccountMessageKind::Invitation,
+                to: req.email.clone(),
+                subject: "You have been invited to Arc Zero".to_string(),
+                body: format!(
+                    "You have been invited to Arc Zero as {}. The link expires at {}.",
+                    req.role.as_str(),
+                    expires_at.to_rfc3339()
+                ),
+                link,
+            })
+            .await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        Ok(invitation)
+    }
+
+    pub async fn list_invitations(&self) -> Result<Vec<Invitation>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM user_invitations ORDER BY created_at DESC",
+            INVITATION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list invitations: {}", e)))?;
+
+        rows.into_iter().map(row_to_invitation).collect()
+    }
+
+    pub async fn revoke_invitation(&self, id: Uuid) -> Result<Invitation, AppError> {
+        let row = sqlx::query(&format!(
+            "UPDATE user_invitations SET revoked_at = NOW()
+             WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL
+             RETURNING {}",
+            INVITATION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to revoke invitation: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Open invitation not found".to_string()))?;
+
+        row_to_invitation(row)
+    }
+
+    /// Create the invited account with the invitee's own password
+    pub async fn accept_invitation(&self, req: AcceptInvitationRequest) -> Result<UserAccount, AppError> {
+        self.ensure_password_login()?;
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM user_invitations
+             WHERE token_hash = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > NOW()",
+            INVITATION_COLUMNS
+        ))
+        .bind(hash_token(&req.token))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch invitation: {}", e)))?
+        .ok_or_else(|| AppError::Validation("Invitation is invalid or has expired".to_string()))?;
+        let invitation = row_to_invitation(row)?;
+
+        validate_password(&self.password_policy, &req.password)?;
+
+        let user = self
+            .auth_service
+            .register(RegisterRequest {
+                email: invitation.email.clone(),
+                password: req.password,
+                full_name: req.full_name.or(invitation.full_name),
+                role: invitation.role,
+            })
+            .await?;
+
+        sqlx::query("UPDATE user_invitations SET accepted_at = NOW(), user_id = $2 WHERE id = $1")
+            .bind(invitation.id)
+            .bind(user.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to mark invitation accepted: {}", e)))?;
+
+        self.get(user.id).await
+    }
+
+    /// Send a reset link. Unknown or deactivated emails (and service accounts) succeed
+    /// silently so that the endpoint cannot be used to discover accounts.
+    pub async fn request_password_reset(&self, email: &str) -> Result<(), AppError> {
+        self.ensure_password_login()?;
+        let user: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM users WHERE email = $1 AND is_active AND NOT is_service_account",
+        )
+            .bind(email)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to look up user: {}", e)))?;
+        let Some((user_id,)) = user else {
+            return Ok(());
+        };
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::minutes(self.accounts.reset_expiry_minutes);
+
+        sqlx::query("INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)")
+            .bind(user_id)
+            .bind(hash_token(&token))
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create reset token: {}", e)))?;
+
+        let link = format!("{}/reset-password?token={}", self.accounts.link_base_url.trim_end_matches('/'), token);
+        self.sender
+            .send(&AccountMessage {
+                kind: AccountMessageKind::PasswordReset,
+                to: email.to_string(),
+                subject: "Reset your Arc Zero password".to_string(),
+                body: format!(
+                    "A password reset was requested for your account. The link expires at {}.",
+                    expires_at.to_rfc3339()
+                ),
+                link,
+            })
+            .await
+    }
+
+    /// Set a new password from a reset token. Also clears any lockout.
+    pub async fn confirm_password_reset(&self, req: ConfirmPasswordResetRequest) -> Result<(), AppError> {
+        self.ensure_password_login()?;
+        let token: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT t.user_id FROM password_reset_tokens t
+             JOIN users u ON u.id = t.user_id
+             WHERE t.token_hash = $1 AND t.used_at IS NULL AND t.expires_at > NOW() AND u.is_active",
+        )
+        .bind(hash_token(&req.token))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch reset token: {}", e)))?;
+        let (user_id,) = token.ok_or_else(|| AppError::Validation("Reset token is invalid or has expired".to_string()))?;
+
+        validate_password(&self.password_policy, &req.new_password)?;
+        let password_hash = AuthService::hash_password(&req.new_password)?;
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query(
+            "UPDATE users SET password_hash = $2, password_changed_at = NOW(), failed_login_attempts = 0,
+                    locked_until = NULL, updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(&password_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update password: {}", e)))?;
+
+        // Every outstanding token for the user is spent, not just the one used
+        sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to consume reset tokens: {}", e)))?;
+
+        // Whoever knew the old password is logged out everywhere
+        AuthService::revoke_user_sessions(&mut tx, user_id, None).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Change the caller's own password after re-checking the current one.
+    /// Other sessions are revoked; `current_session` stays logged in.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_session: Option<Uuid>,
+        req: ChangePasswordRequest,
+    ) -> Result<(), AppError> {
+        self.ensure_password_login()?;
+
+        let row = sqlx::query("SELECT password_hash FROM users WHERE id = $1 AND is_active")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch user: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let current_hash: Option<String> = row.try_get("password_hash")
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Missing password_hash: {}", e)))?;
+        let current_hash = current_hash
+            .ok_or_else(|| AppError::Validation("This account signs in with single sign-on".to_string()))?;
+
+        if !AuthService::verify_password(&req.current_password, &current_hash)? {
+            return Err(AppError::Validation("Current password is incorrect".to_string()));
+        }
+        if req.current_password == req.new_password {
+            return Err(AppError::Validation("New password must differ from the current password".to_string()));
+        }
+        validate_password(&self.password_policy, &req.new_password)?;
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("UPDATE users SET password_hash = $2, password_changed_at = NOW(), updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(AuthService::hash_password(&req.new_password)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to update password: {}", e)))?;
+
+        AuthService::revoke_user_sessions(&mut tx, user_id, current_session).await?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn ensure_password_login(&self) -> Result<(), AppError> {
+        if self.accounts.password_login_enabled {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden("Password login is disabled; accounts are managed by single sign-on".to_string()))
+        }
+    }
+
+    async fn ensure_other_admin(&self, id: Uuid) -> Result<(), AppError> {
+        let (others,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM users WHERE role = 'admin' AND is_active AND id <> $1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count admins: {}", e)))?;
+
+        if others == 0 {
+            return Err(AppError::Validation("At least one active admin is required".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Human-readable policy violations; empty when the password is acceptable
+pub fn password_violations(policy: &PasswordPolicy, password: &str) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        violations.push(format!("must be at least {} characters", policy.min_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        violations.push("must contain an uppercase letter".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        violations.push("must contain a lowercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push("must contain a digit".to_string());
+    }
+    if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+        violations.push("must contain a symbol".to_string());
+    }
+
+    violations
+}
+
+fn validate_password(policy: &PasswordPolicy, password: &str) -> Result<(), AppError> {
+    let violations = password_violations(policy, password);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Password {}", violations.join(", "))))
+    }
+}
+
+/// 256-bit random token, hex encoded
+pub(crate) fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn parse_role(row: &sqlx::postgres::PgRow) -> Result<UserRole, AppError> {
+    let role: String = row.try_get("role").map_err(missing("role"))?;
+    UserRole::parse(&role).ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid user role: {}", role)))
+}
+
+fn row_to_account(row: sqlx::postgres::PgRow) -> Result<UserAccount, AppError> {
+    Ok(UserAccount {
+        id: row.try_get("id").map_err(missing("id"))?,
+        email: row.try_get("email").map_err(missing("email"))?,
+        full_name: row.try_get("full_name").map_err(missing("full_name"))?,
+        role: parse_role(&row)?,
+        is_active: row.try_get("is_active").map_err(missing("is_active"))?,
+        failed_login_attempts: row.try_get("failed_login_attempts").map_err(missing("failed_login_attempts"))?,
+        locked_until: row.try_get("locked_until").map_err(missing("locked_until"))?,
+        deactivated_at: row.try_get("deactivated_at").map_err(missing("deactivated_at"))?,
+        password_changed_at: row.try_get("password_changed_at").map_err(missing("password_changed_at"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+fn row_to_invitation(row: sqlx::postgres::PgRow) -> Result<Invitation, AppError> {
+    let expires_at: chrono::DateTime<Utc> = row.try_get("expires_at").map_err(missing("expires_at"))?;
+    let accepted_at: Option<chrono::DateTime<Utc>> = row.try_get("accepted_at").map_err(missing("accepted_at"))?;
+    let revoked_at: Option<chrono::DateTime<Utc>> = row.try_get("revoked_at").map_err(missing("revoked_at"))?;
+
+    let status = if accepted_at.is_some() {
+        InvitationStatus::Accepted
+    } else if revoked_at.is_some() {
+        InvitationStatus::Revoked
+    } else if expires_at <= Utc::now() {
+        InvitationStatus::Expired
+    } else {
+        InvitationStatus::Pending
+    };
+
+    Ok(Invitation {
+        id: row.try_get("id").map_err(missing("id"))?,
+        email: row.try_get("email").map_err(missing("email"))?,
+        full_name: row.try_get("full_name").map_err(missing("full_name"))?,
+        role: parse_role(&row)?,
+        status,
+        invited_by: row.try_get("invited_by").map_err(missing("invited_by"))?,
+        user_id: row.try_get("user_id").map_err(missing("user_id"))?,
+        expires_at,
+        accepted_at,
+        revoked_at,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+    })
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_policy_reports_every_violation() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+        };
+
+        assert_eq!(
+            password_violations(&policy, "short"),
+            vec![
+                "must be at least 12 characters",
+                "must contain an uppercase letter",
+                "must contain a digit",
+                "must contain a symbol",
+            ]
+        );
+        assert!(password_violations(&policy, "Correct-Horse-42").is_empty());
+        assert_eq!(password_violations(&PasswordPolicy::default(), "changeme123").len(), 2);
+    }
+
+    #[test]
+    fn test_tokens_are_random_and_hashed() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+
+        let hashed = hash_token(&token);
+        assert_eq!(hashed.len(), 64);
+        assert_ne!(hashed, token);
+        assert_eq!(hashed, hash_token(&token));
+    }
+}
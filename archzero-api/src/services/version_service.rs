@@ -0,0 +1,342 @@
+/*!
+ * Version History Service
+ *
+ * Append-only version rows for cards and relationships. Writes are recorded by
+ * CardService and RelationshipService inside the same transaction as the change;
+ * the actor comes from the request scope set by `auth_middleware`.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::card::Card;
+use crate::models::relationship::Relationship;
+use crate::models::version::{Actor, CardVersion, RelationshipVersion, VersionOperation};
+use crate::models::workspace::DEFAULT_WORKSPACE_ID;
+
+tokio::task_local! {
+    /// Authenticated user for the current request, if any
+    pub static CURRENT_ACTOR: Actor;
+}
+
+/// Actor for the current request; `None` for unauthenticated routes and background jobs
+pub fn current_actor() -> Option<Actor> {
+    CURRENT_ACTOR.try_with(|actor| actor.clone()).ok()
+}
+
+/// Workspace of the current request; `None` outside a request, meaning every workspace
+pub fn current_workspace() -> Option<Uuid> {
+    CURRENT_ACTOR.try_with(|actor| actor.workspace_id).ok().flatten()
+}
+
+/// Versions of cards in workspace `$param` or shared with it; every version outside a request
+fn card_versions_in_workspace(param: usize) -> String {
+    format!(
+        "(${0}::uuid IS NULL OR workspace_id = ${0}::uuid OR card_id IN (SELECT card_id FROM card_shares WHERE workspace_id = ${0}::uuid))",
+        param
+    )
+}
+
+pub struct VersionService {
+    pool: PgPool,
+}
+
+impl VersionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append a card version. `before`/`after` are `None` for creates and deletes respectively.
+    pub async fn record_card(
+        conn: &mut PgConnection,
+        operation: VersionOperation,
+        before: Option<&Card>,
+        after: Option<&Card>,
+    ) -> Result<(), AppError> {
+        let Some(card) = after.or(before) else {
+            return Ok(());
+        };
+        let (snapshot, diff) = snapshot_and_diff(before, after)?;
+        let actor = current_actor();
+
+        sqlx::query(
+            r#"
+            INSERT INTO card_versions (card_id, version, operation, snapshot, diff, actor_id, actor_email, workspace_id)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4, $5, $6, $7
+            FROM card_versions WHERE card_id = $1
+            "#,
+        )
+        .bind(card.id)
+        .bind(operation.as_str())
+        .bind(&snapshot)
+        .bind(&diff)
+        .bind(actor.as_ref().and_then(|a| a.id))
+        .bind(actor.as_ref().map(|a| a.email.clone()))
+        .bind(card.workspace_id.or_else(current_workspace).unwrap_or(DEFAULT_WORKSPACE_ID))
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record card version: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Append a relationship version
+    pub async fn record_relationship(
+        conn: &mut PgConnection,
+        operation: VersionOperation,
+        before: Option<&Relationship>,
+        after: Option<&Relationship>,
+    ) -> Result<(), AppError> {
+        let Some(relationship) = after.or(before) else {
+            return Ok(());
+        };
+        let (snapshot, diff) = snapshot_and_diff(before, after)?;
+        let actor = current_actor();
+
+        sqlx::query(
+            r#"
+            INSERT INTO relationship_versions
+                (relationship_id, from_card_id, to_card_id, version, operation, snapshot, diff, actor_id, actor_email, workspace_id)
+            SELECT $1, $2, $3, COALESCE(MAX(version), 0) + 1, $4, $5, $6, $7, $8, $9
+            FROM relationship_versions WHERE relationship_id = $1
+            "#,
+        )
+        .bind(relationship.id)
+        .bind(relationship.from_card_id)
+        .bind(relationship.to_card_id)
+        .bind(operation.as_str())
+        .bind(&snapshot)
+        .bind(&diff)
+        .bind(actor.as_ref().and_then(|a| a.id))
+        .bind(actor.as_ref().map(|a| a.email.clone()))
+        .bind(relationship.workspace_id.or_else(current_workspace).unwrap_or(DEFAULT_WORKSPACE_ID))
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to record relationship version: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Full history of a card visible in the current workspace, newest first
+    pub async fn card_history(&self, card_id: Uuid) -> Result<Vec<CardVersion>, AppError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT id, card_id, version, operation, snapshot, diff, actor_id, actor_email, created_at
+            FROM card_versions
+            WHERE card_id = $1 AND {}
+            ORDER BY version DESC
+            "#,
+            card_versions_in_workspace(2)
+        ))
+        .bind(card_id)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card history: {}", e)))?;
+
+        rows.into_iter().map(row_to_card_version).collect()
+    }
+
+    pub async fn card_version(&self, card_id: Uuid, version: i32) -> Result<CardVersion, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT id, card_id, version, operation, snapshot, diff, actor_id, actor_email, created_at
+            FROM card_versions
+            WHERE card_id = $1 AND version = $2 AND {}
+            "#,
+            card_versions_in_workspace(3)
+        ))
+        .bind(card_id)
+        .bind(version)
+        .bind(current_workspace())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card version: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Version {} of card {} not found", version, card_id)))?;
+
+        row_to_card_version(row)
+    }
+
+    /// The card as it was at `at`, reconstructed from the latest version at or before that time
+    pub async fn card_as_of(&self, card_id: Uuid, at: DateTime<Utc>) -> Result<Card, AppError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT id, card_id, version, operation, snapshot, diff, actor_id, actor_email, created_at
+            FROM card_versions
+            WHERE card_id = $1 AND created_at <= $2 AND {}
+            ORDER BY version DESC
+            LIMIT 1
+            "#,
+            card_versions_in_workspace(3)
+        ))
+        .bind(card_id)
+        .bind(at)
+        .bind(current_workspace())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card version: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Card {} did not exist at {}", card_id, at)))?;
+
+        let version = row_to_card_version(row)?;
+        if version.operation == VersionOperation::Delete {
+            return Err(AppError::NotFound(format!("Card {} was deleted at {}", card_id, at)));
+        }
+
+        version.snapshot_card()
+    }
+
+    /// Full history of a relationship in the current workspace, newest first
+    pub async fn relationship_history(&self, relationship_id: Uuid) -> Result<Vec<RelationshipVersion>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, relationship_id, from_card_id, to_card_id, version, operation, snapshot, diff,
+                   actor_id, actor_email, created_at
+            FROM relationship_versions
+            WHERE relationship_id = $1 AND ($2::uuid IS NULL OR workspace_id = $2)
+            ORDER BY version DESC
+            "#,
+        )
+        .bind(relationship_id)
+        .bind(current_workspace())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch relationship history: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(RelationshipVersion {
+                    id: row.try_get("id").map_err(missing("id"))?,
+                    relationship_id: row.try_get("relationship_id").map_err(missing("relationship_id"))?,
+                    from_card_id: row.try_get("from_card_id").map_err(missing("from_card_id"))?,
+                    to_card_id: row.try_get("to_card_id").map_err(missing("to_card_id"))?,
+                    version: row.try_get("version").map_err(missing("version"))?,
+                    operation: parse_operation(&row)?,
+                    snapshot: row.try_get("snapshot").map_err(missing("snapshot"))?,
+                    diff: row.try_get("diff").map_err(missing("diff"))?,
+                    actor_id: row.try_get("actor_id").ok(),
+                    actor_email: row.try_get("actor_email").ok(),
+                    created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl CardVersion {
+    /// Deserialize the stored snapshot back into a card
+    pub fn snapshot_card(&self) -> Result<Card, AppError> {
+        serde_json::from_value(self.snapshot.clone())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid card snapshot: {}", e)))
+    }
+}
+
+fn row_to_card_version(row: sqlx::postgres::PgRow) -> Result<CardVersion, AppError> {
+    Ok(CardVersion {
+        id: row.try_get("id").map_err(missing("id"))?,
+        card_id: row.try_get("card_id").map_err(missing("card_id"))?,
+        version: row.try_get("version").map_err(missing("version"))?,
+        operation: parse_operation(&row)?,
+        snapshot: row.try_get("snapshot").map_err(missing("snapshot"))?,
+        diff: row.try_get("diff").map_err(missing("diff"))?,
+        actor_id: row.try_get("actor_id").ok(),
+        actor_email: row.try_get("actor_email").ok(),
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+    })
+}
+
+fn parse_operation(row: &sqlx::postgres::PgRow) -> Result<VersionOperation, AppError> {
+    let operation: String = row.try_get("operation").map_err(missing("operation"))?;
+    VersionOperation::parse(&operation)
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid version operation: {}", operation)))
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn snapshot_and_diff<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Result<(Value, Value), AppError> {
+    let to_value = |v: Option<&T>| {
+        v.map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize version snapshot: {}", e)))
+    };
+    let before = to_value(before)?;
+    let after = to_value(after)?;
+
+    let diff = diff_values(before.as_ref(), after.as_ref());
+    let snapshot = after.or(before).unwrap_or(Value::Null);
+    Ok((snapshot, diff))
+}
+
+/// Field-level diff of two serialized entities as `{field: {from, to}}`.
+/// Attributes are compared key by key (`attributes.<key>`) and `updatedAt` is ignored.
+pub fn diff_values(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut diff = Map::new();
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        if key == "updatedAt" {
+            continue;
+        }
+        let old = before.get(key).unwrap_or(&Value::Null);
+        let new = after.get(key).unwrap_or(&Value::Null);
+
+        if key == "attributes" && (old.is_object() || new.is_object()) {
+            if let Value::Object(nested) = diff_values(Some(old), Some(new)) {
+                for (attr, change) in nested {
+                    diff.insert(format!("attributes.{}", attr), change);
+                }
+            }
+        } else if old != new {
+            diff.insert(key.clone(), json!({ "from": old, "to": new }));
+        }
+    }
+
+    Value::Object(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_changed_fields_and_attribute_keys() {
+        let before = json!({
+            "name": "CRM",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "attributes": {"hosting": "onprem", "owner": "sales"},
+        });
+        let after = json!({
+            "name": "CRM Cloud",
+            "updatedAt": "2024-02-01T00:00:00Z",
+            "attributes": {"hosting": "saas", "owner": "sales", "vendor": "acme"},
+        });
+
+        let diff = diff_values(Some(&before), Some(&after));
+
+        assert_eq!(diff, json!({
+            "name": {"from": "CRM", "to": "CRM Cloud"},
+            "attributes.hosting": {"from": "onprem", "to": "saas"},
+            "attributes.vendor": {"from": null, "to": "acme"},
+        }));
+    }
+
+    #[test]
+    fn test_diff_of_create_and_delete() {
+        let card = json!({"name": "CRM", "attributes": {}});
+
+        assert_eq!(diff_values(None, Some(&card)), json!({"name": {"from": null, "to": "CRM"}}));
+        assert_eq!(diff_values(Some(&card), None), json!({"name": {"from": "CRM", "to": null}}));
+        assert_eq!(diff_values(Some(&card), Some(&card)), json!({}));
+    }
+}
@@ -0,0 +1,410 @@
+/*!
+ * Workspace Service
+ *
+ * Workspaces, their members and cross-workspace card shares. Every request runs in
+ * one workspace (see `auth_middleware`); `CardService`, `RelationshipService` and the
+ * graph backends scope their queries to it. The default workspace is open to every
+ * user with their global role, other workspaces only to their members.
+ */
+
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::permission::{Action, Permission, Resource};
+use crate::models::user::{Claims, UserRole};
+use crate::models::workspace::*;
+
+/// Creating, deleting and managing any workspace is instance maintenance; API tokens need this scope
+pub const MANAGE_WORKSPACES: Permission = Permission::new(Resource::Admin, Action::Write);
+
+/// Global admin role, and for API tokens the `admin:write` scope
+pub fn require_global_admin(claims: &Claims) -> Result<(), AppError> {
+    if !claims.has_permission(MANAGE_WORKSPACES) {
+        return Err(AppError::Forbidden("Admin role required".to_string()));
+    }
+    Ok(())
+}
+
+const WORKSPACE_COLUMNS: &str = "w.id, w.name, w.description, w.created_by, w.created_at, w.updated_at";
+
+pub struct WorkspaceService {
+    pool: PgPool,
+}
+
+impl WorkspaceService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Workspace and role a request runs with; the default workspace unless `requested`
+    pub async fn resolve(&self, user_id: Uuid, global_role: &UserRole, requested: Option<Uuid>) -> Result<(Uuid, UserRole), AppError> {
+        let workspace_id = requested.unwrap_or(DEFAULT_WORKSPACE_ID);
+        let membership = self.membership(workspace_id, user_id).await?;
+
+        workspace_role(workspace_id, global_role, membership)
+            .map(|role| (workspace_id, role))
+            .ok_or_else(|| AppError::Forbidden(format!("No access to workspace {}", workspace_id)))
+    }
+
+    /// Workspaces the user can open, with their role in each
+    pub async fn list_for_user(&self, user_id: Uuid, global_role: &UserRole) -> Result<Vec<WorkspaceAccess>, AppError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {}, m.role AS member_role FROM workspaces w \
+             LEFT JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = $1 \
+             ORDER BY w.id <> $2, w.name",
+            WORKSPACE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(DEFAULT_WORKSPACE_ID)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list workspaces: {}", e)))?;
+
+        let mut workspaces = Vec::new();
+        for row in rows {
+            let membership = row.try_get::<Option<String>, _>("member_role").map_err(missing("member_role"))?
+                .and_then(|role| UserRole::parse(&role));
+            let workspace = row_to_workspace(&row)?;
+            if let Some(role) = workspace_role(workspace.id, global_role, membership) {
+                workspaces.push(WorkspaceAccess { workspace, role });
+            }
+        }
+        Ok(workspaces)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Workspace, AppError> {
+        let row = sqlx::query(&format!("SELECT {} FROM workspaces w WHERE w.id = $1", WORKSPACE_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch workspace: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Workspace {} not found", id)))?;
+
+        row_to_workspace(&row)
+    }
+
+    /// Create a workspace; the creator becomes its admin
+    pub async fn create(&self, req: CreateWorkspaceRequest, created_by: Uuid) -> Result<Workspace, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Workspace name is required".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to start transaction: {}", e)))?;
+
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO workspaces (name, description, created_by) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(name)
+        .bind(&req.description)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(created_by)
+            .bind(UserRole::Admin.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to add workspace admin: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to commit workspace: {}", e)))?;
+
+        self.get(id).await
+    }
+
+    pub async fn update(&self, id: Uuid, req: UpdateWorkspaceRequest) -> Result<Workspace, AppError> {
+        if req.name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+            return Err(AppError::Validation("Workspace name is required".to_string()));
+        }
+
+        let result = sqlx::query(
+            "UPDATE workspaces SET name = COALESCE($2, name), description = COALESCE($3, description), \
+             updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(&req.description)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Workspace {} not found", id)));
+        }
+        self.get(id).await
+    }
+
+    /// Delete an empty workspace; the default workspace cannot be deleted
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        if id == DEFAULT_WORKSPACE_ID {
+            return Err(AppError::Validation("The default workspace cannot be deleted".to_string()));
+        }
+
+        let cards: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cards WHERE workspace_id = $1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to count workspace cards: {}", e)))?;
+        if cards > 0 {
+            return Err(AppError::Validation(format!("Workspace still holds {} cards", cards)));
+        }
+
+        let result = sqlx::query("DELETE FROM workspaces WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to delete workspace: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Workspace {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Global admins manage every workspace; otherwise the caller needs the admin role in it.
+    /// Scoped API tokens only manage workspaces with the `admin:write` scope.
+    pub async fn require_admin(&self, claims: &Claims, workspace_id: Uuid) -> Result<(), AppError> {
+        if require_global_admin(claims).is_ok() {
+            return Ok(());
+        }
+        if claims.scopes.is_some() {
+            return Err(AppError::Forbidden(format!("API token needs the {} scope", MANAGE_WORKSPACES)));
+        }
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::Auth("Invalid token subject".to_string()))?;
+        match self.membership(workspace_id, user_id).await? {
+            Some(UserRole::Admin) => Ok(()),
+            _ => Err(AppError::Forbidden("Workspace admin role required".to_string())),
+        }
+    }
+
+    pub async fn list_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>, AppError> {
+        self.get(workspace_id).await?;
+
+        let rows = sqlx::query(
+            "SELECT m.user_id, u.email, u.full_name, m.role, m.added_at \
+             FROM workspace_members m JOIN users u ON u.id = m.user_id \
+             WHERE m.workspace_id = $1 ORDER BY u.email",
+        )
+        .bind(workspace_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list workspace members: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let role: String = row.try_get("role").map_err(missing("role"))?;
+                Ok(WorkspaceMember {
+                    user_id: row.try_get("user_id").map_err(missing("user_id"))?,
+                    email: row.try_get("email").map_err(missing("email"))?,
+                    full_name: row.try_get("full_name").map_err(missing("full_name"))?,
+                    role: UserRole::parse(&role)
+                        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Invalid workspace role: {}", role)))?,
+                    added_at: row.try_get("added_at").map_err(missing("added_at"))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Add a member or change their role
+    pub async fn set_member(&self, workspace_id: Uuid, req: SetWorkspaceMemberRequest) -> Result<Vec<WorkspaceMember>, AppError> {
+        self.get(workspace_id).await?;
+
+        sqlx::query(
+            "INSERT INTO workspace_members (workspace_id, user_id, role) VALUES ($1, $2, $3) \
+             ON CONFLICT (workspace_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+        )
+        .bind(workspace_id)
+        .bind(req.user_id)
+        .bind(req.role.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|db| db.code()).as_deref() {
+            Some("23503") => AppError::NotFound(format!("User {} not found", req.user_id)),
+            _ => AppError::Internal(anyhow::anyhow!("Failed to set workspace member: {}", e)),
+        })?;
+
+        self.list_members(workspace_id).await
+    }
+
+    pub async fn remove_member(&self, workspace_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM workspace_members WHERE workspace_id = $1 AND user_id = $2")
+            .bind(workspace_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to remove workspace member: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("User {} is not a member of workspace {}", user_id, workspace_id)));
+        }
+        Ok(())
+    }
+
+    /// Workspaces a card is shared into; only visible from the card's own workspace
+    pub async fn list_card_shares(&self, card_id: Uuid, current: Option<Uuid>) -> Result<Vec<CardShare>, AppError> {
+        self.owned_card(card_id, current).await?;
+
+        let rows = sqlx::query(
+            "SELECT s.card_id, s.workspace_id, w.name AS workspace_name, s.shared_by, s.created_at \
+             FROM card_shares s JOIN workspaces w ON w.id = s.workspace_id \
+             WHERE s.card_id = $1 ORDER BY w.name",
+        )
+        .bind(card_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to list card shares: {}", e)))?;
+
+        rows.iter().map(row_to_share).collect()
+    }
+
+    /// Make a card of the current workspace visible, read-only, in another workspace
+    pub async fn share_card(&self, card_id: Uuid, current: Option<Uuid>, req: ShareCardRequest, shared_by: Uuid) -> Result<Vec<CardShare>, AppError> {
+        let home = self.owned_card(card_id, current).await?;
+        if req.workspace_id == home {
+            return Err(AppError::Validation("A card cannot be shared into its own workspace".to_string()));
+        }
+        self.get(req.workspace_id).await?;
+
+        sqlx::query(
+            "INSERT INTO card_shares (card_id, workspace_id, shared_by) VALUES ($1, $2, $3) \
+             ON CONFLICT (card_id, workspace_id) DO NOTHING",
+        )
+        .bind(card_id)
+        .bind(req.workspace_id)
+        .bind(shared_by)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to share card: {}", e)))?;
+
+        self.list_card_shares(card_id, current).await
+    }
+
+    pub async fn unshare_card(&self, card_id: Uuid, current: Option<Uuid>, workspace_id: Uuid) -> Result<(), AppError> {
+        self.owned_card(card_id, current).await?;
+
+        let result = sqlx::query("DELETE FROM card_shares WHERE card_id = $1 AND workspace_id = $2")
+            .bind(card_id)
+            .bind(workspace_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to unshare card: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Card {} is not shared with workspace {}", card_id, workspace_id)));
+        }
+        Ok(())
+    }
+}
+
+impl WorkspaceService {
+    async fn membership(&self, workspace_id: Uuid, user_id: Uuid) -> Result<Option<UserRole>, AppError> {
+        let row = sqlx::query(
+            "SELECT m.role FROM workspaces w \
+             LEFT JOIN workspace_members m ON m.workspace_id = w.id AND m.user_id = $2 \
+             WHERE w.id = $1",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch workspace membership: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Workspace {} not found", workspace_id)))?;
+
+        Ok(row.try_get::<Option<String>, _>("role").map_err(missing("role"))?
+            .and_then(|role| UserRole::parse(&role)))
+    }
+
+    /// Workspace of an active card owned by the current workspace; shares are managed only from there
+    async fn owned_card(&self, card_id: Uuid, current: Option<Uuid>) -> Result<Uuid, AppError> {
+        let home: Uuid = sqlx::query_scalar("SELECT workspace_id FROM cards WHERE id = $1 AND status = 'active'")
+            .bind(card_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to fetch card: {}", e)))?
+            .filter(|home| current.is_none_or(|current| current == *home))
+            .ok_or_else(|| AppError::NotFound(format!("Card {} not found", card_id)))?;
+        Ok(home)
+    }
+}
+
+/// Role a user holds in a workspace: global admins administer every workspace, a membership
+/// sets the role explicitly, and the default workspace falls back to the global role
+pub fn workspace_role(workspace_id: Uuid, global_role: &UserRole, membership: Option<UserRole>) -> Option<UserRole> {
+    if *global_role == UserRole::Admin {
+        return Some(UserRole::Admin);
+    }
+    match membership {
+        Some(role) => Some(role),
+        None if workspace_id == DEFAULT_WORKSPACE_ID => Some(global_role.clone()),
+        None => None,
+    }
+}
+
+fn missing(column: &'static str) -> impl Fn(sqlx::Error) -> AppError {
+    move |e| AppError::Internal(anyhow::anyhow!("Missing {}: {}", column, e))
+}
+
+fn row_to_workspace(row: &sqlx::postgres::PgRow) -> Result<Workspace, AppError> {
+    Ok(Workspace {
+        id: row.try_get("id").map_err(missing("id"))?,
+        name: row.try_get("name").map_err(missing("name"))?,
+        description: row.try_get("description").map_err(missing("description"))?,
+        created_by: row.try_get("created_by").map_err(missing("created_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+        updated_at: row.try_get("updated_at").map_err(missing("updated_at"))?,
+    })
+}
+
+fn row_to_share(row: &sqlx::postgres::PgRow) -> Result<CardShare, AppError> {
+    Ok(CardShare {
+        card_id: row.try_get("card_id").map_err(missing("card_id"))?,
+        workspace_id: row.try_get("workspace_id").map_err(missing("workspace_id"))?,
+        workspace_name: row.try_get("workspace_name").map_err(missing("workspace_name"))?,
+        shared_by: row.try_get("shared_by").map_err(missing("shared_by"))?,
+        created_at: row.try_get("created_at").map_err(missing("created_at"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_role_resolution() {
+        let other = Uuid::new_v4();
+
+        assert_eq!(workspace_role(DEFAULT_WORKSPACE_ID, &UserRole::Editor, None), Some(UserRole::Editor));
+        assert_eq!(workspace_role(DEFAULT_WORKSPACE_ID, &UserRole::Editor, Some(UserRole::Viewer)), Some(UserRole::Viewer));
+        assert_eq!(workspace_role(other, &UserRole::Editor, None), None);
+        assert_eq!(workspace_role(other, &UserRole::Viewer, Some(UserRole::Architect)), Some(UserRole::Architect));
+        assert_eq!(workspace_role(other, &UserRole::Admin, Some(UserRole::Viewer)), Some(UserRole::Admin));
+    }
+
+    #[tokio::test]
+    async fn test_scoped_tokens_cannot_administer_workspaces() {
+        let claims = |role: UserRole, scopes: Option<&[&str]>| Claims {
+            sub: Uuid::new_v4().to_string(),
+            email: "admin@archzero.local".to_string(),
+            role,
+            exp: 0,
+            sid: None,
+            scopes: scopes.map(|scopes| scopes.iter().map(|scope| scope.to_string()).collect()),
+            workspace_id: None,
+            workspace_role: None,
+        };
+
+        assert!(require_global_admin(&claims(UserRole::Admin, None)).is_ok());
+        assert!(require_global_admin(&claims(UserRole::Admin, Some(&["admin:write"]))).is_ok());
+        assert!(matches!(require_global_admin(&claims(UserRole::Admin, Some(&["cards:read"]))), Err(AppError::Forbidden(_))));
+        assert!(matches!(require_global_admin(&claims(UserRole::Editor, None)), Err(AppError::Forbidden(_))));
+
+        // Refused before any membership lookup
+        let service = WorkspaceService::new(PgPool::connect_lazy("postgres://localhost/unused").unwrap());
+        let scoped = claims(UserRole::Admin, Some(&["cards:read", "cards:write"]));
+        assert!(matches!(service.require_admin(&scoped, Uuid::new_v4()).await, Err(AppError::Forbidden(_))));
+    }
+}
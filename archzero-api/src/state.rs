@@ -4,8 +4,8 @@ use uuid::Uuid;
 use tokio::sync::Mutex;
 
 use crate::services::{
-    CardService, AuthService, RelationshipService, Neo4jService,
-    SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ARBNotificationService, ExportService, ReportService
+    CardService, AuthService, RelationshipService, GraphBackend,
+    SagaOrchestrator, BIAService, TopologyService, MigrationService, TCOService, CsrfService, RateLimitService, CacheService, ArbTemplateService, ARBAuditService, ARBNotificationService, ExportService, ReportService, GraphSyncService, VersionService, ArchitectureStateService, UserService, TeamService, OidcService, ApiTokenService, ScimService, WorkspaceService, MetamodelService, MetamodelMigrationService, CardTypeService, RelationshipRuleService, SavedSearchService, DataQualityService
 };
 
 #[derive(Clone)]
@@ -13,7 +13,7 @@ pub struct AppState {
     pub card_service: Arc<CardService>,
     pub auth_service: Arc<AuthService>,
     pub relationship_service: Arc<RelationshipService>,
-    pub neo4j_service: Arc<Neo4jService>,
+    pub graph_backend: Arc<dyn GraphBackend>,
     pub saga_orchestrator: Arc<SagaOrchestrator>,
     pub bia_service: Arc<BIAService>,
     pub topology_service: Arc<TopologyService>,
@@ -27,5 +27,20 @@ pub struct AppState {
     pub arb_notification_service: Arc<ARBNotificationService>,
     pub export_service: Arc<ExportService>,
     pub report_service: Arc<ReportService>,
+    pub graph_sync_service: Arc<GraphSyncService>,
+    pub version_service: Arc<VersionService>,
+    pub architecture_state_service: Arc<ArchitectureStateService>,
+    pub user_service: Arc<UserService>,
+    pub team_service: Arc<TeamService>,
+    pub oidc_service: Arc<OidcService>,
+    pub api_token_service: Arc<ApiTokenService>,
+    pub scim_service: Arc<ScimService>,
+    pub workspace_service: Arc<WorkspaceService>,
+    pub metamodel_service: Arc<MetamodelService>,
+    pub metamodel_migration_service: Arc<MetamodelMigrationService>,
+    pub card_type_service: Arc<CardTypeService>,
+    pub relationship_rule_service: Arc<RelationshipRuleService>,
+    pub saved_search_service: Arc<SavedSearchService>,
+    pub data_quality_service: Arc<DataQualityService>,
     pub import_jobs: Arc<Mutex<std::collections::HashMap<Uuid, crate::handlers::import::ImportJob>>>,
 }
@@ -1,5 +1,6 @@
 import { BrowserRouter, Routes, Route, Navigate } from 'react-router-dom';
 import { Login } from './pages/Login';
+import { AuthCallback } from './pages/AuthCallback';
 import { Dashboard } from './pages/Dashboard';
 import { CardsPage } from './pages/Cards';
 import { IntelligencePage } from './pages/Intelligence';
@@ -60,6 +61,7 @@ function App() {
       <OfflineBanner />
       <Routes>
         <Route path="/login" element={<Login />} />
+        <Route path="/auth/callback" element={<AuthCallback />} />
 
         {/* Routes with Navigation Layout */}
         <Route
@@ -1,4 +1,4 @@
-import axios, { AxiosError } from 'axios';
+import axios, { AxiosError, InternalAxiosRequestConfig } from 'axios';
 
 const API_BASE_URL = import.meta.env.VITE_API_URL || 'http://localhost:3000';
 
@@ -9,24 +9,100 @@ export const api = axios.create({
   },
 });
 
+const CSRF_COOKIE = 'csrf_token';
+const SAFE_METHODS = ['get', 'head', 'options', 'trace'];
+
+const readCsrfCookie = (): string | null => {
+  const match = document.cookie.match(new RegExp(`(?:^|;\\s*)${CSRF_COOKIE}=([^;]+)`));
+  return match ? decodeURIComponent(match[1]) : null;
+};
+
+// Requests without a Bearer token may be authenticated by a session cookie, which the API
+// only accepts with the double-submit CSRF token; fetch it once when the cookie is missing
+let csrfToken: Promise<string | null> | null = null;
+
+const getCsrfToken = async (): Promise<string | null> => {
+  const cookie = readCsrfCookie();
+  if (cookie) {
+    return cookie;
+  }
+  csrfToken = csrfToken ?? axios
+    .get(`${API_BASE_URL}/api/v1/csrf/token`, { withCredentials: true })
+    .then((response) => response.data.token as string)
+    .catch(() => null)
+    .finally(() => {
+      csrfToken = null;
+    });
+  return csrfToken;
+};
+
 // Add auth interceptor to include token in requests
-api.interceptors.request.use((config) => {
+api.interceptors.request.use(async (config) => {
   const token = localStorage.getItem('auth_token');
   if (token) {
     config.headers.Authorization = `Bearer ${token}`;
   }
+  const workspaceId = localStorage.getItem('workspace_id');
+  if (workspaceId) {
+    config.headers['X-Workspace-Id'] = workspaceId;
+  }
+  if (!token && !SAFE_METHODS.includes((config.method ?? 'get').toLowerCase())) {
+    const csrf = await getCsrfToken();
+    if (csrf) {
+      config.headers['X-CSRF-Token'] = csrf;
+    }
+  }
   return config;
 });
 
+// Refresh tokens are single-use, so concurrent 401s share one refresh call
+let refreshing: Promise<string | null> | null = null;
+
+const refreshAccessToken = async (): Promise<string | null> => {
+  const refreshToken = localStorage.getItem('refresh_token');
+  if (!refreshToken) {
+    return null;
+  }
+  try {
+    const csrf = await getCsrfToken();
+    const response = await axios.post(
+      `${API_BASE_URL}/api/v1/auth/refresh`,
+      { refresh_token: refreshToken },
+      { headers: csrf ? { 'X-CSRF-Token': csrf } : {} }
+    );
+    localStorage.setItem('auth_token', response.data.token);
+    localStorage.setItem('refresh_token', response.data.refresh_token);
+    return response.data.token;
+  } catch {
+    return null;
+  }
+};
+
 // Handle response errors
 api.interceptors.response.use(
   (response) => response,
-  (error: AxiosError<{ error: string }>) => {
+  async (error: AxiosError<{ error: string }>) => {
+    const original = error.config as (InternalAxiosRequestConfig & { _refreshed?: boolean }) | undefined;
+
+    // Access token expired - try once to renew it with the refresh token
+    if (error.response?.status === 401 && original && !original._refreshed && !original.url?.startsWith('/auth/')) {
+      original._refreshed = true;
+      refreshing = refreshing ?? refreshAccessToken().finally(() => {
+        refreshing = null;
+      });
+      const token = await refreshing;
+      if (token) {
+        original.headers.Authorization = `Bearer ${token}`;
+        return api(original);
+      }
+    }
+
     if (error.response?.status === 401) {
       // Unauthorized - clear token and redirect to login
       // But don't redirect if we're already on the login page
       if (!window.location.pathname.includes('/login')) {
         localStorage.removeItem('auth_token');
+        localStorage.removeItem('refresh_token');
         localStorage.removeItem('auth_user');
         window.location.href = '/login';
       }
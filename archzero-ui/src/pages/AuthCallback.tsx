@@ -0,0 +1,49 @@
+import { useEffect, useState } from 'react';
+import { Link, useNavigate } from 'react-router-dom';
+import { useAuthStore } from '@/stores/useAuthStore';
+
+/**
+ * Landing page for single sign-on. The API redirects here with the tokens
+ * (or an error) in the URL fragment.
+ */
+export function AuthCallback() {
+  const navigate = useNavigate();
+  const completeSsoLogin = useAuthStore((s) => s.completeSsoLogin);
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    const params = new URLSearchParams(window.location.hash.slice(1));
+    // Drop the tokens from the address bar and history
+    window.history.replaceState(null, '', window.location.pathname);
+
+    const token = params.get('token');
+    const refreshToken = params.get('refresh_token');
+    if (!token || !refreshToken) {
+      setError(params.get('error') || 'Single sign-on failed');
+      return;
+    }
+
+    completeSsoLogin(token, refreshToken)
+      .then(() => navigate('/dashboard', { replace: true }))
+      .catch(() => setError('Single sign-on failed'));
+  }, [completeSsoLogin, navigate]);
+
+  return (
+    <div className="min-h-screen flex items-center justify-center bg-gray-100">
+      <div className="max-w-md w-full bg-white rounded-lg shadow-md p-8 text-center">
+        {error ? (
+          <>
+            <div data-testid="sso-error" className="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+              {error}
+            </div>
+            <Link to="/login" className="inline-block mt-4 text-blue-600 hover:underline">
+              Back to sign in
+            </Link>
+          </>
+        ) : (
+          <p className="text-gray-600">Signing you in...</p>
+        )}
+      </div>
+    </div>
+  );
+}
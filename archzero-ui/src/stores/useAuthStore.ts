@@ -8,6 +8,7 @@ interface AuthStore {
   token: string | null;
   isAuthenticated: boolean;
   login: (email: string, password: string) => Promise<void>;
+  completeSsoLogin: (token: string, refreshToken: string) => Promise<void>;
   logout: () => void;
   setUser: (user: User) => void;
   setToken: (token: string) => void;
@@ -33,6 +34,7 @@ export const useAuthStore = create<AuthStore>()(
 
           // Store token in localStorage for axios interceptor
           localStorage.setItem('auth_token', data.token);
+          localStorage.setItem('refresh_token', data.refresh_token);
         } catch (error) {
           console.error('Login error:', error);
 
@@ -55,13 +57,35 @@ export const useAuthStore = create<AuthStore>()(
         }
       },
 
+      // Single sign-on returns only tokens; the user is loaded with them
+      completeSsoLogin: async (token: string, refreshToken: string) => {
+        localStorage.setItem('auth_token', token);
+        localStorage.setItem('refresh_token', refreshToken);
+
+        const response = await api.get('/auth/me', { headers: { Authorization: `Bearer ${token}` } });
+        set({
+          user: response.data,
+          token,
+          isAuthenticated: true,
+        });
+      },
+
       logout: () => {
+        // Revoke the server-side session; the local state is cleared regardless
+        const token = localStorage.getItem('auth_token');
+        if (token) {
+          api
+            .post('/auth/logout', null, { headers: { Authorization: `Bearer ${token}` } })
+            .catch(() => undefined);
+        }
+
         set({
           user: null,
           token: null,
           isAuthenticated: false,
         });
         localStorage.removeItem('auth_token');
+        localStorage.removeItem('refresh_token');
         localStorage.removeItem('auth_user');
       },
 
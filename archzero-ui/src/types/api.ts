@@ -41,6 +41,7 @@ export interface Card {
   qualityScore?: number;
   description?: string;
   ownerId?: string;
+  teamId?: string;
   createdAt: string;
   updatedAt: string;
   attributes: Record<string, any>;
@@ -120,6 +121,7 @@ export interface CreateCardRequest {
   qualityScore?: number;
   description?: string;
   ownerId?: string;
+  teamId?: string;
   attributes?: Record<string, any>;
   tags?: string[];
 }
@@ -129,6 +131,8 @@ export interface UpdateCardRequest {
   lifecyclePhase?: LifecyclePhaseValue;
   qualityScore?: number;
   description?: string;
+  ownerId?: string;
+  teamId?: string;
   attributes?: Record<string, any>;
   tags?: string[];
 }